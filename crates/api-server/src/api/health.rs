@@ -1,17 +1,34 @@
-use axum::{http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode, Json};
 
-use crate::models::ApiResponse;
+use crate::{models::ApiResponse, state::AppState};
 
-pub async fn health_check() -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
+pub async fn health_check(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
     use std::time::SystemTime;
 
+    let database = sqlx::query("SELECT 1").execute(&state.db).await.is_ok();
+
+    let mut redis = state.redis.clone();
+    let cache = redis::cmd("PING").query_async::<String>(&mut redis).await.is_ok();
+
+    let endpoints = state.rpc.get_statistics().await;
+    let healthy_rpc = endpoints.iter().filter(|endpoint| endpoint.is_healthy).count();
+
+    let healthy = database && cache && healthy_rpc > 0;
     let health = serde_json::json!({
-        "status": "healthy",
+        "status": if healthy { "healthy" } else { "degraded" },
         "timestamp": SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap()
             .as_millis() as i64,
         "version": "2.0.0",
+        "checks": {
+            "database": database,
+            "redis": cache,
+            "rpc_healthy_endpoints": healthy_rpc,
+            "rpc_total_endpoints": endpoints.len(),
+        },
     });
 
     (StatusCode::OK, Json(ApiResponse::success(health)))
@@ -3,9 +3,10 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use solsniper_core::{Event, Recommendation, TokenInfo};
 
 use crate::{
-    models::{Position, ApiResponse},
+    models::{OpenPositionRequest, Position, ApiResponse},
     state::AppState,
 };
 
@@ -94,3 +95,174 @@ pub async fn close_position(
         }
     }
 }
+
+/// 开仓：评估风险后买入，记录持仓并开始持仓监控
+pub async fn open_position(
+    State(state): State<AppState>,
+    Json(request): Json<OpenPositionRequest>,
+) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
+    let error = |status: StatusCode, code: &str, message: String| {
+        (status, Json(ApiResponse::error(code.to_string(), message)))
+    };
+
+    let Some(trading) = state.trading.clone() else {
+        return error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "TRADING_DISABLED",
+            "Trading wallet not configured (TRADING_KEYPAIR_PATH)".to_string(),
+        );
+    };
+    if !request.amount_sol.is_finite() || request.amount_sol <= 0.0 {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_AMOUNT",
+            format!("Invalid amount: {}", request.amount_sol),
+        );
+    }
+
+    let data = sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT data FROM tokens WHERE mint = $1"
+    )
+    .bind(&request.mint)
+    .fetch_optional(&state.db)
+    .await;
+
+    let token: TokenInfo = match data.map(|data| data.map(serde_json::from_value)) {
+        Ok(Some(Ok(token))) => token,
+        Ok(Some(Err(e))) => {
+            tracing::error!("Stored token data for {} is invalid: {}", request.mint, e);
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "INVALID_TOKEN_DATA",
+                format!("Stored token data is invalid: {}", e),
+            );
+        }
+        Ok(None) => {
+            return error(
+                StatusCode::NOT_FOUND,
+                "TOKEN_NOT_FOUND",
+                format!("Token {} not found", request.mint),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch token: {}", e);
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "DATABASE_ERROR",
+                format!("Failed to fetch token: {}", e),
+            );
+        }
+    };
+
+    // 开仓时的评估结果作为持仓监控的基准
+    let risk = match state.engine.assess(&token).await {
+        Ok(risk) => risk,
+        Err(e) => {
+            tracing::error!("Failed to assess token {}: {}", token.mint, e);
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "ASSESSMENT_FAILED",
+                format!("Failed to assess token: {}", e),
+            );
+        }
+    };
+    if matches!(risk.recommendation, Recommendation::Avoid | Recommendation::StrongAvoid) {
+        return error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "RISK_TOO_HIGH",
+            format!("Risk assessment recommends {:?} (score {:.1})", risk.recommendation, risk.total),
+        );
+    }
+
+    let balance_before = trading.token_balance(&token.mint).await.unwrap_or(0);
+    let result = match trading.execute_buy(&token, request.amount_sol, None).await {
+        Ok(result) if result.success => result,
+        Ok(result) => {
+            return error(
+                StatusCode::BAD_GATEWAY,
+                "TRADE_FAILED",
+                format!("Buy transaction {} failed", result.signature),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Buy failed for {}: {}", token.mint, e);
+            return error(StatusCode::BAD_GATEWAY, "TRADE_FAILED", format!("Buy failed: {}", e));
+        }
+    };
+
+    // 买入后的余额差即成交数量；查询失败时按价格估算
+    let amount_tokens = match trading.token_balance(&token.mint).await {
+        Ok(balance) => balance.saturating_sub(balance_before),
+        Err(e) => {
+            tracing::warn!("Token balance unavailable after buy of {}, estimating from price: {}", token.mint, e);
+            estimate_tokens(&state, &token, request.amount_sol)
+        }
+    };
+    let signature = result.signature.to_string();
+
+    let recorded = sqlx::query_as::<_, (String, i64)>(
+        "WITH trade AS (
+            INSERT INTO trades (mint, side, strategy, amount_sol, amount_tokens, price_usd, status, tx_signature, executed_at)
+            VALUES ($1, 'buy', 'manual', $2, $3, $4, 'executed', $5, NOW())
+            RETURNING id
+        )
+        INSERT INTO positions (mint, trade_id, entry_price, amount_sol, amount_tokens)
+        SELECT $1, trade.id, $4, $2, $3 FROM trade
+        ON CONFLICT (mint) DO UPDATE SET
+            amount_sol = positions.amount_sol + EXCLUDED.amount_sol,
+            amount_tokens = positions.amount_tokens + EXCLUDED.amount_tokens
+        RETURNING id::TEXT, amount_tokens::BIGINT"
+    )
+    .bind(&request.mint)
+    .bind(request.amount_sol)
+    .bind(amount_tokens as i64)
+    .bind(token.price_usd)
+    .bind(&signature)
+    .fetch_one(&state.db)
+    .await;
+
+    let (position_id, held_tokens) = match recorded {
+        Ok((id, held)) => (Some(id), held.max(0) as u64),
+        Err(e) => {
+            // 已成交，记录失败也要继续监控
+            tracing::error!("Buy {} executed but position was not recorded: {}", signature, e);
+            (None, amount_tokens)
+        }
+    };
+
+    if state.monitor.latest(&token.mint).is_some() {
+        state.monitor.update_amount(&token.mint, held_tokens);
+    } else {
+        state.watch_position(token.clone(), held_tokens, &risk);
+    }
+
+    let event = Event::TradeExecuted {
+        signature: signature.clone(),
+        token: token.mint,
+        amount: request.amount_sol,
+        success: true,
+        timestamp: chrono::Utc::now(),
+    };
+    if let Ok(message) = serde_json::to_string(&serde_json::json!({"type": "trade_executed", "data": event})) {
+        state.broadcast_message(message).await;
+    }
+
+    let response = serde_json::json!({
+        "position_id": position_id,
+        "signature": signature,
+        "amount_tokens": amount_tokens,
+        "risk_score": risk.total,
+    });
+    (StatusCode::OK, Json(ApiResponse::success(response)))
+}
+
+/// 按SOL/USD参考价和代币价格估算买入数量（原始单位）
+fn estimate_tokens(state: &AppState, token: &TokenInfo, amount_sol: f64) -> u64 {
+    let Some(quote) = state.sol_price.cached() else {
+        return 0;
+    };
+    if token.price_usd <= 0.0 {
+        return 0;
+    }
+    (amount_sol * quote.price / token.price_usd * 10f64.powi(token.decimals as i32)) as u64
+}
@@ -4,6 +4,8 @@ use axum::{
     Json,
 };
 
+use solsniper_risk_analyzer::scoring::ScoringConfig;
+
 use crate::{
     models::{RiskLimits, UpdateRiskLimitsRequest, ApiResponse},
     state::AppState,
@@ -160,3 +162,57 @@ pub async fn get_risk_status(
         }
     }
 }
+
+pub async fn get_scoring(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<ApiResponse<ScoringConfig>>) {
+    let scoring = state.scoring.read().unwrap().clone();
+    (StatusCode::OK, Json(ApiResponse::success(scoring)))
+}
+
+pub async fn update_scoring(
+    State(state): State<AppState>,
+    Json(payload): Json<ScoringConfig>,
+) -> (StatusCode, Json<ApiResponse<ScoringConfig>>) {
+    if let Err(e) = payload.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("INVALID_SCORING_CONFIG".to_string(), e.to_string())),
+        );
+    }
+
+    let config = match serde_json::to_value(&payload) {
+        Ok(config) => config,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error("SERIALIZATION_ERROR".to_string(), e.to_string())),
+            );
+        }
+    };
+
+    // 保存到数据库
+    let result = sqlx::query(
+        "INSERT INTO risk_scoring_config (config, created_at) VALUES ($1, NOW())"
+    )
+    .bind(config)
+    .execute(&state.db)
+    .await;
+
+    match result {
+        Ok(_) => {
+            *state.scoring.write().unwrap() = payload.clone();
+            (StatusCode::OK, Json(ApiResponse::success(payload)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to update scoring config: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(
+                    "DATABASE_ERROR".to_string(),
+                    format!("Failed to update scoring config: {}", e),
+                )),
+            )
+        }
+    }
+}
@@ -1,8 +1,14 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use solsniper_core::{Candle, CandleInterval, TokenInfo};
+use solsniper_risk_analyzer::score_history::ScoreHistoryEntry;
+use solsniper_risk_analyzer::ExplainedRiskScore;
+use std::str::FromStr;
 
 use crate::{
     models::{Token, ApiResponse},
@@ -65,3 +71,186 @@ pub async fn get_token(
         }
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct CandlesQuery {
+    pub interval: Option<String>,
+    pub limit: Option<usize>,
+}
+
+pub async fn get_token_candles(
+    State(state): State<AppState>,
+    Path(mint): Path<String>,
+    Query(params): Query<CandlesQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<Candle>>>) {
+    let mint = match Pubkey::from_str(&mint) {
+        Ok(mint) => mint,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "INVALID_MINT".to_string(),
+                    format!("Invalid mint address: {}", mint),
+                )),
+            )
+        }
+    };
+
+    let label = params.interval.unwrap_or_else(|| "1m".to_string());
+    let Some(interval) = CandleInterval::parse(&label) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "INVALID_INTERVAL".to_string(),
+                format!("Unsupported interval: {} (expected 1s, 1m, 5m or 1h)", label),
+            )),
+        );
+    };
+
+    let limit = params.limit.unwrap_or(100).min(1000);
+    let candles = state.candles.candles(&mint, interval, limit);
+
+    (StatusCode::OK, Json(ApiResponse::success(candles)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RiskHistoryQuery {
+    pub limit: Option<usize>,
+}
+
+pub async fn get_token_risk_history(
+    State(state): State<AppState>,
+    Path(mint): Path<String>,
+    Query(params): Query<RiskHistoryQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<ScoreHistoryEntry>>>) {
+    let mint = match Pubkey::from_str(&mint) {
+        Ok(mint) => mint,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::error(
+                    "INVALID_MINT".to_string(),
+                    format!("Invalid mint address: {}", mint),
+                )),
+            )
+        }
+    };
+
+    let limit = params.limit.unwrap_or(100).min(1000);
+
+    // 读取引擎记录的历史（与评估写入同一存储）
+    match state.engine.score_history(&mint, limit).await {
+        Ok(entries) => (StatusCode::OK, Json(ApiResponse::success(entries))),
+        Err(e) => {
+            tracing::error!("Failed to fetch risk history: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(
+                    "DATABASE_ERROR".to_string(),
+                    format!("Failed to fetch risk history: {}", e),
+                )),
+            )
+        }
+    }
+}
+
+/// 重新评估代币并返回各分项的权重和贡献
+pub async fn explain_token_risk(
+    State(state): State<AppState>,
+    Path(mint): Path<String>,
+) -> (StatusCode, Json<ApiResponse<ExplainedRiskScore>>) {
+    let result = sqlx::query_scalar::<_, serde_json::Value>(
+        "SELECT data FROM tokens WHERE mint = $1"
+    )
+    .bind(&mint)
+    .fetch_optional(&state.db)
+    .await;
+
+    let data = match result {
+        Ok(Some(data)) => data,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error(
+                    "TOKEN_NOT_FOUND".to_string(),
+                    format!("Token {} not found", mint),
+                )),
+            )
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch token: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(
+                    "DATABASE_ERROR".to_string(),
+                    format!("Failed to fetch token: {}", e),
+                )),
+            );
+        }
+    };
+
+    let token: TokenInfo = match serde_json::from_value(data) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("Stored token data for {} is invalid: {}", mint, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(
+                    "INVALID_TOKEN_DATA".to_string(),
+                    format!("Stored token data is invalid: {}", e),
+                )),
+            );
+        }
+    };
+
+    match state.engine.assess_explained(&token).await {
+        Ok(explained) => (StatusCode::OK, Json(ApiResponse::success(explained))),
+        Err(e) => {
+            tracing::error!("Failed to assess token {}: {}", mint, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(
+                    "ASSESSMENT_FAILED".to_string(),
+                    format!("Failed to assess token: {}", e),
+                )),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    #[tokio::test]
+    #[ignore = "需要运行的PostgreSQL和Redis实例（DATABASE_URL、REDIS_URL）"]
+    async fn test_assessment_appears_in_risk_history() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let token = TokenInfo {
+            mint: Pubkey::new_unique(),
+            symbol: "HIST".to_string(),
+            name: "History Token".to_string(),
+            decimals: 6,
+            liquidity_sol: 50.0,
+            holders_count: 300,
+            created_at: chrono::Utc::now(),
+            ..Default::default()
+        };
+
+        let score = state.engine.assess(&token).await.unwrap();
+
+        let (status, Json(response)) = get_token_risk_history(
+            State(state),
+            Path(token.mint.to_string()),
+            Query(RiskHistoryQuery { limit: Some(10) }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let entries = response.data.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].mint, token.mint);
+        assert!((entries[0].score.total - score.total).abs() < 1e-6);
+    }
+}
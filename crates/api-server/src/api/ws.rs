@@ -75,9 +75,8 @@ async fn websocket_connection(stream: WebSocket, state: AppState) {
     // 移除客户端
     {
         let mut clients = state.ws_clients.write().await;
-        // 从列表中移除断开的客户端
-        // 注意: 我们无法直接判断 UnboundedSender 是否关闭
-        // 实际应用中应该维护一个连接ID映射
+        // 发送任务结束后接收端已释放，对应的发送端即为关闭状态
+        clients.retain(|client| !client.is_closed());
         tracing::info!("WebSocket 客户端断开");
     }
 }
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
+use solsniper_risk_analyzer::position_monitor::AutoExitPolicy;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AppConfig {
@@ -7,6 +8,25 @@ pub struct AppConfig {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub jwt: JwtConfig,
+    /// 风险评分配置文件（读取其中的`[risk.scoring]`）
+    pub scoring_config_path: String,
+    /// Solana RPC端点（风险评估的链上数据）
+    pub rpc_urls: Vec<String>,
+    /// Solana WebSocket端点（实时交易流）
+    pub ws_url: String,
+    /// 实时交易流每秒最多获取的交易数（0表示不启动交易流）
+    pub swap_stream_max_fetches_per_second: u32,
+    /// 社交信号服务（未配置时使用API数据中的社交字段）
+    pub social_api_url: Option<String>,
+    pub social_api_key: Option<String>,
+    /// 卖出模拟使用的钱包地址（需持有少量SOL，未配置时不做模拟）
+    pub honeypot_wallet: Option<String>,
+    /// 自定义行为模式目录（`.toml`/`.json`，覆盖同名内置模式，文件变化时自动重新加载）
+    pub patterns_dir: Option<String>,
+    /// 交易钱包keypair文件（开仓买入和紧急清仓，未配置时不执行交易）
+    pub trading_keypair_path: Option<String>,
+    /// 持仓监控的自动清仓策略
+    pub auto_exit: AutoExitPolicy,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -59,8 +79,44 @@ impl AppConfig {
                     .unwrap_or_else(|_| "your_secret_key_here".to_string()),
                 expiration: 86400, // 24 hours
             },
+            scoring_config_path: std::env::var("SCORING_CONFIG_PATH")
+                .unwrap_or_else(|_| "config.toml".to_string()),
+            rpc_urls: std::env::var("RPC_URLS")
+                .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string())
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect(),
+            ws_url: std::env::var("SOLANA_WS_URL")
+                .unwrap_or_else(|_| "wss://api.mainnet-beta.solana.com".to_string()),
+            swap_stream_max_fetches_per_second: std::env::var("SWAP_STREAM_MAX_FETCH_PER_SEC")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(20),
+            social_api_url: std::env::var("SOCIAL_API_URL").ok(),
+            social_api_key: std::env::var("SOCIAL_API_KEY").ok(),
+            honeypot_wallet: std::env::var("HONEYPOT_WALLET").ok(),
+            patterns_dir: std::env::var("PATTERNS_DIR").ok(),
+            trading_keypair_path: std::env::var("TRADING_KEYPAIR_PATH").ok(),
+            auto_exit: Self::load_auto_exit(),
         };
 
         Ok(config)
     }
+
+    /// 自动清仓策略（AUTO_EXIT_*，未设置的项使用默认值）
+    fn load_auto_exit() -> AutoExitPolicy {
+        fn parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|value| value.parse().ok())
+        }
+        let defaults = AutoExitPolicy::default();
+
+        AutoExitPolicy {
+            enabled: parse("AUTO_EXIT_ENABLED").unwrap_or(defaults.enabled),
+            exit_below_score: parse("AUTO_EXIT_BELOW_SCORE").or(defaults.exit_below_score),
+            max_slippage_bps: parse("AUTO_EXIT_MAX_SLIPPAGE_BPS").unwrap_or(defaults.max_slippage_bps),
+            use_jito: parse("AUTO_EXIT_USE_JITO").unwrap_or(defaults.use_jito),
+            ..defaults
+        }
+    }
 }
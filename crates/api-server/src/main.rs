@@ -52,6 +52,9 @@ async fn main() -> Result<()> {
         // Token routes
         .route("/api/v1/tokens", get(tokens::list_tokens))
         .route("/api/v1/tokens/:mint", get(tokens::get_token))
+        .route("/api/v1/tokens/:mint/candles", get(tokens::get_token_candles))
+        .route("/api/v1/tokens/:mint/risk-history", get(tokens::get_token_risk_history))
+        .route("/api/v1/tokens/:mint/risk-explain", get(tokens::explain_token_risk))
 
         // Strategy routes
         .route("/api/v1/strategies", get(strategies::list_strategies))
@@ -62,6 +65,7 @@ async fn main() -> Result<()> {
 
         // Position routes
         .route("/api/v1/positions", get(positions::list_positions))
+        .route("/api/v1/positions", post(positions::open_position))
         .route("/api/v1/positions/:id", get(positions::get_position))
         .route("/api/v1/positions/:id/close", post(positions::close_position))
 
@@ -78,6 +82,8 @@ async fn main() -> Result<()> {
         .route("/api/v1/risk/limits", get(risk::get_limits))
         .route("/api/v1/risk/limits", put(risk::update_limits))
         .route("/api/v1/risk/status", get(risk::get_risk_status))
+        .route("/api/v1/risk/scoring", get(risk::get_scoring))
+        .route("/api/v1/risk/scoring", put(risk::update_scoring))
 
         // WebSocket
         .route("/ws", get(ws::websocket_handler))
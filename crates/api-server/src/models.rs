@@ -1,7 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use uuid::Uuid;
 
 // ==================== Request/Response Models ====================
 
@@ -188,6 +187,12 @@ pub struct RiskLimits {
     pub block_extreme_risk: bool,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct OpenPositionRequest {
+    pub mint: String,
+    pub amount_sol: f64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateRiskLimitsRequest {
     pub max_position_size_sol: Option<f64>,
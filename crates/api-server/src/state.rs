@@ -1,17 +1,84 @@
 use anyhow::Result;
 use sqlx::{PgPool, postgres::PgPoolOptions};
 use redis::aio::ConnectionManager;
+use solsniper_behavior_pattern::{
+    record_outcome, InMemoryTradeStore, Outcome, PatternSet, PostgresSnapshotStore, SharedWalletLinks,
+};
+use solsniper_core::{
+    AlertType, CandleBuilder, Event, LiquidityTracker, LoadBalancingStrategy, PoolReservePriceSource, PythPriceSource, RiskScore,
+    RpcManager, SolPriceService, TokenInfo, TradeSide,
+};
+use solsniper_data_collector::{
+    BackfillSink, ClmmPoolDecoder, HolderSnapshotFetcher, HttpMetadataFetcher, LpVerifier, MetadataEnricher, OnChainEnricher, ParsedTransaction, PostgresBackfillSink, SwapStream,
+};
+use solsniper_risk_analyzer::deployer_analyzer::{DeployerAnalyzer, PostgresDeployerHistory};
+use solsniper_risk_analyzer::holder_clusters::{HolderClusterDetector, RpcHolderGraph};
+use solsniper_risk_analyzer::honeypot::HoneypotSimulator;
+use solsniper_risk_analyzer::position_monitor::{MonitorUpdate, PositionMonitor};
+use solsniper_risk_analyzer::score_history::PostgresScoreHistory;
+use solsniper_risk_analyzer::scoring::ScoringConfig;
+use solsniper_risk_analyzer::similarity_analyzer::{HttpImageHasher, SimilarityAnalyzer};
+use solsniper_risk_analyzer::social_signals::{CachedSocialProvider, HttpSocialProvider};
+use solsniper_risk_analyzer::RiskAssessmentEngine;
+use solsniper_trading_engine::{RaydiumSwapProvider, TradingEngine, WalletManager};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 
 use crate::config::AppConfig;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub config: AppConfig,
     pub db: PgPool,
     pub redis: ConnectionManager,
     pub ws_clients: Arc<RwLock<Vec<tokio::sync::mpsc::UnboundedSender<String>>>>,
+    pub candles: Arc<CandleBuilder>,
+    /// 风险评分配置（与进程内的RiskAssessmentEngine共享）
+    pub scoring: Arc<std::sync::RwLock<ScoringConfig>>,
+    pub rpc: Arc<RpcManager>,
+    /// SOL/USD参考价格（后台定时刷新）
+    pub sol_price: Arc<SolPriceService>,
+    /// 风险评估引擎
+    pub engine: Arc<RiskAssessmentEngine>,
+    /// 交易执行（配置了交易钱包时）
+    pub trading: Option<Arc<TradingEngine>>,
+    /// 持仓风险监控
+    pub monitor: Arc<PositionMonitor>,
+}
+
+/// 交易流到持仓监控的输入
+struct PositionFeed {
+    monitor: Arc<PositionMonitor>,
+    updates: mpsc::UnboundedSender<MonitorUpdate>,
+}
+
+impl PositionFeed {
+    /// 持仓代币的撤池/加池刷新快照，卖出交给监控识别部署者抛售
+    fn observe(&self, parsed: &ParsedTransaction, liquidity: &LiquidityTracker) {
+        if let Some(event) = &parsed.liquidity {
+            self.refresh(&event.mint, liquidity);
+        }
+
+        if let Some(swap) = &parsed.swap {
+            if swap.trade.side == TradeSide::Sell && self.monitor.latest(&swap.trade.mint).is_some() {
+                let _ = self.updates.send(MonitorUpdate::Sell {
+                    mint: swap.trade.mint,
+                    seller: swap.trade.trader,
+                    token_amount: swap.token_amount_raw,
+                    signature: parsed.signature.clone(),
+                });
+            }
+        }
+    }
+
+    /// 用最新流动性刷新持仓快照（到期时监控会完整重新评估）
+    fn refresh(&self, mint: &Pubkey, liquidity: &LiquidityTracker) {
+        if let Some(mut token) = self.monitor.latest(mint) {
+            liquidity.apply_to(&mut token);
+            let _ = self.updates.send(MonitorUpdate::Token(Box::new(token)));
+        }
+    }
 }
 
 impl AppState {
@@ -30,12 +97,395 @@ impl AppState {
 
         tracing::info!("✅ Redis 连接成功");
 
-        Ok(Self {
-            config,
+        let scoring = Arc::new(std::sync::RwLock::new(Self::load_scoring_config(&config, &db).await));
+
+        let rpc = Arc::new(RpcManager::new(
+            config.rpc_urls.clone(),
+            30,
+            LoadBalancingStrategy::RoundRobin,
+        ));
+
+        let sol_price = Arc::new(SolPriceService::new(vec![
+            Arc::new(PythPriceSource::sol_usd(Arc::clone(&rpc))),
+            Arc::new(PoolReservePriceSource::raydium_sol_usdc(Arc::clone(&rpc))),
+        ]));
+        Arc::clone(&sol_price).start_refresh_task(std::time::Duration::from_secs(30));
+
+        let candles = Arc::new(CandleBuilder::new());
+        let trades = Arc::new(InMemoryTradeStore::default());
+        let liquidity = Arc::new(LiquidityTracker::new());
+
+        let engine = Arc::new(Self::build_engine(
+            &config, &db, &rpc, &sol_price, &candles, &trades, &liquidity, &scoring,
+        ));
+
+        tracing::info!("✅ 风险评估引擎初始化成功");
+
+        let trading = Self::build_trading_engine(&config, &rpc, &sol_price).await;
+
+        // 持仓监控：交易流中的撤池和卖出驱动重新评估，告警推送给WebSocket客户端
+        let mut monitor = PositionMonitor::new(Arc::clone(&engine)).with_liquidity_tracker(Arc::clone(&liquidity));
+        match &trading {
+            Some(trading) => monitor = monitor.with_auto_exit(Arc::clone(trading), config.auto_exit.clone()),
+            None if config.auto_exit.enabled => {
+                tracing::warn!("⚠️ 未配置 TRADING_KEYPAIR_PATH，自动清仓不生效");
+            }
+            None => {}
+        }
+        let monitor_events = monitor.take_event_receiver();
+        let monitor = Arc::new(monitor);
+        let (monitor_updates, updates) = mpsc::unbounded_channel();
+        Arc::clone(&monitor).spawn(updates);
+
+        if config.swap_stream_max_fetches_per_second > 0 {
+            let swaps = Arc::new(
+                SwapStream::new(Arc::clone(&rpc), config.ws_url.clone())
+                    .with_rate_limit(config.swap_stream_max_fetches_per_second),
+            );
+            Self::spawn_swap_consumer(
+                &swaps,
+                Arc::clone(&candles),
+                Arc::clone(&trades),
+                Arc::clone(&liquidity),
+                Arc::new(PostgresBackfillSink::new(db.clone())),
+                PositionFeed {
+                    monitor: Arc::clone(&monitor),
+                    updates: monitor_updates,
+                },
+            );
+            Arc::clone(&swaps).start();
+
+            tracing::info!("✅ 实时交易流已启动（每秒最多获取{}笔交易）", config.swap_stream_max_fetches_per_second);
+        } else {
+            tracing::warn!("⚠️ SWAP_STREAM_MAX_FETCH_PER_SEC=0，实时交易流未启动，K线和持仓监控只使用API数据");
+        }
+
+        let state = Self {
             db,
             redis,
             ws_clients: Arc::new(RwLock::new(Vec::new())),
-        })
+            candles,
+            scoring,
+            rpc,
+            sol_price,
+            engine,
+            trading,
+            monitor,
+        };
+
+        if let Some(events) = monitor_events {
+            state.spawn_event_forwarder(events);
+        }
+        state.spawn_restore_positions();
+
+        tracing::info!("✅ 持仓监控已启动");
+
+        Ok(state)
+    }
+
+    /// 组装风险评估引擎
+    #[allow(clippy::too_many_arguments)]
+    fn build_engine(
+        config: &AppConfig,
+        db: &PgPool,
+        rpc: &Arc<RpcManager>,
+        sol_price: &Arc<SolPriceService>,
+        candles: &Arc<CandleBuilder>,
+        trades: &Arc<InMemoryTradeStore>,
+        liquidity: &Arc<LiquidityTracker>,
+        scoring: &Arc<std::sync::RwLock<ScoringConfig>>,
+    ) -> RiskAssessmentEngine {
+        // 关联簇检测写入钱包关联表，交易图据此识别关联钱包之间的自成交；
+        // 同一RPC关系图也为协同买入检测提供钱包画像
+        let holder_graph = Arc::new(RpcHolderGraph::new(Arc::clone(rpc)));
+        let wallet_links = Arc::new(SharedWalletLinks::default());
+        let cluster_detector =
+            HolderClusterDetector::new(holder_graph.clone()).with_wallet_links(Arc::clone(&wallet_links));
+
+        // 仿盘比对参照集：启动时从已标记rug的代币恢复，之后由评估过程持续补充
+        let similarity = Arc::new(SimilarityAnalyzer::new().with_image_hasher(Arc::new(HttpImageHasher::new())));
+        {
+            let similarity = Arc::clone(&similarity);
+            let db = db.clone();
+            tokio::spawn(async move {
+                match similarity.load_rugged(&db).await {
+                    Ok(count) => tracing::info!("✅ 已恢复 {} 个rug参照代币", count),
+                    Err(e) => tracing::warn!("⚠️ rug参照代币恢复失败: {}", e),
+                }
+            });
+        }
+
+        // 与API共享评分配置，PUT /api/v1/risk/scoring 后立即生效
+        let mut engine = RiskAssessmentEngine::new()
+            .with_scoring_handle(Arc::clone(scoring))
+            .with_score_history(Arc::new(PostgresScoreHistory::new(db.clone())))
+            .with_snapshot_store(Arc::new(PostgresSnapshotStore::new(db.clone())))
+            .with_onchain_enricher(Arc::new(
+                OnChainEnricher::new(Arc::clone(rpc))
+                    .with_holder_fetcher(HolderSnapshotFetcher::new(Arc::clone(rpc)))
+                    .with_lp_verifier(LpVerifier::new(Arc::clone(rpc)))
+                    .with_metadata_enricher(MetadataEnricher::new(
+                        Arc::clone(rpc),
+                        Arc::new(HttpMetadataFetcher::new()),
+                    ))
+                    .with_clmm_decoder(ClmmPoolDecoder::new(Arc::clone(rpc))),
+            ))
+            .with_sol_price_service(Arc::clone(sol_price))
+            .with_candles(Arc::clone(candles))
+            .with_trade_source(trades.clone())
+            .with_liquidity_tracker(Arc::clone(liquidity))
+            .with_wallet_links(wallet_links)
+            .with_wallet_profiles(holder_graph)
+            .with_holder_cluster_detector(Arc::new(cluster_detector))
+            .with_deployer_analyzer(
+                DeployerAnalyzer::new()
+                    .with_source(Arc::new(
+                        PostgresDeployerHistory::new(db.clone()).with_rpc(Arc::clone(rpc)),
+                    ))
+                    .with_rpc(Arc::clone(rpc)),
+            )
+            .with_similarity_analyzer(similarity);
+
+        if let Some(url) = &config.social_api_url {
+            let mut provider = HttpSocialProvider::new(url.clone());
+            if let Some(api_key) = &config.social_api_key {
+                provider = provider.with_api_key(api_key.clone());
+            }
+            engine = engine.with_social_provider(Arc::new(CachedSocialProvider::new(Arc::new(provider))));
+        }
+
+        if let Some(dir) = &config.patterns_dir {
+            match PatternSet::from_dir(dir) {
+                Ok(patterns) => {
+                    tracing::info!("✅ 已加载 {} 个行为模式（{}）", patterns.current().len(), dir);
+                    patterns.spawn_hot_reload(std::time::Duration::from_secs(30));
+                    engine = engine.with_patterns(patterns);
+                }
+                Err(e) => tracing::warn!("⚠️ 行为模式目录加载失败，使用内置模式: {}", e),
+            }
+        }
+
+        if let Some(wallet) = &config.honeypot_wallet {
+            match Pubkey::from_str(wallet) {
+                Ok(wallet) => {
+                    // 最小输出不设限，税费体现为到账缺口而不是滑点失败
+                    let provider = RaydiumSwapProvider::new(Arc::clone(rpc)).with_slippage_bps(10_000);
+                    let simulator = HoneypotSimulator::new(Arc::clone(rpc), Arc::new(provider), wallet);
+                    engine = engine.with_honeypot_simulator(Arc::new(simulator));
+                }
+                Err(e) => tracing::warn!("⚠️ HONEYPOT_WALLET 无效，跳过卖出模拟: {}", e),
+            }
+        }
+
+        engine
+    }
+
+    /// 交易引擎（需要交易钱包）
+    async fn build_trading_engine(
+        config: &AppConfig,
+        rpc: &Arc<RpcManager>,
+        sol_price: &Arc<SolPriceService>,
+    ) -> Option<Arc<TradingEngine>> {
+        let path = config.trading_keypair_path.as_ref()?;
+
+        let client = match rpc.get_client().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!("⚠️ 无可用RPC端点，交易功能不可用: {}", e);
+                return None;
+            }
+        };
+
+        match WalletManager::from_keypair_file(path, Arc::clone(&client)) {
+            Ok(wallet) => {
+                tracing::info!("✅ 交易钱包已加载: {}", wallet.get_primary_pubkey());
+                let engine = TradingEngine::new(Arc::new(wallet), client).with_sol_price_service(Arc::clone(sol_price));
+                Some(Arc::new(engine))
+            }
+            Err(e) => {
+                tracing::warn!("⚠️ 交易钱包加载失败，交易功能不可用: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 消费实时交易流：swap聚合为K线、写入内存成交记录（交易图），并批量落库到`market_trades`；
+    /// 加池/撤池写入流动性历史（流动性分析和撤池模式检测），持仓代币的事件转给持仓监控
+    fn spawn_swap_consumer(
+        swaps: &SwapStream,
+        candles: Arc<CandleBuilder>,
+        trades: Arc<InMemoryTradeStore>,
+        liquidity: Arc<LiquidityTracker>,
+        sink: Arc<dyn BackfillSink>,
+        positions: PositionFeed,
+    ) {
+        const FLUSH_BATCH: usize = 200;
+        let mut receiver = swaps.subscribe();
+
+        tokio::spawn(async move {
+            let mut cleanup = tokio::time::interval(std::time::Duration::from_secs(600));
+            let mut flush = tokio::time::interval(std::time::Duration::from_secs(5));
+            let mut reassess = tokio::time::interval(std::time::Duration::from_secs(30));
+            let mut pending: Vec<ParsedTransaction> = Vec::new();
+
+            loop {
+                tokio::select! {
+                    received = receiver.recv() => match received {
+                        Ok(parsed) => {
+                            if let Some(event) = &parsed.liquidity {
+                                liquidity.record(event);
+                            }
+                            positions.observe(&parsed, &liquidity);
+                            if let Some(swap) = &parsed.swap {
+                                candles.ingest(&swap.trade);
+                                trades.record(swap.trade.clone());
+                                pending.push(parsed);
+                                if pending.len() >= FLUSH_BATCH {
+                                    Self::flush_trades(&sink, std::mem::take(&mut pending));
+                                }
+                            }
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("⚠️ 交易流消费过慢，丢弃 {} 笔交易", skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = flush.tick() => {
+                        if !pending.is_empty() {
+                            Self::flush_trades(&sink, std::mem::take(&mut pending));
+                        }
+                    }
+                    _ = reassess.tick() => {
+                        // 没有链上事件的持仓也定期重新评估
+                        for mint in positions.monitor.watched() {
+                            positions.refresh(&mint, &liquidity);
+                        }
+                    }
+                    _ = cleanup.tick() => {
+                        let evicted = candles.evict_idle(chrono::Duration::hours(24));
+                        if evicted > 0 {
+                            tracing::info!("🧹 清理 {} 个24小时无成交的K线序列", evicted);
+                        }
+                    }
+                }
+            }
+
+            if !pending.is_empty() {
+                Self::flush_trades(&sink, pending);
+            }
+        });
+    }
+
+    /// 后台写入一批实时成交（按签名去重，与历史回填共用）
+    fn flush_trades(sink: &Arc<dyn BackfillSink>, batch: Vec<ParsedTransaction>) {
+        let sink = Arc::clone(sink);
+        tokio::spawn(async move {
+            if let Err(e) = sink.write(&batch).await {
+                tracing::warn!("⚠️ 实时成交写入失败（{} 笔）: {}", batch.len(), e);
+            }
+        });
+    }
+
+    /// 加载评分配置：数据库中最近一次通过API保存的配置优先，其次为TOML文件
+    async fn load_scoring_config(config: &AppConfig, db: &PgPool) -> ScoringConfig {
+        let saved = sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT config FROM risk_scoring_config ORDER BY id DESC LIMIT 1"
+        )
+        .fetch_optional(db)
+        .await;
+
+        match saved {
+            Ok(Some(value)) => match serde_json::from_value::<ScoringConfig>(value) {
+                Ok(scoring) if scoring.validate().is_ok() => return scoring,
+                _ => tracing::warn!("⚠️ 数据库中的评分配置无效，使用配置文件"),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::warn!("⚠️ 读取评分配置失败: {}", e),
+        }
+
+        match ScoringConfig::from_file(&config.scoring_config_path) {
+            Ok(scoring) => scoring,
+            Err(e) => {
+                tracing::warn!("⚠️ {}，使用默认评分配置", e);
+                ScoringConfig::default()
+            }
+        }
+    }
+
+    /// 开始监控新持仓，基准风险分为开仓时的评估结果
+    pub fn watch_position(&self, token: TokenInfo, amount_tokens: u64, baseline: &RiskScore) {
+        self.monitor.watch(token, amount_tokens, baseline);
+    }
+
+    /// 持仓监控的告警和紧急清仓结果推送给WebSocket客户端
+    fn spawn_event_forwarder(&self, mut events: mpsc::UnboundedReceiver<Event>) {
+        let state = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                // 持仓监控确认的rug写入结果标注，供模式评估和仿盘参照使用
+                if let Event::RiskAlert {
+                    token,
+                    alert_type: AlertType::RugPullDetected,
+                    message,
+                    timestamp,
+                } = &event
+                {
+                    if let Err(e) = record_outcome(&state.db, token, Outcome::Rugged, *timestamp, message).await {
+                        tracing::warn!("⚠️ rug结果标注写入失败 {}: {}", token, e);
+                    }
+                }
+
+                let kind = match &event {
+                    Event::RiskAlert { .. } => "risk_alert",
+                    Event::TradeExecuted { .. } => "trade_executed",
+                    _ => "event",
+                };
+                let message = serde_json::json!({
+                    "type": kind,
+                    "data": event,
+                });
+                match serde_json::to_string(&message) {
+                    Ok(message) => state.broadcast_message(message).await,
+                    Err(e) => tracing::warn!("⚠️ 持仓事件序列化失败: {}", e),
+                }
+            }
+        });
+    }
+
+    /// 重启后恢复对已有持仓的监控（以当前评估结果为基准）
+    fn spawn_restore_positions(&self) {
+        let state = self.clone();
+        tokio::spawn(async move {
+            let rows = sqlx::query_as::<_, (serde_json::Value, i64)>(
+                "SELECT t.data, p.amount_tokens::BIGINT FROM positions p JOIN tokens t ON t.mint = p.mint"
+            )
+            .fetch_all(&state.db)
+            .await;
+
+            let rows = match rows {
+                Ok(rows) => rows,
+                Err(e) => {
+                    tracing::warn!("⚠️ 读取持仓失败，未恢复持仓监控: {}", e);
+                    return;
+                }
+            };
+
+            for (data, amount_tokens) in rows {
+                let token: TokenInfo = match serde_json::from_value(data) {
+                    Ok(token) => token,
+                    Err(e) => {
+                        tracing::warn!("⚠️ 持仓代币数据无效: {}", e);
+                        continue;
+                    }
+                };
+                match state.engine.assess(&token).await {
+                    Ok(baseline) => state.watch_position(token, amount_tokens.max(0) as u64, &baseline),
+                    Err(e) => tracing::warn!("⚠️ 持仓 {} 评估失败，未恢复监控: {}", token.mint, e),
+                }
+            }
+
+            tracing::info!("✅ 已恢复 {} 个持仓的监控", state.monitor.watched().len());
+        });
     }
 
     pub async fn broadcast_message(&self, message: String) {
@@ -0,0 +1,605 @@
+use solsniper_core::{Result, SwapTrade, TradeSide};
+use crate::trade_graph::WalletLinks;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+/// 钱包画像
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WalletProfile {
+    /// 首个SOL资助地址
+    pub funder: Option<Pubkey>,
+    /// 钱包最早一笔交易的时间（历史过长无法确定时为None）
+    pub first_seen: Option<DateTime<Utc>>,
+}
+
+/// 钱包画像数据源trait
+#[async_trait]
+pub trait WalletProfileSource: Send + Sync {
+    async fn profile(&self, wallet: &Pubkey) -> Result<WalletProfile>;
+
+    /// 数据源名称
+    fn name(&self) -> &str;
+}
+
+/// 固定的画像表（回放记录的历史等），未记录的钱包视为没有画像信息
+#[async_trait]
+impl WalletProfileSource for HashMap<Pubkey, WalletProfile> {
+    async fn profile(&self, wallet: &Pubkey) -> Result<WalletProfile> {
+        Ok(self.get(wallet).copied().unwrap_or_default())
+    }
+
+    fn name(&self) -> &str {
+        "recorded"
+    }
+}
+
+/// 默认忽略的资助地址：主流交易所的Solana热钱包
+///
+/// 从交易所提币的钱包共享同一个资助地址，不能据此判定关联
+pub fn default_ignored_funders() -> Vec<Pubkey> {
+    [
+        // Binance
+        "5tzFkiKscXHK5ZXCGbXZxdw7gTjjD1mBwuoFbhUvuAi9",
+        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+        "2ojv9BAiHUrvsm9gxDe7fJSzbNZSJcxZvf8dqmWGHG8S",
+        // Coinbase
+        "H8sMJSCQxfKiFTCfDR3DUMLPwcRbM61LGFJ8N4dK3WjS",
+        "GJRs4FwHtemZ5ZE9x3FNvJ8TMwitKTh21yxdRPqn7npE",
+        "2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSprPicm",
+        // OKX
+        "5VCwKtCXgCJ6kit5FybXjvriW3xELsFDhYrPSqtJNmcD",
+        "is6MTRHEgyFLNTfYcuV4QBWLjrZBfmhVNYR6ccgr8KV",
+        // Bybit
+        "AC5RDfQFmDS1deWZos921JfqscXdByf8BKHs5ACWjtW2",
+        // Kraken
+        "FWznbcNXWQuHTawe9RxvQ2LdCENssh12dsznf4RiouN5",
+        // KuCoin
+        "BmFdpraQhkiDQE6SnfG5omcA1VwzqfXrwtNYBwWTymy6",
+        // Gate.io
+        "u6PJ8DtQuPFnfmwHbGFULQ4u4EgjDiyYKjVEsynXq2w",
+        // MEXC
+        "ASTyfSima4LLAdDgoFGkgqoKowG1LZFDr9fAQrg7iaJZ",
+    ]
+    .iter()
+    .filter_map(|address| Pubkey::from_str(address).ok())
+    .collect()
+}
+
+/// 开盘时刻
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LaunchAnchor {
+    /// 建池或创建mint交易所在slot
+    Slot(u64),
+    /// 只知道代币创建时间（`TokenInfo::created_at`）
+    Time(DateTime<Utc>),
+}
+
+/// 协同买入检测参数
+#[derive(Debug, Clone)]
+pub struct CoordinatedBuyConfig {
+    /// 开盘窗口（创建slot后的slot数）
+    pub launch_slots: u64,
+
+    /// 只有创建时间时，首笔成交距创建不超过此时长才视为成交历史覆盖了开盘
+    pub launch_time_tolerance: Duration,
+
+    /// 开盘窗口内同一slot至少多少个钱包买入视为捆绑；窗口外只有同资金来源的钱包才算捆绑
+    pub min_bundle_size: usize,
+
+    /// 首次活动距买入不超过此时长的钱包视为新钱包
+    pub fresh_wallet_age: Duration,
+
+    /// 最多查询画像的钱包数
+    pub max_profiled_wallets: usize,
+
+    /// 不作为共同资金来源的地址（交易所热钱包等）
+    pub ignored_funders: HashSet<Pubkey>,
+}
+
+impl Default for CoordinatedBuyConfig {
+    fn default() -> Self {
+        Self {
+            launch_slots: 3,
+            launch_time_tolerance: Duration::seconds(10),
+            min_bundle_size: 3,
+            fresh_wallet_age: Duration::hours(72),
+            max_profiled_wallets: 50,
+            ignored_funders: default_ignored_funders().into_iter().collect(),
+        }
+    }
+}
+
+/// 同一slot的一组捆绑买入
+#[derive(Debug, Clone)]
+pub struct BuyGroup {
+    pub slot: u64,
+    pub wallets: Vec<Pubkey>,
+    /// 买入的代币数量（UI单位）
+    pub token_amount: f64,
+    /// 组内有交易支付了Jito小费
+    pub jito_bundle: bool,
+}
+
+/// 同一资金来源（或已知关联）的买入钱包
+#[derive(Debug, Clone)]
+pub struct FundingCluster {
+    pub funder: Pubkey,
+    pub wallets: Vec<Pubkey>,
+    pub token_amount: f64,
+}
+
+/// 协同买入检测结果
+#[derive(Debug, Clone, Default)]
+pub struct CoordinatedBuyReport {
+    /// 开盘窗口起始slot（成交历史没有覆盖开盘时为None，以下开盘指标同样为None）
+    pub launch_slot: Option<u64>,
+    /// 开盘窗口内买入的钱包
+    pub launch_buyers: Option<Vec<Pubkey>>,
+    /// 开盘窗口内买入的新钱包
+    pub fresh_launch_buyers: Option<Vec<Pubkey>>,
+    /// 同一slot的捆绑买入
+    pub bundles: Vec<BuyGroup>,
+    /// 同一资金来源的买入钱包
+    pub funding_clusters: Vec<FundingCluster>,
+
+    /// 开盘窗口内（狙击）买入的代币数量
+    pub sniper_token_amount: Option<f64>,
+    /// 捆绑或同资金来源钱包买入的代币数量（不重复计算）
+    pub bundle_token_amount: f64,
+    /// 狙击买入占总供应量比例（0-1）
+    pub sniper_supply_share: Option<f64>,
+    /// 捆绑买入占总供应量比例（0-1）
+    pub bundle_supply_share: Option<f64>,
+
+    /// 协同钱包的买入（按时间升序）
+    coordinated_buys: Vec<(DateTime<Utc>, Pubkey)>,
+}
+
+impl CoordinatedBuyReport {
+    /// 协同钱包数（捆绑、同资金来源、开盘新钱包）
+    pub fn coordinated_wallets(&self) -> usize {
+        self.coordinated_buys.iter().map(|(_, wallet)| *wallet).collect::<HashSet<_>>().len()
+    }
+
+    /// 任意`timeframe`窗口内买入的协同钱包数最大值
+    pub fn max_wallets_within(&self, timeframe: Duration) -> usize {
+        let mut window: VecDeque<(DateTime<Utc>, Pubkey)> = VecDeque::new();
+        let mut counts: HashMap<Pubkey, usize> = HashMap::new();
+        let mut max_wallets = 0;
+
+        for &(timestamp, wallet) in &self.coordinated_buys {
+            window.push_back((timestamp, wallet));
+            *counts.entry(wallet).or_default() += 1;
+
+            while let Some(&(oldest, oldest_wallet)) = window.front() {
+                if timestamp - oldest <= timeframe {
+                    break;
+                }
+                window.pop_front();
+                if let Some(count) = counts.get_mut(&oldest_wallet) {
+                    *count -= 1;
+                    if *count == 0 {
+                        counts.remove(&oldest_wallet);
+                    }
+                }
+            }
+
+            max_wallets = max_wallets.max(counts.len());
+        }
+
+        max_wallets
+    }
+}
+
+/// 开盘窗口起始slot
+///
+/// 以真实的创建slot（或创建时间）为准，而不是已有成交中最早的一笔：
+/// 最早成交落在开盘窗口之外时说明成交历史没有覆盖开盘，返回None。
+/// 开盘窗口内确实无人买入时与历史缺失无法区分，同样返回None
+pub fn launch_window_start(trades: &[SwapTrade], anchor: LaunchAnchor, config: &CoordinatedBuyConfig) -> Option<u64> {
+    let first = trades.iter().min_by_key(|trade| (trade.slot, trade.timestamp))?;
+
+    match anchor {
+        LaunchAnchor::Slot(slot) => (first.slot <= slot + config.launch_slots).then_some(slot),
+        LaunchAnchor::Time(created_at) => {
+            (first.timestamp <= created_at + config.launch_time_tolerance).then_some(first.slot)
+        }
+    }
+}
+
+/// 需要查询画像的钱包
+///
+/// 依次为开盘窗口内的买入者、同一slot多钱包买入的参与者，其余买入者按买入数量从大到小补足，
+/// 共同资金来源检测因此也覆盖开盘之后的大额买入。`launch_slot`见`launch_window_start`
+pub fn profile_candidates(
+    trades: &[SwapTrade],
+    launch_slot: Option<u64>,
+    config: &CoordinatedBuyConfig,
+) -> Vec<Pubkey> {
+    let in_launch = |slot: u64| launch_slot.is_some_and(|launch| slot <= launch + config.launch_slots);
+
+    let mut by_slot: BTreeMap<u64, HashSet<Pubkey>> = BTreeMap::new();
+    let mut bought: HashMap<Pubkey, f64> = HashMap::new();
+    for trade in trades.iter().filter(|trade| trade.side == TradeSide::Buy) {
+        by_slot.entry(trade.slot).or_default().insert(trade.trader);
+        *bought.entry(trade.trader).or_default() += trade.token_amount;
+    }
+
+    let mut candidates = Vec::new();
+    let mut seen = HashSet::new();
+    for (slot, wallets) in &by_slot {
+        if in_launch(*slot) || wallets.len() >= 2 {
+            let mut wallets: Vec<Pubkey> = wallets.iter().copied().collect();
+            wallets.sort();
+            candidates.extend(wallets.into_iter().filter(|wallet| seen.insert(*wallet)));
+        }
+    }
+
+    let mut rest: Vec<(Pubkey, f64)> = bought.into_iter().filter(|(wallet, _)| !seen.contains(wallet)).collect();
+    rest.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    candidates.extend(rest.into_iter().map(|(wallet, _)| wallet));
+
+    candidates.truncate(config.max_profiled_wallets);
+    candidates
+}
+
+/// 按slot/捆绑和资金来源分析买入
+///
+/// - `launch_slot`: 开盘窗口起始slot（`launch_window_start`），None时不计算开盘指标
+/// - `profiles`: 已查询的钱包画像（未查询的钱包不参与新钱包和资金来源判断）
+/// - `links`: 已知的钱包关联关系，与资金来源（已排除`ignored_funders`）一起用于合并买入钱包
+/// - `total_supply`: 总供应量（UI单位），用于计算占比
+pub fn analyze_coordinated_buys(
+    trades: &[SwapTrade],
+    launch_slot: Option<u64>,
+    profiles: &HashMap<Pubkey, WalletProfile>,
+    links: Option<&dyn WalletLinks>,
+    total_supply: f64,
+    config: &CoordinatedBuyConfig,
+) -> CoordinatedBuyReport {
+    let mut buys: Vec<&SwapTrade> = trades.iter().filter(|trade| trade.side == TradeSide::Buy).collect();
+    buys.sort_by_key(|trade| (trade.slot, trade.timestamp));
+
+    let mut report = CoordinatedBuyReport {
+        launch_slot,
+        ..Default::default()
+    };
+    if trades.is_empty() {
+        return report;
+    }
+    let in_launch = |slot: u64| launch_slot.is_some_and(|launch| slot <= launch + config.launch_slots);
+
+    let mut coordinated = vec![false; buys.len()];
+    let mut bundled = vec![false; buys.len()];
+
+    // 开盘窗口内的买入，新钱包视为协同
+    let mut sniper_token_amount = 0.0;
+    let mut launch_buyers = HashSet::new();
+    let mut fresh_buyers = HashSet::new();
+    for (i, buy) in buys.iter().enumerate() {
+        if !in_launch(buy.slot) {
+            continue;
+        }
+        sniper_token_amount += buy.token_amount;
+        launch_buyers.insert(buy.trader);
+
+        let fresh = profiles
+            .get(&buy.trader)
+            .and_then(|profile| profile.first_seen)
+            .is_some_and(|first_seen| buy.timestamp - first_seen <= config.fresh_wallet_age);
+        if fresh {
+            fresh_buyers.insert(buy.trader);
+            coordinated[i] = true;
+        }
+    }
+
+    // 资金来源：画像中的首个资助地址（排除交易所等公共地址），其次为已知关联实体
+    let funders: Vec<Option<Pubkey>> = buys
+        .iter()
+        .map(|buy| {
+            profiles
+                .get(&buy.trader)
+                .and_then(|profile| profile.funder)
+                .filter(|funder| !config.ignored_funders.contains(funder))
+                .or_else(|| links.and_then(|links| links.entity_of(&buy.trader)))
+        })
+        .collect();
+
+    // 同一slot的捆绑买入：开盘窗口内多钱包同时买入，或任意时刻同资金来源的钱包同时买入。
+    // Jito小费本身不作为证据，狙击机器人普遍付小费
+    let mut by_slot: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+    for (i, buy) in buys.iter().enumerate() {
+        by_slot.entry(buy.slot).or_default().push(i);
+    }
+    for (slot, indices) in &by_slot {
+        let wallets: HashSet<Pubkey> = indices.iter().map(|&i| buys[i].trader).collect();
+        if wallets.len() < 2 {
+            continue;
+        }
+
+        let members: Vec<usize> = if in_launch(*slot) && wallets.len() >= config.min_bundle_size.max(2) {
+            indices.clone()
+        } else {
+            let mut funded: HashMap<Pubkey, HashSet<Pubkey>> = HashMap::new();
+            for &i in indices {
+                if let Some(funder) = funders[i] {
+                    funded.entry(funder).or_default().insert(buys[i].trader);
+                }
+            }
+            indices
+                .iter()
+                .copied()
+                .filter(|&i| funders[i].is_some_and(|funder| funded[&funder].len() >= 2))
+                .collect()
+        };
+        if members.is_empty() {
+            continue;
+        }
+
+        for &i in &members {
+            coordinated[i] = true;
+            bundled[i] = true;
+        }
+        let mut wallets: Vec<Pubkey> = members.iter().map(|&i| buys[i].trader).collect();
+        wallets.sort();
+        wallets.dedup();
+        report.bundles.push(BuyGroup {
+            slot: *slot,
+            wallets,
+            token_amount: members.iter().map(|&i| buys[i].token_amount).sum(),
+            jito_bundle: members.iter().any(|&i| buys[i].jito_tip_lamports.is_some()),
+        });
+    }
+
+    // 同一资金来源或已知关联的买入钱包
+    let mut by_funder: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+    for (i, funder) in funders.iter().enumerate() {
+        if let Some(funder) = funder {
+            by_funder.entry(*funder).or_default().push(i);
+        }
+    }
+    for (funder, indices) in by_funder {
+        let mut wallets: Vec<Pubkey> = indices.iter().map(|&i| buys[i].trader).collect();
+        wallets.sort();
+        wallets.dedup();
+        if wallets.len() < 2 {
+            continue;
+        }
+
+        for &i in &indices {
+            coordinated[i] = true;
+            bundled[i] = true;
+        }
+        report.funding_clusters.push(FundingCluster {
+            funder,
+            wallets,
+            token_amount: indices.iter().map(|&i| buys[i].token_amount).sum(),
+        });
+    }
+    report.funding_clusters.sort_by(|a, b| b.token_amount.total_cmp(&a.token_amount));
+
+    report.bundle_token_amount = buys
+        .iter()
+        .zip(&bundled)
+        .filter(|(_, bundled)| **bundled)
+        .map(|(buy, _)| buy.token_amount)
+        .sum();
+    if total_supply > 0.0 {
+        report.bundle_supply_share = Some((report.bundle_token_amount / total_supply).min(1.0));
+    }
+
+    if launch_slot.is_some() {
+        let sorted = |wallets: HashSet<Pubkey>| {
+            let mut wallets: Vec<Pubkey> = wallets.into_iter().collect();
+            wallets.sort();
+            wallets
+        };
+        report.sniper_token_amount = Some(sniper_token_amount);
+        report.sniper_supply_share = (total_supply > 0.0).then(|| (sniper_token_amount / total_supply).min(1.0));
+        report.launch_buyers = Some(sorted(launch_buyers));
+        report.fresh_launch_buyers = Some(sorted(fresh_buyers));
+    }
+
+    report.coordinated_buys = buys
+        .iter()
+        .zip(&coordinated)
+        .filter(|(_, coordinated)| **coordinated)
+        .map(|(buy, _)| (buy.timestamp, buy.trader))
+        .collect();
+    report.coordinated_buys.sort();
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buy(mint: Pubkey, trader: Pubkey, slot: u64, tokens: f64, tip: Option<u64>) -> SwapTrade {
+        SwapTrade {
+            mint,
+            trader,
+            side: TradeSide::Buy,
+            sol_amount: 1.0,
+            token_amount: tokens,
+            signature: format!("sig-{}-{}", trader, slot),
+            slot,
+            // 每个slot约400ms
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+                + Duration::milliseconds((slot as i64 - 100) * 400),
+            jito_tip_lamports: tip,
+        }
+    }
+
+    /// 按`profile_candidates`的结果从已知画像中取值，模拟画像数据源
+    fn fetch_profiles(
+        trades: &[SwapTrade],
+        known: &HashMap<Pubkey, WalletProfile>,
+        config: &CoordinatedBuyConfig,
+    ) -> HashMap<Pubkey, WalletProfile> {
+        profile_candidates(trades, launch_window_start(trades, LaunchAnchor::Slot(100), config), config)
+            .into_iter()
+            .map(|wallet| (wallet, known.get(&wallet).copied().unwrap_or_default()))
+            .collect()
+    }
+
+    #[test]
+    fn test_launch_bundle_and_shared_funder() {
+        let mint = Pubkey::new_unique();
+        let funder = Pubkey::new_unique();
+        let bundle: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let (late_a, late_b, organic) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut trades: Vec<SwapTrade> = bundle
+            .iter()
+            .map(|wallet| buy(mint, *wallet, 100, 50_000_000.0, Some(100_000)))
+            .collect();
+        // 开盘后较晚、不同slot但由同一地址资助
+        trades.push(buy(mint, late_a, 400, 10_000_000.0, None));
+        trades.push(buy(mint, late_b, 450, 10_000_000.0, None));
+        trades.push(buy(mint, organic, 500, 5_000_000.0, None));
+
+        let launch_time = trades[0].timestamp;
+        let mut known: HashMap<Pubkey, WalletProfile> = bundle
+            .iter()
+            .map(|wallet| {
+                let profile = WalletProfile { funder: None, first_seen: Some(launch_time - Duration::hours(1)) };
+                (*wallet, profile)
+            })
+            .collect();
+        for wallet in [late_a, late_b] {
+            known.insert(wallet, WalletProfile { funder: Some(funder), first_seen: None });
+        }
+
+        let config = CoordinatedBuyConfig::default();
+        let candidates = profile_candidates(&trades, Some(100), &config);
+        assert_eq!(candidates.len(), 7);
+        assert_eq!(&candidates[4..6], &[late_a.min(late_b), late_a.max(late_b)][..]);
+
+        let profiles = fetch_profiles(&trades, &known, &config);
+        let report = analyze_coordinated_buys(&trades, Some(100), &profiles, None, 1_000_000_000.0, &config);
+        assert_eq!(report.launch_slot, Some(100));
+        assert_eq!(report.fresh_launch_buyers.as_ref().map(Vec::len), Some(4));
+        assert_eq!(report.bundles.len(), 1);
+        assert!(report.bundles[0].jito_bundle);
+        assert_eq!(report.funding_clusters.len(), 1);
+        assert_eq!(report.funding_clusters[0].funder, funder);
+
+        assert!((report.sniper_supply_share.unwrap() - 0.2).abs() < 1e-9);
+        assert!((report.bundle_supply_share.unwrap() - 0.22).abs() < 1e-9);
+        assert_eq!(report.coordinated_wallets(), 6);
+
+        // 捆绑的4个钱包在同一slot，加上同资金来源的两个钱包相隔约2分钟
+        assert_eq!(report.max_wallets_within(Duration::seconds(10)), 4);
+        assert_eq!(report.max_wallets_within(Duration::seconds(300)), 6);
+    }
+
+    #[test]
+    fn test_concurrent_buyers_after_launch_not_bundled() {
+        let mint = Pubkey::new_unique();
+        let funder = Pubkey::new_unique();
+        let launcher = Pubkey::new_unique();
+        let snipers: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let (linked_a, linked_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut trades = vec![buy(mint, launcher, 100, 1_000_000.0, None)];
+        // 开盘很久之后同一slot的独立狙击买入（都付了Jito小费）
+        trades.extend(snipers.iter().map(|wallet| buy(mint, *wallet, 900, 2_000_000.0, Some(50_000))));
+        // 同一slot中两个由同一地址资助的钱包
+        trades.push(buy(mint, linked_a, 1_200, 3_000_000.0, None));
+        trades.push(buy(mint, linked_b, 1_200, 3_000_000.0, None));
+        trades.push(buy(mint, Pubkey::new_unique(), 1_200, 1_000_000.0, None));
+
+        let known: HashMap<Pubkey, WalletProfile> = [linked_a, linked_b]
+            .into_iter()
+            .map(|wallet| (wallet, WalletProfile { funder: Some(funder), first_seen: None }))
+            .collect();
+
+        let config = CoordinatedBuyConfig::default();
+        let profiles = fetch_profiles(&trades, &known, &config);
+        let report = analyze_coordinated_buys(&trades, Some(100), &profiles, None, 1_000_000_000.0, &config);
+
+        assert_eq!(report.bundles.len(), 1);
+        assert_eq!(report.bundles[0].slot, 1_200);
+        assert_eq!(report.bundles[0].wallets.len(), 2);
+        assert!((report.bundle_supply_share.unwrap() - 0.006).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exchange_funded_buyers_not_clustered() {
+        let mint = Pubkey::new_unique();
+        let exchange = default_ignored_funders()[0];
+        let buyers: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+
+        let trades: Vec<SwapTrade> = buyers
+            .iter()
+            .enumerate()
+            .map(|(i, wallet)| buy(mint, *wallet, 100 + i as u64 * 50, 1_000_000.0, None))
+            .collect();
+        let known: HashMap<Pubkey, WalletProfile> = buyers
+            .iter()
+            .map(|wallet| (*wallet, WalletProfile { funder: Some(exchange), first_seen: None }))
+            .collect();
+
+        let config = CoordinatedBuyConfig::default();
+        let profiles = fetch_profiles(&trades, &known, &config);
+        assert_eq!(profiles.len(), 5);
+
+        let report = analyze_coordinated_buys(&trades, Some(100), &profiles, None, 1_000_000_000.0, &config);
+        assert!(report.funding_clusters.is_empty());
+        assert_eq!(report.coordinated_wallets(), 0);
+    }
+
+    #[test]
+    fn test_profile_candidates_prefer_large_later_buyers() {
+        let mint = Pubkey::new_unique();
+        let sniper = Pubkey::new_unique();
+        let (small, large) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let trades = vec![
+            buy(mint, sniper, 100, 1_000_000.0, None),
+            buy(mint, small, 500, 1_000.0, None),
+            buy(mint, large, 600, 9_000_000.0, None),
+        ];
+
+        let config = CoordinatedBuyConfig { max_profiled_wallets: 2, ..Default::default() };
+        assert_eq!(profile_candidates(&trades, Some(100), &config), vec![sniper, large]);
+    }
+
+    #[test]
+    fn test_history_without_launch_has_no_launch_metrics() {
+        let mint = Pubkey::new_unique();
+        let fresh: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+
+        // 成交历史从创建后约2分钟才开始，最早一批买入不能当作狙击
+        let trades: Vec<SwapTrade> = fresh.iter().map(|wallet| buy(mint, *wallet, 400, 50_000_000.0, None)).collect();
+        let known: HashMap<Pubkey, WalletProfile> = fresh
+            .iter()
+            .map(|wallet| (*wallet, WalletProfile { funder: None, first_seen: Some(trades[0].timestamp) }))
+            .collect();
+        let config = CoordinatedBuyConfig::default();
+
+        let launch_slot = launch_window_start(&trades, LaunchAnchor::Slot(100), &config);
+        assert_eq!(launch_slot, None);
+
+        let profiles = fetch_profiles(&trades, &known, &config);
+        let report = analyze_coordinated_buys(&trades, launch_slot, &profiles, None, 1_000_000_000.0, &config);
+        assert_eq!(report.launch_slot, None);
+        assert!(report.launch_buyers.is_none());
+        assert!(report.fresh_launch_buyers.is_none());
+        assert!(report.sniper_token_amount.is_none());
+        assert!(report.sniper_supply_share.is_none());
+        // 同一slot的多钱包买入不在开盘窗口内，没有共同资金来源时不算捆绑
+        assert!(report.bundles.is_empty());
+
+        // 只有创建时间时按时间容差判断
+        let created_at = trades[0].timestamp - Duration::minutes(2);
+        assert_eq!(launch_window_start(&trades, LaunchAnchor::Time(created_at), &config), None);
+        let created_at = trades[0].timestamp - Duration::seconds(2);
+        assert_eq!(launch_window_start(&trades, LaunchAnchor::Time(created_at), &config), Some(400));
+    }
+}
@@ -0,0 +1,1140 @@
+use solsniper_core::{Error, Result, RiskLevel, SwapTrade, TokenInfo};
+use crate::coordinated_buys::{
+    launch_window_start, profile_candidates, CoordinatedBuyConfig, LaunchAnchor, WalletProfile, WalletProfileSource,
+};
+use crate::patterns::{Pattern, PatternSet};
+use crate::recognizer::BehaviorPatternRecognizer;
+use crate::snapshots::{TokenSnapshot, TokenSnapshotStore};
+use crate::trade_graph::{PostgresTradeSource, TradeSource};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+/// 代币的最终结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Outcome {
+    /// 撤池/归零
+    Rugged,
+    /// 大幅上涨
+    Pumped,
+    /// 无明显变化
+    Flat,
+}
+
+impl Outcome {
+    /// 模式预测的结果；定义中未指定时按风险等级推断
+    pub fn predicted_by(pattern: &Pattern) -> Self {
+        pattern.predicts.unwrap_or(match pattern.risk_level {
+            RiskLevel::Critical | RiskLevel::High => Self::Rugged,
+            RiskLevel::Medium => Self::Pumped,
+            RiskLevel::Low => Self::Flat,
+        })
+    }
+
+    /// `token_outcomes`表中的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rugged => "rugged",
+            Self::Pumped => "pumped",
+            Self::Flat => "flat",
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "rugged" => Ok(Self::Rugged),
+            "pumped" => Ok(Self::Pumped),
+            "flat" => Ok(Self::Flat),
+            other => Err(Error::InvalidInput(format!("Unknown outcome: {}", other))),
+        }
+    }
+}
+
+/// 带结果标注的代币历史
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabeledHistory {
+    pub mint: Pubkey,
+    pub outcome: Outcome,
+    /// 结果发生的时间（之后的快照不参与预测）
+    pub outcome_at: DateTime<Utc>,
+    pub snapshots: Vec<TokenSnapshot>,
+    #[serde(default)]
+    pub trades: Vec<SwapTrade>,
+    /// 建池或创建mint交易所在slot（`token_launches`中有记录时）
+    #[serde(default)]
+    pub launch_slot: Option<u64>,
+    /// 买入钱包的画像（导出时查询记录，回放协同买入时与线上使用相同的证据）
+    #[serde(default)]
+    pub wallet_profiles: Vec<(Pubkey, WalletProfile)>,
+}
+
+impl LabeledHistory {
+    /// 开盘时刻：记录的创建slot，否则为首个快照中的代币创建时间
+    pub fn launch_anchor(&self) -> Option<LaunchAnchor> {
+        self.launch_slot
+            .map(LaunchAnchor::Slot)
+            .or_else(|| self.snapshots.first().map(|snapshot| LaunchAnchor::Time(snapshot.token.created_at)))
+    }
+}
+
+/// 读取标注历史（`.jsonl`每行一条，否则为JSON数组）
+pub fn load_histories(path: impl AsRef<Path>) -> Result<Vec<LabeledHistory>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    if path.extension().and_then(|ext| ext.to_str()) == Some("jsonl") {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    Error::Serialization(format!("{}:{}: invalid history: {}", path.display(), i + 1, e))
+                })
+            })
+            .collect()
+    } else {
+        serde_json::from_str(&content)
+            .map_err(|e| Error::Serialization(format!("{}: invalid histories: {}", path.display(), e)))
+    }
+}
+
+/// 写出标注历史（`.jsonl`，每行一条），可再由`load_histories`读取
+pub fn write_histories(path: impl AsRef<Path>, histories: &[LabeledHistory]) -> Result<()> {
+    let path = path.as_ref();
+    let io_error = |e: std::io::Error| Error::Config(format!("Failed to write {}: {}", path.display(), e));
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path).map_err(io_error)?);
+    for history in histories {
+        let line = serde_json::to_string(history)
+            .map_err(|e| Error::Serialization(format!("History of {}: {}", history.mint, e)))?;
+        writeln!(writer, "{}", line).map_err(io_error)?;
+    }
+    writer.flush().map_err(io_error)
+}
+
+/// 从快照推断代币结果的规则
+///
+/// 与部署者历史的rug判定一致：流动性从峰值（至少1 SOL）撤到0.5 SOL或峰值的一定比例以下、
+/// 或24h跌幅超过阈值视为rug；价格达到首个快照的若干倍视为拉升；观测满一定时长仍无变化视为平稳
+#[derive(Debug, Clone)]
+pub struct OutcomeRules {
+    /// 流动性低于峰值的此比例视为撤池
+    pub rug_liquidity_ratio: f64,
+
+    /// 24h涨跌幅（%）不高于此值视为归零
+    pub rug_price_change_24h: f64,
+
+    /// 价格达到首个快照的此倍数视为拉升
+    pub pump_multiple: f64,
+
+    /// 观测满此时长仍无结果时标注为平稳
+    pub observation: Duration,
+}
+
+impl Default for OutcomeRules {
+    fn default() -> Self {
+        Self {
+            rug_liquidity_ratio: 0.1,
+            rug_price_change_24h: -90.0,
+            pump_multiple: 3.0,
+            observation: Duration::hours(24),
+        }
+    }
+}
+
+impl OutcomeRules {
+    /// 按时间顺序的快照推断结果及发生时间；观测不足时返回None
+    pub fn label(&self, snapshots: &[TokenSnapshot]) -> Option<(Outcome, DateTime<Utc>)> {
+        let first = snapshots.first()?;
+        let first_price = first.token.price_usd;
+        let mut peak_liquidity: f64 = 0.0;
+
+        for snapshot in snapshots {
+            let token = &snapshot.token;
+            peak_liquidity = peak_liquidity.max(token.liquidity_sol);
+
+            let drained =
+                peak_liquidity >= 1.0 && token.liquidity_sol < (peak_liquidity * self.rug_liquidity_ratio).max(0.5);
+            if drained || token.price_change_24h <= self.rug_price_change_24h {
+                return Some((Outcome::Rugged, snapshot.timestamp));
+            }
+            if first_price > 0.0 && token.price_usd >= first_price * self.pump_multiple {
+                return Some((Outcome::Pumped, snapshot.timestamp));
+            }
+        }
+
+        let flat_at = first.timestamp + self.observation;
+        (snapshots.last()?.timestamp >= flat_at).then_some((Outcome::Flat, flat_at))
+    }
+}
+
+/// 写入`token_outcomes`，已有标注时保留原值（人工标注和先记录的结果优先）
+///
+/// 返回是否写入了新标注
+pub async fn record_outcome(
+    pool: &PgPool,
+    mint: &Pubkey,
+    outcome: Outcome,
+    outcome_at: DateTime<Utc>,
+    note: &str,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "INSERT INTO token_outcomes (mint, outcome, outcome_at, note)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (mint) DO NOTHING",
+    )
+    .bind(mint.to_string())
+    .bind(outcome.as_str())
+    .bind(outcome_at)
+    .bind(note)
+    .execute(pool)
+    .await
+    .map_err(|e| Error::Database(format!("Outcome insert failed: {}", e)))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 从PostgreSQL读取记录的代币历史
+///
+/// 快照来自`token_snapshots`，成交来自`market_trades`；结果优先取`token_outcomes`中的人工标注，
+/// 其次按`OutcomeRules`从快照推断，无法确定结果的代币跳过。
+/// `token_snapshots`有保留期限，需要长期评估的数据应定期用`write_histories`导出，
+/// 或开启`with_record_inferred`把推断出的结果写回`token_outcomes`
+pub struct PostgresHistoryLoader {
+    pool: PgPool,
+    rules: OutcomeRules,
+    since: Option<DateTime<Utc>>,
+    max_tokens: usize,
+    max_trades: usize,
+
+    /// 是否把从快照推断的结果写入`token_outcomes`
+    record_inferred: bool,
+}
+
+impl PostgresHistoryLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            rules: OutcomeRules::default(),
+            since: None,
+            max_tokens: 1_000,
+            max_trades: 5_000,
+            record_inferred: false,
+        }
+    }
+
+    /// 把从快照推断的结果写入`token_outcomes`，快照过期后仍保留标注
+    pub fn with_record_inferred(mut self, record: bool) -> Self {
+        self.record_inferred = record;
+        self
+    }
+
+    pub fn with_rules(mut self, rules: OutcomeRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// 只读取首个快照在`since`之后的代币
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub async fn load(&self) -> Result<Vec<LabeledHistory>> {
+        let since = self.since.unwrap_or(DateTime::UNIX_EPOCH);
+        let mints: Vec<String> = sqlx::query_scalar(
+            "SELECT mint FROM token_snapshots
+             GROUP BY mint
+             HAVING MIN(created_at) >= $1
+             ORDER BY MIN(created_at)
+             LIMIT $2",
+        )
+        .bind(since)
+        .bind(self.max_tokens as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Snapshot mint query failed: {}", e)))?;
+
+        let labels = self.recorded_outcomes(&mints).await?;
+        let trades = PostgresTradeSource::new(self.pool.clone());
+        let mut histories = Vec::new();
+
+        for mint in &mints {
+            let Ok(mint_key) = Pubkey::from_str(mint) else {
+                continue;
+            };
+            let snapshots = self.snapshots(mint).await?;
+            let (outcome, outcome_at) = match labels.get(mint).copied() {
+                Some(label) => label,
+                None => {
+                    let Some((outcome, outcome_at)) = self.rules.label(&snapshots) else {
+                        continue;
+                    };
+                    if self.record_inferred {
+                        record_outcome(&self.pool, &mint_key, outcome, outcome_at, "inferred from snapshots").await?;
+                    }
+                    (outcome, outcome_at)
+                }
+            };
+
+            let mut history_trades = trades.launch_trades(&mint_key, self.max_trades).await?;
+            history_trades.retain(|trade| trade.timestamp < outcome_at);
+            let launch_slot = trades.launch_slot(&mint_key).await?;
+
+            histories.push(LabeledHistory {
+                mint: mint_key,
+                outcome,
+                outcome_at,
+                snapshots,
+                trades: history_trades,
+                launch_slot,
+                wallet_profiles: Vec::new(),
+            });
+        }
+
+        Ok(histories)
+    }
+
+    /// `token_outcomes`中的人工标注
+    async fn recorded_outcomes(&self, mints: &[String]) -> Result<HashMap<String, (Outcome, DateTime<Utc>)>> {
+        let rows = sqlx::query("SELECT mint, outcome, outcome_at FROM token_outcomes WHERE mint = ANY($1)")
+            .bind(mints)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Outcome query failed: {}", e)))?;
+
+        let mut labels = HashMap::new();
+        for row in rows {
+            let decode = |e: sqlx::Error| Error::Database(format!("Outcome decode failed: {}", e));
+            let mint: String = row.try_get("mint").map_err(decode)?;
+            let outcome: String = row.try_get("outcome").map_err(decode)?;
+            let outcome_at: DateTime<Utc> = row.try_get("outcome_at").map_err(decode)?;
+            labels.insert(mint, (outcome.parse()?, outcome_at));
+        }
+        Ok(labels)
+    }
+
+    async fn snapshots(&self, mint: &str) -> Result<Vec<TokenSnapshot>> {
+        let rows = sqlx::query("SELECT data, created_at FROM token_snapshots WHERE mint = $1 ORDER BY created_at ASC")
+            .bind(mint)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Snapshot query failed: {}", e)))?;
+
+        let mut snapshots = Vec::with_capacity(rows.len());
+        for row in rows {
+            let decode = |e: sqlx::Error| Error::Database(format!("Snapshot decode failed: {}", e));
+            let data: serde_json::Value = row.try_get("data").map_err(decode)?;
+            let timestamp: DateTime<Utc> = row.try_get("created_at").map_err(decode)?;
+            match serde_json::from_value::<TokenInfo>(data) {
+                Ok(token) => snapshots.push(TokenSnapshot { timestamp, token }),
+                Err(e) => tracing::debug!("Skipping invalid snapshot of {}: {}", mint, e),
+            }
+        }
+        Ok(snapshots)
+    }
+}
+
+/// 为历史中的买入钱包查询并记录画像（已记录的钱包跳过）
+///
+/// 钱包的首个资助地址和首次活动时间不随时间变化，事后查询与线上评估时看到的一致
+pub async fn record_wallet_profiles(
+    histories: &mut [LabeledHistory],
+    source: &dyn WalletProfileSource,
+    config: &CoordinatedBuyConfig,
+) {
+    for history in histories.iter_mut() {
+        let known: HashSet<Pubkey> = history.wallet_profiles.iter().map(|(wallet, _)| *wallet).collect();
+        let launch_slot = history
+            .launch_anchor()
+            .and_then(|anchor| launch_window_start(&history.trades, anchor, config));
+        for wallet in profile_candidates(&history.trades, launch_slot, config) {
+            if known.contains(&wallet) {
+                continue;
+            }
+            match source.profile(&wallet).await {
+                Ok(profile) => history.wallet_profiles.push((wallet, profile)),
+                Err(e) => tracing::debug!("Wallet profile unavailable for {} via {}: {}", wallet, source.name(), e),
+            }
+        }
+    }
+}
+
+/// 在某个阈值下的预测效果
+#[derive(Debug, Clone, Serialize)]
+pub struct ThresholdMetrics {
+    pub threshold: f64,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub precision: Option<f64>,
+    pub recall: Option<f64>,
+    pub f1: f64,
+    /// 命中到结果发生的平均提前量（分钟，仅统计正确命中）
+    pub mean_lead_time_minutes: Option<f64>,
+    pub median_lead_time_minutes: Option<f64>,
+}
+
+/// 留出验证集上的效果
+#[derive(Debug, Clone, Serialize)]
+pub struct HoldoutMetrics {
+    /// 当前阈值
+    pub current: ThresholdMetrics,
+    /// 建议阈值（在训练集上拟合）
+    pub suggested: Option<ThresholdMetrics>,
+}
+
+/// 单个模式的评估结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PatternEvaluation {
+    pub name: String,
+    pub predicts: Outcome,
+    /// 当前阈值下的效果（全部代币）
+    pub current: ThresholdMetrics,
+    /// 训练集上F1明显更高、且在验证集上不变差的阈值（没有更好的阈值时为None）
+    pub suggestion: Option<ThresholdMetrics>,
+    /// 验证集上的效果（未留出验证集时为None）
+    pub holdout: Option<HoldoutMetrics>,
+}
+
+/// 评估报告
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationReport {
+    pub tokens: usize,
+    /// 留出的验证集代币数
+    pub holdout_tokens: usize,
+    pub outcomes: BTreeMap<Outcome, usize>,
+    pub patterns: Vec<PatternEvaluation>,
+    /// 评估结果的局限（未留出验证集、缺少钱包画像等）
+    pub warnings: Vec<String>,
+}
+
+impl fmt::Display for EvaluationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{:.1}%", value * 100.0));
+        let minutes = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{:.0}m", value));
+
+        writeln!(
+            f,
+            "代币数: {}（验证集 {}）  结果分布: {:?}",
+            self.tokens, self.holdout_tokens, self.outcomes
+        )?;
+        for warning in &self.warnings {
+            writeln!(f, "⚠️ {}", warning)?;
+        }
+        for pattern in &self.patterns {
+            let current = &pattern.current;
+            writeln!(
+                f,
+                "{} -> {:?}: 阈值 {:.2}  精确率 {}  召回率 {}  F1 {:.2}  TP/FP/FN {}/{}/{}  提前量(均值/中位数) {}/{}",
+                pattern.name,
+                pattern.predicts,
+                current.threshold,
+                percent(current.precision),
+                percent(current.recall),
+                current.f1,
+                current.true_positives,
+                current.false_positives,
+                current.false_negatives,
+                minutes(current.mean_lead_time_minutes),
+                minutes(current.median_lead_time_minutes),
+            )?;
+            if let Some(suggestion) = &pattern.suggestion {
+                writeln!(
+                    f,
+                    "    建议阈值 {:.2}: 精确率 {}  召回率 {}  F1 {:.2}（训练集）",
+                    suggestion.threshold,
+                    percent(suggestion.precision),
+                    percent(suggestion.recall),
+                    suggestion.f1,
+                )?;
+            }
+            if let Some(holdout) = &pattern.holdout {
+                write!(f, "    验证集: 当前阈值 F1 {:.2}", holdout.current.f1)?;
+                if let Some(suggested) = &holdout.suggested {
+                    write!(f, "  建议阈值 F1 {:.2}", suggested.f1)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 一个代币在各时刻的模式置信度
+struct Trace {
+    mint: Pubkey,
+    outcome: Outcome,
+    outcome_at: DateTime<Utc>,
+    /// 模式名 -> (时刻, 置信度)
+    confidences: HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+}
+
+/// 一个代币的回放数据
+///
+/// 同时作为识别器的快照存储和成交数据源：快照由评估器按时间追加，
+/// 读取时只返回截止时刻之前的数据，并平移到当前时间（识别器以当前时间为基准计算窗口）
+struct ReplayData {
+    mint: Pubkey,
+    snapshots: RwLock<Vec<TokenSnapshot>>,
+    /// 按时间升序
+    trades: Vec<SwapTrade>,
+    launch_slot: Option<u64>,
+    /// (截止时刻, 平移量)
+    clock: RwLock<(DateTime<Utc>, Duration)>,
+}
+
+impl ReplayData {
+    fn new(history: &LabeledHistory) -> Self {
+        let mut trades: Vec<SwapTrade> =
+            history.trades.iter().filter(|trade| trade.timestamp < history.outcome_at).cloned().collect();
+        trades.sort_by_key(|trade| (trade.timestamp, trade.slot));
+
+        Self {
+            mint: history.mint,
+            snapshots: RwLock::new(Vec::new()),
+            trades,
+            launch_slot: history.launch_slot,
+            clock: RwLock::new((DateTime::UNIX_EPOCH, Duration::zero())),
+        }
+    }
+
+    /// 推进到`snapshot`所在时刻，返回平移后的当前代币
+    fn advance(&self, snapshot: &TokenSnapshot) -> TokenInfo {
+        let offset = Utc::now() - snapshot.timestamp;
+        *self.clock.write().unwrap() = (snapshot.timestamp, offset);
+        shift(&snapshot.token, offset)
+    }
+
+    /// 追加截止时刻的快照（回放到该时刻时识别器读取的历史包含它）
+    fn push(&self, snapshot: &TokenSnapshot) {
+        self.snapshots.write().unwrap().push(snapshot.clone());
+    }
+
+    /// 截止时刻之前（含）的成交
+    fn visible_trades(&self) -> &[SwapTrade] {
+        let (cutoff, _) = *self.clock.read().unwrap();
+        let end = self.trades.partition_point(|trade| trade.timestamp <= cutoff);
+        &self.trades[..end]
+    }
+
+    fn shift_trade(trade: &SwapTrade, offset: Duration) -> SwapTrade {
+        let mut trade = trade.clone();
+        trade.timestamp += offset;
+        trade
+    }
+}
+
+#[async_trait]
+impl TokenSnapshotStore for ReplayData {
+    /// 回放时的快照由评估器追加，识别器写入的当前快照忽略
+    async fn record(&self, _token: &TokenInfo, _at: DateTime<Utc>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn snapshots(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<TokenSnapshot>> {
+        if *mint != self.mint {
+            return Ok(Vec::new());
+        }
+        let (_, offset) = *self.clock.read().unwrap();
+        let snapshots = self.snapshots.read().unwrap();
+        let start = snapshots.partition_point(|snapshot| snapshot.timestamp + offset < since);
+
+        Ok(snapshots[start..]
+            .iter()
+            .map(|snapshot| TokenSnapshot {
+                timestamp: snapshot.timestamp + offset,
+                token: shift(&snapshot.token, offset),
+            })
+            .collect())
+    }
+
+    fn name(&self) -> &str {
+        "replay"
+    }
+}
+
+#[async_trait]
+impl TradeSource for ReplayData {
+    async fn trades(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<SwapTrade>> {
+        if *mint != self.mint {
+            return Ok(Vec::new());
+        }
+        let (_, offset) = *self.clock.read().unwrap();
+        let visible = self.visible_trades();
+        let start = visible.partition_point(|trade| trade.timestamp + offset < since);
+        Ok(visible[start..].iter().map(|trade| Self::shift_trade(trade, offset)).collect())
+    }
+
+    async fn launch_trades(&self, mint: &Pubkey, limit: usize) -> Result<Vec<SwapTrade>> {
+        if *mint != self.mint {
+            return Ok(Vec::new());
+        }
+        let (_, offset) = *self.clock.read().unwrap();
+        Ok(self.visible_trades().iter().take(limit).map(|trade| Self::shift_trade(trade, offset)).collect())
+    }
+
+    async fn launch_slot(&self, mint: &Pubkey) -> Result<Option<u64>> {
+        Ok(self.launch_slot.filter(|_| *mint == self.mint))
+    }
+
+    fn name(&self) -> &str {
+        "replay"
+    }
+}
+
+/// 模式评估器
+///
+/// 按时间逐个回放快照（和成交），在每个时刻运行识别器，
+/// 模式在结果发生前首次命中即视为对该代币做出预测。
+/// 代币按mint确定性地划出验证集，建议阈值只在训练集上拟合，并报告其在验证集上的效果
+pub struct PatternEvaluator {
+    patterns: Vec<Pattern>,
+
+    /// 阈值扫描步长
+    threshold_step: f64,
+
+    /// 建议阈值至少提升的F1
+    min_f1_gain: f64,
+
+    /// 留作验证集的代币比例
+    holdout_fraction: f64,
+}
+
+impl PatternEvaluator {
+    pub fn new(patterns: Vec<Pattern>) -> Self {
+        Self {
+            patterns,
+            threshold_step: 0.05,
+            min_f1_gain: 0.05,
+            holdout_fraction: 0.3,
+        }
+    }
+
+    pub fn with_threshold_step(mut self, step: f64) -> Self {
+        self.threshold_step = step.clamp(0.01, 0.5);
+        self
+    }
+
+    pub fn with_min_f1_gain(mut self, gain: f64) -> Self {
+        self.min_f1_gain = gain;
+        self
+    }
+
+    /// 留作验证集的代币比例（0表示不留出，建议阈值在同一数据上拟合和评估）
+    pub fn with_holdout(mut self, fraction: f64) -> Self {
+        self.holdout_fraction = fraction.clamp(0.0, 0.9);
+        self
+    }
+
+    pub async fn evaluate(&self, histories: &[LabeledHistory]) -> Result<EvaluationReport> {
+        // 阈值置零回放，记录每个时刻的原始置信度，之后离线套用不同阈值
+        let probe: Vec<Pattern> = self
+            .patterns
+            .iter()
+            .cloned()
+            .map(|mut pattern| {
+                pattern.confidence_threshold = 0.0;
+                pattern
+            })
+            .collect();
+        let probe = PatternSet::from_patterns(probe);
+
+        let mut traces = Vec::with_capacity(histories.len());
+        for history in histories {
+            traces.push(self.replay(history, &probe).await?);
+        }
+
+        let mut outcomes = BTreeMap::new();
+        for history in histories {
+            *outcomes.entry(history.outcome).or_insert(0) += 1;
+        }
+
+        let mut warnings = Vec::new();
+        let all: Vec<&Trace> = traces.iter().collect();
+        let (mut train, mut holdout): (Vec<&Trace>, Vec<&Trace>) =
+            all.iter().copied().partition(|trace| !in_holdout(&trace.mint, self.holdout_fraction));
+        if train.is_empty() || holdout.is_empty() {
+            warnings.push("未留出验证集：建议阈值在同一批代币上拟合和评估，可能过拟合".to_string());
+            train = all.clone();
+            holdout = Vec::new();
+        }
+
+        let missing_profiles = histories
+            .iter()
+            .filter(|history| !history.trades.is_empty() && history.wallet_profiles.is_empty())
+            .count();
+        if missing_profiles > 0 {
+            warnings.push(format!(
+                "{} 个代币有成交但没有钱包画像，协同买入只按slot和已知关联判断，与线上证据不一致",
+                missing_profiles
+            ));
+        }
+
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|pattern| self.evaluate_pattern(pattern, &all, &train, &holdout))
+            .collect();
+
+        Ok(EvaluationReport {
+            tokens: histories.len(),
+            holdout_tokens: holdout.len(),
+            outcomes,
+            patterns,
+            warnings,
+        })
+    }
+
+    /// 回放一个代币的历史
+    ///
+    /// 快照存储和成交数据源在整个回放中复用，每个时刻只推进时钟并追加一个快照
+    async fn replay(&self, history: &LabeledHistory, patterns: &PatternSet) -> Result<Trace> {
+        let mut snapshots: Vec<&TokenSnapshot> = history
+            .snapshots
+            .iter()
+            .filter(|snapshot| snapshot.timestamp < history.outcome_at)
+            .collect();
+        snapshots.sort_by_key(|snapshot| snapshot.timestamp);
+
+        let mut trace = Trace {
+            mint: history.mint,
+            outcome: history.outcome,
+            outcome_at: history.outcome_at,
+            confidences: HashMap::new(),
+        };
+
+        let data = Arc::new(ReplayData::new(history));
+        let mut recognizer = BehaviorPatternRecognizer::new()
+            .with_patterns(patterns.clone())
+            .with_snapshot_store(data.clone());
+        if !data.trades.is_empty() {
+            recognizer = recognizer.with_trade_source(data.clone());
+        }
+        if !history.wallet_profiles.is_empty() {
+            let profiles: HashMap<Pubkey, WalletProfile> = history.wallet_profiles.iter().copied().collect();
+            recognizer = recognizer.with_wallet_profiles(Arc::new(profiles));
+        }
+
+        for current in snapshots {
+            let token = data.advance(current);
+            data.push(current);
+            let analysis = recognizer.analyze(&token).await?;
+            for matched in analysis.matches {
+                trace
+                    .confidences
+                    .entry(matched.pattern.name)
+                    .or_default()
+                    .push((current.timestamp, matched.confidence));
+            }
+        }
+
+        Ok(trace)
+    }
+
+    fn evaluate_pattern(
+        &self,
+        pattern: &Pattern,
+        all: &[&Trace],
+        train: &[&Trace],
+        holdout: &[&Trace],
+    ) -> PatternEvaluation {
+        let predicts = Outcome::predicted_by(pattern);
+        let current = Self::metrics_at(&pattern.name, predicts, pattern.confidence_threshold, all);
+        let train_current = Self::metrics_at(&pattern.name, predicts, pattern.confidence_threshold, train);
+
+        let steps = (1.0 / self.threshold_step).ceil() as usize;
+        let best = (1..steps)
+            .map(|i| i as f64 * self.threshold_step)
+            .map(|threshold| Self::metrics_at(&pattern.name, predicts, threshold, train))
+            .filter(|metrics| metrics.true_positives > 0)
+            .max_by(|a, b| {
+                a.f1.total_cmp(&b.f1).then_with(|| {
+                    // F1相同时选离当前阈值更近的
+                    let distance = |m: &ThresholdMetrics| (m.threshold - pattern.confidence_threshold).abs();
+                    distance(b).total_cmp(&distance(a))
+                })
+            });
+
+        let mut suggestion = best.filter(|best| {
+            best.f1 >= train_current.f1 + self.min_f1_gain
+                && (best.threshold - pattern.confidence_threshold).abs() > f64::EPSILON
+        });
+
+        let holdout = (!holdout.is_empty()).then(|| {
+            let current = Self::metrics_at(&pattern.name, predicts, pattern.confidence_threshold, holdout);
+            let suggested = suggestion
+                .as_ref()
+                .map(|suggestion| Self::metrics_at(&pattern.name, predicts, suggestion.threshold, holdout));
+            HoldoutMetrics { current, suggested }
+        });
+
+        // 验证集上变差的建议不采纳
+        if let Some(HoldoutMetrics { current, suggested: Some(suggested) }) = &holdout {
+            if suggested.f1 < current.f1 {
+                suggestion = None;
+            }
+        }
+
+        PatternEvaluation {
+            name: pattern.name.clone(),
+            predicts,
+            current,
+            suggestion,
+            holdout,
+        }
+    }
+
+    fn metrics_at(name: &str, predicts: Outcome, threshold: f64, traces: &[&Trace]) -> ThresholdMetrics {
+        let (mut true_positives, mut false_positives, mut false_negatives) = (0, 0, 0);
+        let mut lead_times = Vec::new();
+
+        for trace in traces {
+            let first_match = trace
+                .confidences
+                .get(name)
+                .and_then(|points| points.iter().find(|(_, confidence)| *confidence > threshold))
+                .map(|(at, _)| *at);
+
+            match (first_match, trace.outcome == predicts) {
+                (Some(at), true) => {
+                    true_positives += 1;
+                    lead_times.push((trace.outcome_at - at).num_seconds() as f64 / 60.0);
+                }
+                (Some(_), false) => false_positives += 1,
+                (None, true) => false_negatives += 1,
+                (None, false) => {}
+            }
+        }
+
+        let ratio = |numerator: usize, denominator: usize| {
+            if denominator > 0 {
+                Some(numerator as f64 / denominator as f64)
+            } else {
+                None
+            }
+        };
+        let precision = ratio(true_positives, true_positives + false_positives);
+        let recall = ratio(true_positives, true_positives + false_negatives);
+        let f1 = match (precision, recall) {
+            (Some(p), Some(r)) if p + r > 0.0 => 2.0 * p * r / (p + r),
+            _ => 0.0,
+        };
+
+        lead_times.sort_by(f64::total_cmp);
+        let mean_lead_time_minutes =
+            (!lead_times.is_empty()).then(|| lead_times.iter().sum::<f64>() / lead_times.len() as f64);
+        let median_lead_time_minutes = (!lead_times.is_empty()).then(|| {
+            let mid = lead_times.len() / 2;
+            if lead_times.len() % 2 == 0 {
+                (lead_times[mid - 1] + lead_times[mid]) / 2.0
+            } else {
+                lead_times[mid]
+            }
+        });
+
+        ThresholdMetrics {
+            threshold,
+            true_positives,
+            false_positives,
+            false_negatives,
+            precision,
+            recall,
+            f1,
+            mean_lead_time_minutes,
+            median_lead_time_minutes,
+        }
+    }
+}
+
+/// 按mint确定性地划分验证集
+fn in_holdout(mint: &Pubkey, fraction: f64) -> bool {
+    // FNV-1a，同一mint每次划分一致
+    let hash = mint
+        .to_bytes()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3));
+    ((hash % 10_000) as f64) < fraction * 10_000.0
+}
+
+/// 把代币中的绝对时间平移`offset`
+fn shift(token: &TokenInfo, offset: Duration) -> TokenInfo {
+    let mut token = token.clone();
+    token.created_at += offset;
+    token.lp_unlock_at = token.lp_unlock_at.map(|at| at + offset);
+    token.graduated_at = token.graduated_at.map(|at| at + offset);
+    token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PatternLibrary;
+
+    fn sample_token(mint: Pubkey, at: DateTime<Utc>) -> TokenInfo {
+        TokenInfo {
+            mint,
+            symbol: "TEST".to_string(),
+            name: "Test Token".to_string(),
+            decimals: 9,
+            liquidity_sol: 50.0,
+            liquidity_usd: 5000.0,
+            total_supply: 1_000_000_000,
+            circulating_supply: 1_000_000_000,
+            holders_count: 50,
+            top10_ratio: 0.3,
+            top20_ratio: 0.4,
+            top50_ratio: 0.6,
+            mint_authority_revoked: true,
+            freeze_authority_revoked: true,
+            created_at: at - Duration::hours(3),
+            age_minutes: 180,
+            age_hours: 3.0,
+            txns_1h_total: 20,
+            txns_1h_buys: 5,
+            txns_1h_sells: 15,
+            volume_1h: 500.0,
+            volume_6h: 2000.0,
+            volume_24h: 5000.0,
+            price_usd: 0.00001,
+            volatility_1h: 0.1,
+            twitter_mentions: 5,
+            telegram_members: 20,
+            sentiment_score: 0.5,
+            dex: "Raydium".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn history(outcome: Outcome, states: &[(i64, f64, f64, f64)]) -> LabeledHistory {
+        let mint = Pubkey::new_unique();
+        let outcome_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let snapshots = states
+            .iter()
+            .map(|&(minutes_before, liquidity_sol, top10_ratio, price_usd)| {
+                let timestamp = outcome_at - Duration::minutes(minutes_before);
+                let mut token = sample_token(mint, timestamp);
+                token.liquidity_sol = liquidity_sol;
+                token.top10_ratio = top10_ratio;
+                token.price_usd = price_usd;
+                TokenSnapshot { timestamp, token }
+            })
+            .collect();
+
+        LabeledHistory {
+            mint,
+            outcome,
+            outcome_at,
+            snapshots,
+            trades: Vec::new(),
+            launch_slot: None,
+            wallet_profiles: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fast_rug_precision_recall_and_lead_time() {
+        let histories = vec![
+            // 20分钟前撤池、大户卖出
+            history(
+                Outcome::Rugged,
+                &[(60, 80.0, 0.85, 0.00003), (40, 78.0, 0.85, 0.00003), (20, 10.0, 0.3, 0.00001)],
+            ),
+            // 平稳
+            history(
+                Outcome::Flat,
+                &[(60, 50.0, 0.3, 0.00001), (40, 50.0, 0.3, 0.00001), (20, 51.0, 0.3, 0.00001)],
+            ),
+        ];
+
+        let report = PatternEvaluator::new(PatternLibrary::load_all())
+            .evaluate(&histories)
+            .await
+            .unwrap();
+        assert_eq!(report.tokens, 2);
+        assert_eq!(report.outcomes.get(&Outcome::Rugged), Some(&1));
+
+        let fast_rug = report.patterns.iter().find(|p| p.name == "快速撤池 Rug").unwrap();
+        assert_eq!(fast_rug.predicts, Outcome::Rugged);
+        assert_eq!(fast_rug.current.true_positives, 1);
+        assert_eq!(fast_rug.current.false_positives, 0);
+        assert_eq!(fast_rug.current.precision, Some(1.0));
+        assert_eq!(fast_rug.current.recall, Some(1.0));
+        assert_eq!(fast_rug.current.median_lead_time_minutes, Some(20.0));
+        assert!(fast_rug.suggestion.is_none());
+    }
+
+    #[test]
+    fn test_suggests_lower_threshold() {
+        let at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let trace = |outcome: Outcome, confidence: f64| Trace {
+            mint: Pubkey::new_unique(),
+            outcome,
+            outcome_at: at,
+            confidences: [("p".to_string(), vec![(at - Duration::minutes(30), confidence)])]
+                .into_iter()
+                .collect(),
+        };
+        let traces = [
+            trace(Outcome::Rugged, 0.6),
+            trace(Outcome::Rugged, 0.9),
+            trace(Outcome::Flat, 0.2),
+        ];
+
+        let mut pattern = PatternLibrary::load_all().remove(0);
+        pattern.name = "p".to_string();
+        pattern.confidence_threshold = 0.8;
+
+        let traces: Vec<&Trace> = traces.iter().collect();
+        let evaluation = PatternEvaluator::new(vec![]).evaluate_pattern(&pattern, &traces, &traces, &[]);
+        assert!(evaluation.holdout.is_none());
+        assert_eq!(evaluation.current.recall, Some(0.5));
+        let suggestion = evaluation.suggestion.unwrap();
+        assert_eq!(suggestion.recall, Some(1.0));
+        assert_eq!(suggestion.precision, Some(1.0));
+        assert!(suggestion.threshold >= 0.2 && suggestion.threshold < 0.6);
+    }
+
+    #[test]
+    fn test_holdout_rejects_overfit_suggestion() {
+        let at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let trace = |outcome: Outcome, confidence: f64| Trace {
+            mint: Pubkey::new_unique(),
+            outcome,
+            outcome_at: at,
+            confidences: [("p".to_string(), vec![(at - Duration::minutes(30), confidence)])]
+                .into_iter()
+                .collect(),
+        };
+        // 训练集上降低阈值能多命中，验证集上同样的阈值只带来误报
+        let train = [trace(Outcome::Rugged, 0.6), trace(Outcome::Rugged, 0.9), trace(Outcome::Flat, 0.2)];
+        let holdout = [trace(Outcome::Rugged, 0.9), trace(Outcome::Flat, 0.6), trace(Outcome::Flat, 0.5)];
+        let train: Vec<&Trace> = train.iter().collect();
+        let holdout: Vec<&Trace> = holdout.iter().collect();
+        let all: Vec<&Trace> = train.iter().chain(&holdout).copied().collect();
+
+        let mut pattern = PatternLibrary::load_all().remove(0);
+        pattern.name = "p".to_string();
+        pattern.confidence_threshold = 0.8;
+
+        let evaluation = PatternEvaluator::new(vec![]).evaluate_pattern(&pattern, &all, &train, &holdout);
+        let holdout = evaluation.holdout.unwrap();
+        assert_eq!(holdout.current.f1, 1.0);
+        assert!(holdout.suggested.unwrap().f1 < 1.0);
+        assert!(evaluation.suggestion.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_small_dataset_warns_without_holdout() {
+        let histories = vec![history(Outcome::Flat, &[(60, 50.0, 0.3, 0.00001), (20, 50.0, 0.3, 0.00001)])];
+
+        let report = PatternEvaluator::new(PatternLibrary::load_all())
+            .evaluate(&histories)
+            .await
+            .unwrap();
+        assert_eq!(report.holdout_tokens, 0);
+        assert!(report.warnings.iter().any(|warning| warning.contains("验证集")));
+    }
+
+    #[test]
+    fn test_outcome_rules_label() {
+        let mint = Pubkey::new_unique();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let snapshots = |states: &[(i64, f64, f64)]| -> Vec<TokenSnapshot> {
+            states
+                .iter()
+                .map(|&(minutes, liquidity_sol, price_usd)| {
+                    let timestamp = start + Duration::minutes(minutes);
+                    let mut token = sample_token(mint, timestamp);
+                    token.liquidity_sol = liquidity_sol;
+                    token.price_usd = price_usd;
+                    TokenSnapshot { timestamp, token }
+                })
+                .collect()
+        };
+        let rules = OutcomeRules::default();
+
+        let rugged = snapshots(&[(0, 40.0, 1.0), (30, 60.0, 1.2), (90, 3.0, 0.1)]);
+        assert_eq!(rules.label(&rugged), Some((Outcome::Rugged, start + Duration::minutes(90))));
+
+        let pumped = snapshots(&[(0, 40.0, 1.0), (60, 45.0, 2.0), (120, 80.0, 3.5)]);
+        assert_eq!(rules.label(&pumped), Some((Outcome::Pumped, start + Duration::minutes(120))));
+
+        let flat = snapshots(&[(0, 40.0, 1.0), (24 * 60, 42.0, 1.1)]);
+        assert_eq!(rules.label(&flat), Some((Outcome::Flat, start + Duration::hours(24))));
+
+        // 观测不足24小时，结果未知
+        let young = snapshots(&[(0, 40.0, 1.0), (60, 41.0, 1.1)]);
+        assert_eq!(rules.label(&young), None);
+    }
+
+    #[test]
+    fn test_histories_roundtrip_with_profiles() {
+        let mut history = history(Outcome::Rugged, &[(60, 80.0, 0.85, 0.00003)]);
+        let wallet = Pubkey::new_unique();
+        history.wallet_profiles.push((wallet, WalletProfile { funder: Some(Pubkey::new_unique()), first_seen: None }));
+
+        let path = std::env::temp_dir().join(format!("histories-{}.jsonl", Pubkey::new_unique()));
+        write_histories(&path, std::slice::from_ref(&history)).unwrap();
+        let loaded = load_histories(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].mint, history.mint);
+        assert_eq!(loaded[0].wallet_profiles, history.wallet_profiles);
+    }
+
+    #[tokio::test]
+    async fn test_replay_hides_future_trades() {
+        let mut history = history(Outcome::Rugged, &[(60, 80.0, 0.85, 0.00003), (30, 80.0, 0.85, 0.00003)]);
+        let trader = Pubkey::new_unique();
+        history.trades = [50, 40, 20]
+            .into_iter()
+            .map(|minutes_before| SwapTrade {
+                mint: history.mint,
+                trader,
+                side: solsniper_core::TradeSide::Buy,
+                sol_amount: 1.0,
+                token_amount: 1_000.0,
+                signature: format!("sig-{}", minutes_before),
+                slot: 1_000 - minutes_before as u64,
+                timestamp: history.outcome_at - Duration::minutes(minutes_before),
+                jito_tip_lamports: None,
+            })
+            .collect();
+
+        let data = ReplayData::new(&history);
+        data.advance(&history.snapshots[0]);
+        assert!(data.launch_trades(&history.mint, 10).await.unwrap().is_empty());
+
+        data.push(&history.snapshots[0]);
+        data.advance(&history.snapshots[1]);
+        let trades = data.launch_trades(&history.mint, 10).await.unwrap();
+        assert_eq!(trades.len(), 2);
+        // 平移到当前时间：第二个快照在30分钟前，最近一笔可见成交在其10分钟前
+        assert!((Utc::now() - trades[1].timestamp - Duration::minutes(10)).num_seconds().abs() <= 1);
+
+        let snapshots = data.snapshots(&history.mint, Utc::now() - Duration::hours(6)).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+    }
+}
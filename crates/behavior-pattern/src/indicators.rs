@@ -1,9 +1,11 @@
-use solsniper_core::{BehaviorPattern, RiskLevel};
+use solsniper_core::{Error, Result};
 use serde::{Deserialize, Serialize};
-use chrono::Duration;
 
 /// 行为指标
+///
+/// 模式文件中以`type`字段区分变体，例如`{ type = "VolumeSpike", multiplier = 5.0 }`
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum Indicator {
     // === Rug Pull 模式 ===
     /// 流动性突然下降
@@ -83,4 +85,46 @@ impl Indicator {
             Self::FakeVolume { .. } => 0.8,
         }
     }
+
+    /// 校验阈值是否在有效范围内
+    pub fn validate(&self) -> Result<()> {
+        let percent = |name: &str, value: f64| {
+            if value.is_finite() && value > 0.0 && value <= 100.0 {
+                Ok(())
+            } else {
+                Err(Error::Config(format!("{:?}: {} must be in (0, 100], got {}", self, name, value)))
+            }
+        };
+        let ratio = |name: &str, value: f64| {
+            if value.is_finite() && value > 0.0 && value <= 1.0 {
+                Ok(())
+            } else {
+                Err(Error::Config(format!("{:?}: {} must be in (0, 1], got {}", self, name, value)))
+            }
+        };
+        let positive = |name: &str, value: f64| {
+            if value.is_finite() && value > 0.0 {
+                Ok(())
+            } else {
+                Err(Error::Config(format!("{:?}: {} must be positive, got {}", self, name, value)))
+            }
+        };
+
+        match self {
+            Self::SuddenLiquidityDrop { threshold_pct } => percent("threshold_pct", *threshold_pct),
+            Self::CreatorSellOff { threshold_pct } => percent("threshold_pct", *threshold_pct),
+            Self::LpUnlock { .. } => Ok(()),
+            Self::CoordinatedBuying { wallet_count, timeframe_seconds } => {
+                positive("wallet_count", *wallet_count as f64)?;
+                positive("timeframe_seconds", *timeframe_seconds as f64)
+            }
+            Self::VolumeSpike { multiplier } => positive("multiplier", *multiplier),
+            Self::PriceParabolicRise { slope } => positive("slope", *slope),
+            Self::OrganicGrowth { holder_increase_rate } => positive("holder_increase_rate", *holder_increase_rate),
+            Self::SteadyVolume { variance } => positive("variance", *variance),
+            Self::DistributedHolding { max_top10_ratio } => ratio("max_top10_ratio", *max_top10_ratio),
+            Self::WashTrading { same_wallet_ratio } => ratio("same_wallet_ratio", *same_wallet_ratio),
+            Self::FakeVolume { suspicious_tx_ratio } => ratio("suspicious_tx_ratio", *suspicious_tx_ratio),
+        }
+    }
 }
@@ -1,12 +1,29 @@
 pub mod patterns;
 pub mod recognizer;
 pub mod indicators;
+pub mod snapshots;
+pub mod coordinated_buys;
+pub mod trade_graph;
+pub mod evaluation;
 
-use solsniper_core::{TokenInfo, BehaviorPattern, RiskLevel, Result};
+use solsniper_core::BehaviorPattern;
 
 pub use patterns::*;
 pub use recognizer::BehaviorPatternRecognizer;
 pub use indicators::*;
+pub use snapshots::{InMemorySnapshotStore, PostgresSnapshotStore, TokenSnapshot, TokenSnapshotStore};
+pub use coordinated_buys::{
+    default_ignored_funders, launch_window_start, BuyGroup, CoordinatedBuyConfig, CoordinatedBuyReport,
+    FundingCluster, LaunchAnchor, WalletProfile, WalletProfileSource,
+};
+pub use evaluation::{
+    load_histories, record_outcome, record_wallet_profiles, write_histories, EvaluationReport, HoldoutMetrics, LabeledHistory,
+    Outcome, OutcomeRules, PatternEvaluation, PatternEvaluator, PostgresHistoryLoader, ThresholdMetrics,
+};
+pub use trade_graph::{
+    InMemoryTradeStore, PostgresTradeSource, SharedWalletLinks, TradeGraph, TradeGraphConfig, TradeGraphReport,
+    TradeSource, WalletLinks,
+};
 
 /// 模式匹配结果
 #[derive(Debug, Clone)]
@@ -16,10 +33,18 @@ pub struct PatternMatch {
     pub matched_indicators: Vec<String>,
 }
 
+/// 一次行为分析的结果
+#[derive(Debug, Clone)]
+pub struct PatternAnalysis {
+    pub matches: Vec<PatternMatch>,
+    /// 最近1小时的交易图（有成交数据时）
+    pub trade_graph: Option<TradeGraphReport>,
+    /// 开盘狙击/捆绑分析（有成交数据时）
+    pub coordinated_buys: Option<CoordinatedBuyReport>,
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
     fn test_pattern_recognition() {
         // 基础测试
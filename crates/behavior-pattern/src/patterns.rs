@@ -1,128 +1,354 @@
-use solsniper_core::{BehaviorPattern, RiskLevel};
+use solsniper_core::{Error, Result, RiskLevel};
+use crate::evaluation::Outcome;
 use crate::indicators::Indicator;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// 内置模式定义
+const BUILTIN_PATTERNS: &str = include_str!("../patterns/builtin.toml");
 
 /// 预定义模式库
 pub struct PatternLibrary;
 
 impl PatternLibrary {
-    /// 加载所有预定义模式
+    /// 加载所有内置模式
     pub fn load_all() -> Vec<Pattern> {
-        vec![
-            Self::fast_rug_pull(),
-            Self::slow_rug_pull(),
-            Self::coordinated_pump(),
-            Self::organic_growth(),
-            Self::wash_trading(),
-        ]
-    }
-
-    /// 模式 1: 快速撤池 Rug
-    fn fast_rug_pull() -> Pattern {
-        Pattern {
-            name: "快速撤池 Rug".to_string(),
-            description: "项目方在短时间内撤除流动性".to_string(),
-            indicators: vec![
-                Indicator::SuddenLiquidityDrop {
-                    threshold_pct: 80.0,
-                },
-                Indicator::CreatorSellOff {
-                    threshold_pct: 50.0,
-                },
-            ],
-            confidence_threshold: 0.8,
-            risk_level: RiskLevel::Critical,
-        }
+        Self::parse(BUILTIN_PATTERNS, config::FileFormat::Toml, "builtin.toml")
+            .expect("built-in patterns must be valid")
     }
 
-    /// 模式 2: 慢速撤池 Rug
-    fn slow_rug_pull() -> Pattern {
-        Pattern {
-            name: "慢速撤池 Rug".to_string(),
-            description: "项目方逐步撤除流动性，避免触发告警".to_string(),
-            indicators: vec![
-                Indicator::SuddenLiquidityDrop {
-                    threshold_pct: 30.0,
-                },
-                Indicator::LpUnlock {
-                    time_after_launch_hours: 24 * 7,
-                },
-                Indicator::CreatorSellOff {
-                    threshold_pct: 30.0,
-                },
-            ],
-            confidence_threshold: 0.7,
-            risk_level: RiskLevel::High,
-        }
+    /// 从TOML或JSON文件加载模式（按扩展名判断格式）
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Vec<Pattern>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => config::FileFormat::Toml,
+            Some("json") => config::FileFormat::Json,
+            _ => return Err(Error::Config(format!("Unsupported pattern file: {}", path.display()))),
+        };
+
+        Self::parse(&content, format, &path.display().to_string())
     }
 
-    /// 模式 3: 协同拉盘
-    fn coordinated_pump() -> Pattern {
-        Pattern {
-            name: "协同拉盘".to_string(),
-            description: "多个钱包短时间内大量买入".to_string(),
-            indicators: vec![
-                Indicator::CoordinatedBuying {
-                    wallet_count: 20,
-                    timeframe_seconds: 300,
-                },
-                Indicator::VolumeSpike {
-                    multiplier: 5.0,
-                },
-                Indicator::PriceParabolicRise {
-                    slope: 2.0,
-                },
-            ],
-            confidence_threshold: 0.75,
-            risk_level: RiskLevel::Medium,
+    /// 内置模式加上目录中的自定义模式
+    ///
+    /// 目录下的`.toml`/`.json`文件按文件名顺序加载，同名模式覆盖内置定义；
+    /// 多个自定义文件定义同名模式时报错，避免结果取决于文件名顺序
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<Pattern>> {
+        let mut patterns = Self::load_all();
+        let mut defined_in: HashMap<String, PathBuf> = HashMap::new();
+
+        for path in pattern_files(dir.as_ref())? {
+            for pattern in Self::from_file(&path)? {
+                if let Some(previous) = defined_in.insert(pattern.name.clone(), path.clone()) {
+                    return Err(Error::Config(format!(
+                        "Duplicate pattern '{}' in {} and {}",
+                        pattern.name,
+                        previous.display(),
+                        path.display()
+                    )));
+                }
+                match patterns.iter_mut().find(|existing| existing.name == pattern.name) {
+                    Some(existing) => *existing = pattern,
+                    None => patterns.push(pattern),
+                }
+            }
         }
+
+        Ok(patterns)
     }
 
-    /// 模式 4: 有机增长
-    fn organic_growth() -> Pattern {
-        Pattern {
-            name: "有机增长".to_string(),
-            description: "持有者稳步增加，交易量稳定".to_string(),
-            indicators: vec![
-                Indicator::OrganicGrowth {
-                    holder_increase_rate: 10.0,
-                },
-                Indicator::SteadyVolume {
-                    variance: 0.3,
-                },
-                Indicator::DistributedHolding {
-                    max_top10_ratio: 0.4,
-                },
-            ],
-            confidence_threshold: 0.8,
-            risk_level: RiskLevel::Low,
+    fn parse(content: &str, format: config::FileFormat, source: &str) -> Result<Vec<Pattern>> {
+        #[derive(Deserialize)]
+        struct PatternFile {
+            #[serde(default)]
+            patterns: Vec<Pattern>,
         }
-    }
 
-    /// 模式 5: 洗售交易
-    fn wash_trading() -> Pattern {
-        Pattern {
-            name: "洗售交易".to_string(),
-            description: "同一钱包反复买卖制造虚假交易量".to_string(),
-            indicators: vec![
-                Indicator::WashTrading {
-                    same_wallet_ratio: 0.6,
-                },
-                Indicator::FakeVolume {
-                    suspicious_tx_ratio: 0.7,
-                },
-            ],
-            confidence_threshold: 0.75,
-            risk_level: RiskLevel::High,
+        let file: PatternFile = config::Config::builder()
+            .add_source(config::File::from_str(content, format))
+            .build()
+            .and_then(|settings| settings.try_deserialize())
+            .map_err(|e| Error::Config(format!("Invalid pattern file {}: {}", source, e)))?;
+
+        let mut names = HashSet::new();
+        for pattern in &file.patterns {
+            pattern
+                .validate()
+                .map_err(|e| Error::Config(format!("{}: {}", source, e)))?;
+            if !names.insert(pattern.name.as_str()) {
+                return Err(Error::Config(format!("{}: duplicate pattern '{}'", source, pattern.name)));
+            }
         }
+
+        Ok(file.patterns)
+    }
+}
+
+/// 模式目录中的定义文件（按文件名排序）
+fn pattern_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| Error::Config(format!("Failed to read pattern dir {}: {}", dir.display(), e)))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && matches!(path.extension().and_then(|ext| ext.to_str()), Some("toml") | Some("json"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// 模式中的指标及其权重
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternIndicator {
+    #[serde(flatten)]
+    pub indicator: Indicator,
+
+    /// 覆盖指标的默认权重
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
+
+impl PatternIndicator {
+    pub fn weight(&self) -> f64 {
+        self.weight.unwrap_or_else(|| self.indicator.weight())
+    }
+}
+
+impl From<Indicator> for PatternIndicator {
+    fn from(indicator: Indicator) -> Self {
+        Self { indicator, weight: None }
     }
 }
 
 /// 模式定义
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pattern {
     pub name: String,
+    #[serde(default)]
     pub description: String,
-    pub indicators: Vec<Indicator>,
+    pub indicators: Vec<PatternIndicator>,
     pub confidence_threshold: f64,
     pub risk_level: RiskLevel,
+
+    /// 模式预示的结果，用于评估；省略时按风险等级推断
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub predicts: Option<Outcome>,
+}
+
+impl Pattern {
+    /// 校验模式定义
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(Error::Config("Pattern name must not be empty".to_string()));
+        }
+        if self.indicators.is_empty() {
+            return Err(Error::Config(format!("Pattern '{}' has no indicators", self.name)));
+        }
+        if !(self.confidence_threshold > 0.0 && self.confidence_threshold <= 1.0) {
+            return Err(Error::Config(format!(
+                "Pattern '{}': confidence_threshold must be in (0, 1], got {}",
+                self.name, self.confidence_threshold
+            )));
+        }
+
+        for indicator in &self.indicators {
+            if let Some(weight) = indicator.weight {
+                if !weight.is_finite() || weight <= 0.0 {
+                    return Err(Error::Config(format!(
+                        "Pattern '{}': weight of {:?} must be positive, got {}",
+                        self.name, indicator.indicator, weight
+                    )));
+                }
+            }
+            indicator
+                .indicator
+                .validate()
+                .map_err(|e| Error::Config(format!("Pattern '{}': {}", self.name, e)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 可热更新的模式集合
+///
+/// 克隆共享同一份模式；重新加载失败时保留原有模式
+#[derive(Clone)]
+pub struct PatternSet {
+    patterns: Arc<RwLock<Arc<Vec<Pattern>>>>,
+    dir: Option<PathBuf>,
+}
+
+impl PatternSet {
+    /// 仅包含内置模式
+    pub fn builtin() -> Self {
+        Self::from_patterns(PatternLibrary::load_all())
+    }
+
+    pub fn from_patterns(patterns: Vec<Pattern>) -> Self {
+        Self {
+            patterns: Arc::new(RwLock::new(Arc::new(patterns))),
+            dir: None,
+        }
+    }
+
+    /// 内置模式加上目录中的自定义模式，之后可通过`reload`重新加载
+    pub fn from_dir(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        let mut set = Self::from_patterns(PatternLibrary::load_dir(&dir)?);
+        set.dir = Some(dir);
+        Ok(set)
+    }
+
+    /// 当前模式
+    pub fn current(&self) -> Arc<Vec<Pattern>> {
+        self.patterns.read().unwrap().clone()
+    }
+
+    /// 重新加载模式目录，返回加载的模式数量
+    pub fn reload(&self) -> Result<usize> {
+        let Some(dir) = &self.dir else {
+            return Ok(self.current().len());
+        };
+
+        let patterns = PatternLibrary::load_dir(dir)?;
+        let count = patterns.len();
+        *self.patterns.write().unwrap() = Arc::new(patterns);
+        Ok(count)
+    }
+
+    /// 定期检查模式目录，文件变化时重新加载
+    pub fn spawn_hot_reload(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let set = self.clone();
+
+        tokio::spawn(async move {
+            let Some(dir) = set.dir.clone() else {
+                return;
+            };
+            let mut last_seen = modification_times(&dir);
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+
+                let current = modification_times(&dir);
+                if current == last_seen {
+                    continue;
+                }
+                last_seen = current;
+
+                match set.reload() {
+                    Ok(count) => tracing::info!("Reloaded {} behavior patterns from {}", count, dir.display()),
+                    Err(e) => tracing::warn!("Keeping previous behavior patterns, reload failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+impl Default for PatternSet {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// 模式文件的修改时间，用于检测变化
+fn modification_times(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    pattern_files(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("solsniper-patterns-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_builtin_patterns() {
+        let patterns = PatternLibrary::load_all();
+        assert_eq!(patterns.len(), 5);
+
+        let fast_rug = patterns.iter().find(|p| p.name == "快速撤池 Rug").unwrap();
+        assert!(matches!(fast_rug.risk_level, RiskLevel::Critical));
+        assert!(matches!(
+            fast_rug.indicators[0].indicator,
+            Indicator::SuddenLiquidityDrop { threshold_pct } if threshold_pct == 80.0
+        ));
+        assert_eq!(fast_rug.indicators[0].weight(), 1.0);
+    }
+
+    #[test]
+    fn test_load_dir_overrides_and_validates() {
+        let dir = temp_dir("load");
+        std::fs::write(
+            dir.join("custom.json"),
+            r#"{"patterns": [
+                {"name": "快速撤池 Rug", "confidence_threshold": 0.6, "risk_level": "Critical",
+                 "indicators": [{"type": "SuddenLiquidityDrop", "threshold_pct": 60.0, "weight": 2.0}]},
+                {"name": "狙击集中", "confidence_threshold": 0.5, "risk_level": "High",
+                 "indicators": [{"type": "DistributedHolding", "max_top10_ratio": 0.5}]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let set = PatternSet::from_dir(&dir).unwrap();
+        let patterns = set.current();
+        assert_eq!(patterns.len(), 6);
+        let fast_rug = patterns.iter().find(|p| p.name == "快速撤池 Rug").unwrap();
+        assert_eq!(fast_rug.confidence_threshold, 0.6);
+        assert_eq!(fast_rug.indicators[0].weight(), 2.0);
+
+        // 无效定义不会替换已加载的模式
+        std::fs::write(
+            dir.join("custom.json"),
+            r#"{"patterns": [{"name": "坏模式", "confidence_threshold": 0.5, "risk_level": "High",
+                "indicators": [{"type": "WashTrading", "same_wallet_ratio": 1.5}]}]}"#,
+        )
+        .unwrap();
+        assert!(set.reload().is_err());
+        assert_eq!(set.current().len(), 6);
+
+        // 未知指标类型
+        std::fs::write(
+            dir.join("custom.json"),
+            r#"{"patterns": [{"name": "未知", "confidence_threshold": 0.5, "risk_level": "High",
+                "indicators": [{"type": "MoonSoon"}]}]}"#,
+        )
+        .unwrap();
+        assert!(set.reload().is_err());
+
+        // 两个文件定义同名模式
+        let pattern = r#"{"patterns": [{"name": "狙击集中", "confidence_threshold": 0.5, "risk_level": "High",
+            "indicators": [{"type": "DistributedHolding", "max_top10_ratio": 0.5}]}]}"#;
+        std::fs::write(dir.join("custom.json"), pattern).unwrap();
+        std::fs::write(dir.join("extra.json"), pattern).unwrap();
+        let error = set.reload().unwrap_err().to_string();
+        assert!(error.contains("Duplicate pattern '狙击集中'"));
+        std::fs::remove_file(dir.join("extra.json")).unwrap();
+
+        std::fs::remove_file(dir.join("custom.json")).unwrap();
+        assert_eq!(set.reload().unwrap(), 5);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,46 +1,370 @@
-use solsniper_core::{TokenInfo, BehaviorPattern, Result};
-use crate::{Pattern, PatternMatch, Indicator, PatternLibrary};
+use solsniper_core::{TokenInfo, BehaviorPattern, LiquidityTracker, Result, TradeSide};
+use crate::{Pattern, PatternAnalysis, PatternMatch, Indicator, PatternSet};
+use crate::coordinated_buys::{
+    analyze_coordinated_buys, launch_window_start, profile_candidates, LaunchAnchor, CoordinatedBuyConfig, CoordinatedBuyReport, WalletProfile,
+    WalletProfileSource,
+};
+use crate::snapshots::{
+    InMemorySnapshotStore, PostgresSnapshotStore, SnapshotSeries, TokenSnapshot, TokenSnapshotStore,
+};
+use crate::trade_graph::{PostgresTradeSource, TradeGraph, TradeGraphConfig, TradeSource, WalletLinks};
+use chrono::Utc;
 use sqlx::PgPool;
 use solana_sdk::pubkey::Pubkey;
-use std::collections::{HashMap, HashSet};
-use std::time::Duration;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+
+/// 开盘分析最多读取的成交数
+const LAUNCH_TRADE_LIMIT: usize = 500;
+
+/// 钱包画像缓存容量
+const PROFILE_CACHE_CAPACITY: usize = 50_000;
+
+/// 同时查询的钱包画像数
+const PROFILE_CONCURRENCY: usize = 8;
+
+/// 钱包画像缓存（超过容量时淘汰最早写入的钱包）
+struct ProfileCache {
+    profiles: HashMap<Pubkey, WalletProfile>,
+    order: VecDeque<Pubkey>,
+    capacity: usize,
+}
+
+impl ProfileCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            profiles: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&self, wallet: &Pubkey) -> Option<WalletProfile> {
+        self.profiles.get(wallet).copied()
+    }
+
+    fn insert(&mut self, wallet: Pubkey, profile: WalletProfile) {
+        if self.profiles.insert(wallet, profile).is_none() {
+            self.order.push_back(wallet);
+        }
+        while self.profiles.len() > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.profiles.remove(&oldest);
+        }
+    }
+}
+
+/// 一次匹配中基于成交数据的证据
+#[derive(Default)]
+struct TradeEvidence {
+    graph: Option<TradeGraph>,
+    coordinated: Option<CoordinatedBuyReport>,
+}
 
 /// 行为模式识别器
 pub struct BehaviorPatternRecognizer {
-    /// 已知模式库
-    known_patterns: Vec<Pattern>,
+    /// 已知模式库（可热更新）
+    known_patterns: PatternSet,
+
+    /// 流动性历史（用于判断真实的流动性下降）
+    liquidity: Option<Arc<LiquidityTracker>>,
+
+    /// 代币快照时间序列（指标基于真实变化计算）
+    snapshots: Arc<dyn TokenSnapshotStore>,
+
+    /// 计算指标时回看的时长
+    history_window: chrono::Duration,
+
+    /// 解码后的成交（用于构建交易图，未配置时洗售/刷量使用启发式判断）
+    trades: Option<Arc<dyn TradeSource>>,
 
-    /// 数据库连接(用于查询历史数据)
-    db: Option<PgPool>,
+    /// 钱包关联关系（关联钱包之间的买卖视为自成交）
+    wallet_links: Option<Arc<dyn WalletLinks>>,
+
+    /// 交易图检测参数
+    trade_graph_config: TradeGraphConfig,
+
+    /// 构建交易图至少需要的成交笔数
+    min_graph_trades: usize,
+
+    /// 钱包画像（资金来源、新钱包判断）
+    wallet_profiles: Option<Arc<dyn WalletProfileSource>>,
+
+    /// 已查询的钱包画像
+    profile_cache: RwLock<ProfileCache>,
+
+    /// 协同买入检测参数
+    coordinated_config: CoordinatedBuyConfig,
+}
+
+impl Default for BehaviorPatternRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BehaviorPatternRecognizer {
     pub fn new() -> Self {
         Self {
-            known_patterns: PatternLibrary::load_all(),
-            db: None,
+            known_patterns: PatternSet::builtin(),
+            liquidity: None,
+            snapshots: Arc::new(InMemorySnapshotStore::default()),
+            history_window: chrono::Duration::hours(6),
+            trades: None,
+            wallet_links: None,
+            trade_graph_config: TradeGraphConfig::default(),
+            min_graph_trades: 10,
+            wallet_profiles: None,
+            profile_cache: RwLock::new(ProfileCache::new(PROFILE_CACHE_CAPACITY)),
+            coordinated_config: CoordinatedBuyConfig::default(),
         }
     }
 
     pub fn with_database(db_url: &str) -> Result<Self> {
+        let db = PgPool::connect_lazy(db_url)
+            .map_err(|e| solsniper_core::Error::Database(e.to_string()))?;
+
         Ok(Self {
-            known_patterns: PatternLibrary::load_all(),
-            db: Some(
-                PgPool::connect_lazy(db_url)
-                    .map_err(|e| solsniper_core::Error::Database(e.to_string()))?
-            ),
+            known_patterns: PatternSet::builtin(),
+            snapshots: Arc::new(PostgresSnapshotStore::new(db.clone())),
+            trades: Some(Arc::new(PostgresTradeSource::new(db))),
+            liquidity: None,
+            history_window: chrono::Duration::hours(6),
+            wallet_links: None,
+            trade_graph_config: TradeGraphConfig::default(),
+            min_graph_trades: 10,
+            wallet_profiles: None,
+            profile_cache: RwLock::new(ProfileCache::new(PROFILE_CACHE_CAPACITY)),
+            coordinated_config: CoordinatedBuyConfig::default(),
         })
     }
 
+    /// 使用自定义模式集合（例如`PatternSet::from_dir`加载的模式）
+    pub fn with_patterns(mut self, patterns: PatternSet) -> Self {
+        self.known_patterns = patterns;
+        self
+    }
+
+    /// 当前使用的模式集合
+    pub fn patterns(&self) -> &PatternSet {
+        &self.known_patterns
+    }
+
+    /// 使用自定义快照存储
+    pub fn with_snapshot_store(mut self, store: Arc<dyn TokenSnapshotStore>) -> Self {
+        self.snapshots = store;
+        self
+    }
+
+    /// 设置指标回看时长
+    pub fn with_history_window(mut self, window: chrono::Duration) -> Self {
+        self.history_window = window;
+        self
+    }
+
+    /// 使用自定义成交数据源构建交易图
+    pub fn with_trade_source(mut self, source: Arc<dyn TradeSource>) -> Self {
+        self.trades = Some(source);
+        self
+    }
+
+    /// 钱包关联关系
+    pub fn with_wallet_links(mut self, links: Arc<dyn WalletLinks>) -> Self {
+        self.wallet_links = Some(links);
+        self
+    }
+
+    /// 设置交易图检测参数
+    pub fn with_trade_graph_config(mut self, config: TradeGraphConfig) -> Self {
+        self.trade_graph_config = config;
+        self
+    }
+
+    /// 钱包画像数据源（资金来源、首次活动时间）
+    pub fn with_wallet_profiles(mut self, source: Arc<dyn WalletProfileSource>) -> Self {
+        self.wallet_profiles = Some(source);
+        self
+    }
+
+    /// 设置协同买入检测参数
+    pub fn with_coordinated_buy_config(mut self, config: CoordinatedBuyConfig) -> Self {
+        self.coordinated_config = config;
+        self
+    }
+
+    /// 最近1小时成交构建的交易图
+    ///
+    /// 未配置数据源、查询失败或成交不足时返回None
+    pub async fn trade_graph(&self, mint: &Pubkey) -> Option<TradeGraph> {
+        let source = self.trades.as_ref()?;
+
+        let trades = match source.trades(mint, Utc::now() - chrono::Duration::hours(1)).await {
+            Ok(trades) => trades,
+            Err(e) => {
+                tracing::warn!("Trades unavailable for {} via {}: {}", mint, source.name(), e);
+                return None;
+            }
+        };
+        if trades.len() < self.min_graph_trades {
+            return None;
+        }
+
+        Some(TradeGraph::build(trades, self.wallet_links.as_deref(), &self.trade_graph_config))
+    }
+
+    /// 开盘阶段和最近成交的协同买入分析
+    ///
+    /// 未配置数据源或没有成交时返回None；未配置钱包画像时只按slot/捆绑和已知关联判断
+    pub async fn coordinated_buys(&self, token: &TokenInfo) -> Option<CoordinatedBuyReport> {
+        let graph = self.trade_graph(&token.mint).await;
+        self.analyze_coordinated_buys(token, graph.as_ref()).await
+    }
+
+    async fn analyze_coordinated_buys(
+        &self,
+        token: &TokenInfo,
+        graph: Option<&TradeGraph>,
+    ) -> Option<CoordinatedBuyReport> {
+        let source = self.trades.as_ref()?;
+
+        let mut trades = match source.launch_trades(&token.mint, LAUNCH_TRADE_LIMIT).await {
+            Ok(trades) => trades,
+            Err(e) => {
+                tracing::warn!("Launch trades unavailable for {} via {}: {}", token.mint, source.name(), e);
+                Vec::new()
+            }
+        };
+
+        // 合并最近成交（与开盘成交可能重叠）
+        if let Some(graph) = graph {
+            let seen: HashSet<(String, bool)> = trades
+                .iter()
+                .map(|trade| (trade.signature.clone(), trade.side == TradeSide::Buy))
+                .collect();
+            trades.extend(
+                graph
+                    .trades()
+                    .iter()
+                    .filter(|trade| !seen.contains(&(trade.signature.clone(), trade.side == TradeSide::Buy)))
+                    .cloned(),
+            );
+        }
+        if trades.is_empty() {
+            return None;
+        }
+
+        // 以真实的创建slot为开盘起点，没有记录时退回代币创建时间
+        let anchor = match source.launch_slot(&token.mint).await {
+            Ok(Some(slot)) => LaunchAnchor::Slot(slot),
+            Ok(None) => LaunchAnchor::Time(token.created_at),
+            Err(e) => {
+                tracing::debug!("Launch slot unavailable for {} via {}: {}", token.mint, source.name(), e);
+                LaunchAnchor::Time(token.created_at)
+            }
+        };
+        let launch_slot = launch_window_start(&trades, anchor, &self.coordinated_config);
+
+        let profiles = self
+            .wallet_profiles_of(&profile_candidates(&trades, launch_slot, &self.coordinated_config))
+            .await;
+
+        Some(analyze_coordinated_buys(
+            &trades,
+            launch_slot,
+            &profiles,
+            self.wallet_links.as_deref(),
+            token.total_supply as f64,
+            &self.coordinated_config,
+        ))
+    }
+
+    /// 查询钱包画像（带缓存，未缓存的钱包并发查询）
+    async fn wallet_profiles_of(&self, wallets: &[Pubkey]) -> HashMap<Pubkey, WalletProfile> {
+        let Some(source) = &self.wallet_profiles else {
+            return HashMap::new();
+        };
+
+        let mut profiles = HashMap::new();
+        let mut missing = Vec::new();
+        {
+            let cache = self.profile_cache.read().unwrap();
+            for wallet in wallets {
+                match cache.get(wallet) {
+                    Some(profile) => {
+                        profiles.insert(*wallet, profile);
+                    }
+                    None => missing.push(*wallet),
+                }
+            }
+        }
+
+        let fetched: Vec<(Pubkey, Result<WalletProfile>)> = stream::iter(missing)
+            .map(|wallet| async move { (wallet, source.profile(&wallet).await) })
+            .buffer_unordered(PROFILE_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut cache = self.profile_cache.write().unwrap();
+        for (wallet, profile) in fetched {
+            match profile {
+                Ok(profile) => {
+                    cache.insert(wallet, profile);
+                    profiles.insert(wallet, profile);
+                }
+                Err(e) => tracing::debug!("Wallet profile unavailable for {} via {}: {}", wallet, source.name(), e),
+            }
+        }
+
+        profiles
+    }
+
+    /// 记录当前快照并读取回看窗口内的历史（含当前快照）
+    async fn history(&self, token: &TokenInfo) -> Vec<TokenSnapshot> {
+        let now = Utc::now();
+        if let Err(e) = self.snapshots.record(token, now).await {
+            tracing::warn!("Failed to record snapshot of {} via {}: {}", token.mint, self.snapshots.name(), e);
+        }
+
+        match self.snapshots.snapshots(&token.mint, now - self.history_window).await {
+            Ok(history) if !history.is_empty() => history,
+            Ok(_) => vec![TokenSnapshot { timestamp: now, token: token.clone() }],
+            Err(e) => {
+                tracing::warn!("Snapshot history unavailable for {} via {}: {}", token.mint, self.snapshots.name(), e);
+                vec![TokenSnapshot { timestamp: now, token: token.clone() }]
+            }
+        }
+    }
+
+    /// 使用加池/撤池事件构建的流动性历史
+    pub fn with_liquidity_tracker(mut self, tracker: Arc<LiquidityTracker>) -> Self {
+        self.liquidity = Some(tracker);
+        self
+    }
+
     /// 匹配所有模式
     pub async fn match_patterns(&self, token: &TokenInfo) -> Result<Vec<PatternMatch>> {
+        Ok(self.analyze(token).await?.matches)
+    }
+
+    /// 匹配所有模式，并返回成交数据的分析结果
+    pub async fn analyze(&self, token: &TokenInfo) -> Result<PatternAnalysis> {
         let mut matches = Vec::new();
+        let history = self.history(token).await;
+        let series = SnapshotSeries::new(&history);
+        let graph = self.trade_graph(&token.mint).await;
+        let coordinated = self.analyze_coordinated_buys(token, graph.as_ref()).await;
+        let evidence = TradeEvidence { graph, coordinated };
+
+        let patterns = self.known_patterns.current();
 
-        for pattern in &self.known_patterns {
-            let confidence = self.calculate_pattern_confidence(token, pattern).await?;
+        for pattern in patterns.iter() {
+            let confidence = self.calculate_pattern_confidence(token, &series, &evidence, pattern).await?;
 
             if confidence > pattern.confidence_threshold {
-                let matched_indicators = self.get_matched_indicators(token, pattern).await?;
+                let matched_indicators = self.get_matched_indicators(token, &series, &evidence, pattern).await?;
 
                 matches.push(PatternMatch {
                     pattern: BehaviorPattern {
@@ -59,13 +383,19 @@ impl BehaviorPatternRecognizer {
         // 按置信度排序
         matches.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
 
-        Ok(matches)
+        Ok(PatternAnalysis {
+            matches,
+            trade_graph: evidence.graph.as_ref().map(TradeGraph::report),
+            coordinated_buys: evidence.coordinated,
+        })
     }
 
     /// 计算模式置信度
     async fn calculate_pattern_confidence(
         &self,
         token: &TokenInfo,
+        series: &SnapshotSeries<'_>,
+        evidence: &TradeEvidence,
         pattern: &Pattern,
     ) -> Result<f64> {
         let mut total_weight = 0.0;
@@ -75,7 +405,7 @@ impl BehaviorPatternRecognizer {
             let weight = indicator.weight();
             total_weight += weight;
 
-            if self.matches_indicator(token, indicator).await? {
+            if self.matches_indicator(token, series, evidence, &indicator.indicator).await? {
                 matched_weight += weight;
             }
         }
@@ -91,51 +421,81 @@ impl BehaviorPatternRecognizer {
     async fn matches_indicator(
         &self,
         token: &TokenInfo,
+        series: &SnapshotSeries<'_>,
+        evidence: &TradeEvidence,
         indicator: &Indicator,
     ) -> Result<bool> {
         match indicator {
             Indicator::SuddenLiquidityDrop { threshold_pct } => {
-                // 简化: 检查流动性变化
-                // 实际应该查询历史数据
-                let current_liq = token.liquidity_sol;
-                // TODO: 查询初始流动性
-                let initial_liq = current_liq * 2.0; // 假设
-                let drop_pct = (initial_liq - current_liq) / initial_liq * 100.0;
-                Ok(drop_pct > *threshold_pct)
+                // 以峰值为基准（峰值不低于初始流动性）；没有加池/撤池历史时使用最近1小时的快照
+                let stats = self
+                    .liquidity
+                    .as_ref()
+                    .and_then(|tracker| tracker.stats(&token.mint, chrono::Duration::hours(1)));
+                let drop_pct = match stats {
+                    Some(stats) => Some(stats.drop_from_peak_pct.max(stats.largest_removal_pct)),
+                    None => series
+                        .since(Utc::now() - chrono::Duration::hours(1))
+                        .liquidity_drop_from_peak_pct(),
+                };
+                Ok(drop_pct.is_some_and(|drop_pct| drop_pct > *threshold_pct))
             }
 
             Indicator::CreatorSellOff { threshold_pct } => {
-                self.detect_creator_selloff(token, *threshold_pct).await
+                // 大户持仓比例较峰值大幅下降且价格同时下跌
+                Ok(series
+                    .top_holder_exit()
+                    .is_some_and(|(drop_pct, price_change_pct)| drop_pct >= *threshold_pct && price_change_pct < 0.0))
             }
 
             Indicator::LpUnlock { time_after_launch_hours } => {
-                Ok(!token.lp_locked && token.age_hours >= *time_after_launch_hours as f64)
+                // 有链上解锁时间时以其为准，否则退回lp_locked标志
+                let unlocked = match token.lp_unlock_at {
+                    Some(unlock_at) => unlock_at <= chrono::Utc::now(),
+                    None => !token.lp_locked,
+                };
+                Ok(!token.lp_burned && unlocked && token.age_hours >= *time_after_launch_hours as f64)
             }
 
             Indicator::CoordinatedBuying { wallet_count, timeframe_seconds } => {
-                self.detect_coordinated_buying(token, *wallet_count, *timeframe_seconds).await
+                self.detect_coordinated_buying(token, evidence.coordinated.as_ref(), *wallet_count, *timeframe_seconds)
             }
 
             Indicator::VolumeSpike { multiplier } => {
-                let avg_volume = token.volume_6h / 6.0;
-                Ok(token.volume_1h >= avg_volume * multiplier)
+                // 与1小时前的快照均值比较，历史不足时用6小时均值
+                match series.volume_multiple() {
+                    Some(multiple) => Ok(multiple >= *multiplier),
+                    None => {
+                        let avg_volume = token.volume_6h / 6.0;
+                        Ok(token.volume_1h >= avg_volume * multiplier)
+                    }
+                }
             }
 
             Indicator::PriceParabolicRise { slope } => {
-                Ok(token.price_change_1h > *slope * 100.0)
+                // 涨幅超过阈值且后半小时涨得比前半小时快（加速上涨）
+                match series.price_halves() {
+                    Some((first, second)) => {
+                        let total = ((1.0 + first / 100.0) * (1.0 + second / 100.0) - 1.0) * 100.0;
+                        Ok(total > *slope * 100.0 && second > first && first > 0.0)
+                    }
+                    None => Ok(token.price_change_1h > *slope * 100.0),
+                }
             }
 
             Indicator::OrganicGrowth { holder_increase_rate } => {
-                // 假设初始100个持有者
-                let initial_holders = 100.0;
-                let growth_rate = (token.holders_count as f64 - initial_holders)
-                    / token.age_hours.max(0.1);
-                Ok(growth_rate >= *holder_increase_rate)
+                // 至少半小时、3个快照，持有者持续增加
+                let growth = series.holder_growth_per_hour();
+                Ok(series.len() >= 3
+                    && series.span_hours() >= 0.5
+                    && series.holders_non_decreasing(0.05)
+                    && growth.is_some_and(|growth| growth >= *holder_increase_rate))
             }
 
             Indicator::SteadyVolume { variance } => {
-                // TODO: 计算交易量方差
-                Ok(token.volatility_1h <= *variance)
+                // 1小时成交量的变异系数
+                Ok(series.len() >= 3
+                    && series.volume_variation().is_some_and(|variation| variation <= *variance))
             }
 
             Indicator::DistributedHolding { max_top10_ratio } => {
@@ -143,11 +503,11 @@ impl BehaviorPatternRecognizer {
             }
 
             Indicator::WashTrading { same_wallet_ratio } => {
-                self.detect_wash_trading(token, *same_wallet_ratio).await
+                self.detect_wash_trading(token, evidence.graph.as_ref(), *same_wallet_ratio)
             }
 
             Indicator::FakeVolume { suspicious_tx_ratio } => {
-                self.detect_fake_volume(token, *suspicious_tx_ratio).await
+                self.detect_fake_volume(token, evidence.graph.as_ref(), *suspicious_tx_ratio)
             }
         }
     }
@@ -156,13 +516,31 @@ impl BehaviorPatternRecognizer {
     async fn get_matched_indicators(
         &self,
         token: &TokenInfo,
+        series: &SnapshotSeries<'_>,
+        evidence: &TradeEvidence,
         pattern: &Pattern,
     ) -> Result<Vec<String>> {
         let mut matched = Vec::new();
 
         for indicator in &pattern.indicators {
-            if self.matches_indicator(token, indicator).await? {
-                matched.push(format!("{:?}", indicator));
+            if self.matches_indicator(token, series, evidence, &indicator.indicator).await? {
+                // 基于交易图判断时附上可疑成交量占比
+                let detail = match (&indicator.indicator, &evidence.graph, &evidence.coordinated) {
+                    (Indicator::WashTrading { .. }, Some(graph), _) => {
+                        format!(" (对倒成交量占比 {:.1}%)", graph.report().wash_ratio() * 100.0)
+                    }
+                    (Indicator::FakeVolume { .. }, Some(graph), _) => {
+                        format!(" (可疑成交量占比 {:.1}%)", graph.report().suspicious_ratio() * 100.0)
+                    }
+                    (Indicator::CoordinatedBuying { .. }, _, Some(report)) => format!(
+                        " (协同钱包 {}，狙击买入占供应量 {:.1}%，捆绑买入占 {:.1}%)",
+                        report.coordinated_wallets(),
+                        report.sniper_supply_share.unwrap_or(0.0) * 100.0,
+                        report.bundle_supply_share.unwrap_or(0.0) * 100.0
+                    ),
+                    _ => String::new(),
+                };
+                matched.push(format!("{:?}{}", indicator.indicator, detail));
             }
         }
 
@@ -173,89 +551,31 @@ impl BehaviorPatternRecognizer {
     // Advanced Detection Methods
     // ========================================
 
-    /// 检测创建者卖出行为
-    ///
-    /// 通过分析以下指标:
-    /// 1. 识别创建者地址（pool创建者或最大初始持有者）
-    /// 2. 查询创建者的交易历史
-    /// 3. 计算卖出比例
-    async fn detect_creator_selloff(&self, token: &TokenInfo, threshold_pct: f64) -> Result<bool> {
-        // 如果没有数据库连接，使用启发式方法
-        if self.db.is_none() {
-            return self.heuristic_creator_selloff(token, threshold_pct);
-        }
-
-        // TODO: 实际数据库查询实现
-        // 1. 从数据库查询pool创建交易，提取创建者地址
-        // 2. 查询创建者的所有卖出交易
-        // 3. 计算卖出比例
-
-        // 临时启发式方法
-        self.heuristic_creator_selloff(token, threshold_pct)
-    }
-
-    /// 启发式创建者卖出检测
-    ///
-    /// 基于间接指标:
-    /// - 流动性突降 + top10持有量下降 = 可能创建者卖出
-    /// - 高卖压（sells >> buys）+ 价格暴跌
-    fn heuristic_creator_selloff(&self, token: &TokenInfo, threshold_pct: f64) -> Result<bool> {
-        // 指标1: Top10持有比例是否异常低（可能已卖出）
-        let top10_suspiciously_low = token.top10_ratio < 0.3 && token.age_hours < 24.0;
-
-        // 指标2: 流动性相对于总供应量过低（创建者可能移除了流动性）
-        let liquidity_too_low = token.liquidity_sol < 5.0 && token.age_hours > 1.0;
-
-        // 指标3: 交易数据显示大量卖出
-        let heavy_sell_pressure = if token.txns_1h_total > 0 {
-            let sell_ratio = token.txns_1h_sells as f64 / token.txns_1h_total as f64;
-            sell_ratio > 0.7 // 70%以上是卖单
-        } else {
-            false
-        };
-
-        // 指标4: 价格持续暴跌
-        let price_crash = token.price_change_1h < -30.0 && token.price_change_6h < -50.0;
-
-        // 综合判断：至少满足3个指标
-        let indicator_count = [
-            top10_suspiciously_low,
-            liquidity_too_low,
-            heavy_sell_pressure,
-            price_crash,
-        ]
-        .iter()
-        .filter(|&&x| x)
-        .count();
-
-        Ok(indicator_count >= 3)
-    }
-
     /// 检测协同买入（pump组织）
     ///
     /// 特征:
-    /// 1. 短时间内多个新钱包买入
-    /// 2. 买入金额相近
-    /// 3. 买入时间间隔极短
-    async fn detect_coordinated_buying(
+    /// 1. 开盘前几个区块内多个新钱包买入
+    /// 2. 同一slot/Jito捆绑买入
+    /// 3. 买入钱包由同一地址资助
+    ///
+    /// 开盘新钱包数或`timeframe_seconds`内的协同钱包数达到`min_wallet_count`时命中；
+    /// 没有成交数据时使用启发式判断
+    fn detect_coordinated_buying(
         &self,
         token: &TokenInfo,
-        min_wallet_count: u32,
+        report: Option<&CoordinatedBuyReport>,
+        min_wallet_count: usize,
         timeframe_seconds: u64,
     ) -> Result<bool> {
-        if self.db.is_none() {
-            return self.heuristic_coordinated_buying(token, min_wallet_count);
+        match report {
+            Some(report) => Ok(report.fresh_launch_buyers.as_ref().map_or(0, Vec::len) >= min_wallet_count
+                || report.max_wallets_within(chrono::Duration::seconds(timeframe_seconds as i64)) >= min_wallet_count),
+            None => self.heuristic_coordinated_buying(token),
         }
-
-        // TODO: 数据库查询实现
-        // 查询最近timeframe_seconds秒内的所有买入交易
-        // 分析钱包地址、金额、时间间隔
-
-        self.heuristic_coordinated_buying(token, min_wallet_count)
     }
 
     /// 启发式协同买入检测
-    fn heuristic_coordinated_buying(&self, token: &TokenInfo, min_wallet_count: u32) -> Result<bool> {
+    fn heuristic_coordinated_buying(&self, token: &TokenInfo) -> Result<bool> {
         // 指标1: 短时间内持有者数量激增
         let holder_growth_rate = token.holders_count as f64 / token.age_hours.max(0.1);
         let rapid_holder_growth = holder_growth_rate > 50.0; // 每小时新增50+持有者
@@ -287,23 +607,24 @@ impl BehaviorPatternRecognizer {
     ///
     /// 特征:
     /// 1. 同一钱包在短时间内反复买入卖出
-    /// 2. 交易金额相近
+    /// 2. 关联钱包之间互相买卖
     /// 3. 交易形成循环（A->B->A）
-    async fn detect_wash_trading(&self, token: &TokenInfo, max_same_wallet_ratio: f64) -> Result<bool> {
-        if self.db.is_none() {
-            return self.heuristic_wash_trading(token, max_same_wallet_ratio);
+    ///
+    /// 对倒成交量占比达到`max_same_wallet_ratio`时命中；没有交易图时使用启发式判断
+    fn detect_wash_trading(
+        &self,
+        token: &TokenInfo,
+        graph: Option<&TradeGraph>,
+        max_same_wallet_ratio: f64,
+    ) -> Result<bool> {
+        match graph {
+            Some(graph) => Ok(graph.report().wash_ratio() >= max_same_wallet_ratio),
+            None => self.heuristic_wash_trading(token),
         }
-
-        // TODO: 数据库查询实现
-        // 1. 查询最近1小时的所有交易
-        // 2. 构建交易图，检测循环
-        // 3. 统计同一钱包交易比例
-
-        self.heuristic_wash_trading(token, max_same_wallet_ratio)
     }
 
     /// 启发式洗盘检测
-    fn heuristic_wash_trading(&self, token: &TokenInfo, max_same_wallet_ratio: f64) -> Result<bool> {
+    fn heuristic_wash_trading(&self, token: &TokenInfo) -> Result<bool> {
         // 指标1: 高交易量但持有者数量不增长
         let high_volume_low_holder_growth = token.volume_1h > 500.0 && token.holders_count < 50;
 
@@ -338,24 +659,24 @@ impl BehaviorPatternRecognizer {
     /// 检测虚假交易量
     ///
     /// 特征:
-    /// 1. 机器人账户交易（新账户、无历史）
-    /// 2. 交易金额整数化（如正好1 SOL、10 SOL）
-    /// 3. 交易时间规律（每N秒一次）
-    async fn detect_fake_volume(&self, token: &TokenInfo, max_suspicious_ratio: f64) -> Result<bool> {
-        if self.db.is_none() {
-            return self.heuristic_fake_volume(token, max_suspicious_ratio);
+    /// 1. 对倒成交（往返、关联钱包、循环）
+    /// 2. 固定金额的机器人成交
+    ///
+    /// 可疑成交量占比达到`max_suspicious_ratio`时命中；没有交易图时使用启发式判断
+    fn detect_fake_volume(
+        &self,
+        token: &TokenInfo,
+        graph: Option<&TradeGraph>,
+        max_suspicious_ratio: f64,
+    ) -> Result<bool> {
+        match graph {
+            Some(graph) => Ok(graph.report().suspicious_ratio() >= max_suspicious_ratio),
+            None => self.heuristic_fake_volume(token),
         }
-
-        // TODO: 数据库查询实现
-        // 1. 查询所有交易
-        // 2. 检测机器人模式：规律时间、整数金额
-        // 3. 统计可疑交易比例
-
-        self.heuristic_fake_volume(token, max_suspicious_ratio)
     }
 
     /// 启发式虚假交易量检测
-    fn heuristic_fake_volume(&self, token: &TokenInfo, max_suspicious_ratio: f64) -> Result<bool> {
+    fn heuristic_fake_volume(&self, token: &TokenInfo) -> Result<bool> {
         // 指标1: 交易量极高但持有者极少（明显刷量）
         let volume_holder_mismatch = token.volume_24h > 10000.0 && token.holders_count < 100;
 
@@ -393,13 +714,12 @@ impl BehaviorPatternRecognizer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trade_graph::InMemoryTradeStore;
     use chrono::Utc;
+    use solsniper_core::SwapTrade;
 
-    #[tokio::test]
-    async fn test_pattern_recognition() {
-        let recognizer = BehaviorPatternRecognizer::new();
-
-        let token = TokenInfo {
+    fn sample_token() -> TokenInfo {
+        TokenInfo {
             mint: Pubkey::new_unique(),
             symbol: "TEST".to_string(),
             name: "Test Token".to_string(),
@@ -407,15 +727,13 @@ mod tests {
             liquidity_sol: 10.0, // 低流动性
             liquidity_usd: 1000.0,
             lp_locked: false, // 未锁定
-            lp_burned: false,
             total_supply: 1_000_000_000,
             circulating_supply: 1_000_000_000,
             holders_count: 50,
-            top10_ratio: 0.8, // 高度集中
+            top10_ratio: 0.3, // 大户已卖出
             top20_ratio: 0.9,
             top50_ratio: 0.95,
             mint_authority_revoked: false, // 未撤销权限
-            freeze_authority_revoked: false,
             buy_tax: 5.0,
             sell_tax: 10.0, // 高卖出税
             created_at: Utc::now(),
@@ -436,10 +754,25 @@ mod tests {
             telegram_members: 20,
             discord_members: Some(10),
             sentiment_score: 0.2, // 负面情绪
-            is_verified: false,
             pool_address: Some(Pubkey::new_unique()),
             dex: "Raydium".to_string(),
-        };
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pattern_recognition() {
+        let store = Arc::new(InMemorySnapshotStore::default());
+        let recognizer = BehaviorPatternRecognizer::new().with_snapshot_store(store.clone());
+
+        let token = sample_token();
+
+        // 30分钟前：流动性80 SOL，Top10持有85%，价格是现在的3倍
+        let mut earlier = token.clone();
+        earlier.liquidity_sol = 80.0;
+        earlier.top10_ratio = 0.85;
+        earlier.price_usd = 0.00003;
+        store.record(&earlier, Utc::now() - chrono::Duration::minutes(30)).await.unwrap();
 
         let matches = recognizer.match_patterns(&token).await.unwrap();
 
@@ -455,4 +788,127 @@ mod tests {
         // 这个代币特征应该匹配 Rug Pull 模式
         assert!(matches.iter().any(|m| m.pattern.name.contains("Rug")));
     }
+
+    #[tokio::test]
+    async fn test_indicators_need_history() {
+        let recognizer = BehaviorPatternRecognizer::new();
+        let mut token = sample_token();
+        token.holders_count = 5_000;
+        token.age_hours = 1.0;
+
+        // 只有一个快照时不判定有机增长或稳定交易量
+        let history = recognizer.history(&token).await;
+        let series = SnapshotSeries::new(&history);
+        let organic = Indicator::OrganicGrowth { holder_increase_rate: 10.0 };
+        let steady = Indicator::SteadyVolume { variance: 0.3 };
+        assert!(!recognizer.matches_indicator(&token, &series, &TradeEvidence::default(), &organic).await.unwrap());
+        assert!(!recognizer.matches_indicator(&token, &series, &TradeEvidence::default(), &steady).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_wash_trading_from_trade_graph() {
+        let trades = Arc::new(InMemoryTradeStore::default());
+        let recognizer = BehaviorPatternRecognizer::new().with_trade_source(trades.clone());
+        let token = sample_token();
+        let washer = Pubkey::new_unique();
+
+        // 同一钱包反复买入后卖出相同数量
+        for i in 0..6 {
+            for (side, offset) in [(TradeSide::Buy, 0), (TradeSide::Sell, 20)] {
+                trades.record(SwapTrade {
+                    mint: token.mint,
+                    trader: washer,
+                    side,
+                    sol_amount: 1.0 + i as f64 * 0.5,
+                    token_amount: 10_000.0 + i as f64 * 5_000.0,
+                    signature: format!("wash-{}-{}", i, offset),
+                    slot: 100 + i * 10 + offset as u64,
+                    timestamp: Utc::now() - chrono::Duration::minutes(30) + chrono::Duration::seconds(i as i64 * 60 + offset),
+                    jito_tip_lamports: None,
+                });
+            }
+        }
+
+        let graph = recognizer.trade_graph(&token.mint).await.unwrap();
+        assert_eq!(graph.report().wash_ratio(), 1.0);
+
+        let history = recognizer.history(&token).await;
+        let series = SnapshotSeries::new(&history);
+        let wash = Indicator::WashTrading { same_wallet_ratio: 0.6 };
+        let evidence = TradeEvidence { graph: Some(graph), coordinated: None };
+        assert!(recognizer.matches_indicator(&token, &series, &evidence, &wash).await.unwrap());
+    }
+
+    /// 固定画像数据源，记录查询次数
+    struct StubProfiles {
+        profiles: HashMap<Pubkey, WalletProfile>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl WalletProfileSource for StubProfiles {
+        async fn profile(&self, wallet: &Pubkey) -> Result<WalletProfile> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(self.profiles.get(wallet).copied().unwrap_or_default())
+        }
+
+        fn name(&self) -> &str {
+            "stub"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coordinated_buys_profile_later_buyers() {
+        let trades = Arc::new(InMemoryTradeStore::default());
+        let token = sample_token();
+        let funder = Pubkey::new_unique();
+        let (launcher, late_a, late_b) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        // 开盘一笔买入，之后两个同资金来源的钱包在不同slot买入
+        for (i, wallet) in [launcher, late_a, late_b].into_iter().enumerate() {
+            trades.record(SwapTrade {
+                mint: token.mint,
+                trader: wallet,
+                side: TradeSide::Buy,
+                sol_amount: 1.0,
+                token_amount: 10_000_000.0,
+                signature: format!("buy-{}", i),
+                slot: 100 + i as u64 * 500,
+                timestamp: Utc::now() - chrono::Duration::minutes(30) + chrono::Duration::minutes(i as i64 * 5),
+                jito_tip_lamports: None,
+            });
+        }
+
+        let source = Arc::new(StubProfiles {
+            profiles: [late_a, late_b]
+                .into_iter()
+                .map(|wallet| (wallet, WalletProfile { funder: Some(funder), first_seen: None }))
+                .collect(),
+            calls: Default::default(),
+        });
+        let recognizer = BehaviorPatternRecognizer::new()
+            .with_trade_source(trades)
+            .with_wallet_profiles(source.clone());
+
+        let report = recognizer.coordinated_buys(&token).await.unwrap();
+        assert_eq!(report.funding_clusters.len(), 1);
+        assert_eq!(report.funding_clusters[0].funder, funder);
+        assert_eq!(source.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        // 第二次评估命中缓存
+        recognizer.coordinated_buys(&token).await.unwrap();
+        assert_eq!(source.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_profile_cache_evicts_oldest() {
+        let mut cache = ProfileCache::new(2);
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        cache.insert(a, WalletProfile::default());
+        cache.insert(b, WalletProfile::default());
+        cache.insert(c, WalletProfile::default());
+
+        assert!(cache.get(&a).is_none());
+        assert!(cache.get(&b).is_some() && cache.get(&c).is_some());
+    }
 }
@@ -0,0 +1,533 @@
+use solsniper_core::{Error, Result, TokenInfo};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{PgPool, Row};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+
+/// 两次清理过期快照的间隔
+const PRUNE_INTERVAL_MINUTES: i64 = 60;
+
+/// 节流表的最大mint数（超过时丢弃已过节流间隔的记录）
+const MAX_THROTTLED_MINTS: usize = 50_000;
+
+/// 某一时刻的代币状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub token: TokenInfo,
+}
+
+/// 代币快照时间序列存储trait
+#[async_trait]
+pub trait TokenSnapshotStore: Send + Sync {
+    /// 记录代币在`at`时刻的状态
+    async fn record(&self, token: &TokenInfo, at: DateTime<Utc>) -> Result<()>;
+
+    /// `since`之后的快照（按时间正序）
+    async fn snapshots(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<TokenSnapshot>>;
+
+    /// 存储名称
+    fn name(&self) -> &str;
+}
+
+/// 内存快照存储
+///
+/// 每个mint保留一个定长环形缓冲区；间隔过近的快照覆盖上一条
+pub struct InMemorySnapshotStore {
+    series: RwLock<HashMap<Pubkey, VecDeque<TokenSnapshot>>>,
+
+    /// 每个mint保留的快照数
+    capacity: usize,
+
+    /// 最多跟踪的mint数（超过时淘汰最久未更新的）
+    max_mints: usize,
+
+    /// 两条快照的最小间隔
+    min_interval: Duration,
+}
+
+impl InMemorySnapshotStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            series: RwLock::new(HashMap::new()),
+            capacity,
+            max_mints: 5_000,
+            min_interval: Duration::seconds(10),
+        }
+    }
+
+    pub fn with_max_mints(mut self, max_mints: usize) -> Self {
+        self.max_mints = max_mints;
+        self
+    }
+
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    pub fn tracked_mints(&self) -> usize {
+        self.series.read().unwrap().len()
+    }
+}
+
+impl Default for InMemorySnapshotStore {
+    fn default() -> Self {
+        // 10秒间隔下约2小时，间隔更大时覆盖更长时间
+        Self::new(720)
+    }
+}
+
+#[async_trait]
+impl TokenSnapshotStore for InMemorySnapshotStore {
+    async fn record(&self, token: &TokenInfo, at: DateTime<Utc>) -> Result<()> {
+        let mut series = self.series.write().unwrap();
+
+        if !series.contains_key(&token.mint) && series.len() >= self.max_mints {
+            let stalest = series
+                .iter()
+                .min_by_key(|(_, snapshots)| snapshots.back().map(|s| s.timestamp))
+                .map(|(mint, _)| *mint);
+            if let Some(mint) = stalest {
+                series.remove(&mint);
+            }
+        }
+
+        let snapshots = series.entry(token.mint).or_default();
+        let snapshot = TokenSnapshot {
+            timestamp: at,
+            token: token.clone(),
+        };
+
+        match snapshots.back().map(|last| last.timestamp) {
+            Some(last) if at < last => {
+                // 乱序快照按时间插入
+                let index = snapshots.partition_point(|s| s.timestamp <= at);
+                snapshots.insert(index, snapshot);
+            }
+            Some(last) if at - last < self.min_interval => {
+                snapshots.pop_back();
+                snapshots.push_back(snapshot);
+            }
+            _ => snapshots.push_back(snapshot),
+        }
+
+        while snapshots.len() > self.capacity {
+            snapshots.pop_front();
+        }
+
+        Ok(())
+    }
+
+    async fn snapshots(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<TokenSnapshot>> {
+        Ok(self
+            .series
+            .read()
+            .unwrap()
+            .get(mint)
+            .map(|snapshots| {
+                snapshots
+                    .iter()
+                    .filter(|s| s.timestamp >= since)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn name(&self) -> &str {
+        "memory"
+    }
+}
+
+/// PostgreSQL快照存储
+///
+/// 写入`token_snapshots`表，TokenInfo以JSONB保存。
+/// 同一mint距上次写入不足`min_interval`的快照直接丢弃，写入时每小时清理一次超过保留期的快照
+pub struct PostgresSnapshotStore {
+    pool: PgPool,
+    /// 单次读取的最大快照数
+    max_rows: i64,
+
+    /// 同一mint两条快照的最小间隔
+    min_interval: Duration,
+
+    /// 快照保留时长
+    retention: Duration,
+
+    /// mint -> 上次写入的快照时间
+    last_recorded: Mutex<HashMap<Pubkey, DateTime<Utc>>>,
+
+    /// 上次清理过期快照的时间
+    last_pruned: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl PostgresSnapshotStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            max_rows: 2_000,
+            min_interval: Duration::seconds(10),
+            retention: Duration::days(7),
+            last_recorded: Mutex::new(HashMap::new()),
+            last_pruned: Mutex::new(None),
+        }
+    }
+
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// 是否写入该快照（距同一mint上次写入不足最小间隔时跳过，乱序快照照常写入）
+    fn should_record(&self, mint: &Pubkey, at: DateTime<Utc>) -> bool {
+        let mut last_recorded = self.last_recorded.lock().unwrap();
+
+        if let Some(last) = last_recorded.get(mint) {
+            if at >= *last && at - *last < self.min_interval {
+                return false;
+            }
+        }
+
+        if last_recorded.len() >= MAX_THROTTLED_MINTS {
+            let cutoff = at - self.min_interval;
+            last_recorded.retain(|_, last| *last >= cutoff);
+        }
+        let last = last_recorded.entry(*mint).or_insert(at);
+        *last = (*last).max(at);
+
+        true
+    }
+
+    /// 距上次清理超过间隔时返回true并记录本次清理时间
+    fn prune_due(&self, now: DateTime<Utc>) -> bool {
+        let mut last_pruned = self.last_pruned.lock().unwrap();
+        match *last_pruned {
+            Some(last) if now - last < Duration::minutes(PRUNE_INTERVAL_MINUTES) => false,
+            _ => {
+                *last_pruned = Some(now);
+                true
+            }
+        }
+    }
+
+    /// 删除超过保留期的快照
+    async fn prune(&self, now: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM token_snapshots WHERE created_at < $1")
+            .bind(now - self.retention)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Token snapshot cleanup failed: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl TokenSnapshotStore for PostgresSnapshotStore {
+    async fn record(&self, token: &TokenInfo, at: DateTime<Utc>) -> Result<()> {
+        if !self.should_record(&token.mint, at) {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        if self.prune_due(now) {
+            match self.prune(now).await {
+                Ok(deleted) if deleted > 0 => tracing::info!("🧹 清理 {} 条过期代币快照", deleted),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+
+        let data = serde_json::to_value(token)
+            .map_err(|e| Error::Serialization(format!("Token snapshot serialization failed: {}", e)))?;
+
+        sqlx::query("INSERT INTO token_snapshots (mint, data, created_at) VALUES ($1, $2, $3)")
+            .bind(token.mint.to_string())
+            .bind(data)
+            .bind(at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Token snapshot insert failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn snapshots(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<TokenSnapshot>> {
+        let rows = sqlx::query(
+            "SELECT data, created_at FROM (
+                 SELECT data, created_at FROM token_snapshots
+                 WHERE mint = $1 AND created_at >= $2
+                 ORDER BY created_at DESC
+                 LIMIT $3
+             ) recent
+             ORDER BY created_at ASC",
+        )
+        .bind(mint.to_string())
+        .bind(since)
+        .bind(self.max_rows)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Token snapshot query failed: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let data: serde_json::Value = row
+                    .try_get("data")
+                    .map_err(|e| Error::Database(format!("Token snapshot decode failed: {}", e)))?;
+                let timestamp: DateTime<Utc> = row
+                    .try_get("created_at")
+                    .map_err(|e| Error::Database(format!("Token snapshot decode failed: {}", e)))?;
+
+                Ok(TokenSnapshot {
+                    timestamp,
+                    token: serde_json::from_value(data)
+                        .map_err(|e| Error::Serialization(format!("Invalid token snapshot: {}", e)))?,
+                })
+            })
+            .collect()
+    }
+
+    fn name(&self) -> &str {
+        "postgres"
+    }
+}
+
+/// 基于快照序列计算的指标
+pub struct SnapshotSeries<'a> {
+    snapshots: &'a [TokenSnapshot],
+}
+
+impl<'a> SnapshotSeries<'a> {
+    /// `snapshots`须按时间正序
+    pub fn new(snapshots: &'a [TokenSnapshot]) -> Self {
+        Self { snapshots }
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// `at`之后的快照
+    pub fn since(&self, at: DateTime<Utc>) -> SnapshotSeries<'a> {
+        let start = self.snapshots.partition_point(|s| s.timestamp < at);
+        SnapshotSeries::new(&self.snapshots[start..])
+    }
+
+    /// 首尾快照的时间跨度（小时）
+    pub fn span_hours(&self) -> f64 {
+        match (self.snapshots.first(), self.snapshots.last()) {
+            (Some(first), Some(last)) => (last.timestamp - first.timestamp).num_seconds() as f64 / 3600.0,
+            _ => 0.0,
+        }
+    }
+
+    /// 每小时新增持有者数
+    pub fn holder_growth_per_hour(&self) -> Option<f64> {
+        let (first, last) = (self.snapshots.first()?, self.snapshots.last()?);
+        let hours = self.span_hours();
+        if hours <= 0.0 {
+            return None;
+        }
+        Some((last.token.holders_count as f64 - first.token.holders_count as f64) / hours)
+    }
+
+    /// 持有者数是否从未明显减少（单步减少不超过`tolerance`比例）
+    pub fn holders_non_decreasing(&self, tolerance: f64) -> bool {
+        self.snapshots.windows(2).all(|pair| {
+            let before = pair[0].token.holders_count as f64;
+            let after = pair[1].token.holders_count as f64;
+            after >= before * (1.0 - tolerance)
+        })
+    }
+
+    /// 1小时成交量的变异系数（标准差/均值）
+    pub fn volume_variation(&self) -> Option<f64> {
+        let volumes: Vec<f64> = self.snapshots.iter().map(|s| s.token.volume_1h).collect();
+        let mean = volumes.iter().sum::<f64>() / volumes.len().max(1) as f64;
+        if volumes.len() < 2 || mean <= 0.0 {
+            return None;
+        }
+        let variance = volumes.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / volumes.len() as f64;
+        Some(variance.sqrt() / mean)
+    }
+
+    /// 流动性较窗口内峰值下降的百分比
+    pub fn liquidity_drop_from_peak_pct(&self) -> Option<f64> {
+        let current = self.snapshots.last()?.token.liquidity_sol;
+        let peak = self
+            .snapshots
+            .iter()
+            .map(|s| s.token.liquidity_sol)
+            .fold(f64::NEG_INFINITY, f64::max);
+        if peak <= 0.0 {
+            return None;
+        }
+        Some(((peak - current) / peak * 100.0).max(0.0))
+    }
+
+    /// Top10持有比例较窗口内峰值的相对下降（%），以及期间的价格变化（%）
+    ///
+    /// 大户（通常包含部署者）集中卖出时Top10比例下降、价格同时下跌
+    pub fn top_holder_exit(&self) -> Option<(f64, f64)> {
+        let last = self.snapshots.last()?;
+        let peak = self
+            .snapshots
+            .iter()
+            .max_by(|a, b| a.token.top10_ratio.total_cmp(&b.token.top10_ratio))?;
+        if peak.token.top10_ratio <= 0.0 || peak.token.price_usd <= 0.0 {
+            return None;
+        }
+
+        let drop_pct = (peak.token.top10_ratio - last.token.top10_ratio) / peak.token.top10_ratio * 100.0;
+        let price_change_pct = (last.token.price_usd - peak.token.price_usd) / peak.token.price_usd * 100.0;
+        Some((drop_pct.max(0.0), price_change_pct))
+    }
+
+    /// 当前1小时成交量相对此前快照平均值的倍数
+    pub fn volume_multiple(&self) -> Option<f64> {
+        let (last, earlier) = self.snapshots.split_last()?;
+        let cutoff = last.timestamp - Duration::hours(1);
+        let baseline: Vec<f64> = earlier
+            .iter()
+            .filter(|s| s.timestamp <= cutoff)
+            .map(|s| s.token.volume_1h)
+            .collect();
+        let mean = baseline.iter().sum::<f64>() / baseline.len().max(1) as f64;
+        if baseline.is_empty() || mean <= 0.0 {
+            return None;
+        }
+        Some(last.token.volume_1h / mean)
+    }
+
+    /// 最近1小时内前后两半的涨幅（%）
+    pub fn price_halves(&self) -> Option<(f64, f64)> {
+        let last = self.snapshots.last()?;
+        let start = last.timestamp - Duration::hours(1);
+        let middle = last.timestamp - Duration::minutes(30);
+
+        let price_at = |at: DateTime<Utc>| -> Option<f64> {
+            self.snapshots
+                .iter()
+                .rev()
+                .find(|s| s.timestamp <= at)
+                .map(|s| s.token.price_usd)
+                .filter(|price| *price > 0.0)
+        };
+
+        let (p0, p1, p2) = (price_at(start)?, price_at(middle)?, last.token.price_usd);
+        Some(((p1 - p0) / p0 * 100.0, (p2 - p1) / p1 * 100.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(mint: Pubkey, holders: u64, volume_1h: f64, liquidity_sol: f64) -> TokenInfo {
+        TokenInfo {
+            mint,
+            symbol: "SNAP".to_string(),
+            name: "Snapshot Token".to_string(),
+            decimals: 6,
+            liquidity_sol,
+            liquidity_usd: liquidity_sol * 150.0,
+            lp_locked: true,
+            total_supply: 1_000_000_000,
+            circulating_supply: 1_000_000_000,
+            holders_count: holders,
+            top10_ratio: 0.35,
+            top20_ratio: 0.5,
+            top50_ratio: 0.7,
+            mint_authority_revoked: true,
+            freeze_authority_revoked: true,
+            created_at: Utc::now(),
+            age_minutes: 300,
+            age_hours: 5.0,
+            txns_1h_total: 100,
+            txns_1h_buys: 60,
+            txns_1h_sells: 40,
+            volume_1h,
+            volume_6h: volume_1h * 6.0,
+            volume_24h: volume_1h * 24.0,
+            price_usd: 0.0001,
+            volatility_1h: 0.1,
+            twitter_mentions: 10,
+            telegram_members: 100,
+            sentiment_score: 0.5,
+            dex: "Raydium".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ring_buffer_and_min_interval() {
+        let store = InMemorySnapshotStore::new(3).with_min_interval(Duration::seconds(30));
+        let mint = Pubkey::new_unique();
+        let start = Utc::now() - Duration::hours(1);
+
+        for i in 0..5 {
+            store.record(&token(mint, 100 + i, 1000.0, 50.0), start + Duration::minutes(i as i64)).await.unwrap();
+        }
+        // 间隔过近，覆盖最后一条
+        store.record(&token(mint, 200, 1000.0, 50.0), start + Duration::seconds(250)).await.unwrap();
+
+        let snapshots = store.snapshots(&mint, start).await.unwrap();
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots.last().unwrap().token.holders_count, 200);
+        assert!(snapshots.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    }
+
+    #[tokio::test]
+    async fn test_postgres_store_throttles_and_prunes_hourly() {
+        let pool = PgPool::connect_lazy("postgres://localhost/solsniper").unwrap();
+        let store = PostgresSnapshotStore::new(pool).with_min_interval(Duration::seconds(30));
+        let mint = Pubkey::new_unique();
+        let start = Utc::now();
+
+        assert!(store.should_record(&mint, start));
+        assert!(!store.should_record(&mint, start + Duration::seconds(10)));
+        assert!(store.should_record(&mint, start + Duration::seconds(30)));
+        // 乱序快照不节流
+        assert!(store.should_record(&mint, start - Duration::minutes(5)));
+        assert!(store.should_record(&Pubkey::new_unique(), start));
+
+        assert!(store.prune_due(start));
+        assert!(!store.prune_due(start + Duration::minutes(30)));
+        assert!(store.prune_due(start + Duration::minutes(61)));
+    }
+
+    #[tokio::test]
+    async fn test_series_deltas() {
+        let store = InMemorySnapshotStore::default();
+        let mint = Pubkey::new_unique();
+        let start = Utc::now() - Duration::hours(4);
+
+        for (i, (holders, volume, liquidity)) in [(100, 1000.0, 80.0), (140, 1100.0, 80.0), (180, 900.0, 60.0), (220, 1000.0, 20.0)]
+            .into_iter()
+            .enumerate()
+        {
+            store.record(&token(mint, holders, volume, liquidity), start + Duration::hours(i as i64)).await.unwrap();
+        }
+
+        let snapshots = store.snapshots(&mint, start).await.unwrap();
+        let series = SnapshotSeries::new(&snapshots);
+
+        assert!((series.holder_growth_per_hour().unwrap() - 40.0).abs() < 1e-9);
+        assert!(series.holders_non_decreasing(0.05));
+        assert!(series.volume_variation().unwrap() < 0.1);
+        assert!((series.liquidity_drop_from_peak_pct().unwrap() - 75.0).abs() < 1e-9);
+    }
+}
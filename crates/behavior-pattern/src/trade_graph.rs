@@ -0,0 +1,907 @@
+use solsniper_core::{Error, Result, SwapTrade, TradeSide};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use solana_sdk::pubkey::Pubkey;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+/// 代币成交数据源trait
+#[async_trait]
+pub trait TradeSource: Send + Sync {
+    /// mint在`since`之后的成交（按时间升序）
+    async fn trades(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<SwapTrade>>;
+
+    /// mint最早的`limit`笔成交（按时间升序，用于开盘分析）
+    async fn launch_trades(&self, mint: &Pubkey, limit: usize) -> Result<Vec<SwapTrade>>;
+
+    /// 建池或创建mint交易所在slot，数据源不知道时为None
+    async fn launch_slot(&self, _mint: &Pubkey) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// 数据源名称
+    fn name(&self) -> &str;
+}
+
+/// 单个mint的内存成交
+#[derive(Default)]
+struct MintTrades {
+    /// 最早观察到的若干笔（按slot升序，开盘分析用，不随新成交淘汰）
+    launch: Vec<SwapTrade>,
+    /// 最近的若干笔（按时间升序）
+    recent: VecDeque<SwapTrade>,
+}
+
+/// 内存成交记录
+///
+/// 由实时解码的swap写入，每个mint保留最早观察到的若干笔和最近的若干笔。
+/// 最早的成交只有在开盘前就开始订阅时才是开盘成交，开盘分析据真实创建slot判断是否覆盖（见`launch_window_start`）
+pub struct InMemoryTradeStore {
+    trades: RwLock<HashMap<Pubkey, MintTrades>>,
+    max_per_mint: usize,
+    max_launch_per_mint: usize,
+    max_mints: usize,
+}
+
+impl InMemoryTradeStore {
+    pub fn new(max_per_mint: usize, max_mints: usize) -> Self {
+        Self {
+            trades: RwLock::new(HashMap::new()),
+            max_per_mint,
+            max_launch_per_mint: 200,
+            max_mints,
+        }
+    }
+
+    /// 每个mint单独保留的最早成交笔数
+    pub fn with_max_launch_per_mint(mut self, max_launch_per_mint: usize) -> Self {
+        self.max_launch_per_mint = max_launch_per_mint;
+        self
+    }
+
+    /// 记录一笔成交
+    pub fn record(&self, trade: SwapTrade) {
+        let mut trades = self.trades.write().unwrap();
+
+        // 超过mint上限时丢弃最久没有成交的mint
+        if !trades.contains_key(&trade.mint) && trades.len() >= self.max_mints {
+            let stalest = trades
+                .iter()
+                .min_by_key(|(_, history)| history.recent.back().map(|trade| trade.timestamp))
+                .map(|(mint, _)| *mint);
+            if let Some(mint) = stalest {
+                trades.remove(&mint);
+            }
+        }
+
+        let history = trades.entry(trade.mint).or_default();
+
+        let launch_position = history
+            .launch
+            .partition_point(|existing| (existing.slot, existing.timestamp) <= (trade.slot, trade.timestamp));
+        if launch_position < self.max_launch_per_mint {
+            history.launch.insert(launch_position, trade.clone());
+            history.launch.truncate(self.max_launch_per_mint);
+        }
+
+        let position = history
+            .recent
+            .iter()
+            .rposition(|existing| existing.timestamp <= trade.timestamp)
+            .map_or(0, |i| i + 1);
+        history.recent.insert(position, trade);
+        while history.recent.len() > self.max_per_mint {
+            history.recent.pop_front();
+        }
+    }
+}
+
+impl Default for InMemoryTradeStore {
+    fn default() -> Self {
+        Self::new(5_000, 2_000)
+    }
+}
+
+#[async_trait]
+impl TradeSource for InMemoryTradeStore {
+    async fn trades(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<SwapTrade>> {
+        let trades = self.trades.read().unwrap();
+        Ok(trades
+            .get(mint)
+            .map(|history| history.recent.iter().filter(|trade| trade.timestamp >= since).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn launch_trades(&self, mint: &Pubkey, limit: usize) -> Result<Vec<SwapTrade>> {
+        let trades = self.trades.read().unwrap();
+        Ok(trades
+            .get(mint)
+            .map(|history| history.launch.iter().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn name(&self) -> &str {
+        "memory"
+    }
+}
+
+/// PostgreSQL成交记录
+///
+/// 读取`market_trades`表中的链上成交（历史回填与实时交易流写入），代币数量按成交记录的精度换算为UI单位
+pub struct PostgresTradeSource {
+    pool: PgPool,
+    max_trades: usize,
+}
+
+impl PostgresTradeSource {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool, max_trades: 5_000 }
+    }
+
+    fn decode(mint: &Pubkey, rows: &[PgRow]) -> Result<Vec<SwapTrade>> {
+        let decode = |e: sqlx::Error| Error::Database(format!("Trade decode failed: {}", e));
+        let mut trades = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let trader: String = row.try_get("trader").map_err(decode)?;
+            let Ok(trader) = Pubkey::from_str(&trader) else {
+                continue;
+            };
+            let side = match row.try_get::<String, _>("side").map_err(decode)?.as_str() {
+                "buy" => TradeSide::Buy,
+                "sell" => TradeSide::Sell,
+                _ => continue,
+            };
+            let traded_at: DateTime<Utc> = row.try_get("traded_at").map_err(decode)?;
+
+            trades.push(SwapTrade {
+                mint: *mint,
+                trader,
+                side,
+                sol_amount: row.try_get::<Option<f64>, _>("sol_amount").map_err(decode)?.unwrap_or(0.0),
+                token_amount: row.try_get::<Option<f64>, _>("token_amount").map_err(decode)?.unwrap_or(0.0),
+                signature: row.try_get::<Option<String>, _>("tx_signature").map_err(decode)?.unwrap_or_default(),
+                slot: row.try_get::<Option<i64>, _>("slot").map_err(decode)?.unwrap_or(0) as u64,
+                timestamp: traded_at,
+                jito_tip_lamports: row
+                    .try_get::<Option<i64>, _>("jito_tip_lamports")
+                    .map_err(decode)?
+                    .map(|tip| tip as u64),
+            });
+        }
+
+        Ok(trades)
+    }
+}
+
+/// 成交查询的公共列
+const TRADE_COLUMNS: &str = "trader, side, amount_sol::float8 AS sol_amount,
+    amount_tokens::float8 / power(10, decimals)::float8 AS token_amount,
+    tx_signature, slot, jito_tip_lamports, block_time AS traded_at";
+
+#[async_trait]
+impl TradeSource for PostgresTradeSource {
+    async fn trades(&self, mint: &Pubkey, since: DateTime<Utc>) -> Result<Vec<SwapTrade>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM market_trades
+             WHERE mint = $1 AND block_time >= $2
+             ORDER BY block_time DESC
+             LIMIT $3",
+            TRADE_COLUMNS
+        ))
+        .bind(mint.to_string())
+        .bind(since)
+        .bind(self.max_trades as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Trade query failed: {}", e)))?;
+
+        let mut trades = Self::decode(mint, &rows)?;
+        trades.reverse();
+        Ok(trades)
+    }
+
+    async fn launch_trades(&self, mint: &Pubkey, limit: usize) -> Result<Vec<SwapTrade>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM market_trades
+             WHERE mint = $1
+             ORDER BY slot ASC, block_time ASC
+             LIMIT $2",
+            TRADE_COLUMNS
+        ))
+        .bind(mint.to_string())
+        .bind(limit.min(self.max_trades) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Launch trade query failed: {}", e)))?;
+
+        Self::decode(mint, &rows)
+    }
+
+    /// `token_launches`中记录的创建slot（历史回填写入）
+    async fn launch_slot(&self, mint: &Pubkey) -> Result<Option<u64>> {
+        let slot: Option<Option<i64>> = sqlx::query_scalar("SELECT launch_slot FROM token_launches WHERE mint = $1")
+            .bind(mint.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Launch slot query failed: {}", e)))?;
+
+        Ok(slot.flatten().map(|slot| slot as u64))
+    }
+
+    fn name(&self) -> &str {
+        "postgres"
+    }
+}
+
+/// 钱包关联关系（同一资金来源、捆绑买入等）
+pub trait WalletLinks: Send + Sync {
+    /// 钱包所属实体，未关联时为None
+    fn entity_of(&self, wallet: &Pubkey) -> Option<Pubkey>;
+}
+
+impl WalletLinks for HashMap<Pubkey, Pubkey> {
+    fn entity_of(&self, wallet: &Pubkey) -> Option<Pubkey> {
+        self.get(wallet).copied()
+    }
+}
+
+/// 可共享更新的钱包关联表
+///
+/// 关联钱包检测等模块写入，交易图读取；有交集的钱包组合并为同一实体。
+/// 超过容量时淘汰最久未读写的钱包
+pub struct SharedWalletLinks {
+    entities: RwLock<HashMap<Pubkey, LinkEntry>>,
+    clock: AtomicU64,
+    max_wallets: usize,
+}
+
+/// 钱包所属实体及最近一次读写的逻辑时间
+struct LinkEntry {
+    entity: Pubkey,
+    last_used: AtomicU64,
+}
+
+impl SharedWalletLinks {
+    pub fn new(max_wallets: usize) -> Self {
+        Self {
+            entities: RwLock::new(HashMap::new()),
+            clock: AtomicU64::new(0),
+            max_wallets,
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// 把一组钱包标记为同一实体
+    pub fn link(&self, wallets: &[Pubkey]) {
+        if wallets.len() < 2 {
+            return;
+        }
+
+        let mut entities = self.entities.write().unwrap();
+
+        let merged: HashSet<Pubkey> = wallets
+            .iter()
+            .filter_map(|wallet| entities.get(wallet).map(|entry| entry.entity))
+            .collect();
+        let entity = merged.iter().min().copied().unwrap_or(wallets[0]);
+
+        if merged.len() > 1 {
+            for entry in entities.values_mut() {
+                if merged.contains(&entry.entity) {
+                    entry.entity = entity;
+                }
+            }
+        }
+        for wallet in wallets {
+            let last_used = AtomicU64::new(self.tick());
+            entities.insert(*wallet, LinkEntry { entity, last_used });
+        }
+
+        if entities.len() > self.max_wallets {
+            Self::evict(&mut entities, self.max_wallets - self.max_wallets / 10);
+        }
+    }
+
+    /// 淘汰最久未读写的钱包，直到剩余`target`个
+    fn evict(entities: &mut HashMap<Pubkey, LinkEntry>, target: usize) {
+        let mut by_age: Vec<(u64, Pubkey)> = entities
+            .iter()
+            .map(|(wallet, entry)| (entry.last_used.load(Ordering::Relaxed), *wallet))
+            .collect();
+        by_age.sort_unstable();
+
+        let excess = entities.len().saturating_sub(target);
+        for (_, wallet) in by_age.into_iter().take(excess) {
+            entities.remove(&wallet);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SharedWalletLinks {
+    fn default() -> Self {
+        Self::new(200_000)
+    }
+}
+
+impl WalletLinks for SharedWalletLinks {
+    fn entity_of(&self, wallet: &Pubkey) -> Option<Pubkey> {
+        let entities = self.entities.read().unwrap();
+        let entry = entities.get(wallet)?;
+        entry.last_used.store(self.tick(), Ordering::Relaxed);
+        Some(entry.entity)
+    }
+}
+
+/// 交易图检测参数
+#[derive(Debug, Clone)]
+pub struct TradeGraphConfig {
+    /// 同一实体买入后卖出（或卖出后买回）视为往返交易的最长间隔
+    pub round_trip_window: Duration,
+
+    /// 卖出与买入配对为对手方成交的最长间隔
+    pub counterparty_window: Duration,
+
+    /// 代币数量相对误差在此范围内视为同一笔筹码
+    pub amount_tolerance: f64,
+
+    /// 同一实体至少出现这么多次往返才标记（单次买入后卖出是正常的快进快出）
+    pub min_round_trips: usize,
+
+    /// 判定固定金额机器人至少需要的成交笔数（买卖双向都需要至少2笔，单向定投不计）
+    pub min_uniform_trades: usize,
+
+    /// 成交金额变异系数不超过此值视为固定金额
+    pub uniform_max_variation: f64,
+}
+
+impl Default for TradeGraphConfig {
+    fn default() -> Self {
+        Self {
+            round_trip_window: Duration::minutes(10),
+            counterparty_window: Duration::seconds(60),
+            amount_tolerance: 0.1,
+            min_round_trips: 2,
+            min_uniform_trades: 5,
+            uniform_max_variation: 0.05,
+        }
+    }
+}
+
+/// 交易图中的一条边：筹码从卖方流向买方
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeEdge {
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    /// 卖出成交在交易列表中的下标
+    pub sell_index: usize,
+    /// 买入成交在交易列表中的下标
+    pub buy_index: usize,
+}
+
+/// 单笔成交的可疑标记
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TradeFlags {
+    /// 同一钱包往返买卖
+    pub round_trip: bool,
+    /// 关联钱包之间互相买卖
+    pub self_trade: bool,
+    /// 筹码在多个钱包间循环流转（A -> B -> A）
+    pub cycle: bool,
+    /// 固定金额的机器人成交
+    pub uniform_size: bool,
+}
+
+impl TradeFlags {
+    /// 是否属于对倒（洗售）成交
+    pub fn is_wash(&self) -> bool {
+        self.round_trip || self.self_trade || self.cycle
+    }
+
+    pub fn is_suspicious(&self) -> bool {
+        self.is_wash() || self.uniform_size
+    }
+}
+
+/// 交易图分析结果
+#[derive(Debug, Clone, Default)]
+pub struct TradeGraphReport {
+    pub trade_count: usize,
+    pub wallet_count: usize,
+    pub edge_count: usize,
+
+    /// 总成交量（SOL）
+    pub total_volume_sol: f64,
+    pub round_trip_volume_sol: f64,
+    pub self_trade_volume_sol: f64,
+    pub cycle_volume_sol: f64,
+    pub uniform_volume_sol: f64,
+    /// 对倒成交量（往返、关联钱包、循环，不重复计算）
+    pub wash_volume_sol: f64,
+    /// 全部可疑成交量（不重复计算）
+    pub suspicious_volume_sol: f64,
+
+    /// 参与可疑成交的钱包
+    pub suspicious_wallets: Vec<Pubkey>,
+}
+
+impl TradeGraphReport {
+    /// 对倒成交量占比（0-1）
+    pub fn wash_ratio(&self) -> f64 {
+        self.share(self.wash_volume_sol)
+    }
+
+    /// 可疑成交量占比（0-1）
+    pub fn suspicious_ratio(&self) -> f64 {
+        self.share(self.suspicious_volume_sol)
+    }
+
+    fn share(&self, volume: f64) -> f64 {
+        if self.total_volume_sol > 0.0 {
+            (volume / self.total_volume_sol).min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// 单个代币的交易图
+///
+/// 钱包（按关联关系合并为实体）为节点，成交为边：
+/// 卖出与随后数量相近的买入配对，表示筹码从卖方流向买方
+pub struct TradeGraph {
+    trades: Vec<SwapTrade>,
+    entities: Vec<Pubkey>,
+    edges: Vec<TradeEdge>,
+    flags: Vec<TradeFlags>,
+}
+
+impl TradeGraph {
+    pub fn build(mut trades: Vec<SwapTrade>, links: Option<&dyn WalletLinks>, config: &TradeGraphConfig) -> Self {
+        trades.sort_by_key(|trade| (trade.timestamp, trade.slot));
+        let entities: Vec<Pubkey> = trades
+            .iter()
+            .map(|trade| links.and_then(|links| links.entity_of(&trade.trader)).unwrap_or(trade.trader))
+            .collect();
+
+        let mut graph = Self {
+            flags: vec![TradeFlags::default(); trades.len()],
+            edges: Vec::new(),
+            trades,
+            entities,
+        };
+
+        graph.mark_round_trips(config);
+        graph.build_edges(config);
+        graph.mark_cycles();
+        graph.mark_uniform_sizes(config);
+        graph
+    }
+
+    pub fn trades(&self) -> &[SwapTrade] {
+        &self.trades
+    }
+
+    pub fn edges(&self) -> &[TradeEdge] {
+        &self.edges
+    }
+
+    pub fn flags(&self) -> &[TradeFlags] {
+        &self.flags
+    }
+
+    /// 汇总可疑成交量
+    pub fn report(&self) -> TradeGraphReport {
+        let mut report = TradeGraphReport {
+            trade_count: self.trades.len(),
+            wallet_count: self.trades.iter().map(|trade| trade.trader).collect::<HashSet<_>>().len(),
+            edge_count: self.edges.len(),
+            ..Default::default()
+        };
+        let mut suspicious_wallets = HashSet::new();
+
+        for (trade, flags) in self.trades.iter().zip(&self.flags) {
+            let volume = trade.sol_amount;
+            report.total_volume_sol += volume;
+            if flags.round_trip {
+                report.round_trip_volume_sol += volume;
+            }
+            if flags.self_trade {
+                report.self_trade_volume_sol += volume;
+            }
+            if flags.cycle {
+                report.cycle_volume_sol += volume;
+            }
+            if flags.uniform_size {
+                report.uniform_volume_sol += volume;
+            }
+            if flags.is_wash() {
+                report.wash_volume_sol += volume;
+            }
+            if flags.is_suspicious() {
+                report.suspicious_volume_sol += volume;
+                suspicious_wallets.insert(trade.trader);
+            }
+        }
+
+        report.suspicious_wallets = suspicious_wallets.into_iter().collect();
+        report.suspicious_wallets.sort();
+        report
+    }
+
+    fn amounts_match(a: f64, b: f64, tolerance: f64) -> bool {
+        a > 0.0 && b > 0.0 && (a - b).abs() <= a.max(b) * tolerance
+    }
+
+    /// 同一实体在窗口内反复反向成交相近数量的筹码
+    fn mark_round_trips(&mut self, config: &TradeGraphConfig) {
+        let mut paired = vec![false; self.trades.len()];
+        let mut pairs_by_entity: HashMap<Pubkey, Vec<(usize, usize)>> = HashMap::new();
+
+        for i in 0..self.trades.len() {
+            if paired[i] {
+                continue;
+            }
+            let first = &self.trades[i];
+
+            let counterpart = (i + 1..self.trades.len())
+                .take_while(|&j| self.trades[j].timestamp - first.timestamp <= config.round_trip_window)
+                .find(|&j| {
+                    let second = &self.trades[j];
+                    !paired[j]
+                        && self.entities[j] == self.entities[i]
+                        && second.side != first.side
+                        && Self::amounts_match(first.token_amount, second.token_amount, config.amount_tolerance)
+                });
+
+            if let Some(j) = counterpart {
+                paired[i] = true;
+                paired[j] = true;
+                pairs_by_entity.entry(self.entities[i]).or_default().push((i, j));
+            }
+        }
+
+        for pairs in pairs_by_entity.values() {
+            if pairs.len() < config.min_round_trips.max(1) {
+                continue;
+            }
+            for &(i, j) in pairs {
+                // 同一钱包为往返交易，关联钱包之间为自成交
+                if self.trades[j].trader == self.trades[i].trader {
+                    self.flags[i].round_trip = true;
+                    self.flags[j].round_trip = true;
+                } else {
+                    self.flags[i].self_trade = true;
+                    self.flags[j].self_trade = true;
+                }
+            }
+        }
+    }
+
+    /// 卖出与随后窗口内数量相近的买入配对为边
+    fn build_edges(&mut self, config: &TradeGraphConfig) {
+        let mut used = vec![false; self.trades.len()];
+
+        for i in 0..self.trades.len() {
+            let sell = &self.trades[i];
+            if sell.side != TradeSide::Sell {
+                continue;
+            }
+
+            let buy = (i + 1..self.trades.len())
+                .take_while(|&j| self.trades[j].timestamp - sell.timestamp <= config.counterparty_window)
+                .find(|&j| {
+                    let buy = &self.trades[j];
+                    !used[j]
+                        && buy.side == TradeSide::Buy
+                        && self.entities[j] != self.entities[i]
+                        && Self::amounts_match(sell.token_amount, buy.token_amount, config.amount_tolerance)
+                });
+
+            if let Some(j) = buy {
+                used[j] = true;
+                self.edges.push(TradeEdge {
+                    seller: self.entities[i],
+                    buyer: self.entities[j],
+                    sell_index: i,
+                    buy_index: j,
+                });
+            }
+        }
+    }
+
+    /// 位于实体图环路上的边（两端处于同一强连通分量）
+    fn mark_cycles(&mut self) {
+        let components = strongly_connected(&self.edges);
+
+        for edge in &self.edges {
+            let same_component = matches!(
+                (components.get(&edge.seller), components.get(&edge.buyer)),
+                (Some(a), Some(b)) if a == b
+            );
+            if same_component {
+                self.flags[edge.sell_index].cycle = true;
+                self.flags[edge.buy_index].cycle = true;
+            }
+        }
+    }
+
+    /// 同一钱包双向反复以几乎相同的金额成交（刷量机器人）
+    ///
+    /// 只买不卖（或只卖不买）的固定金额序列是定投/分批出货，不标记
+    fn mark_uniform_sizes(&mut self, config: &TradeGraphConfig) {
+        let mut by_wallet: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+        for (i, trade) in self.trades.iter().enumerate() {
+            if trade.sol_amount > 0.0 {
+                by_wallet.entry(trade.trader).or_default().push(i);
+            }
+        }
+
+        for indices in by_wallet.values() {
+            if indices.len() < config.min_uniform_trades.max(2) {
+                continue;
+            }
+            let buys = indices.iter().filter(|&&i| self.trades[i].side == TradeSide::Buy).count();
+            if buys < 2 || indices.len() - buys < 2 {
+                continue;
+            }
+
+            let amounts: Vec<f64> = indices.iter().map(|&i| self.trades[i].sol_amount).collect();
+            let mean = amounts.iter().sum::<f64>() / amounts.len() as f64;
+            let variance = amounts.iter().map(|amount| (amount - mean).powi(2)).sum::<f64>() / amounts.len() as f64;
+
+            if variance.sqrt() / mean <= config.uniform_max_variation {
+                for &i in indices {
+                    self.flags[i].uniform_size = true;
+                }
+            }
+        }
+    }
+}
+
+/// 实体图的强连通分量（Kosaraju），只返回包含多个实体的分量
+fn strongly_connected(edges: &[TradeEdge]) -> HashMap<Pubkey, usize> {
+    let mut forward: HashMap<Pubkey, Vec<Pubkey>> = HashMap::new();
+    let mut backward: HashMap<Pubkey, Vec<Pubkey>> = HashMap::new();
+    for edge in edges {
+        forward.entry(edge.seller).or_default().push(edge.buyer);
+        backward.entry(edge.buyer).or_default().push(edge.seller);
+    }
+    let mut nodes: Vec<Pubkey> = forward.keys().chain(backward.keys()).copied().collect();
+    nodes.sort();
+    nodes.dedup();
+
+    // 第一遍：按完成顺序记录节点
+    let mut visited = HashSet::new();
+    let mut order = Vec::with_capacity(nodes.len());
+    for &start in &nodes {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut stack = vec![(start, 0usize)];
+        while let Some((node, next)) = stack.pop() {
+            let neighbors = forward.get(&node).map(Vec::as_slice).unwrap_or_default();
+            if next < neighbors.len() {
+                stack.push((node, next + 1));
+                let neighbor = neighbors[next];
+                if visited.insert(neighbor) {
+                    stack.push((neighbor, 0));
+                }
+            } else {
+                order.push(node);
+            }
+        }
+    }
+
+    // 第二遍：在反向图上按完成顺序逆序展开
+    // 单节点分量最后再剔除，避免已归属的节点被后续分量重新收入
+    let mut component_of = HashMap::new();
+    let mut sizes = Vec::new();
+    for &start in order.iter().rev() {
+        if component_of.contains_key(&start) {
+            continue;
+        }
+        let component = sizes.len();
+        let mut size = 1;
+        let mut stack = vec![start];
+        component_of.insert(start, component);
+        while let Some(node) = stack.pop() {
+            for &neighbor in backward.get(&node).map(Vec::as_slice).unwrap_or_default() {
+                if let Entry::Vacant(entry) = component_of.entry(neighbor) {
+                    entry.insert(component);
+                    size += 1;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+
+    component_of.retain(|_, component| sizes[*component] >= 2);
+    component_of
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(mint: Pubkey, trader: Pubkey, side: TradeSide, sol: f64, tokens: f64, seconds: i64) -> SwapTrade {
+        SwapTrade {
+            mint,
+            trader,
+            side,
+            sol_amount: sol,
+            token_amount: tokens,
+            signature: format!("sig-{}-{}", trader, seconds),
+            slot: 1_000 + seconds as u64,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap(),
+            jito_tip_lamports: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_launch_trades_survive_eviction() {
+        let mint = Pubkey::new_unique();
+        let store = InMemoryTradeStore::new(5, 10).with_max_launch_per_mint(3);
+
+        // 乱序到达：第1秒的成交晚于第2秒写入
+        for seconds in [0, 2, 1, 3, 4, 5, 6, 7, 8, 9] {
+            store.record(trade(mint, Pubkey::new_unique(), TradeSide::Buy, 1.0, 1_000.0, seconds));
+        }
+
+        let launch = store.launch_trades(&mint, 10).await.unwrap();
+        assert_eq!(launch.iter().map(|t| t.slot).collect::<Vec<_>>(), vec![1_000, 1_001, 1_002]);
+
+        let recent = store.trades(&mint, DateTime::UNIX_EPOCH).await.unwrap();
+        assert_eq!(recent.len(), 5);
+        assert_eq!(recent[0].slot, 1_005);
+    }
+
+    #[test]
+    fn test_round_trip_and_self_trade() {
+        let mint = Pubkey::new_unique();
+        let (a, b, organic) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let links: HashMap<Pubkey, Pubkey> = [(b, a)].into_iter().collect();
+
+        let trades = vec![
+            // a自己买入后卖出
+            trade(mint, a, TradeSide::Buy, 1.0, 1_000.0, 0),
+            trade(mint, a, TradeSide::Sell, 1.0, 1_000.0, 60),
+            // 关联钱包b买入、a卖出
+            trade(mint, b, TradeSide::Buy, 2.0, 2_000.0, 120),
+            trade(mint, a, TradeSide::Sell, 2.0, 2_050.0, 180),
+            // 正常买入
+            trade(mint, organic, TradeSide::Buy, 5.0, 4_000.0, 240),
+        ];
+
+        let report = TradeGraph::build(trades, Some(&links), &TradeGraphConfig::default()).report();
+        assert_eq!(report.round_trip_volume_sol, 2.0);
+        assert_eq!(report.self_trade_volume_sol, 4.0);
+        assert!((report.wash_ratio() - 6.0 / 11.0).abs() < 1e-9);
+        assert!(!report.suspicious_wallets.contains(&organic));
+    }
+
+    #[test]
+    fn test_cycle_between_wallets() {
+        let mint = Pubkey::new_unique();
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        // 筹码 a -> b -> c -> a
+        let trades = vec![
+            trade(mint, a, TradeSide::Sell, 1.0, 1_000.0, 0),
+            trade(mint, b, TradeSide::Buy, 1.0, 1_000.0, 5),
+            trade(mint, b, TradeSide::Sell, 1.2, 1_000.0, 700),
+            trade(mint, c, TradeSide::Buy, 1.2, 1_000.0, 710),
+            trade(mint, c, TradeSide::Sell, 1.4, 1_000.0, 1_400),
+            trade(mint, a, TradeSide::Buy, 1.4, 1_000.0, 1_410),
+        ];
+
+        let graph = TradeGraph::build(trades, None, &TradeGraphConfig::default());
+        assert_eq!(graph.edges().len(), 3);
+        let report = graph.report();
+        assert_eq!(report.cycle_volume_sol, report.total_volume_sol);
+        assert_eq!(report.suspicious_wallets.len(), 3);
+    }
+
+    #[test]
+    fn test_single_flip_not_round_trip() {
+        let mint = Pubkey::new_unique();
+        let trader = Pubkey::new_unique();
+
+        // 买入后很快全部卖出只有一次，属于正常快进快出
+        let trades = vec![
+            trade(mint, trader, TradeSide::Buy, 1.0, 1_000.0, 0),
+            trade(mint, trader, TradeSide::Sell, 1.3, 1_000.0, 120),
+        ];
+
+        let report = TradeGraph::build(trades, None, &TradeGraphConfig::default()).report();
+        assert_eq!(report.wash_volume_sol, 0.0);
+        assert!(report.suspicious_wallets.is_empty());
+    }
+
+    #[test]
+    fn test_uniform_size_bot() {
+        let mint = Pubkey::new_unique();
+        let bot = Pubkey::new_unique();
+        let trader = Pubkey::new_unique();
+
+        // 机器人以固定0.5 SOL交替买卖（代币数量不同，不构成往返）
+        let mut trades: Vec<SwapTrade> = (0..6)
+            .map(|i| {
+                let side = if i % 2 == 0 { TradeSide::Buy } else { TradeSide::Sell };
+                trade(mint, bot, side, 0.5, 400.0 + i as f64 * 100.0, i * 30)
+            })
+            .collect();
+        trades.extend((0..6).map(|i| trade(mint, trader, TradeSide::Buy, 0.2 + i as f64 * 0.3, 500.0, i * 40)));
+
+        let report = TradeGraph::build(trades, None, &TradeGraphConfig::default()).report();
+        assert!((report.uniform_volume_sol - 3.0).abs() < 1e-9);
+        assert_eq!(report.suspicious_wallets, vec![bot]);
+    }
+
+    #[test]
+    fn test_dca_buys_not_uniform_bot() {
+        let mint = Pubkey::new_unique();
+        let dca = Pubkey::new_unique();
+
+        // 定投：固定金额只买不卖
+        let trades: Vec<SwapTrade> = (0..8)
+            .map(|i| trade(mint, dca, TradeSide::Buy, 0.5, 400.0 + i as f64 * 50.0, i * 300))
+            .collect();
+
+        let report = TradeGraph::build(trades, None, &TradeGraphConfig::default()).report();
+        assert_eq!(report.uniform_volume_sol, 0.0);
+        assert!(report.suspicious_wallets.is_empty());
+    }
+
+    #[test]
+    fn test_shared_links_merge() {
+        let links = SharedWalletLinks::default();
+        let (a, b, c, d) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        links.link(&[a, b]);
+        links.link(&[c, d]);
+        links.link(&[b, c]);
+
+        let entity = links.entity_of(&a).unwrap();
+        assert!([b, c, d].iter().all(|wallet| links.entity_of(wallet) == Some(entity)));
+    }
+
+    #[test]
+    fn test_shared_links_evict_least_recently_used() {
+        let links = SharedWalletLinks::new(4);
+        let (a, b, c, d, e, f) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        links.link(&[a, b]);
+        links.link(&[c, d]);
+        // 读取a后a比b、c更新
+        assert_eq!(links.entity_of(&a), Some(a));
+        links.link(&[e, f]);
+
+        assert_eq!(links.len(), 4);
+        assert_eq!(links.entity_of(&a), Some(a));
+        assert_eq!(links.entity_of(&b), None);
+        assert_eq!(links.entity_of(&c), None);
+        assert_eq!(links.entity_of(&e), links.entity_of(&f));
+    }
+}
@@ -65,9 +65,11 @@ impl L1MemoryCache {
             name: "L1-Memory".to_string(),
         }
     }
+}
 
+impl Default for L1MemoryCache {
     /// 创建默认配置（10000条目，30秒TTL）
-    pub fn default() -> Self {
+    fn default() -> Self {
         Self::new(10_000, Duration::from_secs(30))
     }
 }
@@ -158,7 +160,7 @@ impl L2RedisCache {
 
         // Ping测试
         redis::cmd("PING")
-            .query_async::<_, String>(&mut conn)
+            .query_async::<String>(&mut conn)
             .await
             .map_err(|e| Error::Internal(format!("Redis ping failed: {}", e)))?;
 
@@ -212,7 +214,7 @@ impl CacheLayer for L2RedisCache {
         let bytes = bincode::serialize(value)
             .map_err(|e| Error::Internal(format!("L2 serialize error: {}", e)))?;
 
-        conn.set_ex(key, bytes, ttl.as_secs() as u64)
+        conn.set_ex::<_, _, ()>(key, bytes, ttl.as_secs())
             .await
             .map_err(|e| Error::Internal(format!("Redis set error: {}", e)))?;
 
@@ -227,7 +229,7 @@ impl CacheLayer for L2RedisCache {
             .await
             .map_err(|e| Error::Internal(format!("Redis connection error: {}", e)))?;
 
-        conn.del(key)
+        conn.del::<_, ()>(key)
             .await
             .map_err(|e| Error::Internal(format!("Redis delete error: {}", e)))?;
 
@@ -282,10 +284,10 @@ impl CacheLayer for L2RedisCache {
         for (key, value, ttl) in items {
             let bytes = bincode::serialize(value)
                 .map_err(|e| Error::Internal(format!("L2 batch serialize error: {}", e)))?;
-            pipe.set_ex(*key, bytes, ttl.as_secs() as u64);
+            pipe.set_ex(*key, bytes, ttl.as_secs());
         }
 
-        pipe.query_async(&mut conn)
+        pipe.query_async::<()>(&mut conn)
             .await
             .map_err(|e| Error::Internal(format!("Redis pipeline error: {}", e)))?;
 
@@ -306,9 +308,11 @@ impl CacheLayer for L2RedisCache {
 /// - 批量操作支持
 /// - 缓存穿透保护
 /// - 统计信息收集
-pub struct TieredCacheManager {
-    l1: Arc<dyn CacheLayer>,
-    l2: Arc<dyn CacheLayer>,
+///
+/// `CacheLayer`带泛型方法，不能作为trait对象使用，因此缓存层以类型参数传入
+pub struct TieredCacheManager<L1 = L1MemoryCache, L2 = L2RedisCache> {
+    l1: Arc<L1>,
+    l2: Arc<L2>,
     l1_ttl: Duration,
     l2_ttl: Duration,
     stats: Arc<tokio::sync::RwLock<CacheStats>>,
@@ -348,6 +352,23 @@ impl CacheStats {
 }
 
 impl TieredCacheManager {
+    /// 创建默认配置
+    ///
+    /// L1: 30秒，L2: 120秒
+    pub async fn with_default_config(redis_url: &str) -> Result<Self> {
+        let l1 = Arc::new(L1MemoryCache::default());
+        let l2 = Arc::new(L2RedisCache::new(redis_url).await?);
+
+        Ok(Self::new(
+            l1,
+            l2,
+            Duration::from_secs(30),
+            Duration::from_secs(120),
+        ))
+    }
+}
+
+impl<L1: CacheLayer + 'static, L2: CacheLayer + 'static> TieredCacheManager<L1, L2> {
     /// 创建新的分层缓存管理器
     ///
     /// # 参数
@@ -356,8 +377,8 @@ impl TieredCacheManager {
     /// - `l1_ttl`: L1缓存TTL
     /// - `l2_ttl`: L2缓存TTL
     pub fn new(
-        l1: Arc<dyn CacheLayer>,
-        l2: Arc<dyn CacheLayer>,
+        l1: Arc<L1>,
+        l2: Arc<L2>,
         l1_ttl: Duration,
         l2_ttl: Duration,
     ) -> Self {
@@ -370,21 +391,6 @@ impl TieredCacheManager {
         }
     }
 
-    /// 创建默认配置
-    ///
-    /// L1: 30秒，L2: 120秒
-    pub async fn with_default_config(redis_url: &str) -> Result<Self> {
-        let l1 = Arc::new(L1MemoryCache::default());
-        let l2 = Arc::new(L2RedisCache::new(redis_url).await?);
-
-        Ok(Self::new(
-            l1,
-            l2,
-            Duration::from_secs(30),
-            Duration::from_secs(120),
-        ))
-    }
-
     /// 获取缓存值
     ///
     /// 查找顺序：L1 -> L2 -> 返回None
@@ -541,7 +547,7 @@ impl TieredCacheManager {
         fetcher: F,
     ) -> Result<T>
     where
-        T: DeserializeOwned + Serialize + Send + Sync + Clone,
+        T: DeserializeOwned + Serialize + Send + Sync + Clone + 'static,
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T>>,
     {
@@ -608,12 +614,13 @@ mod tests {
 
     #[test]
     fn test_cache_stats() {
-        let mut stats = CacheStats::default();
-        stats.total_requests = 100;
-        stats.l1_hits = 60;
-        stats.l1_misses = 40;
-        stats.l2_hits = 25;
-        stats.l2_misses = 15;
+        let stats = CacheStats {
+            total_requests: 100,
+            l1_hits: 60,
+            l1_misses: 40,
+            l2_hits: 25,
+            l2_misses: 15,
+        };
 
         assert_eq!(stats.l1_hit_rate(), 0.6);
         assert_eq!(stats.l2_hit_rate(), 0.25);
@@ -0,0 +1,823 @@
+use crate::{Error, Result, TokenInfo};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// 交易方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// 解码后的单笔swap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapTrade {
+    pub mint: Pubkey,
+    pub trader: Pubkey,
+    pub side: TradeSide,
+    /// SOL数量（UI单位）
+    pub sol_amount: f64,
+    /// 代币数量（UI单位）
+    pub token_amount: f64,
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: DateTime<Utc>,
+    /// 交易中支付的Jito小费（lamports），非捆绑交易为None
+    #[serde(default)]
+    pub jito_tip_lamports: Option<u64>,
+}
+
+impl SwapTrade {
+    /// 成交价格（SOL/代币）
+    pub fn price_sol(&self) -> Option<f64> {
+        if self.token_amount > 0.0 && self.sol_amount > 0.0 {
+            Some(self.sol_amount / self.token_amount)
+        } else {
+            None
+        }
+    }
+}
+
+/// K线周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CandleInterval {
+    OneSecond,
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl CandleInterval {
+    pub const ALL: [CandleInterval; 4] = [
+        Self::OneSecond,
+        Self::OneMinute,
+        Self::FiveMinutes,
+        Self::OneHour,
+    ];
+
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Self::OneSecond => 1,
+            Self::OneMinute => 60,
+            Self::FiveMinutes => 300,
+            Self::OneHour => 3600,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OneSecond => "1s",
+            Self::OneMinute => "1m",
+            Self::FiveMinutes => "5m",
+            Self::OneHour => "1h",
+        }
+    }
+
+    pub fn parse(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|interval| interval.label() == label)
+    }
+
+    /// 时间所在K线的开盘时间
+    pub fn bucket_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let secs = timestamp.timestamp();
+        let start = secs - secs.rem_euclid(self.seconds());
+        DateTime::from_timestamp(start, 0).unwrap_or(timestamp)
+    }
+}
+
+/// OHLCV K线（价格单位SOL，成交量单位SOL）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub mint: Pubkey,
+    pub interval: CandleInterval,
+    pub open_time: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume_sol: f64,
+    pub buy_volume_sol: f64,
+    pub sell_volume_sol: f64,
+    pub buys: u32,
+    pub sells: u32,
+    /// 开盘/收盘价对应交易的时间（乱序到达的交易据此决定是否更新open/close）
+    #[serde(default)]
+    pub first_trade_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_trade_at: DateTime<Utc>,
+}
+
+impl Candle {
+    fn from_trade(trade: &SwapTrade, price: f64, interval: CandleInterval) -> Self {
+        let mut candle = Self {
+            mint: trade.mint,
+            interval,
+            open_time: interval.bucket_start(trade.timestamp),
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume_sol: 0.0,
+            buy_volume_sol: 0.0,
+            sell_volume_sol: 0.0,
+            buys: 0,
+            sells: 0,
+            first_trade_at: trade.timestamp,
+            last_trade_at: trade.timestamp,
+        };
+        candle.add_volume(trade);
+        candle
+    }
+
+    fn update(&mut self, trade: &SwapTrade, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        if trade.timestamp >= self.last_trade_at {
+            self.close = price;
+            self.last_trade_at = trade.timestamp;
+        }
+        if trade.timestamp < self.first_trade_at {
+            self.open = price;
+            self.first_trade_at = trade.timestamp;
+        }
+        self.add_volume(trade);
+    }
+
+    fn add_volume(&mut self, trade: &SwapTrade) {
+        self.volume_sol += trade.sol_amount;
+        match trade.side {
+            TradeSide::Buy => {
+                self.buy_volume_sol += trade.sol_amount;
+                self.buys += 1;
+            }
+            TradeSide::Sell => {
+                self.sell_volume_sol += trade.sol_amount;
+                self.sells += 1;
+            }
+        }
+    }
+
+    pub fn close_time(&self) -> DateTime<Utc> {
+        self.open_time + Duration::seconds(self.interval.seconds())
+    }
+}
+
+/// 窗口成交量统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VolumeSummary {
+    pub volume_sol: f64,
+    pub buy_volume_sol: f64,
+    pub sell_volume_sol: f64,
+    pub buys: u64,
+    pub sells: u64,
+}
+
+/// K线持久化trait
+#[async_trait]
+pub trait CandleStore: Send + Sync {
+    /// 保存（覆盖同一开盘时间的）K线
+    async fn save(&self, candles: &[Candle]) -> Result<()>;
+
+    /// 读取最近的K线（按时间升序）
+    async fn load(&self, mint: &Pubkey, interval: CandleInterval, limit: usize) -> Result<Vec<Candle>>;
+
+    fn name(&self) -> &str;
+}
+
+/// Redis K线存储
+///
+/// 每个mint+周期一个有序集合，score为开盘时间戳
+pub struct RedisCandleStore {
+    conn: ConnectionManager,
+    max_candles: usize,
+}
+
+impl RedisCandleStore {
+    pub async fn new(redis_url: &str, max_candles: usize) -> Result<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| Error::Database(format!("Redis client error: {}", e)))?;
+        let conn = ConnectionManager::new(client)
+            .await
+            .map_err(|e| Error::Database(format!("Redis connection error: {}", e)))?;
+
+        Ok(Self { conn, max_candles })
+    }
+
+    fn key(mint: &Pubkey, interval: CandleInterval) -> String {
+        format!("candles:{}:{}", mint, interval.label())
+    }
+}
+
+#[async_trait]
+impl CandleStore for RedisCandleStore {
+    async fn save(&self, candles: &[Candle]) -> Result<()> {
+        if candles.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        for candle in candles {
+            let key = Self::key(&candle.mint, candle.interval);
+            let score = candle.open_time.timestamp();
+            let member = serde_json::to_string(candle)
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+
+            pipe.cmd("ZREMRANGEBYSCORE").arg(&key).arg(score).arg(score).ignore();
+            pipe.cmd("ZADD").arg(&key).arg(score).arg(member).ignore();
+            pipe.cmd("ZREMRANGEBYRANK")
+                .arg(&key)
+                .arg(0)
+                .arg(-(self.max_candles as i64) - 1)
+                .ignore();
+        }
+
+        let mut conn = self.conn.clone();
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| Error::Database(format!("Redis candle save error: {}", e)))
+    }
+
+    async fn load(&self, mint: &Pubkey, interval: CandleInterval, limit: usize) -> Result<Vec<Candle>> {
+        let mut conn = self.conn.clone();
+        let members: Vec<String> = redis::cmd("ZRANGE")
+            .arg(Self::key(mint, interval))
+            .arg(-(limit as i64))
+            .arg(-1)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| Error::Database(format!("Redis candle load error: {}", e)))?;
+
+        members
+            .iter()
+            .map(|member| {
+                serde_json::from_str(member).map_err(|e| Error::Serialization(e.to_string()))
+            })
+            .collect()
+    }
+
+    fn name(&self) -> &str {
+        "redis"
+    }
+}
+
+/// K线缓冲容量配置
+#[derive(Debug, Clone)]
+pub struct CandleConfig {
+    pub capacity_1s: usize,
+    pub capacity_1m: usize,
+    pub capacity_5m: usize,
+    pub capacity_1h: usize,
+
+    /// 最多追踪的mint数量，超出时淘汰最久没有成交的mint
+    pub max_mints: usize,
+}
+
+impl Default for CandleConfig {
+    fn default() -> Self {
+        Self {
+            capacity_1s: 300,  // 5分钟
+            capacity_1m: 360,  // 6小时
+            capacity_5m: 288,  // 24小时
+            capacity_1h: 168,  // 7天
+            max_mints: 5_000,
+        }
+    }
+}
+
+impl CandleConfig {
+    pub fn capacity(&self, interval: CandleInterval) -> usize {
+        match interval {
+            CandleInterval::OneSecond => self.capacity_1s,
+            CandleInterval::OneMinute => self.capacity_1m,
+            CandleInterval::FiveMinutes => self.capacity_5m,
+            CandleInterval::OneHour => self.capacity_1h,
+        }
+    }
+}
+
+/// K线构建器
+///
+/// 将解码后的swap聚合为各周期OHLCV，每个mint+周期保存在固定容量的环形缓冲中。
+/// 已收盘的K线在配置存储后由`flush`批量持久化
+pub struct CandleBuilder {
+    series: DashMap<(Pubkey, CandleInterval), VecDeque<Candle>>,
+    /// mint最近一次收到成交的（本地）时间，用于淘汰
+    last_seen: DashMap<Pubkey, DateTime<Utc>>,
+    /// mint最早聚合的成交时间，此后的成交都经过本构建器（预热的K线可能有停机空缺，不计入）
+    observed_since: DashMap<Pubkey, DateTime<Utc>>,
+    config: CandleConfig,
+    store: Option<Arc<dyn CandleStore>>,
+    pending: Mutex<Vec<Candle>>,
+}
+
+impl CandleBuilder {
+    pub fn new() -> Self {
+        Self::with_config(CandleConfig::default())
+    }
+
+    pub fn with_config(config: CandleConfig) -> Self {
+        Self {
+            series: DashMap::new(),
+            last_seen: DashMap::new(),
+            observed_since: DashMap::new(),
+            config,
+            store: None,
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 设置持久化存储
+    pub fn with_store(mut self, store: Arc<dyn CandleStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// 聚合一笔swap
+    ///
+    /// 乱序到达的交易会更新缓冲中对应的K线，早于缓冲范围的交易被丢弃
+    pub fn ingest(&self, trade: &SwapTrade) {
+        let Some(price) = trade.price_sol() else {
+            return;
+        };
+
+        if self.last_seen.insert(trade.mint, Utc::now()).is_none() {
+            self.evict_excess_mints();
+        }
+        self.observed_since
+            .entry(trade.mint)
+            .and_modify(|since| *since = (*since).min(trade.timestamp))
+            .or_insert(trade.timestamp);
+
+        for interval in CandleInterval::ALL {
+            let bucket = interval.bucket_start(trade.timestamp);
+            let mut series = self.series.entry((trade.mint, interval)).or_default();
+
+            match series.back().map(|candle| candle.open_time) {
+                Some(last) if bucket == last => {
+                    series.back_mut().unwrap().update(trade, price);
+                }
+                Some(last) if bucket < last => {
+                    let position = series.iter().position(|c| c.open_time >= bucket);
+                    match position {
+                        Some(index) if series[index].open_time == bucket => {
+                            series[index].update(trade, price);
+                            // 已收盘K线被修正，重新持久化
+                            self.queue_persist(series[index].clone());
+                        }
+                        // 缓冲范围内的空缺K线（早于缓冲起点的交易丢弃）
+                        Some(index) if index > 0 => {
+                            series.insert(index, Candle::from_trade(trade, price, interval));
+                        }
+                        _ => {}
+                    }
+                }
+                previous => {
+                    if previous.is_some() {
+                        self.queue_persist(series.back().unwrap().clone());
+                    }
+                    series.push_back(Candle::from_trade(trade, price, interval));
+                    while series.len() > self.config.capacity(interval) {
+                        series.pop_front();
+                    }
+                }
+            }
+        }
+    }
+
+    /// 追踪的mint超过上限时淘汰最久没有成交的mint
+    fn evict_excess_mints(&self) {
+        while self.last_seen.len() > self.config.max_mints {
+            let oldest = self
+                .last_seen
+                .iter()
+                .min_by_key(|entry| *entry.value())
+                .map(|entry| *entry.key());
+
+            match oldest {
+                Some(mint) => self.remove(&mint),
+                None => break,
+            }
+        }
+    }
+
+    /// 淘汰超过`max_idle`没有成交的mint，返回淘汰数量
+    pub fn evict_idle(&self, max_idle: Duration) -> usize {
+        let cutoff = Utc::now() - max_idle;
+        let idle: Vec<Pubkey> = self
+            .last_seen
+            .iter()
+            .filter(|entry| *entry.value() < cutoff)
+            .map(|entry| *entry.key())
+            .collect();
+
+        for mint in &idle {
+            self.remove(mint);
+        }
+        idle.len()
+    }
+
+    fn queue_persist(&self, candle: Candle) {
+        if self.store.is_some() {
+            self.pending.lock().unwrap().push(candle);
+        }
+    }
+
+    /// 持久化已收盘的K线，返回写入数量
+    pub async fn flush(&self) -> Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+
+        let candles = std::mem::take(&mut *self.pending.lock().unwrap());
+        if candles.is_empty() {
+            return Ok(0);
+        }
+
+        if let Err(e) = store.save(&candles).await {
+            // 写入失败时放回队列，下次重试
+            self.pending.lock().unwrap().extend(candles);
+            return Err(e);
+        }
+
+        Ok(candles.len())
+    }
+
+    /// 从存储预热缓冲（如重启后）
+    pub async fn warm_up(&self, mint: &Pubkey) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+
+        for interval in CandleInterval::ALL {
+            let candles = store.load(mint, interval, self.config.capacity(interval)).await?;
+            if !candles.is_empty() {
+                self.series.insert((*mint, interval), candles.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// 最近的K线（按时间升序）
+    pub fn candles(&self, mint: &Pubkey, interval: CandleInterval, limit: usize) -> Vec<Candle> {
+        self.series
+            .get(&(*mint, interval))
+            .map(|series| {
+                let skip = series.len().saturating_sub(limit);
+                series.iter().skip(skip).cloned().collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 最新成交价（SOL）
+    pub fn latest_price(&self, mint: &Pubkey) -> Option<f64> {
+        self.series
+            .get(&(*mint, CandleInterval::OneSecond))
+            .and_then(|series| series.back().map(|candle| candle.close))
+    }
+
+    /// 选择缓冲能覆盖窗口的最细周期
+    fn interval_for_window(&self, window: Duration) -> CandleInterval {
+        [CandleInterval::OneMinute, CandleInterval::FiveMinutes]
+            .into_iter()
+            .find(|interval| {
+                interval.seconds() * self.config.capacity(*interval) as i64 >= window.num_seconds()
+            })
+            .unwrap_or(CandleInterval::OneHour)
+    }
+
+    /// `[now - window, now]`内的K线
+    ///
+    /// 以当前时间为基准：停止交易的代币窗口内没有K线，而不是沿用最后一段行情
+    fn window_candles(&self, mint: &Pubkey, window: Duration, now: DateTime<Utc>) -> Vec<Candle> {
+        let interval = self.interval_for_window(window);
+        let Some(series) = self.series.get(&(*mint, interval)) else {
+            return Vec::new();
+        };
+
+        let start = interval.bucket_start(now - window);
+        series
+            .iter()
+            .filter(|candle| candle.open_time >= start && candle.open_time <= now)
+            .cloned()
+            .collect()
+    }
+
+    /// 窗口内涨跌幅（%）
+    pub fn price_change_pct(&self, mint: &Pubkey, window: Duration) -> Option<f64> {
+        self.price_change_pct_at(mint, window, Utc::now())
+    }
+
+    /// 截至`now`的窗口涨跌幅（%），用于回放
+    pub fn price_change_pct_at(&self, mint: &Pubkey, window: Duration, now: DateTime<Utc>) -> Option<f64> {
+        let candles = self.window_candles(mint, window, now);
+        let first = candles.first()?;
+        let last = candles.last()?;
+
+        if first.open <= 0.0 {
+            return None;
+        }
+        Some((last.close - first.open) / first.open * 100.0)
+    }
+
+    /// 窗口内成交量
+    pub fn volume(&self, mint: &Pubkey, window: Duration) -> VolumeSummary {
+        self.volume_at(mint, window, Utc::now())
+    }
+
+    /// 截至`now`的窗口成交量，用于回放
+    pub fn volume_at(&self, mint: &Pubkey, window: Duration, now: DateTime<Utc>) -> VolumeSummary {
+        self.window_candles(mint, window, now)
+            .iter()
+            .fold(VolumeSummary::default(), |mut summary, candle| {
+                summary.volume_sol += candle.volume_sol;
+                summary.buy_volume_sol += candle.buy_volume_sol;
+                summary.sell_volume_sol += candle.sell_volume_sol;
+                summary.buys += candle.buys as u64;
+                summary.sells += candle.sells as u64;
+                summary
+            })
+    }
+
+    /// 窗口内收盘价对数收益率的标准差
+    pub fn volatility(&self, mint: &Pubkey, window: Duration) -> Option<f64> {
+        self.volatility_at(mint, window, Utc::now())
+    }
+
+    /// 截至`now`的窗口波动率，用于回放
+    pub fn volatility_at(&self, mint: &Pubkey, window: Duration, now: DateTime<Utc>) -> Option<f64> {
+        let closes: Vec<f64> = self
+            .window_candles(mint, window, now)
+            .iter()
+            .map(|candle| candle.close)
+            .filter(|close| *close > 0.0)
+            .collect();
+
+        if closes.len() < 3 {
+            return None;
+        }
+
+        let returns: Vec<f64> = closes.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+
+        Some(variance.sqrt())
+    }
+
+    /// 截至`now`的窗口是否完整经过本构建器
+    ///
+    /// 窗口起点（代币创建晚于起点时为创建时间）之后的成交都已聚合才算覆盖，
+    /// 允许一个K线周期的误差
+    pub fn covers(&self, mint: &Pubkey, window: Duration, now: DateTime<Utc>, created_at: DateTime<Utc>) -> bool {
+        let Some(observed_since) = self.observed_since.get(mint).map(|since| *since) else {
+            return false;
+        };
+
+        let start = (now - window).max(created_at);
+        let tolerance = Duration::seconds(self.interval_for_window(window).seconds());
+        observed_since <= start + tolerance
+    }
+
+    /// 用K线数据覆盖TokenInfo中的API快照字段
+    ///
+    /// 成交量按`sol_usd`换算为USD；只覆盖K线完整覆盖的窗口（见`covers`），其余字段保持原值
+    pub fn apply_to(&self, token: &mut TokenInfo, sol_usd: f64) {
+        self.apply_to_at(token, sol_usd, Utc::now());
+    }
+
+    /// 截至`now`覆盖TokenInfo，用于回放
+    pub fn apply_to_at(&self, token: &mut TokenInfo, sol_usd: f64, now: DateTime<Utc>) {
+        if !self.series.contains_key(&(token.mint, CandleInterval::OneMinute)) {
+            return;
+        }
+
+        let mint = token.mint;
+        let covers = |window: Duration| self.covers(&mint, window, now, token.created_at);
+        let (hour, six_hours, day) = (Duration::hours(1), Duration::hours(6), Duration::hours(24));
+        let (covers_1h, covers_6h, covers_24h) = (covers(hour), covers(six_hours), covers(day));
+
+        if covers_1h {
+            let volume_1h = self.volume_at(&mint, hour, now);
+            token.txns_1h_buys = volume_1h.buys;
+            token.txns_1h_sells = volume_1h.sells;
+            token.txns_1h_total = volume_1h.buys + volume_1h.sells;
+            token.volume_1h = volume_1h.volume_sol * sol_usd;
+
+            if let Some(change) = self.price_change_pct_at(&mint, hour, now) {
+                token.price_change_1h = change;
+            }
+            if let Some(volatility) = self.volatility_at(&mint, hour, now) {
+                token.volatility_1h = volatility;
+            }
+        }
+        if covers_6h {
+            token.volume_6h = self.volume_at(&mint, six_hours, now).volume_sol * sol_usd;
+            if let Some(change) = self.price_change_pct_at(&mint, six_hours, now) {
+                token.price_change_6h = change;
+            }
+        }
+        if covers_24h {
+            token.volume_24h = self.volume_at(&mint, day, now).volume_sol * sol_usd;
+            if let Some(change) = self.price_change_pct_at(&mint, day, now) {
+                token.price_change_24h = change;
+            }
+        }
+
+        if let Some(price) = self.latest_price(&mint) {
+            token.price_usd = price * sol_usd;
+        }
+    }
+
+    /// 停止追踪mint
+    pub fn remove(&self, mint: &Pubkey) {
+        for interval in CandleInterval::ALL {
+            self.series.remove(&(*mint, interval));
+        }
+        self.last_seen.remove(mint);
+        self.observed_since.remove(mint);
+    }
+
+    pub fn tracked_mints(&self) -> usize {
+        self.series
+            .iter()
+            .filter(|entry| entry.key().1 == CandleInterval::OneMinute)
+            .count()
+    }
+}
+
+impl Default for CandleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(mint: Pubkey, secs: i64, side: TradeSide, sol: f64, tokens: f64) -> SwapTrade {
+        SwapTrade {
+            mint,
+            trader: Pubkey::new_unique(),
+            side,
+            sol_amount: sol,
+            token_amount: tokens,
+            signature: format!("sig-{}", secs),
+            slot: secs as u64,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            jito_tip_lamports: None,
+        }
+    }
+
+    #[test]
+    fn test_ohlcv_aggregation() {
+        let builder = CandleBuilder::new();
+        let mint = Pubkey::new_unique();
+
+        // 1_700_000_000 对齐到分钟后为 1_699_999_980，前两笔同属一根1m K线
+        builder.ingest(&trade(mint, 0, TradeSide::Buy, 1.0, 1000.0)); // 0.001
+        builder.ingest(&trade(mint, 10, TradeSide::Buy, 3.0, 1000.0)); // 0.003
+        builder.ingest(&trade(mint, 15, TradeSide::Sell, 2.0, 1000.0)); // 0.002
+        builder.ingest(&trade(mint, 90, TradeSide::Buy, 1.0, 250.0)); // 0.004
+
+        let candles = builder.candles(&mint, CandleInterval::OneMinute, 10);
+        assert_eq!(candles.len(), 2);
+
+        let first = &candles[0];
+        assert!((first.open - 0.001).abs() < 1e-12);
+        assert!((first.high - 0.003).abs() < 1e-12);
+        assert!((first.close - 0.002).abs() < 1e-12);
+        assert!((first.buy_volume_sol - 4.0).abs() < 1e-12);
+        assert!((first.sell_volume_sol - 2.0).abs() < 1e-12);
+        assert_eq!((first.buys, first.sells), (2, 1));
+
+        assert_eq!(builder.candles(&mint, CandleInterval::OneSecond, 10).len(), 4);
+        assert_eq!(builder.latest_price(&mint), Some(0.004));
+
+        let now = DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+        let change = builder.price_change_pct_at(&mint, Duration::hours(1), now).unwrap();
+        assert!((change - 300.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ring_buffer_and_late_trade() {
+        let builder = CandleBuilder::with_config(CandleConfig {
+            capacity_1s: 3,
+            ..Default::default()
+        });
+        let mint = Pubkey::new_unique();
+
+        for secs in 0..5 {
+            builder.ingest(&trade(mint, secs, TradeSide::Buy, 1.0, 100.0));
+        }
+        // 乱序到达，更新已有K线
+        builder.ingest(&trade(mint, 3, TradeSide::Sell, 1.0, 50.0));
+
+        let candles = builder.candles(&mint, CandleInterval::OneSecond, 10);
+        assert_eq!(candles.len(), 3);
+        assert_eq!(candles[1].sells, 1);
+        assert!((candles[1].high - 0.02).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_apply_to_token_counts() {
+        let builder = CandleBuilder::new();
+        let mint = Pubkey::new_unique();
+
+        builder.ingest(&trade(mint, 0, TradeSide::Buy, 2.0, 1000.0));
+        builder.ingest(&trade(mint, 30, TradeSide::Sell, 1.0, 500.0));
+
+        let now = DateTime::from_timestamp(1_700_000_060, 0).unwrap();
+        let volume = builder.volume_at(&mint, Duration::hours(1), now);
+        assert_eq!(volume.buys + volume.sells, 2);
+        assert!((volume.volume_sol - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_apply_to_only_covered_windows() {
+        let builder = CandleBuilder::new();
+        let mint = Pubkey::new_unique();
+
+        builder.ingest(&trade(mint, 0, TradeSide::Buy, 2.0, 1000.0));
+        builder.ingest(&trade(mint, 1_800, TradeSide::Buy, 1.0, 250.0));
+
+        // 代币3天前创建，构建器只看到了最近1小时
+        let now = DateTime::from_timestamp(1_700_000_000 + 3_600, 0).unwrap();
+        let mut token = TokenInfo {
+            mint,
+            created_at: now - Duration::days(3),
+            volume_6h: 500.0,
+            volume_24h: 900.0,
+            price_change_24h: -40.0,
+            ..Default::default()
+        };
+        builder.apply_to_at(&mut token, 100.0, now);
+
+        assert_eq!(token.txns_1h_total, 2);
+        assert!((token.volume_1h - 300.0).abs() < 1e-9);
+        assert!((token.price_change_1h - 100.0).abs() < 1e-9);
+        // 6h/24h窗口没有完整覆盖，保留API值
+        assert_eq!(token.volume_6h, 500.0);
+        assert_eq!(token.volume_24h, 900.0);
+        assert_eq!(token.price_change_24h, -40.0);
+
+        // 创建后即开始观察的代币，所有窗口都被覆盖
+        token.created_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        builder.apply_to_at(&mut token, 100.0, now);
+        assert!((token.volume_24h - 300.0).abs() < 1e-9);
+        assert!((token.price_change_24h - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_anchored_on_now() {
+        let builder = CandleBuilder::new();
+        let mint = Pubkey::new_unique();
+
+        builder.ingest(&trade(mint, 0, TradeSide::Buy, 1.0, 1000.0));
+        builder.ingest(&trade(mint, 600, TradeSide::Buy, 2.0, 1000.0));
+
+        // 两小时后1h窗口内没有成交
+        let later = DateTime::from_timestamp(1_700_000_000 + 7_200, 0).unwrap();
+        assert_eq!(builder.price_change_pct_at(&mint, Duration::hours(1), later), None);
+        assert_eq!(builder.volume_at(&mint, Duration::hours(1), later), VolumeSummary::default());
+    }
+
+    #[test]
+    fn test_late_trade_keeps_close() {
+        let builder = CandleBuilder::new();
+        let mint = Pubkey::new_unique();
+
+        builder.ingest(&trade(mint, 0, TradeSide::Buy, 1.0, 1000.0)); // 0.001
+        builder.ingest(&trade(mint, 20, TradeSide::Buy, 2.0, 1000.0)); // 0.002
+        // 同一分钟内更早的交易晚到：更新open，不覆盖close
+        builder.ingest(&trade(mint, -10, TradeSide::Sell, 0.5, 1000.0)); // 0.0005
+
+        let candle = &builder.candles(&mint, CandleInterval::OneMinute, 1)[0];
+        assert!((candle.close - 0.002).abs() < 1e-12);
+        assert!((candle.open - 0.0005).abs() < 1e-12);
+        assert!((candle.low - 0.0005).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_evicts_least_recent_mint() {
+        let builder = CandleBuilder::with_config(CandleConfig {
+            max_mints: 2,
+            ..Default::default()
+        });
+        let mints: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+        for mint in &mints {
+            builder.ingest(&trade(*mint, 0, TradeSide::Buy, 1.0, 1000.0));
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        assert_eq!(builder.tracked_mints(), 2);
+        assert!(builder.latest_price(&mints[0]).is_none());
+        assert!(builder.latest_price(&mints[2]).is_some());
+    }
+}
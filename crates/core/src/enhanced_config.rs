@@ -12,15 +12,21 @@ pub enum Environment {
     Production,
 }
 
-impl Environment {
-    pub fn from_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
+impl std::str::FromStr for Environment {
+    type Err = std::convert::Infallible;
+
+    /// 未识别的环境名按开发环境处理
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
             "development" | "dev" => Environment::Development,
             "staging" | "stage" => Environment::Staging,
             "production" | "prod" => Environment::Production,
             _ => Environment::Development,
-        }
+        })
     }
+}
+
+impl Environment {
 
     pub fn as_str(&self) -> &str {
         match self {
@@ -64,7 +70,7 @@ where
     pub async fn new(config_path: &str) -> Result<Self> {
         // 从环境变量获取环境
         let env_str = std::env::var("APP_ENV").unwrap_or_else(|_| "development".to_string());
-        let environment = Environment::from_str(&env_str);
+        let environment: Environment = env_str.parse().unwrap_or(Environment::Development);
 
         // 构建环境特定的配置文件路径
         let env_config_path = Self::get_env_config_path(config_path, environment);
@@ -165,7 +171,10 @@ where
     }
 
     /// 启动热重载监听器
-    pub fn spawn_hot_reload_watcher(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+    pub fn spawn_hot_reload_watcher(self: Arc<Self>) -> tokio::task::JoinHandle<()>
+    where
+        T: Send + Sync + 'static,
+    {
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
 
@@ -286,12 +295,12 @@ mod tests {
 
     #[test]
     fn test_environment_from_str() {
-        assert_eq!(Environment::from_str("development"), Environment::Development);
-        assert_eq!(Environment::from_str("dev"), Environment::Development);
-        assert_eq!(Environment::from_str("production"), Environment::Production);
-        assert_eq!(Environment::from_str("prod"), Environment::Production);
-        assert_eq!(Environment::from_str("staging"), Environment::Staging);
-        assert_eq!(Environment::from_str("unknown"), Environment::Development);
+        assert_eq!("development".parse::<Environment>().unwrap(), Environment::Development);
+        assert_eq!("dev".parse::<Environment>().unwrap(), Environment::Development);
+        assert_eq!("production".parse::<Environment>().unwrap(), Environment::Production);
+        assert_eq!("prod".parse::<Environment>().unwrap(), Environment::Production);
+        assert_eq!("staging".parse::<Environment>().unwrap(), Environment::Staging);
+        assert_eq!("unknown".parse::<Environment>().unwrap(), Environment::Development);
     }
 
     #[test]
@@ -7,6 +7,9 @@ pub mod cache_manager;
 pub mod risk_controller;
 pub mod enhanced_config;
 pub mod metrics;
+pub mod price_service;
+pub mod candles;
+pub mod liquidity_history;
 
 pub use types::*;
 pub use error::{Error, Result};
@@ -16,6 +19,15 @@ pub use rpc_manager::{RpcManager, LoadBalancingStrategy, EndpointHealth};
 pub use cache_manager::{TieredCacheManager, CacheLayer, CacheStats, L1MemoryCache, L2RedisCache};
 pub use risk_controller::{RiskController, RiskControlConfig, RiskCheckResult, Position, DailyStats, RiskStats};
 pub use enhanced_config::{EnhancedConfigManager, Environment, ConfigValidator, ConfigChange};
+pub use price_service::{
+    SolPriceService, SolPriceSource, PriceQuote, PoolReservePriceSource, PythPriceSource, FixedPriceSource
+};
+pub use candles::{
+    CandleBuilder, CandleConfig, CandleInterval, CandleStore, Candle, RedisCandleStore, SwapTrade, TradeSide, VolumeSummary
+};
+pub use liquidity_history::{
+    DrainKind, DrainThresholds, LiquidityEvent, LiquidityEventKind, LiquidityStats, LiquidityTracker
+};
 pub use metrics::{
     MetricsCollector, MetricsSummary, TradingMetrics, StrategyMetrics,
     SystemHealthMetrics, RpcEndpointMetrics, MetricType, MetricValue,
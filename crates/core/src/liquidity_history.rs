@@ -0,0 +1,441 @@
+use crate::TokenInfo;
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::VecDeque;
+
+/// 流动性变动方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiquidityEventKind {
+    Add,
+    Remove,
+}
+
+/// 解码后的加池/撤池事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidityEvent {
+    pub mint: Pubkey,
+    /// 池子地址（由余额变化推导时未知）
+    pub pool: Option<Pubkey>,
+    pub kind: LiquidityEventKind,
+    /// SOL侧数量
+    pub sol_amount: f64,
+    /// 代币侧数量（UI单位）
+    pub token_amount: f64,
+    /// 加池/撤池的钱包
+    pub provider: Pubkey,
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 一次撤池
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidityRemoval {
+    pub timestamp: DateTime<Utc>,
+    pub sol_amount: f64,
+    /// 撤池前的流动性
+    pub liquidity_before: f64,
+    pub provider: Pubkey,
+}
+
+impl LiquidityRemoval {
+    /// 占撤池前流动性的百分比
+    pub fn pct_of_pool(&self) -> f64 {
+        if self.liquidity_before <= 0.0 {
+            return 100.0;
+        }
+        (self.sol_amount / self.liquidity_before * 100.0).min(100.0)
+    }
+}
+
+/// 流动性抽离类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DrainKind {
+    /// 单笔或短时间内大额撤池
+    Fast,
+    /// 多次小额撤池逐步抽干
+    Slow,
+}
+
+/// 抽离判定阈值
+#[derive(Debug, Clone)]
+pub struct DrainThresholds {
+    /// 单笔撤池占比（%）超过该值视为快速抽离
+    pub fast_single_removal_pct: f64,
+    /// `fast_window`内流动性下降（%）超过该值视为快速抽离
+    pub fast_window_drop_pct: f64,
+    pub fast_window: Duration,
+    /// 自峰值下降（%）超过该值且撤池次数足够时视为缓慢抽离
+    pub slow_drop_from_peak_pct: f64,
+    pub slow_min_removals: usize,
+}
+
+impl Default for DrainThresholds {
+    fn default() -> Self {
+        Self {
+            fast_single_removal_pct: 50.0,
+            fast_window_drop_pct: 50.0,
+            fast_window: Duration::minutes(5),
+            slow_drop_from_peak_pct: 50.0,
+            slow_min_removals: 3,
+        }
+    }
+}
+
+/// 流动性统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityStats {
+    /// 首次加池的流动性
+    pub initial_sol: f64,
+    pub peak_sol: f64,
+    pub current_sol: f64,
+    pub drop_from_initial_pct: f64,
+    pub drop_from_peak_pct: f64,
+    /// 窗口内流动性变化（%，负数为下降）
+    pub window_change_pct: f64,
+    /// 窗口内撤出的SOL
+    pub removed_sol_window: f64,
+    /// 窗口内撤池速度（SOL/分钟）
+    pub withdrawal_velocity: f64,
+    /// 窗口内最大单笔撤池
+    pub largest_removal_sol: f64,
+    pub largest_removal_pct: f64,
+    /// 全部撤池次数（不限窗口）
+    pub removal_count: usize,
+    pub first_seen_at: DateTime<Utc>,
+}
+
+impl LiquidityStats {
+    /// 判断是否在抽离流动性
+    pub fn drain(&self, thresholds: &DrainThresholds) -> Option<DrainKind> {
+        if self.largest_removal_pct >= thresholds.fast_single_removal_pct {
+            return Some(DrainKind::Fast);
+        }
+        if -self.window_change_pct >= thresholds.fast_window_drop_pct {
+            return Some(DrainKind::Fast);
+        }
+        if self.drop_from_peak_pct >= thresholds.slow_drop_from_peak_pct
+            && self.removal_count >= thresholds.slow_min_removals
+        {
+            return Some(DrainKind::Slow);
+        }
+        None
+    }
+}
+
+/// 流动性时间点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub liquidity_sol: f64,
+}
+
+/// 单个代币的流动性历史
+#[derive(Debug, Clone)]
+pub struct LiquidityHistory {
+    pub initial_sol: f64,
+    pub peak_sol: f64,
+    pub current_sol: f64,
+    pub first_seen_at: DateTime<Utc>,
+    pub points: VecDeque<LiquidityPoint>,
+    pub removals: Vec<LiquidityRemoval>,
+    /// 更新次数（加池/撤池/快照），用于判断历史是否变化
+    pub updates: u64,
+}
+
+impl LiquidityHistory {
+    fn new(liquidity_sol: f64, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            initial_sol: liquidity_sol,
+            peak_sol: liquidity_sol,
+            current_sol: liquidity_sol,
+            first_seen_at: timestamp,
+            points: VecDeque::from([LiquidityPoint {
+                timestamp,
+                liquidity_sol,
+            }]),
+            removals: Vec::new(),
+            updates: 0,
+        }
+    }
+
+    fn set_current(&mut self, liquidity_sol: f64, timestamp: DateTime<Utc>, max_points: usize) {
+        self.current_sol = liquidity_sol.max(0.0);
+        self.peak_sol = self.peak_sol.max(self.current_sol);
+        self.updates += 1;
+        self.points.push_back(LiquidityPoint {
+            timestamp,
+            liquidity_sol: self.current_sol,
+        });
+        while self.points.len() > max_points {
+            self.points.pop_front();
+        }
+    }
+
+    /// 窗口起点的流动性（窗口早于记录起点时取最早记录）
+    fn liquidity_at(&self, at: DateTime<Utc>) -> f64 {
+        self.points
+            .iter()
+            .rev()
+            .find(|point| point.timestamp <= at)
+            .or_else(|| self.points.front())
+            .map(|point| point.liquidity_sol)
+            .unwrap_or(self.initial_sol)
+    }
+
+    /// 计算统计
+    pub fn stats(&self, now: DateTime<Utc>, window: Duration) -> LiquidityStats {
+        let pct_below = |reference: f64| -> f64 {
+            if reference <= 0.0 {
+                return 0.0;
+            }
+            ((reference - self.current_sol) / reference * 100.0).max(0.0)
+        };
+
+        let window_start = now - window;
+        let start_liquidity = self.liquidity_at(window_start);
+        let window_change_pct = if start_liquidity > 0.0 {
+            (self.current_sol - start_liquidity) / start_liquidity * 100.0
+        } else {
+            0.0
+        };
+
+        let in_window = || self.removals.iter().filter(move |removal| removal.timestamp >= window_start);
+
+        let removed_sol_window: f64 = in_window().map(|removal| removal.sol_amount).sum();
+        let window_minutes = (window.num_seconds() as f64 / 60.0).max(1.0 / 60.0);
+
+        let largest = in_window().max_by(|a, b| a.sol_amount.total_cmp(&b.sol_amount));
+
+        LiquidityStats {
+            initial_sol: self.initial_sol,
+            peak_sol: self.peak_sol,
+            current_sol: self.current_sol,
+            drop_from_initial_pct: pct_below(self.initial_sol),
+            drop_from_peak_pct: pct_below(self.peak_sol),
+            window_change_pct,
+            removed_sol_window,
+            withdrawal_velocity: removed_sol_window / window_minutes,
+            largest_removal_sol: largest.map(|r| r.sol_amount).unwrap_or(0.0),
+            largest_removal_pct: in_window().map(|r| r.pct_of_pool()).fold(0.0, f64::max),
+            removal_count: self.removals.len(),
+            first_seen_at: self.first_seen_at,
+        }
+    }
+}
+
+/// 流动性历史追踪器
+///
+/// 由解码后的加池/撤池事件驱动，池子储备快照用于校正swap带来的漂移
+pub struct LiquidityTracker {
+    histories: DashMap<Pubkey, LiquidityHistory>,
+    max_points: usize,
+    thresholds: DrainThresholds,
+}
+
+impl LiquidityTracker {
+    pub fn new() -> Self {
+        Self {
+            histories: DashMap::new(),
+            max_points: 1_000,
+            thresholds: DrainThresholds::default(),
+        }
+    }
+
+    pub fn with_thresholds(mut self, thresholds: DrainThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    pub fn thresholds(&self) -> &DrainThresholds {
+        &self.thresholds
+    }
+
+    /// 记录一次加池/撤池
+    pub fn record(&self, event: &LiquidityEvent) {
+        let Some(mut history) = self.histories.get_mut(&event.mint) else {
+            // 没有基线时撤池无法换算占比，只把首次加池作为初始流动性
+            if event.kind == LiquidityEventKind::Add {
+                self.histories
+                    .insert(event.mint, LiquidityHistory::new(event.sol_amount, event.timestamp));
+            }
+            return;
+        };
+
+        match event.kind {
+            LiquidityEventKind::Add => {
+                let liquidity = history.current_sol + event.sol_amount;
+                history.set_current(liquidity, event.timestamp, self.max_points);
+            }
+            LiquidityEventKind::Remove => {
+                let liquidity_before = history.current_sol;
+                history.removals.push(LiquidityRemoval {
+                    timestamp: event.timestamp,
+                    sol_amount: event.sol_amount,
+                    liquidity_before,
+                    provider: event.provider,
+                });
+                history.set_current(liquidity_before - event.sol_amount, event.timestamp, self.max_points);
+            }
+        }
+    }
+
+    /// 记录池子储备快照
+    pub fn observe(&self, mint: &Pubkey, liquidity_sol: f64, timestamp: DateTime<Utc>) {
+        match self.histories.get_mut(mint) {
+            Some(mut history) => history.set_current(liquidity_sol, timestamp, self.max_points),
+            None => {
+                self.histories
+                    .insert(*mint, LiquidityHistory::new(liquidity_sol, timestamp));
+            }
+        }
+    }
+
+    /// 流动性统计（未追踪的mint返回None）
+    pub fn stats(&self, mint: &Pubkey, window: Duration) -> Option<LiquidityStats> {
+        self.histories
+            .get(mint)
+            .map(|history| history.stats(Utc::now(), window))
+    }
+
+    /// 判断是否在抽离流动性
+    pub fn drain(&self, mint: &Pubkey) -> Option<DrainKind> {
+        self.stats(mint, self.thresholds.fast_window)?
+            .drain(&self.thresholds)
+    }
+
+    /// 用最新快照写入TokenInfo
+    pub fn apply_to(&self, token: &mut TokenInfo) {
+        if let Some(history) = self.histories.get(&token.mint) {
+            token.liquidity_sol = history.current_sol;
+        }
+    }
+
+    /// mint历史的版本（每次更新递增，未追踪时为0）
+    pub fn version(&self, mint: &Pubkey) -> u64 {
+        self.histories
+            .get(mint)
+            .map(|history| history.updates + 1)
+            .unwrap_or(0)
+    }
+
+    pub fn history(&self, mint: &Pubkey) -> Option<LiquidityHistory> {
+        self.histories.get(mint).map(|history| history.clone())
+    }
+
+    pub fn remove(&self, mint: &Pubkey) {
+        self.histories.remove(mint);
+    }
+
+    pub fn tracked_mints(&self) -> usize {
+        self.histories.len()
+    }
+}
+
+impl Default for LiquidityTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(mint: Pubkey, kind: LiquidityEventKind, sol_amount: f64, minutes_ago: i64) -> LiquidityEvent {
+        LiquidityEvent {
+            mint,
+            pool: None,
+            kind,
+            sol_amount,
+            token_amount: 0.0,
+            provider: Pubkey::new_unique(),
+            signature: String::new(),
+            slot: 0,
+            timestamp: Utc::now() - Duration::minutes(minutes_ago),
+        }
+    }
+
+    #[test]
+    fn test_fast_drain() {
+        let tracker = LiquidityTracker::new();
+        let mint = Pubkey::new_unique();
+
+        tracker.record(&event(mint, LiquidityEventKind::Add, 100.0, 60));
+        tracker.record(&event(mint, LiquidityEventKind::Remove, 80.0, 1));
+
+        let stats = tracker.stats(&mint, Duration::minutes(5)).unwrap();
+        assert_eq!(stats.initial_sol, 100.0);
+        assert_eq!(stats.current_sol, 20.0);
+        assert!((stats.largest_removal_pct - 80.0).abs() < 1e-9);
+        assert!((stats.drop_from_initial_pct - 80.0).abs() < 1e-9);
+        assert_eq!(tracker.drain(&mint), Some(DrainKind::Fast));
+    }
+
+    #[test]
+    fn test_slow_drain() {
+        let tracker = LiquidityTracker::new();
+        let mint = Pubkey::new_unique();
+
+        tracker.record(&event(mint, LiquidityEventKind::Add, 100.0, 300));
+        tracker.record(&event(mint, LiquidityEventKind::Add, 20.0, 240));
+        // 每次撤出约20%，间隔一小时
+        for (minutes_ago, amount) in [(180, 24.0), (120, 20.0), (60, 16.0), (10, 13.0)] {
+            tracker.record(&event(mint, LiquidityEventKind::Remove, amount, minutes_ago));
+        }
+
+        let stats = tracker.stats(&mint, Duration::minutes(5)).unwrap();
+        assert_eq!(stats.peak_sol, 120.0);
+        assert_eq!(stats.removal_count, 4);
+        assert!(stats.largest_removal_pct < 50.0);
+        assert_eq!(tracker.drain(&mint), Some(DrainKind::Slow));
+    }
+
+    #[test]
+    fn test_snapshot_without_removals_is_not_drain() {
+        let tracker = LiquidityTracker::new();
+        let mint = Pubkey::new_unique();
+
+        tracker.observe(&mint, 50.0, Utc::now() - Duration::hours(2));
+        tracker.observe(&mint, 45.0, Utc::now());
+
+        let stats = tracker.stats(&mint, Duration::hours(1)).unwrap();
+        assert!((stats.window_change_pct + 10.0).abs() < 1e-9);
+        assert_eq!(tracker.drain(&mint), None);
+    }
+
+    #[test]
+    fn test_old_large_removal_outside_window() {
+        let tracker = LiquidityTracker::new();
+        let mint = Pubkey::new_unique();
+
+        // 两小时前撤出60%后重新加池，近期没有撤池
+        tracker.record(&event(mint, LiquidityEventKind::Add, 100.0, 180));
+        tracker.record(&event(mint, LiquidityEventKind::Remove, 60.0, 120));
+        tracker.record(&event(mint, LiquidityEventKind::Add, 60.0, 90));
+
+        let stats = tracker.stats(&mint, Duration::minutes(5)).unwrap();
+        assert_eq!(stats.largest_removal_pct, 0.0);
+        assert_eq!(stats.removal_count, 1);
+        assert_eq!(tracker.drain(&mint), None);
+
+        let stats = tracker.stats(&mint, Duration::hours(3)).unwrap();
+        assert!((stats.largest_removal_pct - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_version_changes_on_update() {
+        let tracker = LiquidityTracker::new();
+        let mint = Pubkey::new_unique();
+        assert_eq!(tracker.version(&mint), 0);
+
+        tracker.record(&event(mint, LiquidityEventKind::Add, 100.0, 10));
+        let first = tracker.version(&mint);
+        assert_ne!(first, 0);
+
+        tracker.record(&event(mint, LiquidityEventKind::Remove, 10.0, 5));
+        assert_ne!(tracker.version(&mint), first);
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant, SystemTime};
+use std::time::SystemTime;
 use tokio::sync::RwLock;
 
 /// 指标类型
@@ -253,6 +253,12 @@ pub struct SystemHealthMetrics {
     pub updated_at: SystemTime,
 }
 
+impl Default for SystemHealthMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SystemHealthMetrics {
     pub fn new() -> Self {
         Self {
@@ -373,6 +379,12 @@ pub struct MetricsCollector {
     enabled: bool,
 }
 
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MetricsCollector {
     /// 创建新的指标收集器
     pub fn new() -> Self {
@@ -826,7 +838,7 @@ mod tests {
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
         let health = collector.get_system_health().await;
-        assert!(health.uptime_secs >= 0);
+        assert!(health.start_time <= SystemTime::now());
     }
 
     #[tokio::test]
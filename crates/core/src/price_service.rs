@@ -0,0 +1,447 @@
+use crate::{Error, Result, RpcManager, TokenInfo};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+/// Pyth SOL/USD价格账户（PriceUpdateV2，shard 0）
+pub const PYTH_SOL_USD_FEED: &str = "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE";
+
+/// Raydium V4 SOL-USDC池子的SOL vault
+pub const RAYDIUM_SOL_USDC_SOL_VAULT: &str = "DQyrAcCrDXQ7NeoqGgDCZwBvWDcYmFCjSb9JtteuvPpz";
+
+/// Raydium V4 SOL-USDC池子的USDC vault
+pub const RAYDIUM_SOL_USDC_USDC_VAULT: &str = "HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz";
+
+/// SOL/USD报价
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceQuote {
+    pub price: f64,
+    /// 置信区间（绝对值，预言机提供时）
+    pub confidence: Option<f64>,
+    pub source: String,
+    pub published_at: DateTime<Utc>,
+}
+
+impl PriceQuote {
+    pub fn age(&self) -> Duration {
+        Utc::now() - self.published_at
+    }
+
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.age() > max_age
+    }
+}
+
+/// SOL/USD价格来源trait
+#[async_trait]
+pub trait SolPriceSource: Send + Sync {
+    /// 获取最新报价
+    async fn fetch(&self) -> Result<PriceQuote>;
+
+    /// 来源名称
+    fn name(&self) -> &str;
+}
+
+/// 从SOL-USDC池子储备计算价格
+///
+/// 读取池子两个vault的SPL token账户余额，价格 = USDC储备 / SOL储备
+pub struct PoolReservePriceSource {
+    rpc: Arc<RpcManager>,
+    name: String,
+    sol_vault: Pubkey,
+    usdc_vault: Pubkey,
+}
+
+impl PoolReservePriceSource {
+    pub fn new(rpc: Arc<RpcManager>, name: impl Into<String>, sol_vault: Pubkey, usdc_vault: Pubkey) -> Self {
+        Self {
+            rpc,
+            name: name.into(),
+            sol_vault,
+            usdc_vault,
+        }
+    }
+
+    /// Raydium V4 SOL-USDC池子
+    pub fn raydium_sol_usdc(rpc: Arc<RpcManager>) -> Self {
+        Self::new(
+            rpc,
+            "raydium-sol-usdc",
+            Pubkey::from_str(RAYDIUM_SOL_USDC_SOL_VAULT).unwrap(),
+            Pubkey::from_str(RAYDIUM_SOL_USDC_USDC_VAULT).unwrap(),
+        )
+    }
+
+    /// 由储备计算价格（SOL 9位精度，USDC 6位精度）
+    pub fn price_from_reserves(sol_reserve: u64, usdc_reserve: u64) -> Option<f64> {
+        if sol_reserve == 0 {
+            return None;
+        }
+        Some((usdc_reserve as f64 / 1e6) / (sol_reserve as f64 / 1e9))
+    }
+}
+
+#[async_trait]
+impl SolPriceSource for PoolReservePriceSource {
+    async fn fetch(&self) -> Result<PriceQuote> {
+        let keys = [self.sol_vault, self.usdc_vault];
+
+        let accounts = self
+            .rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || client.get_multiple_accounts(&keys))
+                    .await
+                    .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                    .map_err(|e| Error::Rpc(format!("getMultipleAccounts failed: {}", e)))
+            })
+            .await?;
+
+        // SPL token账户: amount位于偏移64
+        let amounts: Vec<u64> = accounts
+            .iter()
+            .map(|account| {
+                let account = account
+                    .as_ref()
+                    .ok_or_else(|| Error::NotFound(format!("Vault account missing for {}", self.name)))?;
+                if account.data.len() < 72 {
+                    return Err(Error::Serialization(format!("Invalid vault account for {}", self.name)));
+                }
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&account.data[64..72]);
+                Ok(u64::from_le_bytes(bytes))
+            })
+            .collect::<Result<_>>()?;
+
+        let price = Self::price_from_reserves(amounts[0], amounts[1])
+            .ok_or_else(|| Error::InvalidInput(format!("Empty SOL reserve in {}", self.name)))?;
+
+        Ok(PriceQuote {
+            price,
+            confidence: None,
+            source: self.name.clone(),
+            published_at: Utc::now(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Pyth预言机价格来源（PriceUpdateV2账户）
+pub struct PythPriceSource {
+    rpc: Arc<RpcManager>,
+    feed: Pubkey,
+}
+
+impl PythPriceSource {
+    pub fn new(rpc: Arc<RpcManager>, feed: Pubkey) -> Self {
+        Self { rpc, feed }
+    }
+
+    /// 使用默认SOL/USD价格账户
+    pub fn sol_usd(rpc: Arc<RpcManager>) -> Self {
+        Self::new(rpc, Pubkey::from_str(PYTH_SOL_USD_FEED).unwrap())
+    }
+
+    /// 解码PriceUpdateV2
+    ///
+    /// 布局:
+    /// - [0..8]   discriminator
+    /// - [8..40]  write_authority
+    /// - verification_level: Partial{num_signatures: u8}（2字节）或 Full（1字节）
+    /// - price_message: feed_id [32], price i64, conf u64, exponent i32, publish_time i64, ...
+    pub fn decode_price_update(data: &[u8]) -> Result<PriceQuote> {
+        if data.len() < 41 {
+            return Err(Error::Serialization("Price update account too short".to_string()));
+        }
+
+        let mut offset = 40 + if data[40] == 0 { 2 } else { 1 };
+        offset += 32; // feed_id
+
+        if data.len() < offset + 28 {
+            return Err(Error::Serialization("Price update account truncated".to_string()));
+        }
+
+        let read_i64 = |at: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[at..at + 8]);
+            i64::from_le_bytes(bytes)
+        };
+
+        let price = read_i64(offset);
+        let conf = read_i64(offset + 8) as u64;
+        let mut exponent_bytes = [0u8; 4];
+        exponent_bytes.copy_from_slice(&data[offset + 16..offset + 20]);
+        let exponent = i32::from_le_bytes(exponent_bytes);
+        let publish_time = read_i64(offset + 20);
+
+        let scale = 10f64.powi(exponent);
+        let published_at = DateTime::from_timestamp(publish_time, 0)
+            .ok_or_else(|| Error::Serialization(format!("Invalid publish time: {}", publish_time)))?;
+
+        Ok(PriceQuote {
+            price: price as f64 * scale,
+            confidence: Some(conf as f64 * scale),
+            source: "pyth".to_string(),
+            published_at,
+        })
+    }
+}
+
+#[async_trait]
+impl SolPriceSource for PythPriceSource {
+    async fn fetch(&self) -> Result<PriceQuote> {
+        let feed = self.feed;
+
+        let account = self
+            .rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || client.get_account(&feed))
+                    .await
+                    .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                    .map_err(|e| Error::Rpc(format!("getAccountInfo failed: {}", e)))
+            })
+            .await?;
+
+        Self::decode_price_update(&account.data)
+    }
+
+    fn name(&self) -> &str {
+        "pyth"
+    }
+}
+
+/// 固定价格来源（测试和离线回放）
+pub struct FixedPriceSource {
+    price: f64,
+}
+
+impl FixedPriceSource {
+    pub fn new(price: f64) -> Self {
+        Self { price }
+    }
+}
+
+#[async_trait]
+impl SolPriceSource for FixedPriceSource {
+    async fn fetch(&self) -> Result<PriceQuote> {
+        Ok(PriceQuote {
+            price: self.price,
+            confidence: None,
+            source: "fixed".to_string(),
+            published_at: Utc::now(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "fixed"
+    }
+}
+
+/// SOL/USD参考价格服务
+///
+/// 聚合多个来源，丢弃过期/越界/置信区间过宽的报价后取中位数并缓存。
+/// 所有由SOL换算USD的地方都应通过此服务取价，而不是从API字段反推
+pub struct SolPriceService {
+    sources: Vec<Arc<dyn SolPriceSource>>,
+    cache: RwLock<Option<PriceQuote>>,
+
+    /// 报价最大允许时延
+    max_staleness: Duration,
+
+    /// 合理价格区间
+    min_price: f64,
+    max_price: f64,
+
+    /// 最大置信区间占比
+    max_confidence_ratio: f64,
+}
+
+impl SolPriceService {
+    pub fn new(sources: Vec<Arc<dyn SolPriceSource>>) -> Self {
+        Self {
+            sources,
+            cache: RwLock::new(None),
+            max_staleness: Duration::seconds(60),
+            min_price: 1.0,
+            max_price: 10_000.0,
+            max_confidence_ratio: 0.02,
+        }
+    }
+
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = max_staleness;
+        self
+    }
+
+    pub fn with_price_bounds(mut self, min_price: f64, max_price: f64) -> Self {
+        self.min_price = min_price;
+        self.max_price = max_price;
+        self
+    }
+
+    /// 检查单个报价是否可用
+    fn validate(&self, quote: &PriceQuote) -> std::result::Result<(), String> {
+        if !quote.price.is_finite() || quote.price < self.min_price || quote.price > self.max_price {
+            return Err(format!("price {:.4} out of bounds", quote.price));
+        }
+        if quote.is_stale(self.max_staleness) {
+            return Err(format!("stale by {}s", quote.age().num_seconds()));
+        }
+        if let Some(confidence) = quote.confidence {
+            if confidence / quote.price > self.max_confidence_ratio {
+                return Err(format!("confidence interval too wide ({:.4})", confidence));
+            }
+        }
+        Ok(())
+    }
+
+    /// 从所有来源刷新价格
+    pub async fn refresh(&self) -> Result<PriceQuote> {
+        let results = futures_join_all(&self.sources).await;
+
+        let mut valid: Vec<PriceQuote> = Vec::new();
+        for (name, result) in results {
+            match result {
+                Ok(quote) => match self.validate(&quote) {
+                    Ok(()) => valid.push(quote),
+                    Err(reason) => tracing::warn!("SOL price from {} rejected: {}", name, reason),
+                },
+                Err(e) => tracing::warn!("SOL price source {} failed: {}", name, e),
+            }
+        }
+
+        let quote = Self::aggregate(valid)
+            .ok_or_else(|| Error::NotFound("No valid SOL/USD price available".to_string()))?;
+
+        tracing::debug!("💲 SOL/USD reference price: ${:.4} ({})", quote.price, quote.source);
+        *self.cache.write().unwrap() = Some(quote.clone());
+
+        Ok(quote)
+    }
+
+    /// 多个报价取中位数，来源合并记录
+    fn aggregate(mut quotes: Vec<PriceQuote>) -> Option<PriceQuote> {
+        if quotes.is_empty() {
+            return None;
+        }
+
+        quotes.sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap());
+        let mid = quotes.len() / 2;
+        let price = if quotes.len().is_multiple_of(2) {
+            (quotes[mid - 1].price + quotes[mid].price) / 2.0
+        } else {
+            quotes[mid].price
+        };
+
+        Some(PriceQuote {
+            price,
+            confidence: None,
+            source: quotes.iter().map(|q| q.source.as_str()).collect::<Vec<_>>().join("+"),
+            published_at: quotes.iter().map(|q| q.published_at).min()?,
+        })
+    }
+
+    /// 未过期的缓存报价
+    pub fn cached(&self) -> Option<PriceQuote> {
+        self.cache
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|quote| !quote.is_stale(self.max_staleness))
+            .cloned()
+    }
+
+    /// 当前SOL/USD价格（缓存过期时刷新）
+    pub async fn sol_usd(&self) -> Result<f64> {
+        match self.cached() {
+            Some(quote) => Ok(quote.price),
+            None => Ok(self.refresh().await?.price),
+        }
+    }
+
+    /// 启动后台定时刷新
+    pub fn start_refresh_task(self: Arc<Self>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    tracing::warn!("SOL price refresh failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// 用参考价格重新计算TokenInfo中的USD字段
+    ///
+    /// 链上SOL流动性已知时覆盖API给出的liquidity_usd
+    pub fn apply_to(token: &mut TokenInfo, sol_usd: f64) {
+        if token.liquidity_sol > 0.0 {
+            token.liquidity_usd = token.liquidity_sol * sol_usd;
+        }
+    }
+}
+
+/// 并发请求所有来源
+async fn futures_join_all(sources: &[Arc<dyn SolPriceSource>]) -> Vec<(String, Result<PriceQuote>)> {
+    let handles: Vec<_> = sources
+        .iter()
+        .map(|source| {
+            let source = Arc::clone(source);
+            tokio::spawn(async move { (source.name().to_string(), source.fetch().await) })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => results.push(("unknown".to_string(), Err(Error::Internal(e.to_string())))),
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_median_of_sources() {
+        let service = SolPriceService::new(vec![
+            Arc::new(FixedPriceSource::new(150.0)),
+            Arc::new(FixedPriceSource::new(152.0)),
+            Arc::new(FixedPriceSource::new(5.0e6)), // 越界，丢弃
+        ]);
+
+        let price = service.sol_usd().await.unwrap();
+        assert!((price - 151.0).abs() < 1e-9);
+        assert!(service.cached().is_some());
+    }
+
+    #[test]
+    fn test_decode_pyth_price_update() {
+        let mut data = vec![0u8; 8 + 32 + 1 + 32 + 8 + 8 + 4 + 8 + 8];
+        data[40] = 1; // Full
+        let offset = 41 + 32;
+        data[offset..offset + 8].copy_from_slice(&15_012_345_678i64.to_le_bytes());
+        data[offset + 8..offset + 16].copy_from_slice(&5_000_000u64.to_le_bytes());
+        data[offset + 16..offset + 20].copy_from_slice(&(-8i32).to_le_bytes());
+        data[offset + 20..offset + 28].copy_from_slice(&1_700_000_000i64.to_le_bytes());
+
+        let quote = PythPriceSource::decode_price_update(&data).unwrap();
+        assert!((quote.price - 150.12345678).abs() < 1e-6);
+        assert!((quote.confidence.unwrap() - 0.05).abs() < 1e-9);
+        assert!(quote.is_stale(Duration::seconds(60)));
+    }
+
+    #[test]
+    fn test_price_from_reserves() {
+        let price = PoolReservePriceSource::price_from_reserves(1_000 * 1_000_000_000, 150_000 * 1_000_000).unwrap();
+        assert!((price - 150.0).abs() < 1e-9);
+    }
+}
@@ -1,10 +1,10 @@
-use crate::{Error, Result, TokenInfo};
+use crate::{Result, TokenInfo};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashSet;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use tokio::sync::RwLock;
 
 /// 风险控制配置
@@ -67,7 +67,10 @@ impl Default for RiskControlConfig {
 }
 
 /// 交易记录
+///
+/// `timestamp`与`token`目前只在调试输出中使用
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 struct TradeRecord {
     timestamp: Instant,
     token: Pubkey,
@@ -172,6 +175,13 @@ impl RiskStats {
     }
 }
 
+impl Default for RiskController {
+    /// 创建默认配置的控制器
+    fn default() -> Self {
+        Self::new(RiskControlConfig::default())
+    }
+}
+
 impl RiskController {
     /// 创建新的风险控制器
     pub fn new(config: RiskControlConfig) -> Self {
@@ -186,11 +196,6 @@ impl RiskController {
         }
     }
 
-    /// 创建默认配置的控制器
-    pub fn default() -> Self {
-        Self::new(RiskControlConfig::default())
-    }
-
     /// 检查买入交易
     ///
     /// # 参数
@@ -334,7 +339,7 @@ impl RiskController {
         token: Pubkey,
         amount_sol: f64,
         amount_tokens: u64,
-        current_price: f64,
+        _current_price: f64,
     ) {
         let profit_loss = if let Some(position) = self.positions.get(&token) {
             // 计算盈亏
@@ -490,7 +495,7 @@ impl RiskController {
         }
 
         // 持有人数检查
-        if token.holders_count < config.min_token_holders {
+        if token.holders_count < config.min_token_holders as u64 {
             issues.push(format!(
                 "Low holder count: {} (min: {})",
                 token.holders_count, config.min_token_holders
@@ -618,9 +623,7 @@ mod tests {
             name: "Test Token".to_string(),
             decimals: 9,
             total_supply: 1_000_000,
-            circulating_supply: 800_000,
             price_usd: 0.001,
-            market_cap_usd: 800.0,
             liquidity_sol: 50.0,
             liquidity_usd: 7500.0,
             volume_24h: 1000.0,
@@ -636,25 +639,13 @@ mod tests {
             dex: "Raydium".to_string(),
             pool_address: Some(Pubkey::new_unique()),
             creator: Some(Pubkey::new_unique()),
-            age_minutes: 60.0,
+            age_minutes: 60,
             age_hours: 1.0,
-            buy_tax: 0.0,
-            sell_tax: 0.0,
-            is_renounced: true,
-            is_frozen: false,
             txns_1h_buys: 50,
             txns_1h_sells: 30,
             txns_1h_total: 80,
-            txns_6h_buys: 200,
-            txns_6h_sells: 150,
-            txns_6h_total: 350,
             volatility_1h: 0.05,
-            volatility_6h: 0.08,
-            social_twitter: Some("@test".to_string()),
-            social_telegram: None,
-            social_website: None,
-            discovered_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
+            ..Default::default()
         }
     }
 
@@ -699,7 +690,7 @@ mod tests {
     #[tokio::test]
     async fn test_blacklist() {
         let controller = RiskController::default();
-        let mut token = create_test_token();
+        let token = create_test_token();
 
         // Add to blacklist
         controller.add_to_blacklist(token.mint).await;
@@ -1,6 +1,5 @@
 use crate::{Error, Result};
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::commitment_config::CommitmentConfig;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -4,7 +4,7 @@ use solana_sdk::pubkey::Pubkey;
 use uuid::Uuid;
 
 /// Token 基础信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenInfo {
     pub mint: Pubkey,
     pub symbol: String,
@@ -16,6 +16,9 @@ pub struct TokenInfo {
     pub liquidity_usd: f64,
     pub lp_locked: bool,
     pub lp_burned: bool,
+    /// LP锁仓解锁时间（未锁定或永久锁定时为None）
+    #[serde(default)]
+    pub lp_unlock_at: Option<DateTime<Utc>>,
 
     // 供应量信息
     pub total_supply: u64,
@@ -31,6 +34,10 @@ pub struct TokenInfo {
     pub mint_authority_revoked: bool,
     pub freeze_authority_revoked: bool,
 
+    /// Token-2022 扩展（SPL Token为空）
+    #[serde(default)]
+    pub extensions: Vec<TokenExtension>,
+
     // 交易税费
     pub buy_tax: f64,
     pub sell_tax: f64,
@@ -64,9 +71,90 @@ pub struct TokenInfo {
     // 验证状态
     pub is_verified: bool,
 
+    // 元数据信息（Metaplex）
+    /// 元数据是否可修改（未知为None）
+    #[serde(default)]
+    pub metadata_mutable: Option<bool>,
+    /// 元数据更新权限（已放弃为None）
+    #[serde(default)]
+    pub metadata_update_authority: Option<Pubkey>,
+    #[serde(default)]
+    pub metadata_uri: Option<String>,
+
     // 池子信息
     pub pool_address: Option<Pubkey>,
     pub dex: String, // "Raydium", "Orca", "Meteora", "PumpFun"
+
+    // Pump.fun 联合曲线
+    /// 联合曲线完成度（0-100，非Pump.fun代币为None）
+    #[serde(default)]
+    pub bonding_curve_progress: Option<f64>,
+    /// 迁移（毕业）到Raydium的时间
+    #[serde(default)]
+    pub graduated_at: Option<DateTime<Utc>>,
+
+    // 卖出模拟
+    /// 买入+卖出往返模拟是否能成功卖出（未模拟为None）
+    #[serde(default)]
+    pub sellable: Option<bool>,
+
+    // 部署者
+    /// mint的部署者（创建交易的fee payer，未知为None）
+    #[serde(default)]
+    pub creator: Option<Pubkey>,
+}
+
+impl TokenInfo {
+    /// 是否仍在Pump.fun联合曲线上（未毕业）
+    pub fn is_pre_graduation(&self) -> bool {
+        self.bonding_curve_progress.is_some() && self.graduated_at.is_none()
+    }
+
+    /// 距离毕业的分钟数（未毕业为None）
+    pub fn minutes_since_graduation(&self) -> Option<i64> {
+        self.graduated_at
+            .map(|graduated_at| (Utc::now() - graduated_at).num_minutes())
+    }
+}
+
+/// Token-2022 Mint扩展
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TokenExtension {
+    /// 转账手续费
+    TransferFee {
+        basis_points: u16,
+        maximum_fee: u64,
+        authority_retained: bool,
+    },
+    /// 转账钩子（每次转账调用外部程序）
+    TransferHook {
+        program_id: Option<Pubkey>,
+        authority_retained: bool,
+    },
+    /// 永久委托（可转走或销毁任意账户的代币）
+    PermanentDelegate {
+        delegate: Option<Pubkey>,
+    },
+    /// 不可转让
+    NonTransferable,
+    /// 新账户默认状态
+    DefaultAccountState {
+        frozen: bool,
+    },
+    /// Mint关闭权限
+    MintCloseAuthority {
+        authority_retained: bool,
+    },
+    /// 可暂停
+    Pausable {
+        authority_retained: bool,
+    },
+    InterestBearing,
+    ConfidentialTransfer,
+    MetadataPointer,
+    TokenMetadata,
+    /// 未识别的扩展类型
+    Other(u16),
 }
 
 /// 风险评分
@@ -87,9 +175,12 @@ pub struct ScoreBreakdown {
     pub sentiment: Score,
     pub similarity: Score,
     pub behavior: Score,
+    /// 部署者信誉
+    #[serde(default)]
+    pub deployer: Score,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Score {
     pub value: f64,
     pub issues: Vec<String>,
@@ -155,9 +246,40 @@ pub enum Event {
         message: String,
         timestamp: DateTime<Utc>,
     },
+    TokenLaunched {
+        token: Pubkey,
+        creator: Pubkey,
+        timestamp: DateTime<Utc>,
+    },
+    LiquidityAdded {
+        pool: Pubkey,
+        amount: u64,
+        timestamp: DateTime<Utc>,
+    },
+    LargeSwap {
+        pool: Pubkey,
+        amount: u64,
+        timestamp: DateTime<Utc>,
+    },
+    BondingCurveUpdated {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        virtual_sol_reserves: u64,
+        virtual_token_reserves: u64,
+        real_sol_reserves: u64,
+        real_token_reserves: u64,
+        completion_pct: f64,
+        timestamp: DateTime<Utc>,
+    },
+    TokenGraduated {
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        pool: Option<Pubkey>,
+        timestamp: DateTime<Utc>,
+    },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AlertType {
     LiquidityDrop,
     RugPullDetected,
@@ -107,7 +107,7 @@ impl TokenInfoValidator {
         // 5. 异常值检测和修正
         if token.price_change_1h.abs() > 10000.0 {
             tracing::warn!(
-                "Token {} has extreme price change: {}%, capping to ±1000%",
+                "Token {} has extreme price change: {}%, capping to [-1000%, 10000%]",
                 token.symbol,
                 token.price_change_1h
             );
@@ -176,7 +176,7 @@ impl TokenInfoValidator {
     /// - 交易量过低
     /// - 无社交媒体存在
     pub fn calculate_quality_score(token: &TokenInfo) -> f64 {
-        let mut score = 1.0;
+        let mut score: f64 = 1.0;
 
         // 流动性评分
         if token.liquidity_sol < 1.0 {
@@ -257,12 +257,26 @@ impl TokenInfoValidator {
     ///
     /// 返回警告信息列表
     pub fn detect_data_issues(token: &TokenInfo) -> Vec<String> {
+        Self::detect_data_issues_with_sol_price(token, None)
+    }
+
+    /// 检测可能的数据源问题（使用SOL/USD参考价格）
+    ///
+    /// 提供参考价格时，按与参考价格的偏差（>25%）判断流动性数据是否可疑；
+    /// 否则回退到固定区间检查
+    pub fn detect_data_issues_with_sol_price(token: &TokenInfo, sol_usd: Option<f64>) -> Vec<String> {
         let mut issues = Vec::new();
 
         // 流动性与价格不匹配
         if token.liquidity_usd > 0.0 && token.liquidity_sol > 0.0 {
             let implied_sol_price = token.liquidity_usd / token.liquidity_sol;
-            if implied_sol_price < 50.0 || implied_sol_price > 300.0 {
+            let suspicious = match sol_usd {
+                Some(reference) if reference > 0.0 => {
+                    (implied_sol_price - reference).abs() / reference > 0.25
+                }
+                _ => !(50.0..=300.0).contains(&implied_sol_price),
+            };
+            if suspicious {
                 issues.push(format!(
                     "Suspicious SOL price implied from liquidity: ${:.2}",
                     implied_sol_price
@@ -313,6 +327,7 @@ mod tests {
             liquidity_usd: 1500.0,
             lp_locked: true,
             lp_burned: false,
+            lp_unlock_at: None,
             total_supply: 1_000_000_000,
             circulating_supply: 1_000_000_000,
             holders_count: 100,
@@ -321,6 +336,7 @@ mod tests {
             top50_ratio: 0.85,
             mint_authority_revoked: true,
             freeze_authority_revoked: true,
+            extensions: Vec::new(),
             buy_tax: 0.0,
             sell_tax: 0.0,
             created_at: Utc::now(),
@@ -342,8 +358,15 @@ mod tests {
             discord_members: Some(20),
             sentiment_score: 0.5,
             is_verified: false,
+            metadata_mutable: None,
+            metadata_update_authority: None,
+            metadata_uri: None,
             pool_address: Some(Pubkey::new_unique()),
             dex: "Raydium".to_string(),
+            bonding_curve_progress: None,
+            graduated_at: None,
+            sellable: None,
+            creator: None,
         }
     }
 
@@ -401,7 +424,7 @@ mod tests {
 
         TokenInfoValidator::validate_and_sanitize(&mut token).unwrap();
 
-        assert_eq!(token.price_change_1h, 1000.0); // Capped to 1000%
+        assert_eq!(token.price_change_1h, 10000.0); // Capped to 10000%
     }
 
     #[test]
@@ -0,0 +1,554 @@
+use solsniper_core::{Error, Result, RpcManager, TradeSide};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::event_parser::{EventParser, ParsedTransaction};
+use crate::onchain_enricher::fetch_transaction;
+
+/// 获取失败的交易重试轮数
+const FETCH_RETRY_ROUNDS: u32 = 3;
+
+/// 回填范围（slot和时间条件同时生效）
+#[derive(Debug, Clone, Default)]
+pub struct BackfillRange {
+    pub from_slot: Option<u64>,
+    pub to_slot: Option<u64>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// 签名相对回填范围的位置（签名按时间倒序遍历）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangePosition {
+    /// 晚于范围，跳过并继续向前翻页
+    TooNew,
+    InRange,
+    /// 早于范围，遍历结束
+    TooOld,
+}
+
+impl BackfillRange {
+    pub fn position(&self, slot: u64, block_time: Option<DateTime<Utc>>) -> RangePosition {
+        if self.from_slot.map(|from| slot < from).unwrap_or(false) {
+            return RangePosition::TooOld;
+        }
+        if let (Some(since), Some(time)) = (self.since, block_time) {
+            if time < since {
+                return RangePosition::TooOld;
+            }
+        }
+        if self.to_slot.map(|to| slot > to).unwrap_or(false) {
+            return RangePosition::TooNew;
+        }
+        if let (Some(until), Some(time)) = (self.until, block_time) {
+            if time > until {
+                return RangePosition::TooNew;
+            }
+        }
+        RangePosition::InRange
+    }
+
+    /// 范围标识，作为checkpoint的键的一部分（范围变化时重新开始）
+    pub fn key(&self) -> String {
+        let slot = |slot: Option<u64>| slot.map(|s| s.to_string()).unwrap_or_default();
+        let time = |time: Option<DateTime<Utc>>| time.map(|t| t.timestamp().to_string()).unwrap_or_default();
+
+        format!(
+            "slot:{}-{}|time:{}-{}",
+            slot(self.from_slot),
+            slot(self.to_slot),
+            time(self.since),
+            time(self.until)
+        )
+    }
+}
+
+/// 单个程序在某个回填范围内的进度
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackfillCheckpoint {
+    pub program: String,
+    /// `BackfillRange::key`
+    #[serde(default)]
+    pub range: String,
+    /// 已处理的最旧签名，下次从它之前继续
+    pub before: Option<String>,
+    pub oldest_slot: Option<u64>,
+    pub transactions: u64,
+    pub swaps: u64,
+    pub completed: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl BackfillCheckpoint {
+    fn store_key(&self) -> String {
+        format!("{}#{}", self.program, self.range)
+    }
+}
+
+/// 回填进度存储（JSON文件：program#range -> checkpoint）
+pub struct CheckpointStore {
+    path: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn load_all(&self) -> Result<HashMap<String, BackfillCheckpoint>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content = std::fs::read_to_string(&self.path).map_err(|e| {
+            Error::Config(format!("Failed to read checkpoint {}: {}", self.path.display(), e))
+        })?;
+        serde_json::from_str(&content)
+            .map_err(|e| Error::Serialization(format!("Invalid checkpoint file: {}", e)))
+    }
+
+    pub fn load(&self, program: &Pubkey, range: &BackfillRange) -> Result<BackfillCheckpoint> {
+        let checkpoint = BackfillCheckpoint {
+            program: program.to_string(),
+            range: range.key(),
+            ..Default::default()
+        };
+
+        Ok(self.load_all()?.remove(&checkpoint.store_key()).unwrap_or(checkpoint))
+    }
+
+    /// 先写临时文件再重命名，避免中断时损坏
+    pub fn save(&self, checkpoint: &BackfillCheckpoint) -> Result<()> {
+        let mut all = self.load_all()?;
+        all.insert(checkpoint.store_key(), checkpoint.clone());
+
+        let content = serde_json::to_string_pretty(&all)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        let tmp = self.path.with_extension("tmp");
+
+        std::fs::write(&tmp, content)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| Error::Internal(format!("Failed to write checkpoint: {}", e)))
+    }
+}
+
+/// 回填结果写入trait
+#[async_trait]
+pub trait BackfillSink: Send + Sync {
+    /// 写入一批解析后的交易（必须幂等），返回写入的swap数
+    async fn write(&self, transactions: &[ParsedTransaction]) -> Result<usize>;
+}
+
+/// PostgreSQL写入
+///
+/// `tokens`按mint upsert（保留最早出现时间），发币交易按mint upsert到`token_launches`（保留最早发币时间），
+/// swap写入`market_trades`表，按(tx_signature, mint, side)去重；缺少区块时间的交易跳过
+pub struct PostgresBackfillSink {
+    pool: PgPool,
+}
+
+impl PostgresBackfillSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let pool = PgPool::connect(database_url)
+            .await
+            .map_err(|e| Error::Database(format!("PostgreSQL connection error: {}", e)))?;
+        Ok(Self::new(pool))
+    }
+
+    /// 按mint upsert代币，`created_at`保留最早出现时间
+    async fn upsert_token(
+        db_tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        mint: &Pubkey,
+        decimals: Option<u8>,
+        slot: u64,
+        seen_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO tokens (mint, decimals, data, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, NOW())
+             ON CONFLICT (mint) DO UPDATE
+             SET created_at = LEAST(tokens.created_at, EXCLUDED.created_at),
+                 decimals = COALESCE(tokens.decimals, EXCLUDED.decimals)",
+        )
+        .bind(mint.to_string())
+        .bind(decimals.map(|d| d as i32))
+        .bind(serde_json::json!({
+            "mint": mint.to_string(),
+            "decimals": decimals,
+            "source": "backfill",
+            "first_seen_slot": slot,
+        }))
+        .bind(seen_at.naive_utc())
+        .execute(&mut **db_tx)
+        .await
+        .map_err(|e| Error::Database(format!("Token upsert failed: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackfillSink for PostgresBackfillSink {
+    async fn write(&self, transactions: &[ParsedTransaction]) -> Result<usize> {
+        let mut db_tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+        let mut written = 0;
+
+        for parsed in transactions {
+            let Some(block_time) = parsed.block_time else {
+                tracing::debug!("Skipping transaction {} without block time", parsed.signature);
+                continue;
+            };
+
+            if let Some(launch) = &parsed.launch {
+                let decimals = parsed
+                    .swap
+                    .as_ref()
+                    .filter(|swap| swap.trade.mint == launch.mint)
+                    .map(|swap| swap.decimals);
+                Self::upsert_token(&mut db_tx, &launch.mint, decimals, parsed.slot, block_time).await?;
+
+                sqlx::query(
+                    "INSERT INTO token_launches (mint, creator, launched_at, launch_slot)
+                     VALUES ($1, $2, $3, $4)
+                     ON CONFLICT (mint) DO UPDATE
+                     SET creator = CASE WHEN EXCLUDED.launched_at < token_launches.launched_at
+                                        THEN EXCLUDED.creator ELSE token_launches.creator END,
+                         launched_at = LEAST(token_launches.launched_at, EXCLUDED.launched_at),
+                         launch_slot = LEAST(token_launches.launch_slot, EXCLUDED.launch_slot)",
+                )
+                .bind(launch.mint.to_string())
+                .bind(launch.creator.to_string())
+                .bind(block_time)
+                .bind(parsed.slot as i64)
+                .execute(&mut *db_tx)
+                .await
+                .map_err(|e| Error::Database(format!("Token launch upsert failed: {}", e)))?;
+            }
+
+            let Some(swap) = &parsed.swap else {
+                continue;
+            };
+            let trade = &swap.trade;
+            Self::upsert_token(&mut db_tx, &trade.mint, Some(swap.decimals), parsed.slot, block_time).await?;
+
+            let side = match trade.side {
+                TradeSide::Buy => "buy",
+                TradeSide::Sell => "sell",
+            };
+
+            let result = sqlx::query(
+                "INSERT INTO market_trades (mint, trader, side, amount_sol, amount_tokens, decimals,
+                                            tx_signature, slot, block_time, fee_lamports,
+                                            jito_tip_lamports, source)
+                 VALUES ($1, $2, $3, $4::float8, $5::numeric, $6, $7, $8, $9, $10, $11, 'backfill')
+                 ON CONFLICT (tx_signature, mint, side) DO NOTHING",
+            )
+            .bind(trade.mint.to_string())
+            .bind(trade.trader.to_string())
+            .bind(side)
+            .bind(trade.sol_amount)
+            .bind(swap.token_amount_raw.to_string())
+            .bind(swap.decimals as i16)
+            .bind(&parsed.signature)
+            .bind(parsed.slot as i64)
+            .bind(block_time)
+            .bind(parsed.fee_lamports as i64)
+            .bind(trade.jito_tip_lamports.map(|tip| tip as i64))
+            .execute(&mut *db_tx)
+            .await
+            .map_err(|e| Error::Database(format!("Market trade insert failed: {}", e)))?;
+
+            written += result.rows_affected() as usize;
+        }
+
+        db_tx
+            .commit()
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(written)
+    }
+}
+
+/// 历史回填任务
+///
+/// 按`getSignaturesForAddress`从新到旧遍历程序的签名，解析交易写入sink，
+/// 每批处理完成后保存checkpoint，中断后从上次的最旧签名继续。
+/// 批次中有交易重试后仍获取失败时返回错误且不推进checkpoint，下次运行重新处理该批
+pub struct BackfillJob {
+    rpc: Arc<RpcManager>,
+    parser: EventParser,
+    sink: Arc<dyn BackfillSink>,
+    checkpoints: CheckpointStore,
+    range: BackfillRange,
+    batch_size: usize,
+    concurrency: usize,
+}
+
+impl BackfillJob {
+    pub fn new(rpc: Arc<RpcManager>, sink: Arc<dyn BackfillSink>, checkpoints: CheckpointStore) -> Self {
+        Self {
+            rpc,
+            parser: EventParser::new(),
+            sink,
+            checkpoints,
+            range: BackfillRange::default(),
+            batch_size: 1000,
+            concurrency: 8,
+        }
+    }
+
+    pub fn with_range(mut self, range: BackfillRange) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, 1000);
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// 回填单个程序
+    pub async fn run(&self, program: &Pubkey) -> Result<BackfillCheckpoint> {
+        let mut checkpoint = self.checkpoints.load(program, &self.range)?;
+        if checkpoint.completed {
+            tracing::info!("⏭️ Backfill for {} already completed", program);
+            return Ok(checkpoint);
+        }
+
+        tracing::info!(
+            "⏪ Backfilling {} from {}",
+            program,
+            checkpoint.before.as_deref().unwrap_or("latest")
+        );
+
+        loop {
+            let signatures = self.fetch_signatures(program, checkpoint.before.as_deref()).await?;
+            if signatures.is_empty() {
+                checkpoint.completed = true;
+                break;
+            }
+
+            let mut reached_end = false;
+            let mut in_range = Vec::new();
+            for entry in &signatures {
+                let block_time = entry.block_time.and_then(|t| DateTime::from_timestamp(t, 0));
+                match self.range.position(entry.slot, block_time) {
+                    RangePosition::TooNew => {}
+                    RangePosition::InRange if entry.err.is_none() => in_range.push(entry.signature.clone()),
+                    RangePosition::InRange => {}
+                    RangePosition::TooOld => {
+                        reached_end = true;
+                        break;
+                    }
+                }
+            }
+
+            let parsed = self.fetch_batch(in_range).await?;
+            let swaps = self.sink.write(&parsed).await?;
+
+            let last = signatures.last().unwrap();
+            checkpoint.before = Some(last.signature.clone());
+            checkpoint.oldest_slot = Some(last.slot);
+            checkpoint.transactions += parsed.len() as u64;
+            checkpoint.swaps += swaps as u64;
+            checkpoint.updated_at = Some(Utc::now());
+            checkpoint.completed = reached_end || signatures.len() < self.batch_size;
+            self.checkpoints.save(&checkpoint)?;
+
+            tracing::info!(
+                "⏪ {}: slot {} | {} txs, {} swaps total",
+                program,
+                last.slot,
+                checkpoint.transactions,
+                checkpoint.swaps
+            );
+
+            if checkpoint.completed {
+                break;
+            }
+        }
+
+        tracing::info!("✅ Backfill for {} finished: {} swaps", program, checkpoint.swaps);
+        Ok(checkpoint)
+    }
+
+    async fn fetch_signatures(
+        &self,
+        program: &Pubkey,
+        before: Option<&str>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let program = *program;
+        let before = before
+            .map(Signature::from_str)
+            .transpose()
+            .map_err(|e| Error::InvalidInput(format!("Invalid checkpoint signature: {}", e)))?;
+        let limit = self.batch_size;
+
+        self.rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || {
+                    client.get_signatures_for_address_with_config(
+                        &program,
+                        GetConfirmedSignaturesForAddress2Config {
+                            before,
+                            until: None,
+                            limit: Some(limit),
+                            commitment: Some(CommitmentConfig::confirmed()),
+                        },
+                    )
+                })
+                .await
+                .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                .map_err(|e| Error::Rpc(format!("getSignaturesForAddress failed: {}", e)))
+            })
+            .await
+    }
+
+    /// 获取并解析一批交易，失败的交易退避后重试，仍失败时返回错误
+    async fn fetch_batch(&self, signatures: Vec<String>) -> Result<Vec<ParsedTransaction>> {
+        let mut parsed = Vec::new();
+        let mut pending = signatures;
+
+        for round in 0..=FETCH_RETRY_ROUNDS {
+            if round > 0 {
+                tokio::time::sleep(std::time::Duration::from_secs(2u64.pow(round))).await;
+                tracing::info!("🔁 Retrying {} failed transactions (round {})", pending.len(), round);
+            }
+
+            let (fetched, failed) = self.fetch_and_parse(pending).await;
+            parsed.extend(fetched);
+            pending = failed;
+
+            if pending.is_empty() {
+                return Ok(parsed);
+            }
+        }
+
+        Err(Error::Rpc(format!(
+            "Failed to fetch {} transactions after {} retries (first: {})",
+            pending.len(),
+            FETCH_RETRY_ROUNDS,
+            pending[0]
+        )))
+    }
+
+    /// 并发获取并解析交易，返回(解析结果, 获取失败的签名)
+    async fn fetch_and_parse(&self, signatures: Vec<String>) -> (Vec<ParsedTransaction>, Vec<String>) {
+        let results: Vec<std::result::Result<Option<ParsedTransaction>, String>> =
+            stream::iter(signatures)
+                .map(|signature| async move {
+                    match fetch_transaction(&self.rpc, &signature).await {
+                        Ok(tx) => Ok(self.parser.parse_transaction(&tx)),
+                        Err(e) => {
+                            tracing::warn!("Failed to fetch transaction {}: {}", signature, e);
+                            Err(signature)
+                        }
+                    }
+                })
+                .buffer_unordered(self.concurrency)
+                .collect()
+                .await;
+
+        let mut parsed = Vec::new();
+        let mut failed = Vec::new();
+        for result in results {
+            match result {
+                Ok(Some(tx)) => parsed.push(tx),
+                Ok(None) => {}
+                Err(signature) => failed.push(signature),
+            }
+        }
+
+        (parsed, failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_position() {
+        let range = BackfillRange {
+            from_slot: Some(100),
+            to_slot: Some(200),
+            ..Default::default()
+        };
+
+        assert_eq!(range.position(250, None), RangePosition::TooNew);
+        assert_eq!(range.position(150, None), RangePosition::InRange);
+        assert_eq!(range.position(50, None), RangePosition::TooOld);
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let path = std::env::temp_dir().join(format!("backfill-{}.json", Pubkey::new_unique()));
+        let store = CheckpointStore::new(&path);
+        let program = Pubkey::new_unique();
+        let range = BackfillRange::default();
+
+        let mut checkpoint = store.load(&program, &range).unwrap();
+        assert!(checkpoint.before.is_none());
+
+        checkpoint.before = Some("5abc".to_string());
+        checkpoint.swaps = 42;
+        store.save(&checkpoint).unwrap();
+
+        assert_eq!(store.load(&program, &range).unwrap(), checkpoint);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_checkpoint_keyed_by_range() {
+        let path = std::env::temp_dir().join(format!("backfill-{}.json", Pubkey::new_unique()));
+        let store = CheckpointStore::new(&path);
+        let program = Pubkey::new_unique();
+        let recent = BackfillRange {
+            from_slot: Some(200),
+            ..Default::default()
+        };
+        let older = BackfillRange {
+            from_slot: Some(100),
+            to_slot: Some(200),
+            ..Default::default()
+        };
+
+        let mut checkpoint = store.load(&program, &recent).unwrap();
+        checkpoint.before = Some("5abc".to_string());
+        checkpoint.completed = true;
+        store.save(&checkpoint).unwrap();
+
+        // 新范围从头开始，原范围的进度保留
+        let fresh = store.load(&program, &older).unwrap();
+        assert!(fresh.before.is_none());
+        assert!(!fresh.completed);
+        assert_eq!(store.load(&program, &recent).unwrap(), checkpoint);
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,87 @@
+//! 历史回填命令
+//!
+//! 用法:
+//!   solsniper-backfill [--program <PUBKEY|raydium|pumpfun|...>]... [--from-slot N] [--to-slot N]
+//!                      [--since RFC3339] [--until RFC3339] [--checkpoint PATH]
+//!
+//! 环境变量: `RPC_URLS`（逗号分隔）、`DATABASE_URL`
+
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+use solsniper_core::{LoadBalancingStrategy, RpcManager};
+use solsniper_data_collector::backfill::{BackfillJob, BackfillRange, CheckpointStore, PostgresBackfillSink};
+use solsniper_data_collector::dex_programs;
+use std::str::FromStr;
+use std::sync::Arc;
+
+fn parse_program(value: &str) -> anyhow::Result<Pubkey> {
+    Ok(match value {
+        "raydium" | "raydium-amm" => dex_programs::raydium_amm(),
+        "raydium-clmm" => dex_programs::raydium_clmm(),
+        "orca" => dex_programs::orca_whirlpool(),
+        "meteora" => dex_programs::meteora_dlmm(),
+        "pumpfun" => dex_programs::pumpfun(),
+        other => Pubkey::from_str(other)?,
+    })
+}
+
+fn parse_time(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(value)?.with_timezone(&Utc))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info,sqlx=warn".into()),
+        )
+        .init();
+
+    let mut programs = Vec::new();
+    let mut range = BackfillRange::default();
+    let mut checkpoint_path = "backfill_checkpoint.json".to_string();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--program" => programs.push(parse_program(&value()?)?),
+            "--from-slot" => range.from_slot = Some(value()?.parse()?),
+            "--to-slot" => range.to_slot = Some(value()?.parse()?),
+            "--since" => range.since = Some(parse_time(&value()?)?),
+            "--until" => range.until = Some(parse_time(&value()?)?),
+            "--checkpoint" => checkpoint_path = value()?,
+            other => anyhow::bail!("Unknown argument: {}", other),
+        }
+    }
+
+    if programs.is_empty() {
+        programs = vec![
+            dex_programs::raydium_amm(),
+            dex_programs::raydium_clmm(),
+            dex_programs::orca_whirlpool(),
+            dex_programs::meteora_dlmm(),
+            dex_programs::pumpfun(),
+        ];
+    }
+
+    let rpc_urls: Vec<String> = std::env::var("RPC_URLS")
+        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string())
+        .split(',')
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect();
+    let database_url = std::env::var("DATABASE_URL")?;
+
+    let rpc = Arc::new(RpcManager::new(rpc_urls, 30, LoadBalancingStrategy::RoundRobin));
+    let sink = Arc::new(PostgresBackfillSink::connect(&database_url).await?);
+
+    let job = BackfillJob::new(rpc, sink, CheckpointStore::new(checkpoint_path)).with_range(range);
+
+    for program in &programs {
+        job.run(program).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,620 @@
+use solsniper_core::{Error, Result, RpcManager};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use crate::mint_decoder::{read_pubkey, read_u64, MintAccount, TokenProgram};
+use crate::onchain_enricher::{fetch_account, fetch_multiple_accounts};
+
+/// Orca每个TickArray包含的tick数
+const ORCA_TICKS_PER_ARRAY: i32 = 88;
+/// Orca Tick结构大小
+const ORCA_TICK_SIZE: usize = 113;
+
+/// Raydium CLMM每个TickArray包含的tick数
+const RAYDIUM_TICKS_PER_ARRAY: i32 = 60;
+/// Raydium TickState结构大小
+const RAYDIUM_TICK_SIZE: usize = 168;
+
+/// Meteora每个BinArray包含的bin数
+const METEORA_BINS_PER_ARRAY: i64 = 70;
+/// Meteora Bin结构大小
+const METEORA_BIN_SIZE: usize = 144;
+
+/// 集中流动性DEX类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClmmDex {
+    RaydiumClmm,
+    OrcaWhirlpool,
+    MeteoraDlmm,
+}
+
+impl ClmmDex {
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            Self::RaydiumClmm => crate::dex_programs::raydium_clmm(),
+            Self::OrcaWhirlpool => crate::dex_programs::orca_whirlpool(),
+            Self::MeteoraDlmm => crate::dex_programs::meteora_dlmm(),
+        }
+    }
+
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Self> {
+        [Self::RaydiumClmm, Self::OrcaWhirlpool, Self::MeteoraDlmm]
+            .into_iter()
+            .find(|dex| dex.program_id() == *program_id)
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::RaydiumClmm => "Raydium CLMM",
+            Self::OrcaWhirlpool => "Orca Whirlpool",
+            Self::MeteoraDlmm => "Meteora DLMM",
+        }
+    }
+}
+
+/// 解码后的池子状态
+///
+/// tick类池子使用`current_tick`/`sqrt_price_x64`/`liquidity`，
+/// DLMM使用`current_tick`保存active bin id，`tick_spacing`保存bin_step
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClmmPoolState {
+    pub dex: ClmmDex,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub tick_spacing: u16,
+    pub current_tick: i32,
+    pub sqrt_price_x64: Option<u128>,
+    pub liquidity: u128,
+}
+
+impl ClmmPoolState {
+    /// 解码池子账户
+    pub fn decode(dex: ClmmDex, data: &[u8]) -> Result<Self> {
+        match dex {
+            ClmmDex::OrcaWhirlpool => Self::decode_whirlpool(data),
+            ClmmDex::RaydiumClmm => Self::decode_raydium(data),
+            ClmmDex::MeteoraDlmm => Self::decode_lb_pair(data),
+        }
+    }
+
+    /// Whirlpool布局:
+    /// tick_spacing@41, liquidity@49, sqrt_price@65, tick_current_index@81,
+    /// token_mint_a@101, token_vault_a@133, token_mint_b@181, token_vault_b@213
+    fn decode_whirlpool(data: &[u8]) -> Result<Self> {
+        ensure_len(data, 245, "Whirlpool")?;
+
+        Ok(Self {
+            dex: ClmmDex::OrcaWhirlpool,
+            tick_spacing: read_u16(data, 41),
+            liquidity: read_u128(data, 49),
+            sqrt_price_x64: Some(read_u128(data, 65)),
+            current_tick: read_i32(data, 81),
+            mint_a: read_pubkey(data, 101),
+            vault_a: read_pubkey(data, 133),
+            mint_b: read_pubkey(data, 181),
+            vault_b: read_pubkey(data, 213),
+        })
+    }
+
+    /// Raydium PoolState布局:
+    /// token_mint_0@73, token_mint_1@105, token_vault_0@137, token_vault_1@169,
+    /// tick_spacing@235, liquidity@237, sqrt_price_x64@253, tick_current@269
+    fn decode_raydium(data: &[u8]) -> Result<Self> {
+        ensure_len(data, 273, "Raydium CLMM pool")?;
+
+        Ok(Self {
+            dex: ClmmDex::RaydiumClmm,
+            mint_a: read_pubkey(data, 73),
+            mint_b: read_pubkey(data, 105),
+            vault_a: read_pubkey(data, 137),
+            vault_b: read_pubkey(data, 169),
+            tick_spacing: read_u16(data, 235),
+            liquidity: read_u128(data, 237),
+            sqrt_price_x64: Some(read_u128(data, 253)),
+            current_tick: read_i32(data, 269),
+        })
+    }
+
+    /// LbPair布局:
+    /// active_id@76, bin_step@80, token_x_mint@88, token_y_mint@120,
+    /// reserve_x@152, reserve_y@184
+    fn decode_lb_pair(data: &[u8]) -> Result<Self> {
+        ensure_len(data, 216, "Meteora LbPair")?;
+
+        Ok(Self {
+            dex: ClmmDex::MeteoraDlmm,
+            current_tick: read_i32(data, 76),
+            tick_spacing: read_u16(data, 80),
+            mint_a: read_pubkey(data, 88),
+            mint_b: read_pubkey(data, 120),
+            vault_a: read_pubkey(data, 152),
+            vault_b: read_pubkey(data, 184),
+            sqrt_price_x64: None,
+            liquidity: 0,
+        })
+    }
+
+    /// 原始价格（B最小单位 / A最小单位）
+    pub fn raw_price(&self) -> f64 {
+        match (self.dex, self.sqrt_price_x64) {
+            (ClmmDex::MeteoraDlmm, _) => bin_price(self.current_tick, self.tick_spacing),
+            (_, Some(sqrt_price_x64)) => {
+                let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+                sqrt_price * sqrt_price
+            }
+            (_, None) => tick_to_price(self.current_tick),
+        }
+    }
+
+    /// 按精度调整后的价格（1个A值多少B）
+    pub fn price(&self, decimals_a: u8, decimals_b: u8) -> f64 {
+        self.raw_price() * 10f64.powi(decimals_a as i32 - decimals_b as i32)
+    }
+
+    /// 当前价格所在tick/bin数组的起始索引及其相邻数组
+    pub fn nearby_array_indices(&self) -> Vec<i64> {
+        let span = match self.dex {
+            ClmmDex::OrcaWhirlpool => ORCA_TICKS_PER_ARRAY as i64 * self.tick_spacing.max(1) as i64,
+            ClmmDex::RaydiumClmm => RAYDIUM_TICKS_PER_ARRAY as i64 * self.tick_spacing.max(1) as i64,
+            ClmmDex::MeteoraDlmm => METEORA_BINS_PER_ARRAY,
+        };
+
+        let current = (self.current_tick as i64).div_euclid(span);
+        match self.dex {
+            // tick类返回起始tick，DLMM返回数组索引
+            ClmmDex::MeteoraDlmm => vec![current - 1, current, current + 1],
+            _ => vec![(current - 1) * span, current * span, (current + 1) * span],
+        }
+    }
+
+    /// tick/bin数组的PDA地址
+    pub fn array_address(&self, pool: &Pubkey, index: i64) -> Pubkey {
+        let program_id = self.dex.program_id();
+        match self.dex {
+            ClmmDex::OrcaWhirlpool => Pubkey::find_program_address(
+                &[b"tick_array", pool.as_ref(), index.to_string().as_bytes()],
+                &program_id,
+            ),
+            ClmmDex::RaydiumClmm => Pubkey::find_program_address(
+                &[b"tick_array", pool.as_ref(), &(index as i32).to_be_bytes()],
+                &program_id,
+            ),
+            ClmmDex::MeteoraDlmm => Pubkey::find_program_address(
+                &[b"bin_array", pool.as_ref(), &index.to_le_bytes()],
+                &program_id,
+            ),
+        }
+        .0
+    }
+}
+
+/// 已初始化的tick（流动性净变化）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickLiquidity {
+    pub tick: i32,
+    pub liquidity_net: i128,
+}
+
+/// DLMM bin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinLiquidity {
+    pub bin_id: i32,
+    pub amount_x: u64,
+    pub amount_y: u64,
+}
+
+/// 解码Orca TickArray中已初始化的tick
+pub fn decode_orca_tick_array(data: &[u8], tick_spacing: u16) -> Result<Vec<TickLiquidity>> {
+    ensure_len(data, 12 + ORCA_TICK_SIZE * ORCA_TICKS_PER_ARRAY as usize, "Orca TickArray")?;
+    let start_tick = read_i32(data, 8);
+
+    Ok((0..ORCA_TICKS_PER_ARRAY as usize)
+        .filter_map(|i| {
+            let offset = 12 + i * ORCA_TICK_SIZE;
+            let initialized = data[offset] != 0;
+            initialized.then(|| TickLiquidity {
+                tick: start_tick + i as i32 * tick_spacing as i32,
+                liquidity_net: read_i128(data, offset + 1),
+            })
+        })
+        .collect())
+}
+
+/// 解码Raydium TickArrayState中已初始化的tick
+pub fn decode_raydium_tick_array(data: &[u8]) -> Result<Vec<TickLiquidity>> {
+    ensure_len(data, 44 + RAYDIUM_TICK_SIZE * RAYDIUM_TICKS_PER_ARRAY as usize, "Raydium TickArray")?;
+
+    Ok((0..RAYDIUM_TICKS_PER_ARRAY as usize)
+        .filter_map(|i| {
+            let offset = 44 + i * RAYDIUM_TICK_SIZE;
+            let liquidity_gross = read_u128(data, offset + 20);
+            (liquidity_gross > 0).then(|| TickLiquidity {
+                tick: read_i32(data, offset),
+                liquidity_net: read_i128(data, offset + 4),
+            })
+        })
+        .collect())
+}
+
+/// 解码Meteora BinArray中有流动性的bin
+pub fn decode_meteora_bin_array(data: &[u8]) -> Result<Vec<BinLiquidity>> {
+    ensure_len(data, 56 + METEORA_BIN_SIZE * METEORA_BINS_PER_ARRAY as usize, "Meteora BinArray")?;
+    let index = read_u64(data, 8) as i64;
+
+    Ok((0..METEORA_BINS_PER_ARRAY as usize)
+        .filter_map(|i| {
+            let offset = 56 + i * METEORA_BIN_SIZE;
+            let amount_x = read_u64(data, offset);
+            let amount_y = read_u64(data, offset + 8);
+            (amount_x > 0 || amount_y > 0).then(|| BinLiquidity {
+                bin_id: (index * METEORA_BINS_PER_ARRAY + i as i64) as i32,
+                amount_x,
+                amount_y,
+            })
+        })
+        .collect())
+}
+
+/// 近价深度
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolDepth {
+    /// 当前价格（1个A值多少B）
+    pub price: f64,
+    /// 当前活跃流动性（tick类为L，DLMM为活跃bin折合B的数量）
+    pub active_liquidity: f64,
+    /// 价格上涨range_pct以内可买到的A数量
+    pub depth_a: f64,
+    /// 价格下跌range_pct以内可卖出换得的B数量
+    pub depth_b: f64,
+    /// 计算深度所用的价格范围（如0.02 = ±2%）
+    pub range_pct: f64,
+}
+
+impl PoolDepth {
+    /// 近价流动性折合B
+    pub fn total_depth_in_b(&self) -> f64 {
+        self.depth_a * self.price + self.depth_b
+    }
+
+    /// 近价流动性折合SOL（池子两边都不是WSOL时为None）
+    pub fn total_depth_in_sol(&self, state: &ClmmPoolState) -> Option<f64> {
+        let wsol = crate::token_programs::wsol();
+        if state.mint_b == wsol {
+            Some(self.total_depth_in_b())
+        } else if state.mint_a == wsol && self.price > 0.0 {
+            Some(self.depth_a + self.depth_b / self.price)
+        } else {
+            None
+        }
+    }
+}
+
+/// tick类池子的近价深度（原始单位）
+///
+/// 从当前价格沿tick分段向上/向下累积:
+/// - 向上: ΔA = L × (1/√P_lo − 1/√P_hi)
+/// - 向下: ΔB = L × (√P_hi − √P_lo)
+///
+/// 跨越tick时按liquidity_net调整L
+pub fn tick_depth(
+    sqrt_price: f64,
+    liquidity: u128,
+    ticks: &[TickLiquidity],
+    range_pct: f64,
+) -> (f64, f64) {
+    let mut sorted: Vec<TickLiquidity> = ticks.to_vec();
+    sorted.sort_by_key(|t| t.tick);
+
+    let upper_limit = sqrt_price * (1.0 + range_pct).sqrt();
+    let lower_limit = sqrt_price * (1.0 - range_pct).max(0.0).sqrt();
+
+    // 向上：消耗A
+    let mut depth_a = 0.0;
+    let mut current_l = liquidity as f64;
+    let mut current = sqrt_price;
+    for tick in sorted.iter().filter(|t| tick_to_sqrt_price(t.tick) > sqrt_price) {
+        let boundary = tick_to_sqrt_price(tick.tick).min(upper_limit);
+        depth_a += current_l * (1.0 / current - 1.0 / boundary);
+        current = boundary;
+        if boundary >= upper_limit {
+            break;
+        }
+        current_l = (current_l + tick.liquidity_net as f64).max(0.0);
+    }
+    if current < upper_limit {
+        depth_a += current_l * (1.0 / current - 1.0 / upper_limit);
+    }
+
+    // 向下：消耗B
+    let mut depth_b = 0.0;
+    let mut current_l = liquidity as f64;
+    let mut current = sqrt_price;
+    for tick in sorted.iter().rev().filter(|t| tick_to_sqrt_price(t.tick) <= sqrt_price) {
+        let boundary = tick_to_sqrt_price(tick.tick).max(lower_limit);
+        depth_b += current_l * (current - boundary);
+        current = boundary;
+        if boundary <= lower_limit {
+            break;
+        }
+        current_l = (current_l - tick.liquidity_net as f64).max(0.0);
+    }
+    if current > lower_limit {
+        depth_b += current_l * (current - lower_limit);
+    }
+
+    (depth_a.max(0.0), depth_b.max(0.0))
+}
+
+/// DLMM近价深度（原始单位）
+pub fn bin_depth(active_id: i32, bin_step: u16, bins: &[BinLiquidity], range_pct: f64) -> (f64, f64, f64) {
+    let active_price = bin_price(active_id, bin_step);
+    let upper = active_price * (1.0 + range_pct);
+    let lower = active_price * (1.0 - range_pct);
+
+    let mut depth_a = 0.0;
+    let mut depth_b = 0.0;
+    let mut active_liquidity = 0.0;
+
+    for bin in bins {
+        let price = bin_price(bin.bin_id, bin_step);
+
+        if bin.bin_id == active_id {
+            active_liquidity = bin.amount_x as f64 * price + bin.amount_y as f64;
+        }
+        if bin.bin_id >= active_id && price <= upper {
+            depth_a += bin.amount_x as f64;
+        }
+        if bin.bin_id <= active_id && price >= lower {
+            depth_b += bin.amount_y as f64;
+        }
+    }
+
+    (active_liquidity, depth_a, depth_b)
+}
+
+/// tick对应价格: 1.0001^tick
+pub fn tick_to_price(tick: i32) -> f64 {
+    1.0001f64.powi(tick)
+}
+
+fn tick_to_sqrt_price(tick: i32) -> f64 {
+    1.0001f64.powf(tick as f64 / 2.0)
+}
+
+/// DLMM bin价格: (1 + bin_step/10000)^bin_id
+pub fn bin_price(bin_id: i32, bin_step: u16) -> f64 {
+    (1.0 + bin_step as f64 / 10_000.0).powi(bin_id)
+}
+
+/// 集中流动性池解码器
+pub struct ClmmPoolDecoder {
+    rpc: Arc<RpcManager>,
+}
+
+impl ClmmPoolDecoder {
+    pub fn new(rpc: Arc<RpcManager>) -> Self {
+        Self { rpc }
+    }
+
+    /// 获取并解码池子状态
+    pub async fn fetch_pool(&self, pool: &Pubkey) -> Result<ClmmPoolState> {
+        let account = fetch_account(&self.rpc, pool).await?;
+        let dex = ClmmDex::from_program_id(&account.owner).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Pool {} is not owned by a supported CLMM program (owner: {})",
+                pool, account.owner
+            ))
+        })?;
+
+        ClmmPoolState::decode(dex, &account.data)
+    }
+
+    /// 计算池子的当前价格、活跃流动性和近价深度
+    ///
+    /// # 参数
+    /// - `pool`: 池子地址
+    /// - `range_pct`: 深度价格范围（如0.02 = ±2%）
+    pub async fn fetch_depth(&self, pool: &Pubkey, range_pct: f64) -> Result<PoolDepth> {
+        let state = self.fetch_pool(pool).await?;
+        self.depth_of(pool, &state, range_pct).await
+    }
+
+    /// 按已解码的池子状态计算深度（池子账户已在批量读取中获得时使用）
+    pub async fn depth_of(&self, pool: &Pubkey, state: &ClmmPoolState, range_pct: f64) -> Result<PoolDepth> {
+
+        // 读取两边mint的精度
+        let mints = fetch_multiple_accounts(&self.rpc, &[state.mint_a, state.mint_b]).await?;
+        let decimals: Vec<u8> = mints
+            .iter()
+            .map(|account| {
+                let account = account.as_ref().ok_or_else(|| {
+                    Error::NotFound(format!("Mint account for pool {} not found", pool))
+                })?;
+                let program = TokenProgram::from_owner(&account.owner).unwrap_or(TokenProgram::Spl);
+                Ok(MintAccount::decode(program, &account.data)?.decimals)
+            })
+            .collect::<Result<_>>()?;
+        let (decimals_a, decimals_b) = (decimals[0], decimals[1]);
+
+        // 读取相邻的tick/bin数组
+        let arrays: Vec<Pubkey> = state
+            .nearby_array_indices()
+            .into_iter()
+            .map(|index| state.array_address(pool, index))
+            .collect();
+        let array_accounts = fetch_multiple_accounts(&self.rpc, &arrays).await?;
+
+        let (active_liquidity, depth_a_raw, depth_b_raw) = match state.dex {
+            ClmmDex::MeteoraDlmm => {
+                let mut bins = Vec::new();
+                for account in array_accounts.iter().flatten() {
+                    bins.extend(decode_meteora_bin_array(&account.data)?);
+                }
+                bin_depth(state.current_tick, state.tick_spacing, &bins, range_pct)
+            }
+            dex => {
+                let mut ticks = Vec::new();
+                for account in array_accounts.iter().flatten() {
+                    if dex == ClmmDex::OrcaWhirlpool {
+                        ticks.extend(decode_orca_tick_array(&account.data, state.tick_spacing)?);
+                    } else {
+                        ticks.extend(decode_raydium_tick_array(&account.data)?);
+                    }
+                }
+                let (depth_a, depth_b) =
+                    tick_depth(state.raw_price().sqrt(), state.liquidity, &ticks, range_pct);
+                (state.liquidity as f64, depth_a, depth_b)
+            }
+        };
+
+        let depth = PoolDepth {
+            price: state.price(decimals_a, decimals_b),
+            active_liquidity,
+            depth_a: depth_a_raw / 10f64.powi(decimals_a as i32),
+            depth_b: depth_b_raw / 10f64.powi(decimals_b as i32),
+            range_pct,
+        };
+
+        tracing::debug!(
+            "📐 {} pool {}: price {:.9}, depth ±{:.1}%: {:.2} A / {:.2} B",
+            state.dex.name(),
+            pool,
+            depth.price,
+            range_pct * 100.0,
+            depth.depth_a,
+            depth.depth_b
+        );
+
+        Ok(depth)
+    }
+}
+
+fn ensure_len(data: &[u8], len: usize, what: &str) -> Result<()> {
+    if data.len() < len {
+        return Err(Error::Serialization(format!(
+            "{} account too short: {} bytes (expected at least {})",
+            what,
+            data.len(),
+            len
+        )));
+    }
+    Ok(())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_i32(data: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_u128(data: &[u8], offset: usize) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&data[offset..offset + 16]);
+    u128::from_le_bytes(bytes)
+}
+
+fn read_i128(data: &[u8], offset: usize) -> i128 {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&data[offset..offset + 16]);
+    i128::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_whirlpool_price() {
+        let mut data = vec![0u8; 653];
+        data[41..43].copy_from_slice(&64u16.to_le_bytes());
+        data[49..65].copy_from_slice(&1_000_000u128.to_le_bytes());
+        // sqrt_price = 2.0 (Q64.64) -> 原始价格 4.0
+        data[65..81].copy_from_slice(&(2u128 << 64).to_le_bytes());
+        data[81..85].copy_from_slice(&13_863i32.to_le_bytes());
+
+        let state = ClmmPoolState::decode(ClmmDex::OrcaWhirlpool, &data).unwrap();
+
+        assert_eq!(state.tick_spacing, 64);
+        assert_eq!(state.liquidity, 1_000_000);
+        assert!((state.raw_price() - 4.0).abs() < 1e-9);
+        // SOL(9位) / USDC(6位)
+        assert!((state.price(9, 6) - 4000.0).abs() < 1e-6);
+        assert_eq!(state.nearby_array_indices(), vec![5632, 11264, 16896]);
+    }
+
+    #[test]
+    fn test_tick_depth_uniform_liquidity() {
+        // 无tick边界时等价于全范围恒定L
+        let liquidity = 1_000_000u128;
+        let (depth_a, depth_b) = tick_depth(1.0, liquidity, &[], 0.21);
+
+        let upper = 1.21f64.sqrt();
+        let lower = 0.79f64.sqrt();
+        assert!((depth_a - 1_000_000.0 * (1.0 - 1.0 / upper)).abs() < 1e-6);
+        assert!((depth_b - 1_000_000.0 * (1.0 - lower)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_tick_depth_liquidity_ends_at_tick() {
+        // 当前tick上方的tick移除全部流动性
+        let liquidity = 1_000_000u128;
+        let ticks = [TickLiquidity { tick: 100, liquidity_net: -1_000_000 }];
+        let (depth_a, _) = tick_depth(1.0, liquidity, &ticks, 0.5);
+
+        let boundary = tick_to_sqrt_price(100);
+        assert!((depth_a - 1_000_000.0 * (1.0 - 1.0 / boundary)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bin_depth() {
+        let bins = [
+            BinLiquidity { bin_id: -1, amount_x: 0, amount_y: 500 },
+            BinLiquidity { bin_id: 0, amount_x: 100, amount_y: 100 },
+            BinLiquidity { bin_id: 1, amount_x: 300, amount_y: 0 },
+            BinLiquidity { bin_id: 500, amount_x: 1_000, amount_y: 0 },
+        ];
+
+        let (active, depth_a, depth_b) = bin_depth(0, 25, &bins, 0.02);
+
+        assert!((active - 200.0).abs() < 1e-9);
+        assert!((depth_a - 400.0).abs() < 1e-9);
+        assert!((depth_b - 600.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_total_depth_in_sol() {
+        let token = Pubkey::new_unique();
+        let mut state = ClmmPoolState {
+            dex: ClmmDex::OrcaWhirlpool,
+            mint_a: token,
+            mint_b: crate::token_programs::wsol(),
+            vault_a: Pubkey::new_unique(),
+            vault_b: Pubkey::new_unique(),
+            tick_spacing: 64,
+            current_tick: 0,
+            sqrt_price_x64: None,
+            liquidity: 0,
+        };
+        // 1 token = 0.5 SOL
+        let depth = PoolDepth {
+            price: 0.5,
+            active_liquidity: 0.0,
+            depth_a: 100.0,
+            depth_b: 20.0,
+            range_pct: 0.02,
+        };
+        assert!((depth.total_depth_in_sol(&state).unwrap() - 70.0).abs() < 1e-9);
+
+        // SOL在A边：1 SOL = 2 token
+        std::mem::swap(&mut state.mint_a, &mut state.mint_b);
+        let depth = PoolDepth { price: 2.0, depth_a: 10.0, depth_b: 40.0, ..depth };
+        assert!((depth.total_depth_in_sol(&state).unwrap() - 30.0).abs() < 1e-9);
+
+        state.mint_a = Pubkey::new_unique();
+        assert!(depth.total_depth_in_sol(&state).is_none());
+    }
+}
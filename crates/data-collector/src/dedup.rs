@@ -0,0 +1,434 @@
+use solsniper_core::{Error, Result};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use redis::aio::ConnectionManager;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 去重键：交易签名 + 顶层指令索引 + 指令内的事件序号
+///
+/// 同一笔交易无论从日志订阅还是Geyser流到达，
+/// 签名、指令索引和事件序号都相同，因此多实例/多数据源可以共享同一个键空间。
+/// 事件序号区分同一条指令产生的多个事件（如CPI内的多次swap）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DedupKey {
+    pub signature: String,
+    pub instruction_index: usize,
+    pub event_index: usize,
+}
+
+impl DedupKey {
+    pub fn new(signature: impl Into<String>, instruction_index: usize) -> Self {
+        Self {
+            signature: signature.into(),
+            instruction_index,
+            event_index: 0,
+        }
+    }
+
+    /// 指令内的第几个事件（从0开始）
+    pub fn with_event_index(mut self, event_index: usize) -> Self {
+        self.event_index = event_index;
+        self
+    }
+
+    /// 存储层使用的字符串键
+    pub fn cache_key(&self) -> String {
+        format!("dedup:{}:{}:{}", self.signature, self.instruction_index, self.event_index)
+    }
+}
+
+/// 去重存储trait
+#[async_trait]
+pub trait DedupStore: Send + Sync {
+    /// 原子地检查并标记键
+    ///
+    /// 首次见到返回`true`，已存在（重复）返回`false`
+    async fn check_and_mark(&self, key: &DedupKey) -> Result<bool>;
+
+    /// 撤销标记（事件未能送达时调用，允许之后重新处理）
+    async fn unmark(&self, key: &DedupKey) -> Result<()>;
+
+    /// 清理过期记录（自带TTL的存储无需实现）
+    fn purge_expired(&self) -> usize {
+        0
+    }
+
+    /// 存储名称
+    fn name(&self) -> &str;
+}
+
+/// 本地内存去重存储
+///
+/// 仅在单进程内有效，适用于单实例部署或测试
+pub struct LocalDedupStore {
+    seen: DashMap<String, Instant>,
+    ttl: Duration,
+}
+
+impl LocalDedupStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            seen: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// 当前记录数
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl Default for LocalDedupStore {
+    /// 默认TTL为1小时
+    fn default() -> Self {
+        Self::new(Duration::from_secs(3600))
+    }
+}
+
+#[async_trait]
+impl DedupStore for LocalDedupStore {
+    async fn check_and_mark(&self, key: &DedupKey) -> Result<bool> {
+        use dashmap::mapref::entry::Entry;
+
+        match self.seen.entry(key.cache_key()) {
+            Entry::Occupied(mut entry) => {
+                if entry.get().elapsed() < self.ttl {
+                    Ok(false)
+                } else {
+                    // 记录已过期，视为新事件
+                    entry.insert(Instant::now());
+                    Ok(true)
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Instant::now());
+                Ok(true)
+            }
+        }
+    }
+
+    async fn unmark(&self, key: &DedupKey) -> Result<()> {
+        self.seen.remove(&key.cache_key());
+        Ok(())
+    }
+
+    fn purge_expired(&self) -> usize {
+        let before = self.seen.len();
+        self.seen.retain(|_, seen_at| seen_at.elapsed() < self.ttl);
+        before.saturating_sub(self.seen.len())
+    }
+
+    fn name(&self) -> &str {
+        "local"
+    }
+}
+
+/// Redis去重存储
+///
+/// 使用`SET key 1 NX EX ttl`实现跨实例的原子去重
+pub struct RedisDedupStore {
+    conn: ConnectionManager,
+    ttl: Duration,
+}
+
+impl RedisDedupStore {
+    /// 创建新的Redis去重存储
+    ///
+    /// # 参数
+    /// - `redis_url`: Redis连接URL
+    /// - `ttl`: 去重键的过期时间
+    pub async fn new(redis_url: &str, ttl: Duration) -> Result<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| Error::Internal(format!("Redis connection error: {}", e)))?;
+
+        let conn = ConnectionManager::new(client)
+            .await
+            .map_err(|e| Error::Internal(format!("Redis connection test failed: {}", e)))?;
+
+        Ok(Self { conn, ttl })
+    }
+}
+
+#[async_trait]
+impl DedupStore for RedisDedupStore {
+    async fn check_and_mark(&self, key: &DedupKey) -> Result<bool> {
+        let mut conn = self.conn.clone();
+
+        // SET NX 成功返回"OK"，键已存在返回nil
+        let reply: Option<String> = redis::cmd("SET")
+            .arg(key.cache_key())
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(self.ttl.as_secs().max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| Error::Internal(format!("Redis SETNX error: {}", e)))?;
+
+        Ok(reply.is_some())
+    }
+
+    async fn unmark(&self, key: &DedupKey) -> Result<()> {
+        let mut conn = self.conn.clone();
+
+        let _: i64 = redis::cmd("DEL")
+            .arg(key.cache_key())
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| Error::Internal(format!("Redis DEL error: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "redis"
+    }
+}
+
+/// 去重统计快照
+#[derive(Debug, Clone, Default)]
+pub struct DedupStats {
+    /// 通过去重的事件数
+    pub accepted: u64,
+    /// 因重复被丢弃的事件数
+    pub duplicates_dropped: u64,
+    /// 存储错误次数（出错时放行事件）
+    pub store_errors: u64,
+    /// 按数据源统计的重复丢弃数
+    pub duplicates_by_source: Vec<(String, u64)>,
+}
+
+impl DedupStats {
+    pub fn duplicate_rate(&self) -> f64 {
+        let total = self.accepted + self.duplicates_dropped;
+        if total == 0 {
+            return 0.0;
+        }
+        self.duplicates_dropped as f64 / total as f64
+    }
+}
+
+/// 事件去重器
+///
+/// 包装一个可插拔的`DedupStore`并记录丢弃计数
+pub struct EventDeduplicator {
+    store: Arc<dyn DedupStore>,
+    accepted: AtomicU64,
+    duplicates_dropped: AtomicU64,
+    store_errors: AtomicU64,
+    duplicates_by_source: DashMap<String, u64>,
+}
+
+impl EventDeduplicator {
+    pub fn new(store: Arc<dyn DedupStore>) -> Self {
+        Self {
+            store,
+            accepted: AtomicU64::new(0),
+            duplicates_dropped: AtomicU64::new(0),
+            store_errors: AtomicU64::new(0),
+            duplicates_by_source: DashMap::new(),
+        }
+    }
+
+    /// 使用默认本地存储创建
+    pub fn local() -> Self {
+        Self::new(Arc::new(LocalDedupStore::default()))
+    }
+
+    /// 检查事件是否重复
+    ///
+    /// 存储出错时放行事件（宁可重复也不丢失），并计入`store_errors`
+    pub async fn is_duplicate(&self, source: &str, key: &DedupKey) -> bool {
+        match self.store.check_and_mark(key).await {
+            Ok(true) => {
+                self.accepted.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            Ok(false) => {
+                self.duplicates_dropped.fetch_add(1, Ordering::Relaxed);
+                *self.duplicates_by_source.entry(source.to_string()).or_insert(0) += 1;
+                true
+            }
+            Err(e) => {
+                self.store_errors.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    "Dedup store '{}' error for {}: {}, letting event through",
+                    self.store.name(),
+                    key.cache_key(),
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// 释放已标记的键
+    ///
+    /// 事件通过去重后发送失败时调用，否则该事件在所有实例上都会被当作重复而永久丢失
+    pub async fn release(&self, key: &DedupKey) {
+        if let Err(e) = self.store.unmark(key).await {
+            self.store_errors.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                "Dedup store '{}' failed to release {}: {}",
+                self.store.name(),
+                key.cache_key(),
+                e
+            );
+        }
+    }
+
+    /// 清理过期记录
+    pub fn purge_expired(&self) -> usize {
+        self.store.purge_expired()
+    }
+
+    /// 存储名称
+    pub fn store_name(&self) -> &str {
+        self.store.name()
+    }
+
+    /// 获取统计快照
+    pub fn stats(&self) -> DedupStats {
+        let mut duplicates_by_source: Vec<(String, u64)> = self
+            .duplicates_by_source
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        duplicates_by_source.sort_by(|a, b| a.0.cmp(&b.0));
+
+        DedupStats {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            duplicates_dropped: self.duplicates_dropped.load(Ordering::Relaxed),
+            store_errors: self.store_errors.load(Ordering::Relaxed),
+            duplicates_by_source,
+        }
+    }
+}
+
+/// 根据日志行追踪当前所在的顶层指令索引
+///
+/// Solana运行时为每条顶层指令输出一行`Program <id> invoke [1]`，
+/// 遇到该行时索引前进一位，指令内的事件序号归零
+#[derive(Debug, Default)]
+pub struct InstructionIndexTracker {
+    next: usize,
+    current: Option<usize>,
+    events: usize,
+}
+
+impl InstructionIndexTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 处理一行日志，返回该行所属的顶层指令索引
+    pub fn observe(&mut self, log: &str) -> usize {
+        if log.starts_with("Program ") && log.ends_with(" invoke [1]") {
+            self.current = Some(self.next);
+            self.next += 1;
+            self.events = 0;
+        }
+        self.current.unwrap_or(0)
+    }
+
+    /// 当前指令内下一个事件的序号
+    pub fn next_event(&mut self) -> usize {
+        let index = self.events;
+        self.events += 1;
+        index
+    }
+
+    /// 当前指令内下一个事件的去重键
+    pub fn key(&mut self, signature: &str) -> DedupKey {
+        let instruction_index = self.current.unwrap_or(0);
+        DedupKey::new(signature, instruction_index).with_event_index(self.next_event())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_store_drops_duplicates() {
+        let dedup = EventDeduplicator::local();
+        let key = DedupKey::new("sig1", 0);
+
+        assert!(!dedup.is_duplicate("logs", &key).await);
+        assert!(dedup.is_duplicate("geyser", &key).await);
+        assert!(!dedup.is_duplicate("logs", &DedupKey::new("sig1", 1)).await);
+
+        let stats = dedup.stats();
+        assert_eq!(stats.accepted, 2);
+        assert_eq!(stats.duplicates_dropped, 1);
+        assert_eq!(stats.duplicates_by_source, vec![("geyser".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_release_allows_redelivery() {
+        let dedup = EventDeduplicator::local();
+        let key = DedupKey::new("sig2", 0);
+
+        assert!(!dedup.is_duplicate("logs", &key).await);
+        // 发送失败后释放，下次到达时不再视为重复
+        dedup.release(&key).await;
+        assert!(!dedup.is_duplicate("geyser", &key).await);
+        assert!(dedup.is_duplicate("logs", &key).await);
+    }
+
+    #[tokio::test]
+    async fn test_local_store_expiry() {
+        let store = LocalDedupStore::new(Duration::from_millis(0));
+        let key = DedupKey::new("sig", 0);
+
+        assert!(store.check_and_mark(&key).await.unwrap());
+        assert!(store.check_and_mark(&key).await.unwrap());
+        assert_eq!(store.purge_expired(), 1);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_instruction_index_tracker() {
+        let logs = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 invoke [1]",
+            "Program log: initialize2",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Instruction: Transfer",
+        ];
+
+        let mut tracker = InstructionIndexTracker::new();
+        let indices: Vec<usize> = logs.iter().map(|l| tracker.observe(l)).collect();
+        assert_eq!(indices, vec![0, 0, 1, 1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_events_in_same_instruction_get_distinct_keys() {
+        let dedup = EventDeduplicator::local();
+        let mut tracker = InstructionIndexTracker::new();
+
+        tracker.observe("Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]");
+        let first = tracker.key("sig");
+        let second = tracker.key("sig");
+        assert_ne!(first, second);
+        assert!(!dedup.is_duplicate("logs", &first).await);
+        assert!(!dedup.is_duplicate("logs", &second).await);
+
+        // 下一条指令的序号重新从0开始，另一数据源按相同顺序得到相同的键
+        tracker.observe("Program 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 invoke [1]");
+        assert_eq!(tracker.key("sig"), DedupKey::new("sig", 1));
+
+        let mut replay = InstructionIndexTracker::new();
+        replay.observe("Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]");
+        replay.key("sig");
+        assert!(dedup.is_duplicate("geyser", &replay.key("sig")).await);
+    }
+}
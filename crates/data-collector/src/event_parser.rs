@@ -1,8 +1,533 @@
+use solsniper_core::{LiquidityEvent, LiquidityEventKind, SwapTrade, TradeSide};
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction, UiMessage, UiTransactionTokenBalance,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Wrapped SOL mint
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Jito小费账户
+const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// 解析出的swap
+#[derive(Debug, Clone)]
+pub struct ParsedSwap {
+    pub trade: SwapTrade,
+    /// 代币数量（最小单位）
+    pub token_amount_raw: u64,
+    pub decimals: u8,
+}
+
+/// 解析出的发币（Pump.fun创建或建池）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParsedLaunch {
+    pub mint: Pubkey,
+    /// 发起创建/建池的fee payer
+    pub creator: Pubkey,
+}
+
+/// 解析后的交易
+#[derive(Debug, Clone)]
+pub struct ParsedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<DateTime<Utc>>,
+    pub fee_lamports: u64,
+    pub fee_payer: Pubkey,
+    pub swap: Option<ParsedSwap>,
+    /// 加池/撤池
+    pub liquidity: Option<LiquidityEvent>,
+    /// Pump.fun创建代币或首次建池
+    pub launch: Option<ParsedLaunch>,
+    pub logs: Vec<String>,
+}
+
+/// 单个mint的余额变化
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenDelta {
+    pub mint: Pubkey,
+    pub raw_delta: i128,
+    pub decimals: u8,
+}
+
 /// 事件解析器
+///
+/// 根据交易前后的SOL/代币余额变化推导fee payer的swap和加池/撤池，与具体DEX的指令布局无关
 pub struct EventParser;
 
 impl EventParser {
     pub fn new() -> Self {
         Self
     }
+
+    /// 解析已确认的交易（JSON编码）
+    pub fn parse_transaction(&self, tx: &EncodedConfirmedTransactionWithStatusMeta) -> Option<ParsedTransaction> {
+        let meta = tx.transaction.meta.as_ref()?;
+        if meta.err.is_some() {
+            return None;
+        }
+
+        let (signature, account_keys) = Self::signature_and_keys(&tx.transaction.transaction)?;
+        let fee_payer = *account_keys.first()?;
+
+        let block_time = tx.block_time.and_then(|t| DateTime::from_timestamp(t, 0));
+        let logs: Vec<String> = Option::from(meta.log_messages.clone()).unwrap_or_default();
+
+        // fee payer的SOL变化（加回手续费）
+        let pre_sol = *meta.pre_balances.first()? as i128;
+        let post_sol = *meta.post_balances.first()? as i128;
+        let mut sol_delta = post_sol - pre_sol + meta.fee as i128;
+
+        let pre_tokens: Vec<UiTransactionTokenBalance> =
+            Option::from(meta.pre_token_balances.clone()).unwrap_or_default();
+        let post_tokens: Vec<UiTransactionTokenBalance> =
+            Option::from(meta.post_token_balances.clone()).unwrap_or_default();
+
+        let mut deltas = Self::token_deltas(&fee_payer, &pre_tokens, &post_tokens);
+        let jito_tip_lamports = Self::jito_tip(&account_keys, &meta.pre_balances, &meta.post_balances);
+
+        // 扣除小费和代币账户租金，只保留成交额（优先费已包含在fee中）
+        sol_delta += jito_tip_lamports.unwrap_or(0) as i128;
+        sol_delta +=
+            Self::token_account_rent(&meta.pre_balances, &meta.post_balances, &pre_tokens, &post_tokens);
+
+        // WSOL视为SOL
+        let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+        if let Some(index) = deltas.iter().position(|d| d.mint == wsol) {
+            sol_delta += deltas.remove(index).raw_delta;
+        }
+
+        let swap = Self::derive_swap(sol_delta, &deltas).map(|(side, delta, sol_lamports)| {
+            let token_amount_raw = delta.raw_delta.unsigned_abs() as u64;
+            ParsedSwap {
+                trade: SwapTrade {
+                    mint: delta.mint,
+                    trader: fee_payer,
+                    side,
+                    sol_amount: sol_lamports as f64 / 1e9,
+                    token_amount: token_amount_raw as f64 / 10f64.powi(delta.decimals as i32),
+                    signature: signature.clone(),
+                    slot: tx.slot,
+                    timestamp: block_time.unwrap_or_else(Utc::now),
+                    jito_tip_lamports,
+                },
+                token_amount_raw,
+                decimals: delta.decimals,
+            }
+        });
+
+        let liquidity = Self::derive_liquidity(sol_delta, &deltas).map(|(kind, delta, sol_lamports)| {
+            LiquidityEvent {
+                mint: delta.mint,
+                pool: None,
+                kind,
+                sol_amount: sol_lamports as f64 / 1e9,
+                token_amount: delta.raw_delta.unsigned_abs() as f64 / 10f64.powi(delta.decimals as i32),
+                provider: fee_payer,
+                signature: signature.clone(),
+                slot: tx.slot,
+                timestamp: block_time.unwrap_or_else(Utc::now),
+            }
+        });
+
+        let launch = Self::derive_launch(&logs, &Self::account_keys(tx), liquidity.as_ref(), fee_payer);
+
+        Some(ParsedTransaction {
+            signature,
+            slot: tx.slot,
+            block_time,
+            fee_lamports: meta.fee,
+            fee_payer,
+            swap,
+            liquidity,
+            launch,
+            logs,
+        })
+    }
+
+    /// 由日志识别发币
+    ///
+    /// - Pump.fun `Create`：mint为账户列表中联合曲线PDA对应的账户
+    /// - Raydium `initialize2` / PumpSwap `CreatePool`：mint为建池时加入的代币
+    pub fn derive_launch(
+        logs: &[String],
+        account_keys: &[Pubkey],
+        liquidity: Option<&LiquidityEvent>,
+        fee_payer: Pubkey,
+    ) -> Option<ParsedLaunch> {
+        let has_log = |pattern: &str| logs.iter().any(|log| log.contains(pattern));
+
+        if has_log("Program log: Instruction: Create") && !has_log("Program log: Instruction: CreatePool") {
+            let (mint, _) = crate::pumpfun::BondingCurveState::find_mint_in(account_keys)?;
+            return Some(ParsedLaunch { mint, creator: fee_payer });
+        }
+
+        if has_log("initialize2") || has_log("Program log: Instruction: CreatePool") {
+            let liquidity = liquidity.filter(|event| event.kind == LiquidityEventKind::Add)?;
+            return Some(ParsedLaunch {
+                mint: liquidity.mint,
+                creator: fee_payer,
+            });
+        }
+
+        None
+    }
+
+    /// 由余额变化推导swap
+    ///
+    /// 只有一个代币余额变化且与SOL变化方向相反时视为swap，返回(方向, 代币变化, SOL数量lamports)。
+    /// `sol_delta`应已扣除手续费、小费和代币账户租金
+    pub fn derive_swap(
+        sol_delta: i128,
+        deltas: &[TokenDelta],
+    ) -> Option<(TradeSide, TokenDelta, u64)> {
+        let changed: Vec<&TokenDelta> = deltas.iter().filter(|d| d.raw_delta != 0).collect();
+        if changed.len() != 1 {
+            return None;
+        }
+
+        let delta = *changed[0];
+        match (delta.raw_delta > 0, sol_delta) {
+            (true, sol) if sol < 0 => Some((TradeSide::Buy, delta, sol.unsigned_abs() as u64)),
+            (false, sol) if sol > 0 => Some((TradeSide::Sell, delta, sol as u64)),
+            _ => None,
+        }
+    }
+
+    /// 由余额变化推导加池/撤池
+    ///
+    /// 恰好两个代币余额变化：与SOL同向的为池子代币，反向的为LP代币。
+    /// SOL和代币同时减少并收到LP为加池，同时增加并销毁LP为撤池。返回(方向, 代币变化, SOL数量lamports)
+    pub fn derive_liquidity(
+        sol_delta: i128,
+        deltas: &[TokenDelta],
+    ) -> Option<(LiquidityEventKind, TokenDelta, u64)> {
+        let changed: Vec<&TokenDelta> = deltas.iter().filter(|d| d.raw_delta != 0).collect();
+        if changed.len() != 2 || sol_delta == 0 {
+            return None;
+        }
+
+        let same_direction = |delta: &TokenDelta| (delta.raw_delta > 0) == (sol_delta > 0);
+        let token = match (same_direction(changed[0]), same_direction(changed[1])) {
+            (true, false) => changed[0],
+            (false, true) => changed[1],
+            _ => return None,
+        };
+
+        let kind = if sol_delta < 0 {
+            LiquidityEventKind::Add
+        } else {
+            LiquidityEventKind::Remove
+        };
+        Some((kind, *token, sol_delta.unsigned_abs() as u64))
+    }
+
+    /// 支付给Jito小费账户的lamports（捆绑交易）
+    pub fn jito_tip(account_keys: &[Pubkey], pre_balances: &[u64], post_balances: &[u64]) -> Option<u64> {
+        let tip: u64 = account_keys
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| JITO_TIP_ACCOUNTS.contains(&key.to_string().as_str()))
+            .filter_map(|(i, _)| Some(post_balances.get(i)?.saturating_sub(*pre_balances.get(i)?)))
+            .sum();
+
+        (tip > 0).then_some(tip)
+    }
+
+    /// 交易中新建代币账户支付的租金减去关闭代币账户退回的租金（lamports）
+    ///
+    /// WSOL账户的lamports包含包装的SOL，已计入代币余额变化，这里只计算租金部分
+    pub fn token_account_rent(
+        pre_balances: &[u64],
+        post_balances: &[u64],
+        pre_tokens: &[UiTransactionTokenBalance],
+        post_tokens: &[UiTransactionTokenBalance],
+    ) -> i128 {
+        let wrapped = |balance: &UiTransactionTokenBalance| -> i128 {
+            if balance.mint == WSOL_MINT {
+                balance.ui_token_amount.amount.parse().unwrap_or(0)
+            } else {
+                0
+            }
+        };
+        let find = |balances: &[UiTransactionTokenBalance], index: u8| {
+            balances.iter().find(|b| b.account_index == index).cloned()
+        };
+
+        let mut rent: i128 = 0;
+
+        // 新建：交易后存在、交易前lamports为0
+        for balance in post_tokens {
+            let index = balance.account_index as usize;
+            let (Some(pre), Some(post)) = (pre_balances.get(index), post_balances.get(index)) else {
+                continue;
+            };
+            if *pre == 0 && *post > 0 && find(pre_tokens, balance.account_index).is_none() {
+                rent += *post as i128 - wrapped(balance);
+            }
+        }
+
+        // 关闭：交易前存在、交易后lamports为0
+        for balance in pre_tokens {
+            let index = balance.account_index as usize;
+            let (Some(pre), Some(post)) = (pre_balances.get(index), post_balances.get(index)) else {
+                continue;
+            };
+            if *pre > 0 && *post == 0 {
+                rent -= *pre as i128 - wrapped(balance);
+            }
+        }
+
+        rent
+    }
+
+    /// 汇总owner在每个mint上的余额变化
+    fn token_deltas(
+        owner: &Pubkey,
+        pre: &[UiTransactionTokenBalance],
+        post: &[UiTransactionTokenBalance],
+    ) -> Vec<TokenDelta> {
+        let owner = owner.to_string();
+        let mut totals: HashMap<String, (i128, u8)> = HashMap::new();
+
+        let mut accumulate = |balances: &[UiTransactionTokenBalance], sign: i128| {
+            for balance in balances {
+                let balance_owner = match &balance.owner {
+                    OptionSerializer::Some(balance_owner) => balance_owner,
+                    _ => continue,
+                };
+                if *balance_owner != owner {
+                    continue;
+                }
+
+                let amount: i128 = balance.ui_token_amount.amount.parse().unwrap_or(0);
+                let entry = totals
+                    .entry(balance.mint.clone())
+                    .or_insert((0, balance.ui_token_amount.decimals));
+                entry.0 += sign * amount;
+            }
+        };
+
+        accumulate(pre, -1);
+        accumulate(post, 1);
+
+        totals
+            .into_iter()
+            .filter_map(|(mint, (raw_delta, decimals))| {
+                Some(TokenDelta {
+                    mint: Pubkey::from_str(&mint).ok()?,
+                    raw_delta,
+                    decimals,
+                })
+            })
+            .collect()
+    }
+
+    /// 交易涉及的全部账户（静态账户 + 地址查找表加载的账户）
+    pub fn account_keys(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<Pubkey> {
+        let mut keys = Self::signature_and_keys(&tx.transaction.transaction)
+            .map(|(_, keys)| keys)
+            .unwrap_or_default();
+
+        if let Some(meta) = &tx.transaction.meta {
+            if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+                keys.extend(
+                    loaded
+                        .writable
+                        .iter()
+                        .chain(loaded.readonly.iter())
+                        .filter_map(|key| Pubkey::from_str(key).ok()),
+                );
+            }
+        }
+
+        keys
+    }
+
+    /// 读取签名和账户列表
+    fn signature_and_keys(transaction: &EncodedTransaction) -> Option<(String, Vec<Pubkey>)> {
+        match transaction {
+            EncodedTransaction::Json(ui_tx) => {
+                let signature = ui_tx.signatures.first()?.clone();
+                let keys = match &ui_tx.message {
+                    UiMessage::Raw(message) => message
+                        .account_keys
+                        .iter()
+                        .filter_map(|key| Pubkey::from_str(key).ok())
+                        .collect(),
+                    UiMessage::Parsed(message) => message
+                        .account_keys
+                        .iter()
+                        .filter_map(|account| Pubkey::from_str(&account.pubkey).ok())
+                        .collect(),
+                };
+                Some((signature, keys))
+            }
+            other => {
+                let tx = other.decode()?;
+                let signature = tx.signatures.first()?.to_string();
+                Some((signature, tx.message.static_account_keys().to_vec()))
+            }
+        }
+    }
+}
+
+impl Default for EventParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(raw_delta: i128) -> TokenDelta {
+        TokenDelta {
+            mint: Pubkey::new_unique(),
+            raw_delta,
+            decimals: 6,
+        }
+    }
+
+    fn token_balance(account_index: u8, mint: &str, amount: u64) -> UiTransactionTokenBalance {
+        serde_json::from_value(serde_json::json!({
+            "accountIndex": account_index,
+            "mint": mint,
+            "uiTokenAmount": {
+                "uiAmount": null,
+                "decimals": 6,
+                "amount": amount.to_string(),
+                "uiAmountString": "",
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_derive_buy_and_sell() {
+        let (side, _, sol) = EventParser::derive_swap(-1_500_000_000, &[delta(1_000_000)]).unwrap();
+        assert_eq!(side, TradeSide::Buy);
+        assert_eq!(sol, 1_500_000_000);
+
+        let (side, _, sol) = EventParser::derive_swap(700_000_000, &[delta(-1_000_000)]).unwrap();
+        assert_eq!(side, TradeSide::Sell);
+        assert_eq!(sol, 700_000_000);
+    }
+
+    #[test]
+    fn test_ignores_transfers_and_multi_token_swaps() {
+        // 只收到代币、SOL未减少（空投/转账）
+        assert!(EventParser::derive_swap(0, &[delta(1_000)]).is_none());
+        // 代币换代币
+        assert!(EventParser::derive_swap(-5_000, &[delta(1_000), delta(-2_000)]).is_none());
+    }
+
+    #[test]
+    fn test_token_account_rent() {
+        let mint = Pubkey::new_unique().to_string();
+        const RENT: u64 = 2_039_280;
+
+        // 买入：新建代币ATA（账户1）；临时WSOL账户（账户2）新建后关闭，净额为0
+        let pre = [10_000_000_000, 0, 0];
+        let post = [8_000_000_000, RENT, 0];
+        let rent = EventParser::token_account_rent(&pre, &post, &[], &[token_balance(1, &mint, 1_000)]);
+        assert_eq!(rent, RENT as i128);
+
+        // 卖出：关闭持有1 SOL的WSOL账户，只有租金部分算作退款
+        let pre = [1_000_000_000, RENT + 1_000_000_000];
+        let post = [3_000_000_000, 0];
+        let rent = EventParser::token_account_rent(
+            &pre,
+            &post,
+            &[token_balance(1, WSOL_MINT, 1_000_000_000)],
+            &[],
+        );
+        assert_eq!(rent, -(RENT as i128));
+    }
+
+    #[test]
+    fn test_derive_launch() {
+        let creator = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let curve = crate::pumpfun::BondingCurveState::find_address(&mint);
+        let keys = [creator, mint, curve, crate::dex_programs::pumpfun()];
+
+        let create = vec!["Program log: Instruction: Create".to_string()];
+        assert_eq!(
+            EventParser::derive_launch(&create, &keys, None, creator),
+            Some(ParsedLaunch { mint, creator })
+        );
+
+        // 建池：以加池的代币为准
+        let pool_mint = Pubkey::new_unique();
+        let added = LiquidityEvent {
+            mint: pool_mint,
+            pool: None,
+            kind: LiquidityEventKind::Add,
+            sol_amount: 80.0,
+            token_amount: 1_000_000.0,
+            provider: creator,
+            signature: String::new(),
+            slot: 1,
+            timestamp: Utc::now(),
+        };
+        let init = vec!["Program log: initialize2: InitializeInstruction2 { nonce: 254 }".to_string()];
+        assert_eq!(
+            EventParser::derive_launch(&init, &[creator], Some(&added), creator),
+            Some(ParsedLaunch { mint: pool_mint, creator })
+        );
+        let create_pool = vec!["Program log: Instruction: CreatePool".to_string()];
+        assert_eq!(
+            EventParser::derive_launch(&create_pool, &keys, Some(&added), creator).map(|l| l.mint),
+            Some(pool_mint)
+        );
+
+        // 普通交易不是发币
+        let buy = vec!["Program log: Instruction: Buy".to_string()];
+        assert!(EventParser::derive_launch(&buy, &keys, Some(&added), creator).is_none());
+    }
+
+    #[test]
+    fn test_derive_liquidity() {
+        let token = delta(-1_000_000);
+        let lp = delta(50_000);
+
+        let (kind, derived, sol) = EventParser::derive_liquidity(-2_000_000_000, &[lp, token]).unwrap();
+        assert_eq!(kind, LiquidityEventKind::Add);
+        assert_eq!(derived.mint, token.mint);
+        assert_eq!(sol, 2_000_000_000);
+
+        let (kind, derived, _) =
+            EventParser::derive_liquidity(1_900_000_000, &[delta(-50_000), delta(990_000)]).unwrap();
+        assert_eq!(kind, LiquidityEventKind::Remove);
+        assert_eq!(derived.raw_delta, 990_000);
+
+        // 两个代币同向变化不是加池/撤池
+        assert!(EventParser::derive_liquidity(-1_000, &[delta(-10), delta(-20)]).is_none());
+    }
+
+    #[test]
+    fn test_jito_tip() {
+        let payer = Pubkey::new_unique();
+        let tip_account = Pubkey::from_str(JITO_TIP_ACCOUNTS[0]).unwrap();
+
+        let keys = [payer, tip_account];
+        assert_eq!(EventParser::jito_tip(&keys, &[10_000_000, 500], &[8_000_000, 100_500]), Some(100_000));
+        assert_eq!(EventParser::jito_tip(&[payer], &[10_000_000], &[8_000_000]), None);
+    }
 }
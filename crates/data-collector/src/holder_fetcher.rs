@@ -0,0 +1,490 @@
+use solsniper_core::{Error, Holder, Result, RpcManager, TokenInfo};
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::mint_decoder::{read_pubkey, read_u64, MintAccount, TokenProgram};
+use crate::onchain_enricher::{fetch_account, fetch_multiple_accounts};
+
+/// SPL Token账户长度
+const TOKEN_ACCOUNT_LEN: u64 = 165;
+
+/// 代币账户余额（按token account）
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenAccountBalance {
+    /// Token账户地址
+    pub address: Pubkey,
+    /// 账户所有者（钱包）
+    pub owner: Pubkey,
+    /// 原始数量
+    pub amount: u64,
+}
+
+/// 持有者快照配置
+#[derive(Debug, Clone)]
+pub struct HolderSnapshotConfig {
+    /// 鲸鱼阈值（占流通量百分比）
+    pub whale_threshold_pct: f64,
+
+    /// 已知锁仓程序（其PDA持有的代币不计入分布）
+    pub locker_programs: Vec<Pubkey>,
+
+    /// DEX/联合曲线程序（其PDA持有的代币是池子流动性，不计入分布）
+    pub pool_programs: Vec<Pubkey>,
+
+    /// 没有账户数据的池子authority（如Raydium AMM V4 authority）
+    pub pool_authorities: Vec<Pubkey>,
+
+    /// 销毁地址
+    pub burn_addresses: Vec<Pubkey>,
+
+    /// 返回的最大持有者数量
+    pub max_holders: usize,
+}
+
+impl Default for HolderSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            whale_threshold_pct: 2.0,
+            locker_programs: crate::token_programs::known_lockers(),
+            pool_programs: crate::dex_programs::pool_programs(),
+            pool_authorities: vec![crate::dex_programs::raydium_amm_authority()],
+            burn_addresses: crate::token_programs::burn_addresses(),
+            max_holders: 50,
+        }
+    }
+}
+
+/// 持有者分布快照
+#[derive(Debug, Clone)]
+pub struct HolderSnapshot {
+    /// 按持仓降序排列的持有者（已排除池子、销毁和锁仓）
+    pub holders: Vec<Holder>,
+
+    /// 持有者总数；仅有getTokenLargestAccounts结果时为None
+    pub holders_count: Option<u64>,
+
+    pub top10_ratio: f64,
+    pub top20_ratio: f64,
+    pub top50_ratio: f64,
+
+    /// 被排除的数量（池子金库、销毁地址、锁仓）
+    pub excluded_amount: u64,
+
+    /// 计算比例所用的流通量
+    pub circulating_amount: u64,
+}
+
+impl HolderSnapshot {
+    /// 写入TokenInfo
+    pub fn apply_to(&self, token: &mut TokenInfo) {
+        token.top10_ratio = self.top10_ratio;
+        token.top20_ratio = self.top20_ratio;
+        token.top50_ratio = self.top50_ratio;
+
+        if let Some(count) = self.holders_count {
+            token.holders_count = count;
+        }
+    }
+}
+
+/// 持有者分布获取器
+///
+/// 直接从链上构建持有者快照，避免第三方API对新币的分钟级延迟。
+/// 优先扫描全部token账户，RPC不支持时回退到`getTokenLargestAccounts`
+pub struct HolderSnapshotFetcher {
+    rpc: Arc<RpcManager>,
+    config: HolderSnapshotConfig,
+}
+
+impl HolderSnapshotFetcher {
+    pub fn new(rpc: Arc<RpcManager>) -> Self {
+        Self {
+            rpc,
+            config: HolderSnapshotConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: HolderSnapshotConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 获取代币的持有者快照，自动排除其池子和联合曲线
+    pub async fn fetch_token_snapshot(&self, token: &TokenInfo, team_wallets: &[Pubkey]) -> Result<HolderSnapshot> {
+        let excluded: Vec<Pubkey> = token.pool_address.into_iter().collect();
+        self.fetch_snapshot(&token.mint, &excluded, team_wallets).await
+    }
+
+    /// 获取持有者快照
+    ///
+    /// 联合曲线PDA、已知池子authority以及由DEX程序拥有的持有者自动排除
+    ///
+    /// # 参数
+    /// - `mint`: 代币mint
+    /// - `excluded`: 额外需要排除的地址（池子金库token账户或池子地址）
+    /// - `team_wallets`: 已知的团队/部署者钱包
+    pub async fn fetch_snapshot(
+        &self,
+        mint: &Pubkey,
+        excluded: &[Pubkey],
+        team_wallets: &[Pubkey],
+    ) -> Result<HolderSnapshot> {
+        let mint_account = fetch_account(&self.rpc, mint).await?;
+        let program = TokenProgram::from_owner(&mint_account.owner).ok_or_else(|| {
+            Error::InvalidInput(format!("Account {} is not a token mint", mint))
+        })?;
+        let mint_info = MintAccount::decode(program, &mint_account.data)?;
+
+        let (balances, complete) = match self.scan_token_accounts(mint, program).await {
+            Ok(balances) => (balances, true),
+            Err(e) => {
+                tracing::warn!(
+                    "Token account scan failed for {}: {}, falling back to largest accounts",
+                    mint,
+                    e
+                );
+                (self.fetch_largest_accounts(mint).await?, false)
+            }
+        };
+
+        // 检查候选持有者是否为锁仓程序或DEX程序的PDA
+        let program_owned = self.find_program_owned(&balances).await?;
+
+        let mut excluded_set: HashSet<Pubkey> = excluded.iter().copied().collect();
+        excluded_set.extend(self.default_exclusions(mint));
+        excluded_set.extend(program_owned);
+
+        let mut snapshot = compute_distribution(
+            &balances,
+            mint_info.supply,
+            &excluded_set,
+            &team_wallets.iter().copied().collect(),
+            self.config.whale_threshold_pct,
+            self.config.max_holders,
+        );
+
+        if !complete {
+            snapshot.holders_count = None;
+        }
+
+        tracing::debug!(
+            "📊 Holder snapshot for {}: {} holders, top10 {:.1}%, excluded {}",
+            mint,
+            snapshot.holders_count.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+            snapshot.top10_ratio * 100.0,
+            snapshot.excluded_amount
+        );
+
+        Ok(snapshot)
+    }
+
+    /// 扫描mint下的全部token账户
+    async fn scan_token_accounts(
+        &self,
+        mint: &Pubkey,
+        program: TokenProgram,
+    ) -> Result<Vec<TokenAccountBalance>> {
+        let mint = *mint;
+        let program_id = match program {
+            TokenProgram::Spl => crate::token_programs::spl_token(),
+            TokenProgram::Token2022 => crate::token_programs::token_2022(),
+        };
+
+        let accounts = self
+            .rpc
+            .execute_with_retry(|client| async move {
+                // Token-2022账户可能带扩展，长度不固定，只按mint过滤
+                let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    0,
+                    mint.as_ref(),
+                ))];
+                if program == TokenProgram::Spl {
+                    filters.push(RpcFilterType::DataSize(TOKEN_ACCOUNT_LEN));
+                }
+
+                let config = RpcProgramAccountsConfig {
+                    filters: Some(filters),
+                    ..Default::default()
+                };
+
+                tokio::task::spawn_blocking(move || {
+                    client.get_program_accounts_with_config(&program_id, config)
+                })
+                .await
+                .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                .map_err(|e| Error::Rpc(format!("getProgramAccounts failed: {}", e)))
+            })
+            .await?;
+
+        Ok(accounts
+            .into_iter()
+            .filter(|(_, account)| account.data.len() >= 72)
+            .map(|(address, account)| TokenAccountBalance {
+                address,
+                owner: read_pubkey(&account.data, 32),
+                amount: read_u64(&account.data, 64),
+            })
+            .collect())
+    }
+
+    /// 通过getTokenLargestAccounts获取前20个账户
+    async fn fetch_largest_accounts(&self, mint: &Pubkey) -> Result<Vec<TokenAccountBalance>> {
+        let mint = *mint;
+
+        let largest = self
+            .rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || client.get_token_largest_accounts(&mint))
+                    .await
+                    .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                    .map_err(|e| Error::Rpc(format!("getTokenLargestAccounts failed: {}", e)))
+            })
+            .await?;
+
+        let addresses: Vec<Pubkey> = largest
+            .iter()
+            .filter_map(|entry| Pubkey::from_str(&entry.address).ok())
+            .collect();
+
+        // 读取token账户以获得owner
+        let accounts = fetch_multiple_accounts(&self.rpc, &addresses).await?;
+
+        Ok(addresses
+            .into_iter()
+            .zip(accounts)
+            .filter_map(|(address, account)| {
+                let account = account?;
+                if account.data.len() < 72 {
+                    return None;
+                }
+                Some(TokenAccountBalance {
+                    address,
+                    owner: read_pubkey(&account.data, 32),
+                    amount: read_u64(&account.data, 64),
+                })
+            })
+            .collect())
+    }
+
+    /// 不需要查询即可排除的地址：销毁地址、锁仓程序、池子authority和mint的联合曲线
+    fn default_exclusions(&self, mint: &Pubkey) -> Vec<Pubkey> {
+        let mut excluded = Vec::new();
+        excluded.extend(self.config.burn_addresses.iter().copied());
+        excluded.extend(self.config.locker_programs.iter().copied());
+        excluded.extend(self.config.pool_authorities.iter().copied());
+        excluded.push(crate::pumpfun::BondingCurveState::find_address(mint));
+        excluded
+    }
+
+    /// 找出由锁仓程序或DEX程序拥有的持有者（PDA）
+    async fn find_program_owned(&self, balances: &[TokenAccountBalance]) -> Result<Vec<Pubkey>> {
+        if self.config.locker_programs.is_empty() && self.config.pool_programs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 只检查持仓最大的候选，避免对长尾发起大量请求
+        let mut candidates: Vec<&TokenAccountBalance> =
+            balances.iter().filter(|b| b.amount > 0).collect();
+        candidates.sort_by_key(|b| std::cmp::Reverse(b.amount));
+
+        let mut seen = HashSet::new();
+        let owners: Vec<Pubkey> = candidates
+            .iter()
+            .map(|b| b.owner)
+            .filter(|owner| seen.insert(*owner))
+            .take(self.config.max_holders * 2)
+            .collect();
+
+        let accounts = fetch_multiple_accounts(&self.rpc, &owners).await?;
+        let account_owners = accounts.into_iter().map(|account| account.map(|a| a.owner)).collect();
+
+        Ok(program_owned(owners, account_owners, &self.config))
+    }
+}
+
+/// 按持有者账户的owner程序筛选锁仓和池子PDA
+fn program_owned(owners: Vec<Pubkey>, account_owners: Vec<Option<Pubkey>>, config: &HolderSnapshotConfig) -> Vec<Pubkey> {
+    owners
+        .into_iter()
+        .zip(account_owners)
+        .filter_map(|(owner, program)| {
+            let program = program?;
+            (config.locker_programs.contains(&program) || config.pool_programs.contains(&program)).then_some(owner)
+        })
+        .collect()
+}
+
+/// 根据token账户余额计算持有者分布
+///
+/// 余额按owner聚合；owner或token账户地址命中`excluded`的数量从流通量中剔除
+pub fn compute_distribution(
+    balances: &[TokenAccountBalance],
+    supply: u64,
+    excluded: &HashSet<Pubkey>,
+    team_wallets: &HashSet<Pubkey>,
+    whale_threshold_pct: f64,
+    max_holders: usize,
+) -> HolderSnapshot {
+    let mut by_owner: HashMap<Pubkey, u64> = HashMap::new();
+    let mut excluded_amount = 0u64;
+
+    for balance in balances.iter().filter(|b| b.amount > 0) {
+        if excluded.contains(&balance.owner) || excluded.contains(&balance.address) {
+            excluded_amount = excluded_amount.saturating_add(balance.amount);
+            continue;
+        }
+        *by_owner.entry(balance.owner).or_insert(0) += balance.amount;
+    }
+
+    let holders_count = by_owner.len() as u64;
+    let circulating_amount = supply.saturating_sub(excluded_amount);
+
+    let mut ranked: Vec<(Pubkey, u64)> = by_owner.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let ratio_of_top = |n: usize| -> f64 {
+        if circulating_amount == 0 {
+            return 0.0;
+        }
+        let sum: u64 = ranked.iter().take(n).map(|(_, amount)| *amount).sum();
+        (sum as f64 / circulating_amount as f64).min(1.0)
+    };
+
+    let top10_ratio = ratio_of_top(10);
+    let top20_ratio = ratio_of_top(20);
+    let top50_ratio = ratio_of_top(50);
+
+    let holders = ranked
+        .iter()
+        .take(max_holders)
+        .map(|(address, amount)| {
+            let percentage = if circulating_amount == 0 {
+                0.0
+            } else {
+                *amount as f64 / circulating_amount as f64 * 100.0
+            };
+
+            Holder {
+                address: *address,
+                amount: *amount,
+                percentage,
+                is_team: team_wallets.contains(address),
+                is_whale: percentage >= whale_threshold_pct,
+            }
+        })
+        .collect();
+
+    HolderSnapshot {
+        holders,
+        holders_count: Some(holders_count),
+        top10_ratio,
+        top20_ratio,
+        top50_ratio,
+        excluded_amount,
+        circulating_amount,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(owner: Pubkey, amount: u64) -> TokenAccountBalance {
+        TokenAccountBalance {
+            address: Pubkey::new_unique(),
+            owner,
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_compute_distribution_excludes_pool_and_burn() {
+        let pool_vault = Pubkey::new_unique();
+        let burn = crate::token_programs::incinerator();
+        let team = Pubkey::new_unique();
+        let whale = Pubkey::new_unique();
+
+        let mut balances = vec![
+            TokenAccountBalance { address: pool_vault, owner: Pubkey::new_unique(), amount: 500 },
+            balance(burn, 100),
+            balance(team, 150),
+            balance(whale, 100),
+        ];
+        for _ in 0..15 {
+            balances.push(balance(Pubkey::new_unique(), 10));
+        }
+
+        let excluded: HashSet<Pubkey> = [pool_vault, burn].into_iter().collect();
+        let team_wallets: HashSet<Pubkey> = [team].into_iter().collect();
+
+        let snapshot = compute_distribution(&balances, 1000, &excluded, &team_wallets, 2.0, 50);
+
+        assert_eq!(snapshot.excluded_amount, 600);
+        assert_eq!(snapshot.circulating_amount, 400);
+        assert_eq!(snapshot.holders_count, Some(17));
+        // 150 + 100 + 8 * 10 = 330
+        assert!((snapshot.top10_ratio - 330.0 / 400.0).abs() < 1e-9);
+        assert!((snapshot.top20_ratio - 1.0).abs() < 1e-9);
+
+        assert_eq!(snapshot.holders[0].address, team);
+        assert!(snapshot.holders[0].is_team);
+        assert!(snapshot.holders[0].is_whale);
+        assert!(!snapshot.holders[1].is_team);
+    }
+
+    #[test]
+    fn test_pool_and_curve_excluded_automatically() {
+        let rpc = Arc::new(RpcManager::new(
+            vec!["http://localhost:8899".to_string()],
+            1,
+            solsniper_core::LoadBalancingStrategy::RoundRobin,
+        ));
+        let fetcher = HolderSnapshotFetcher::new(rpc);
+        let mint = Pubkey::new_unique();
+
+        // 联合曲线PDA和Raydium V4 authority无需查询即被排除
+        let curve = crate::pumpfun::BondingCurveState::find_address(&mint);
+        let defaults = fetcher.default_exclusions(&mint);
+        assert!(defaults.contains(&curve));
+        assert!(defaults.contains(&crate::dex_programs::raydium_amm_authority()));
+
+        // 由DEX程序或锁仓程序拥有的持有者被排除，普通钱包保留
+        let whirlpool = Pubkey::new_unique();
+        let locker_pda = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let owned = program_owned(
+            vec![whirlpool, locker_pda, wallet],
+            vec![
+                Some(crate::dex_programs::orca_whirlpool()),
+                Some(crate::token_programs::streamflow()),
+                Some(Pubkey::default()),
+            ],
+            &fetcher.config,
+        );
+        assert_eq!(owned, vec![whirlpool, locker_pda]);
+
+        let balances = vec![balance(curve, 700), balance(whirlpool, 200), balance(wallet, 100)];
+        let excluded: HashSet<Pubkey> = defaults.into_iter().chain(owned).collect();
+        let snapshot = compute_distribution(&balances, 1000, &excluded, &HashSet::new(), 2.0, 50);
+
+        assert_eq!(snapshot.holders_count, Some(1));
+        assert_eq!(snapshot.circulating_amount, 100);
+        assert!((snapshot.top10_ratio - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute_distribution_aggregates_by_owner() {
+        let owner = Pubkey::new_unique();
+        let balances = vec![balance(owner, 30), balance(owner, 20), balance(Pubkey::new_unique(), 50)];
+
+        let snapshot = compute_distribution(&balances, 100, &HashSet::new(), &HashSet::new(), 2.0, 50);
+
+        assert_eq!(snapshot.holders_count, Some(2));
+        assert_eq!(snapshot.holders[0].amount, 50);
+        assert_eq!(snapshot.holders[1].amount, 50);
+    }
+}
@@ -39,9 +39,8 @@ impl KafkaProducer {
         let payload = serde_json::to_string(event)
             .map_err(|e| solsniper_core::Error::Serialization(e.to_string()))?;
 
-        let record = FutureRecord::to(topic)
-            .payload(&payload)
-            .key(&format!("{:?}", event));
+        let key = format!("{:?}", event);
+        let record = FutureRecord::to(topic).payload(&payload).key(&key);
 
         self.producer
             .send(record, Duration::from_secs(0))
@@ -53,25 +52,28 @@ impl KafkaProducer {
 
     /// 批量发送事件
     pub async fn send_batch(&self, events: Vec<Event>) -> Result<()> {
-        let mut futures = Vec::new();
-
-        for event in events {
+        // 先序列化全部事件，record借用的payload和key需要活到发送完成
+        let mut prepared = Vec::with_capacity(events.len());
+        for event in &events {
             let topic = match event {
                 Event::PoolCreated { .. } => &self.pool_created_topic,
                 _ => &self.raw_events_topic,
             };
 
-            let payload = serde_json::to_string(&event)
+            let payload = serde_json::to_string(event)
                 .map_err(|e| solsniper_core::Error::Serialization(e.to_string()))?;
 
-            let record = FutureRecord::to(topic)
-                .payload(&payload)
-                .key(&format!("{:?}", event));
-
-            let future = self.producer.send(record, Duration::from_secs(0));
-            futures.push(future);
+            prepared.push((topic, payload, format!("{:?}", event)));
         }
 
+        let futures: Vec<_> = prepared
+            .iter()
+            .map(|(topic, payload, key)| {
+                let record = FutureRecord::to(topic).payload(payload).key(key);
+                self.producer.send(record, Duration::from_secs(0))
+            })
+            .collect();
+
         // 等待所有发送完成
         for future in futures {
             future.await
@@ -89,16 +91,16 @@ mod tests {
     use solana_sdk::pubkey::Pubkey;
 
     #[tokio::test]
+    #[ignore = "需要运行的Kafka实例"]
     async fn test_kafka_producer() {
-        // 需要运行的Kafka实例
-        // let producer = KafkaProducer::new(vec!["localhost:9092".to_string()]).unwrap();
+        let producer = KafkaProducer::new(vec!["localhost:9092".to_string()]).unwrap();
 
-        // let event = Event::PoolCreated {
-        //     pool: Pubkey::new_unique(),
-        //     token: Pubkey::new_unique(),
-        //     timestamp: Utc::now(),
-        // };
+        let event = Event::PoolCreated {
+            pool: Pubkey::new_unique(),
+            token: Pubkey::new_unique(),
+            timestamp: Utc::now(),
+        };
 
-        // producer.send_event(&event).await.unwrap();
+        producer.send_event(&event).await.unwrap();
     }
 }
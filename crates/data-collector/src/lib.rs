@@ -3,16 +3,36 @@ pub mod program_subscriber;
 pub mod event_parser;
 pub mod kafka_producer;
 pub mod parallel_fetcher;
-
-use solsniper_core::{Event, Result};
+pub mod dedup;
+pub mod mint_decoder;
+pub mod onchain_enricher;
+pub mod holder_fetcher;
+pub mod lp_verifier;
+pub mod metadata_fetcher;
+pub mod metadata_decoder;
+pub mod pumpfun;
+pub mod clmm;
+pub mod backfill;
+pub mod swap_stream;
+
+use solsniper_core::Result;
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use dashmap::DashMap;
-use solana_sdk::pubkey::Pubkey;
 
 pub use program_subscriber::ProgramSubscriber;
 pub use kafka_producer::KafkaProducer;
 pub use parallel_fetcher::{ParallelDataCollector, DataSource, ParallelFetchResult, ApiTokenData};
+pub use dedup::{DedupKey, DedupStore, DedupStats, EventDeduplicator, LocalDedupStore, RedisDedupStore};
+pub use mint_decoder::{MintAccount, TokenProgram};
+pub use onchain_enricher::OnChainEnricher;
+pub use holder_fetcher::{HolderSnapshot, HolderSnapshotConfig, HolderSnapshotFetcher};
+pub use lp_verifier::{LockerProgram, LpVerification, LpVerifier, RaydiumAmmLp};
+pub use metadata_fetcher::{MetadataFetcher, OffChainMetadata, HttpMetadataFetcher, FixtureMetadataFetcher};
+pub use metadata_decoder::{MetaplexMetadata, MetadataEnricher, MetadataEnrichment};
+pub use pumpfun::{BondingCurveState, GraduationTracker};
+pub use event_parser::{EventParser, ParsedSwap, ParsedTransaction};
+pub use backfill::{BackfillCheckpoint, BackfillJob, BackfillRange, BackfillSink, CheckpointStore, PostgresBackfillSink};
+pub use clmm::{ClmmDex, ClmmPoolDecoder, ClmmPoolState, PoolDepth};
+pub use swap_stream::SwapStream;
 
 /// 多源数据采集器
 pub struct MultiSourceCollector {
@@ -22,8 +42,8 @@ pub struct MultiSourceCollector {
     /// Kafka生产者
     kafka_producer: Arc<KafkaProducer>,
 
-    /// 事件去重缓存
-    seen_events: Arc<DashMap<u64, std::time::Instant>>,
+    /// 事件去重器（签名+指令索引）
+    dedup: Arc<EventDeduplicator>,
 
     /// 后台清理任务句柄
     cleanup_handle: Option<tokio::task::JoinHandle<()>>,
@@ -32,54 +52,64 @@ pub struct MultiSourceCollector {
 impl MultiSourceCollector {
     pub fn new(kafka_brokers: Vec<String>) -> Result<Self> {
         let kafka_producer = Arc::new(KafkaProducer::new(kafka_brokers)?);
-        let seen_events = Arc::new(DashMap::new());
+        let dedup = Arc::new(EventDeduplicator::local());
 
         // 启动后台清理任务
-        let cleanup_handle = Self::spawn_cleanup_task(Arc::clone(&seen_events));
+        let cleanup_handle = Self::spawn_cleanup_task(Arc::clone(&dedup));
 
         Ok(Self {
             subscribers: Vec::new(),
             kafka_producer,
-            seen_events,
+            dedup,
             cleanup_handle: Some(cleanup_handle),
         })
     }
 
+    /// 替换去重存储
+    ///
+    /// 多实例部署或同时启用日志与Geyser数据源时，应使用`RedisDedupStore`
+    pub fn with_dedup_store(mut self, store: Arc<dyn DedupStore>) -> Self {
+        if let Some(handle) = self.cleanup_handle.take() {
+            handle.abort();
+        }
+
+        self.dedup = Arc::new(EventDeduplicator::new(store));
+        self.cleanup_handle = Some(Self::spawn_cleanup_task(Arc::clone(&self.dedup)));
+        self
+    }
+
+    /// 获取去重统计（重复丢弃数等）
+    pub fn dedup_stats(&self) -> DedupStats {
+        self.dedup.stats()
+    }
+
     /// 启动后台清理任务
     ///
-    /// 每5分钟清理一次超过1小时的旧事件，防止内存泄漏
-    fn spawn_cleanup_task(
-        seen_events: Arc<DashMap<u64, std::time::Instant>>,
-    ) -> tokio::task::JoinHandle<()> {
+    /// 每5分钟清理一次过期的去重记录，防止内存泄漏，并输出去重统计
+    fn spawn_cleanup_task(dedup: Arc<EventDeduplicator>) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(300)); // 5分钟
 
             loop {
                 interval.tick().await;
 
-                let before_count = seen_events.len();
-
-                // 清理超过1小时的事件
-                seen_events.retain(|_, timestamp| {
-                    timestamp.elapsed().as_secs() < 3600
-                });
-
-                let after_count = seen_events.len();
-                let removed_count = before_count.saturating_sub(after_count);
+                let removed_count = dedup.purge_expired();
+                let stats = dedup.stats();
 
                 if removed_count > 0 {
                     tracing::info!(
-                        "🧹 Cleaned up {} expired events from dedup cache ({} -> {})",
+                        "🧹 Cleaned up {} expired events from {} dedup store",
                         removed_count,
-                        before_count,
-                        after_count
-                    );
-                } else {
-                    tracing::debug!(
-                        "🧹 Cleanup task ran: {} events in cache, 0 expired",
-                        after_count
+                        dedup.store_name()
                     );
                 }
+
+                tracing::info!(
+                    "📊 Dedup statistics: {} accepted, {} duplicates dropped, {} store errors",
+                    stats.accepted,
+                    stats.duplicates_dropped,
+                    stats.store_errors
+                );
             }
         })
     }
@@ -97,11 +127,11 @@ impl MultiSourceCollector {
 
         for subscriber in &self.subscribers {
             let kafka = Arc::clone(&self.kafka_producer);
-            let seen = Arc::clone(&self.seen_events);
+            let dedup = Arc::clone(&self.dedup);
             let sub = subscriber.clone();
 
             let handle = tokio::spawn(async move {
-                if let Err(e) = sub.subscribe_and_forward(kafka, seen).await {
+                if let Err(e) = sub.subscribe_and_forward(kafka, dedup).await {
                     tracing::error!("Subscriber error: {}", e);
                 }
             });
@@ -155,6 +185,87 @@ pub mod dex_programs {
     pub fn pumpfun() -> Pubkey {
         Pubkey::from_str("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P").unwrap()
     }
+
+    /// PumpSwap（Pump.fun毕业后的AMM）
+    pub fn pumpswap() -> Pubkey {
+        Pubkey::from_str("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA").unwrap()
+    }
+
+    /// Raydium AMM V4 authority（所有V4池子金库的owner）
+    pub fn raydium_amm_authority() -> Pubkey {
+        Pubkey::from_str("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1").unwrap()
+    }
+
+    /// 池子/联合曲线账户所属的程序（其PDA持有的代币是流动性而非持仓）
+    pub fn pool_programs() -> Vec<Pubkey> {
+        vec![
+            raydium_amm(),
+            raydium_clmm(),
+            orca_whirlpool(),
+            meteora_dlmm(),
+            pumpfun(),
+            pumpswap(),
+        ]
+    }
+}
+
+/// Token Program IDs
+pub mod token_programs {
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    /// SPL Token
+    pub fn spl_token() -> Pubkey {
+        Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap()
+    }
+
+    /// Wrapped SOL
+    pub fn wsol() -> Pubkey {
+        Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap()
+    }
+
+    /// Token-2022
+    pub fn token_2022() -> Pubkey {
+        Pubkey::from_str("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb").unwrap()
+    }
+
+    /// Incinerator（标准销毁地址）
+    pub fn incinerator() -> Pubkey {
+        Pubkey::from_str("1nc1nerator11111111111111111111111111111111").unwrap()
+    }
+
+    /// 销毁地址列表
+    pub fn burn_addresses() -> Vec<Pubkey> {
+        vec![
+            incinerator(),
+            Pubkey::from_str("11111111111111111111111111111111").unwrap(),
+        ]
+    }
+
+    /// Streamflow锁仓
+    pub fn streamflow() -> Pubkey {
+        Pubkey::from_str("strmRqUCoQUgGUan5YhzUZa6KqdzwX5L6FpUxfmKg5m").unwrap()
+    }
+
+    /// Raydium LP锁仓（Burn & Earn）
+    pub fn raydium_locker() -> Pubkey {
+        Pubkey::from_str("LockrWmn6K5twhz3y9w1dQERbmgSaRkfnTeTKbpofwE").unwrap()
+    }
+
+    /// Jupiter Lock
+    pub fn jupiter_lock() -> Pubkey {
+        Pubkey::from_str("LocpQgucEQHbqNABEYvBvwoxCPsSbG91A1QaQhQQqjn").unwrap()
+    }
+
+    /// Metaplex Token Metadata
+    pub fn metaplex_metadata() -> Pubkey {
+        Pubkey::from_str("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s").unwrap()
+    }
+
+    /// 已知锁仓程序
+    pub fn known_lockers() -> Vec<Pubkey> {
+        vec![streamflow(), raydium_locker(), jupiter_lock()]
+    }
 }
 
 #[cfg(test)]
@@ -163,7 +274,7 @@ mod tests {
 
     #[test]
     fn test_dex_programs() {
-        let programs = vec![
+        let programs = [
             dex_programs::raydium_amm(),
             dex_programs::orca_whirlpool(),
             dex_programs::pumpfun(),
@@ -0,0 +1,495 @@
+use solsniper_core::{Error, Result, RpcManager, TokenInfo};
+use chrono::{DateTime, Utc};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::mint_decoder::{read_pubkey, read_u64, MintAccount, TokenProgram};
+use crate::onchain_enricher::{fetch_account, fetch_multiple_accounts};
+
+/// 锁仓程序描述
+#[derive(Debug, Clone)]
+pub struct LockerProgram {
+    pub program_id: Pubkey,
+    pub name: String,
+
+    /// 锁仓账户中解锁时间（unix秒，u64 LE）的偏移；未知布局为None
+    pub unlock_time_offset: Option<usize>,
+
+    /// 锁仓账户中取消时间（unix秒，u64 LE，0表示未取消）的偏移
+    pub canceled_at_offset: Option<usize>,
+
+    /// 是否为永久锁仓（如Raydium Burn & Earn）
+    pub permanent: bool,
+}
+
+impl LockerProgram {
+    /// 默认支持的锁仓程序
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self {
+                program_id: crate::token_programs::streamflow(),
+                name: "Streamflow".to_string(),
+                // Contract { magic: u64, version: u8, created_at: u64,
+                //   amount_withdrawn: u64, canceled_at: u64, end_time: u64, .. }
+                unlock_time_offset: Some(33),
+                canceled_at_offset: Some(25),
+                permanent: false,
+            },
+            Self {
+                program_id: crate::token_programs::raydium_locker(),
+                name: "Raydium Burn & Earn".to_string(),
+                unlock_time_offset: None,
+                canceled_at_offset: None,
+                permanent: true,
+            },
+            Self {
+                program_id: crate::token_programs::jupiter_lock(),
+                name: "Jupiter Lock".to_string(),
+                unlock_time_offset: None,
+                canceled_at_offset: None,
+                permanent: false,
+            },
+        ]
+    }
+
+    /// 从锁仓账户数据中读取解锁时间
+    pub fn decode_unlock_time(&self, data: &[u8]) -> Option<DateTime<Utc>> {
+        Self::decode_timestamp(data, self.unlock_time_offset?)
+    }
+
+    /// 锁仓是否已被取消（取消后代币已退回发送方）
+    pub fn is_canceled(&self, data: &[u8]) -> bool {
+        self.canceled_at_offset
+            .and_then(|offset| Self::decode_timestamp(data, offset))
+            .is_some()
+    }
+
+    fn decode_timestamp(data: &[u8], offset: usize) -> Option<DateTime<Utc>> {
+        if data.len() < offset + 8 {
+            return None;
+        }
+
+        let timestamp = read_u64(data, offset);
+        if timestamp == 0 || timestamp > i64::MAX as u64 {
+            return None;
+        }
+
+        DateTime::from_timestamp(timestamp as i64, 0)
+    }
+}
+
+/// Raydium AMM V4池子账户（AmmInfo）长度
+const RAYDIUM_AMM_LEN: usize = 752;
+
+/// Raydium AMM V4池子的LP信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaydiumAmmLp {
+    pub lp_mint: Pubkey,
+
+    /// 池子记录的LP数量（只随存取流动性变化，直接burn不会减少）
+    pub lp_amount: u64,
+}
+
+impl RaydiumAmmLp {
+    /// 从AmmInfo解码: lp_mint@464, lp_amount@720
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < RAYDIUM_AMM_LEN {
+            return None;
+        }
+
+        Some(Self {
+            lp_mint: read_pubkey(data, 464),
+            lp_amount: read_u64(data, 720),
+        })
+    }
+}
+
+/// LP持仓的分类
+#[derive(Debug, Clone, PartialEq)]
+pub enum LpHoldingKind {
+    /// 在销毁地址
+    Burned,
+    /// 在锁仓程序中
+    Locked {
+        locker: String,
+        unlock_at: Option<DateTime<Utc>>,
+        permanent: bool,
+    },
+    /// 自由流通
+    Free,
+}
+
+/// 单个LP持仓
+#[derive(Debug, Clone)]
+pub struct LpHolding {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub kind: LpHoldingKind,
+}
+
+/// LP验证结果
+#[derive(Debug, Clone)]
+pub struct LpVerification {
+    pub current_supply: u64,
+    pub initial_supply: Option<u64>,
+
+    /// 销毁数量（销毁地址持有 + 供应量减少）
+    pub burned_amount: u64,
+
+    /// 锁仓数量
+    pub locked_amount: u64,
+
+    pub burned_ratio: f64,
+    pub locked_ratio: f64,
+
+    /// 锁仓持仓明细
+    pub locks: Vec<LpHolding>,
+
+    /// 最早的非永久锁仓解锁时间
+    pub unlock_at: Option<DateTime<Utc>>,
+
+    pub lp_burned: bool,
+    pub lp_locked: bool,
+}
+
+impl LpVerification {
+    /// 写入TokenInfo（覆盖API值）
+    pub fn apply_to(&self, token: &mut TokenInfo) {
+        token.lp_burned = self.lp_burned;
+        token.lp_locked = self.lp_locked;
+        token.lp_unlock_at = if self.lp_locked { self.unlock_at } else { None };
+    }
+}
+
+/// LP锁仓/销毁验证器
+///
+/// 读取LP mint供应量和LP持有者，识别销毁（incinerator或供应量减少）
+/// 以及已知锁仓程序中的锁仓仓位
+pub struct LpVerifier {
+    rpc: Arc<RpcManager>,
+    lockers: Vec<LockerProgram>,
+    burn_addresses: Vec<Pubkey>,
+
+    /// 判定为"已销毁"的最小销毁比例
+    burn_threshold: f64,
+
+    /// 判定为"已锁定"的最小锁仓+销毁比例
+    lock_threshold: f64,
+}
+
+impl LpVerifier {
+    pub fn new(rpc: Arc<RpcManager>) -> Self {
+        Self {
+            rpc,
+            lockers: LockerProgram::defaults(),
+            burn_addresses: crate::token_programs::burn_addresses(),
+            burn_threshold: 0.95,
+            lock_threshold: 0.8,
+        }
+    }
+
+    pub fn with_lockers(mut self, lockers: Vec<LockerProgram>) -> Self {
+        self.lockers = lockers;
+        self
+    }
+
+    pub fn with_thresholds(mut self, burn_threshold: f64, lock_threshold: f64) -> Self {
+        self.burn_threshold = burn_threshold;
+        self.lock_threshold = lock_threshold;
+        self
+    }
+
+    /// 验证LP状态
+    ///
+    /// # 参数
+    /// - `lp_mint`: LP代币mint
+    /// - `initial_supply`: 建池时铸造的LP数量（已知时用于检测通过burn指令减少的供应量）
+    pub async fn verify(&self, lp_mint: &Pubkey, initial_supply: Option<u64>) -> Result<LpVerification> {
+        let mint_account = fetch_account(&self.rpc, lp_mint).await?;
+        let program = TokenProgram::from_owner(&mint_account.owner).ok_or_else(|| {
+            Error::InvalidInput(format!("Account {} is not a token mint", lp_mint))
+        })?;
+        let mint = MintAccount::decode(program, &mint_account.data)?;
+
+        let holdings = self.classify_holders(lp_mint).await?;
+
+        let verification = self.summarize(mint.supply, initial_supply, holdings, Utc::now());
+
+        tracing::debug!(
+            "🔒 LP {} verification: burned {:.1}%, locked {:.1}%, unlock_at {:?}",
+            lp_mint,
+            verification.burned_ratio * 100.0,
+            verification.locked_ratio * 100.0,
+            verification.unlock_at
+        );
+
+        Ok(verification)
+    }
+
+    /// 读取LP最大持有者并分类
+    async fn classify_holders(&self, lp_mint: &Pubkey) -> Result<Vec<LpHolding>> {
+        let lp_mint = *lp_mint;
+
+        let largest = self
+            .rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || client.get_token_largest_accounts(&lp_mint))
+                    .await
+                    .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                    .map_err(|e| Error::Rpc(format!("getTokenLargestAccounts failed: {}", e)))
+            })
+            .await?;
+
+        let token_accounts: Vec<Pubkey> = largest
+            .iter()
+            .filter_map(|entry| Pubkey::from_str(&entry.address).ok())
+            .collect();
+
+        // token账户 -> (owner, amount)
+        let balances: Vec<(Pubkey, u64)> = fetch_multiple_accounts(&self.rpc, &token_accounts)
+            .await?
+            .into_iter()
+            .flatten()
+            .filter(|account| account.data.len() >= 72)
+            .map(|account| (read_pubkey(&account.data, 32), read_u64(&account.data, 64)))
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+
+        // 读取owner账户以识别锁仓程序PDA
+        let owners: Vec<Pubkey> = balances.iter().map(|(owner, _)| *owner).collect();
+        let owner_accounts = fetch_multiple_accounts(&self.rpc, &owners).await?;
+
+        Ok(balances
+            .into_iter()
+            .zip(owner_accounts)
+            .map(|((owner, amount), owner_account)| {
+                let kind = if self.burn_addresses.contains(&owner) {
+                    LpHoldingKind::Burned
+                } else if let Some(locker) = self.lockers.iter().find(|l| {
+                    l.program_id == owner
+                        || owner_account.as_ref().map(|a| a.owner == l.program_id).unwrap_or(false)
+                }) {
+                    // 已取消的锁仓视为自由流通
+                    if owner_account.as_ref().map(|a| locker.is_canceled(&a.data)).unwrap_or(false) {
+                        return LpHolding { owner, amount, kind: LpHoldingKind::Free };
+                    }
+
+                    LpHoldingKind::Locked {
+                        locker: locker.name.clone(),
+                        unlock_at: owner_account
+                            .as_ref()
+                            .and_then(|a| locker.decode_unlock_time(&a.data)),
+                        permanent: locker.permanent,
+                    }
+                } else {
+                    LpHoldingKind::Free
+                };
+
+                LpHolding { owner, amount, kind }
+            })
+            .collect())
+    }
+
+    /// 汇总持仓分类为验证结果
+    ///
+    /// 解锁时间不晚于`now`的非永久锁仓已可提取，按自由流通计算
+    pub fn summarize(
+        &self,
+        current_supply: u64,
+        initial_supply: Option<u64>,
+        holdings: Vec<LpHolding>,
+        now: DateTime<Utc>,
+    ) -> LpVerification {
+        // 供应量减少的部分视为已通过burn指令销毁
+        let supply_burned = initial_supply
+            .map(|initial| initial.saturating_sub(current_supply))
+            .unwrap_or(0);
+        let base_supply = initial_supply.unwrap_or(current_supply).max(current_supply);
+
+        let mut burned_amount = supply_burned;
+        let mut locked_amount = 0u64;
+        let mut locks = Vec::new();
+
+        for holding in holdings {
+            match &holding.kind {
+                LpHoldingKind::Burned => burned_amount = burned_amount.saturating_add(holding.amount),
+                LpHoldingKind::Locked { unlock_at: Some(unlock_at), permanent: false, .. }
+                    if *unlock_at <= now => {}
+                LpHoldingKind::Locked { .. } => {
+                    locked_amount = locked_amount.saturating_add(holding.amount);
+                    locks.push(holding);
+                }
+                LpHoldingKind::Free => {}
+            }
+        }
+
+        let ratio = |amount: u64| {
+            if base_supply == 0 {
+                0.0
+            } else {
+                (amount as f64 / base_supply as f64).min(1.0)
+            }
+        };
+
+        let burned_ratio = ratio(burned_amount);
+        let locked_ratio = ratio(locked_amount);
+
+        // 永久锁仓不产生解锁时间；取最早到期的锁仓
+        let unlock_at = locks
+            .iter()
+            .filter_map(|lock| match &lock.kind {
+                LpHoldingKind::Locked { unlock_at, permanent: false, .. } => *unlock_at,
+                _ => None,
+            })
+            .min();
+
+        let lp_burned = burned_ratio >= self.burn_threshold;
+        let lp_locked = locked_amount > 0 && burned_ratio + locked_ratio >= self.lock_threshold;
+
+        LpVerification {
+            current_supply,
+            initial_supply,
+            burned_amount,
+            locked_amount,
+            burned_ratio,
+            locked_ratio,
+            locks,
+            unlock_at,
+            lp_burned,
+            lp_locked,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solsniper_core::LoadBalancingStrategy;
+
+    fn verifier() -> LpVerifier {
+        LpVerifier::new(Arc::new(RpcManager::new(vec![], 30, LoadBalancingStrategy::RoundRobin)))
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    #[test]
+    fn test_decode_raydium_amm_lp() {
+        let lp_mint = Pubkey::new_unique();
+        let mut data = vec![0u8; RAYDIUM_AMM_LEN];
+        data[464..496].copy_from_slice(lp_mint.as_ref());
+        data[720..728].copy_from_slice(&1_000_000u64.to_le_bytes());
+
+        let lp = RaydiumAmmLp::decode(&data).unwrap();
+        assert_eq!(lp.lp_mint, lp_mint);
+        assert_eq!(lp.lp_amount, 1_000_000);
+
+        assert!(RaydiumAmmLp::decode(&data[..700]).is_none());
+    }
+
+    /// 按Streamflow SDK的Contract布局构造1104字节的锁仓账户
+    fn streamflow_contract(created_at: u64, canceled_at: u64, end_time: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 1104];
+        data[0..8].copy_from_slice(&0u64.to_le_bytes()); // magic
+        data[8] = 2; // version
+        data[9..17].copy_from_slice(&created_at.to_le_bytes());
+        data[17..25].copy_from_slice(&0u64.to_le_bytes()); // amount_withdrawn
+        data[25..33].copy_from_slice(&canceled_at.to_le_bytes());
+        data[33..41].copy_from_slice(&end_time.to_le_bytes());
+        data[41..49].copy_from_slice(&0u64.to_le_bytes()); // last_withdrawn_at
+        data[49..81].copy_from_slice(Pubkey::new_unique().as_ref()); // sender
+        data[113..145].copy_from_slice(Pubkey::new_unique().as_ref()); // recipient
+        data[177..209].copy_from_slice(Pubkey::new_unique().as_ref()); // mint
+        data
+    }
+
+    #[test]
+    fn test_burn_by_supply_reduction_and_incinerator() {
+        let holdings = vec![
+            LpHolding { owner: crate::token_programs::incinerator(), amount: 100, kind: LpHoldingKind::Burned },
+            LpHolding { owner: Pubkey::new_unique(), amount: 20, kind: LpHoldingKind::Free },
+        ];
+
+        // 初始1000，当前120：880通过burn指令销毁，100在incinerator
+        let result = verifier().summarize(120, Some(1000), holdings, now());
+
+        assert_eq!(result.burned_amount, 980);
+        assert!(result.lp_burned);
+        assert!(!result.lp_locked);
+    }
+
+    #[test]
+    fn test_lock_with_earliest_unlock_time() {
+        let soon = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let later = DateTime::from_timestamp(1_900_000_000, 0).unwrap();
+
+        let holdings = vec![
+            LpHolding {
+                owner: Pubkey::new_unique(),
+                amount: 500,
+                kind: LpHoldingKind::Locked { locker: "Streamflow".to_string(), unlock_at: Some(later), permanent: false },
+            },
+            LpHolding {
+                owner: Pubkey::new_unique(),
+                amount: 400,
+                kind: LpHoldingKind::Locked { locker: "Streamflow".to_string(), unlock_at: Some(soon), permanent: false },
+            },
+            LpHolding { owner: Pubkey::new_unique(), amount: 100, kind: LpHoldingKind::Free },
+        ];
+
+        let result = verifier().summarize(1000, None, holdings, now());
+
+        assert!(result.lp_locked);
+        assert!(!result.lp_burned);
+        assert_eq!(result.unlock_at, Some(soon));
+        assert!((result.locked_ratio - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_expired_lock_counts_as_free() {
+        let expired = DateTime::from_timestamp(1_600_000_000, 0).unwrap();
+        let active = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+
+        let holdings = vec![
+            LpHolding {
+                owner: Pubkey::new_unique(),
+                amount: 900,
+                kind: LpHoldingKind::Locked { locker: "Streamflow".to_string(), unlock_at: Some(expired), permanent: false },
+            },
+            LpHolding {
+                owner: Pubkey::new_unique(),
+                amount: 100,
+                kind: LpHoldingKind::Locked { locker: "Streamflow".to_string(), unlock_at: Some(active), permanent: false },
+            },
+        ];
+
+        let result = verifier().summarize(1000, None, holdings, now());
+
+        // 仅剩10%仍在锁仓中
+        assert!(!result.lp_locked);
+        assert_eq!(result.locked_amount, 100);
+        assert_eq!(result.locks.len(), 1);
+        assert_eq!(result.unlock_at, Some(active));
+    }
+
+    #[test]
+    fn test_decode_streamflow_contract() {
+        let locker = &LockerProgram::defaults()[0];
+
+        // created_at与end_time相邻字段不能被误读为解锁时间
+        let data = streamflow_contract(1_690_000_000, 0, 1_800_000_000);
+        assert_eq!(
+            locker.decode_unlock_time(&data),
+            DateTime::from_timestamp(1_800_000_000, 0)
+        );
+        assert!(!locker.is_canceled(&data));
+
+        let canceled = streamflow_contract(1_690_000_000, 1_695_000_000, 1_800_000_000);
+        assert!(locker.is_canceled(&canceled));
+
+        // 截断的账户数据无法解码
+        assert_eq!(locker.decode_unlock_time(&data[..40]), None);
+    }
+}
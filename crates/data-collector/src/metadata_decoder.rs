@@ -0,0 +1,320 @@
+use solsniper_core::{Error, Result, RpcManager, TokenInfo};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use crate::metadata_fetcher::{MetadataFetcher, OffChainMetadata};
+use crate::mint_decoder::read_pubkey;
+use crate::onchain_enricher::fetch_account;
+
+/// Metaplex Metadata账户中的Key::MetadataV1
+const KEY_METADATA_V1: u8 = 4;
+
+/// 解码后的Metaplex元数据账户
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaplexMetadata {
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+}
+
+impl MetaplexMetadata {
+    /// 计算mint对应的Metadata PDA
+    pub fn find_address(mint: &Pubkey) -> Pubkey {
+        let program_id = crate::token_programs::metaplex_metadata();
+        Pubkey::find_program_address(
+            &[b"metadata", program_id.as_ref(), mint.as_ref()],
+            &program_id,
+        )
+        .0
+    }
+
+    /// 解码Metadata账户（borsh）
+    ///
+    /// 布局:
+    /// - key: u8
+    /// - update_authority: Pubkey
+    /// - mint: Pubkey
+    /// - data: name, symbol, uri (String), seller_fee_basis_points (u16),
+    ///   creators (Option<Vec<Creator>>)
+    /// - primary_sale_happened: bool
+    /// - is_mutable: bool
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = BorshReader::new(data);
+
+        let key = reader.read_u8()?;
+        if key != KEY_METADATA_V1 {
+            return Err(Error::Serialization(format!("Unexpected metadata key: {}", key)));
+        }
+
+        let update_authority = reader.read_pubkey()?;
+        let mint = reader.read_pubkey()?;
+        let name = reader.read_string()?;
+        let symbol = reader.read_string()?;
+        let uri = reader.read_string()?;
+        let seller_fee_basis_points = reader.read_u16()?;
+
+        // creators: Option<Vec<Creator { address, verified, share }>>
+        if reader.read_u8()? == 1 {
+            let count = reader.read_u32()? as usize;
+            reader.skip(count * 34)?;
+        }
+
+        let primary_sale_happened = reader.read_u8()? != 0;
+        let is_mutable = reader.read_u8()? != 0;
+
+        Ok(Self {
+            update_authority,
+            mint,
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points,
+            primary_sale_happened,
+            is_mutable,
+        })
+    }
+
+    /// 更新权限是否仍由有效地址持有
+    pub fn update_authority_retained(&self) -> bool {
+        self.is_mutable && !crate::token_programs::burn_addresses().contains(&self.update_authority)
+    }
+
+    /// URI检查，返回发现的问题
+    pub fn uri_issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let uri = self.uri.trim();
+
+        if uri.is_empty() {
+            issues.push("元数据URI为空".to_string());
+            return issues;
+        }
+
+        if uri.starts_with("http://") {
+            issues.push(format!("元数据URI未使用HTTPS: {}", uri));
+        } else if !uri.starts_with("https://") && !uri.starts_with("ipfs://") && !uri.starts_with("ar://") {
+            issues.push(format!("元数据URI协议异常: {}", uri));
+        }
+
+        if self.is_mutable && !is_immutable_storage(uri) {
+            issues.push(format!("元数据托管在可变存储: {}", uri));
+        }
+
+        issues
+    }
+}
+
+/// URI是否指向内容寻址的不可变存储（IPFS/Arweave）
+pub fn is_immutable_storage(uri: &str) -> bool {
+    uri.starts_with("ipfs://")
+        || uri.starts_with("ar://")
+        || uri.contains("/ipfs/")
+        || uri.contains("arweave.net/")
+        || uri.contains(".ipfs.")
+}
+
+/// 元数据增强器
+///
+/// 解码链上Metaplex元数据并通过可插拔的`MetadataFetcher`读取链下JSON
+pub struct MetadataEnricher {
+    rpc: Arc<RpcManager>,
+    fetcher: Arc<dyn MetadataFetcher>,
+}
+
+/// 元数据增强结果
+#[derive(Debug, Clone)]
+pub struct MetadataEnrichment {
+    pub on_chain: MetaplexMetadata,
+    pub off_chain: Option<OffChainMetadata>,
+}
+
+impl MetadataEnricher {
+    pub fn new(rpc: Arc<RpcManager>, fetcher: Arc<dyn MetadataFetcher>) -> Self {
+        Self { rpc, fetcher }
+    }
+
+    /// 获取并解码mint的Metaplex元数据
+    pub async fn fetch_metadata(&self, mint: &Pubkey) -> Result<MetaplexMetadata> {
+        let address = MetaplexMetadata::find_address(mint);
+        let account = fetch_account(&self.rpc, &address).await?;
+
+        if account.owner != crate::token_programs::metaplex_metadata() {
+            return Err(Error::InvalidInput(format!(
+                "Metadata account {} has unexpected owner {}",
+                address, account.owner
+            )));
+        }
+
+        MetaplexMetadata::decode(&account.data)
+    }
+
+    /// 用链上元数据填充TokenInfo
+    ///
+    /// 链下JSON获取失败不影响链上字段
+    pub async fn enrich(&self, token: &mut TokenInfo) -> Result<MetadataEnrichment> {
+        let on_chain = self.fetch_metadata(&token.mint).await?;
+        Self::apply_metadata(token, &on_chain);
+
+        let off_chain = if on_chain.uri.is_empty() {
+            None
+        } else {
+            match self.fetcher.fetch(&on_chain.uri).await {
+                Ok(metadata) => Some(metadata),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to fetch off-chain metadata for {} via {}: {}",
+                        token.mint,
+                        self.fetcher.name(),
+                        e
+                    );
+                    None
+                }
+            }
+        };
+
+        Ok(MetadataEnrichment { on_chain, off_chain })
+    }
+
+    /// 将链上元数据写入TokenInfo
+    pub fn apply_metadata(token: &mut TokenInfo, metadata: &MetaplexMetadata) {
+        if !metadata.name.is_empty() {
+            token.name = metadata.name.clone();
+        }
+        if !metadata.symbol.is_empty() {
+            token.symbol = metadata.symbol.clone();
+        }
+
+        token.metadata_mutable = Some(metadata.is_mutable);
+        token.metadata_update_authority = if metadata.update_authority_retained() {
+            Some(metadata.update_authority)
+        } else {
+            None
+        };
+        token.metadata_uri = Some(metadata.uri.clone());
+    }
+}
+
+/// 最小borsh读取器
+struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BorshReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.offset + len > self.data.len() {
+            return Err(Error::Serialization(format!(
+                "Metadata truncated at offset {} (need {} bytes)",
+                self.offset, len
+            )));
+        }
+        let slice = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(slice)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_pubkey(&mut self) -> Result<Pubkey> {
+        let bytes = self.take(32)?;
+        Ok(read_pubkey(bytes, 0))
+    }
+
+    /// 读取borsh字符串，去掉Metaplex的\0填充
+    fn read_string(&mut self) -> Result<String> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.take(len)?;
+        Ok(String::from_utf8_lossy(bytes)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_string(buf: &mut Vec<u8>, value: &str, padded_len: usize) {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(padded_len, 0);
+        buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&bytes);
+    }
+
+    fn encode_metadata(update_authority: Pubkey, uri: &str, is_mutable: bool) -> Vec<u8> {
+        let mut data = vec![KEY_METADATA_V1];
+        data.extend_from_slice(update_authority.as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        encode_string(&mut data, "Test Token", 32);
+        encode_string(&mut data, "TEST", 10);
+        encode_string(&mut data, uri, 200);
+        data.extend_from_slice(&0u16.to_le_bytes());
+        // 一个creator
+        data.push(1);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&[1, 100]);
+        data.push(0);
+        data.push(is_mutable as u8);
+        data
+    }
+
+    #[test]
+    fn test_decode_metadata() {
+        let authority = Pubkey::new_unique();
+        let data = encode_metadata(authority, "https://example.com/token.json", true);
+        let metadata = MetaplexMetadata::decode(&data).unwrap();
+
+        assert_eq!(metadata.name, "Test Token");
+        assert_eq!(metadata.symbol, "TEST");
+        assert_eq!(metadata.uri, "https://example.com/token.json");
+        assert!(metadata.is_mutable);
+        assert!(metadata.update_authority_retained());
+        assert_eq!(metadata.uri_issues().len(), 1);
+    }
+
+    #[test]
+    fn test_immutable_metadata_on_arweave() {
+        let data = encode_metadata(Pubkey::new_unique(), "https://arweave.net/abc", false);
+        let metadata = MetaplexMetadata::decode(&data).unwrap();
+
+        assert!(!metadata.update_authority_retained());
+        assert!(metadata.uri_issues().is_empty());
+    }
+
+    #[test]
+    fn test_is_immutable_storage() {
+        assert!(is_immutable_storage("ipfs://QmHash"));
+        assert!(is_immutable_storage("ar://txid"));
+        assert!(is_immutable_storage("https://ipfs.io/ipfs/QmHash"));
+        assert!(is_immutable_storage("https://arweave.net/txid"));
+        assert!(is_immutable_storage("https://bafybeigdyrzt.ipfs.nftstorage.link/token.json"));
+        assert!(!is_immutable_storage("https://example.com/token.json"));
+        assert!(!is_immutable_storage("https://api.pump.fun/metadata/abc"));
+    }
+}
@@ -0,0 +1,352 @@
+use solsniper_core::{Error, Result};
+use async_trait::async_trait;
+use reqwest::{redirect, Client, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::Duration;
+
+/// 元数据JSON的默认响应大小上限（1MB）
+const DEFAULT_MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 5;
+
+/// 链下元数据JSON（Metaplex标准的常用字段）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OffChainMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub twitter: Option<String>,
+    #[serde(default)]
+    pub telegram: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+}
+
+/// 链下元数据获取trait
+#[async_trait]
+pub trait MetadataFetcher: Send + Sync {
+    /// 获取URI指向的元数据JSON
+    async fn fetch(&self, uri: &str) -> Result<OffChainMetadata>;
+
+    /// 获取器名称
+    fn name(&self) -> &str;
+}
+
+/// IP是否为可公开访问的地址
+///
+/// 拒绝回环、私有、链路本地（含云元数据服务169.254.169.254）、
+/// CGNAT、组播及未指定地址
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let octets = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || octets[0] == 0
+                || (octets[0] == 100 && (octets[1] & 0xc0) == 64))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// 校验URL只指向公网HTTP(S)地址（防止SSRF）
+///
+/// 主机名会被解析，任一解析结果为内网地址即拒绝
+pub async fn ensure_public_url(url: &Url) -> Result<()> {
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return Err(Error::InvalidInput(format!("Unsupported URL scheme: {}", url)));
+    }
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::InvalidInput(format!("URL has no host: {}", url)))?;
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<IpAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| Error::InvalidInput(format!("Failed to resolve {}: {}", host, e)))?
+            .map(|addr| addr.ip())
+            .collect(),
+    };
+
+    if addresses.is_empty() || !addresses.iter().all(|ip| is_public_ip(*ip)) {
+        return Err(Error::InvalidInput(format!("URL resolves to a non-public address: {}", url)));
+    }
+
+    Ok(())
+}
+
+/// HTTP元数据获取器
+///
+/// `ipfs://`和`ar://`会被改写为对应的HTTP网关。
+/// 只访问公网地址（每次重定向都重新校验），响应体超过上限即中止
+pub struct HttpMetadataFetcher {
+    client: Client,
+    ipfs_gateway: String,
+    arweave_gateway: String,
+    max_response_bytes: usize,
+}
+
+impl HttpMetadataFetcher {
+    pub fn new() -> Self {
+        // 重定向手动跟随，以便逐跳校验目标地址
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .redirect(redirect::Policy::none())
+            .build()
+            .unwrap();
+
+        Self {
+            client,
+            ipfs_gateway: "https://ipfs.io/ipfs/".to_string(),
+            arweave_gateway: "https://arweave.net/".to_string(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+
+    /// 设置IPFS网关
+    pub fn with_ipfs_gateway(mut self, gateway: String) -> Self {
+        self.ipfs_gateway = format!("{}/", gateway.trim_end_matches('/'));
+        self
+    }
+
+    /// 设置元数据JSON的响应大小上限
+    pub fn with_max_response_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_response_bytes = max_bytes;
+        self
+    }
+
+    /// 下载URI指向的内容，最多读取`max_bytes`字节
+    ///
+    /// 按块读取响应体，分块传输（无Content-Length）同样受限
+    pub async fn fetch_bytes(&self, uri: &str, max_bytes: usize) -> Result<Vec<u8>> {
+        let mut url = Url::parse(&self.resolve_uri(uri))
+            .map_err(|e| Error::InvalidInput(format!("Invalid metadata URI {}: {}", uri, e)))?;
+
+        let mut redirects = 0;
+        let mut response = loop {
+            ensure_public_url(&url).await?;
+
+            let response = self
+                .client
+                .get(url.clone())
+                .send()
+                .await
+                .map_err(|e| Error::Internal(format!("Metadata request error: {}", e)))?;
+
+            if !response.status().is_redirection() {
+                break response;
+            }
+
+            redirects += 1;
+            if redirects > MAX_REDIRECTS {
+                return Err(Error::Internal(format!("Too many redirects fetching {}", uri)));
+            }
+
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| Error::Internal(format!("Redirect without location ({})", url)))?;
+            url = url
+                .join(location)
+                .map_err(|e| Error::Internal(format!("Invalid redirect location {}: {}", location, e)))?;
+        };
+
+        if !response.status().is_success() {
+            return Err(Error::Internal(format!(
+                "Metadata fetch error: {} ({})",
+                response.status(),
+                url
+            )));
+        }
+
+        if response.content_length().map(|len| len as usize > max_bytes).unwrap_or(false) {
+            return Err(Error::InvalidInput(format!(
+                "Response from {} exceeds {} bytes",
+                url, max_bytes
+            )));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| Error::Internal(format!("Metadata read error: {}", e)))?
+        {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(Error::InvalidInput(format!(
+                    "Response from {} exceeds {} bytes",
+                    url, max_bytes
+                )));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
+
+    /// 将`ipfs://`/`ar://`改写为网关URL
+    pub fn resolve_uri(&self, uri: &str) -> String {
+        if let Some(cid) = uri.strip_prefix("ipfs://") {
+            format!("{}{}", self.ipfs_gateway, cid.trim_start_matches("ipfs/"))
+        } else if let Some(id) = uri.strip_prefix("ar://") {
+            format!("{}{}", self.arweave_gateway, id)
+        } else {
+            uri.to_string()
+        }
+    }
+}
+
+impl Default for HttpMetadataFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MetadataFetcher for HttpMetadataFetcher {
+    async fn fetch(&self, uri: &str) -> Result<OffChainMetadata> {
+        let body = self.fetch_bytes(uri, self.max_response_bytes).await?;
+
+        serde_json::from_slice::<OffChainMetadata>(&body)
+            .map_err(|e| Error::Serialization(format!("Metadata JSON parse error: {}", e)))
+    }
+
+    fn name(&self) -> &str {
+        "http"
+    }
+}
+
+/// 本地fixture元数据获取器
+///
+/// 用于测试和离线回放，按URI返回预置的元数据
+#[derive(Default)]
+pub struct FixtureMetadataFetcher {
+    fixtures: HashMap<String, OffChainMetadata>,
+}
+
+impl FixtureMetadataFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从JSON文件加载fixture（对象：URI -> 元数据）
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read metadata fixtures {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+
+        let fixtures: HashMap<String, OffChainMetadata> = serde_json::from_str(&content)
+            .map_err(|e| Error::Serialization(format!("Invalid metadata fixtures: {}", e)))?;
+
+        Ok(Self { fixtures })
+    }
+
+    /// 添加一条fixture
+    pub fn with_fixture(mut self, uri: impl Into<String>, metadata: OffChainMetadata) -> Self {
+        self.fixtures.insert(uri.into(), metadata);
+        self
+    }
+}
+
+#[async_trait]
+impl MetadataFetcher for FixtureMetadataFetcher {
+    async fn fetch(&self, uri: &str) -> Result<OffChainMetadata> {
+        self.fixtures
+            .get(uri)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("No metadata fixture for {}", uri)))
+    }
+
+    fn name(&self) -> &str {
+        "fixture"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixture_fetcher() {
+        let fetcher = FixtureMetadataFetcher::new().with_fixture(
+            "https://arweave.net/abc",
+            OffChainMetadata {
+                name: Some("Test Token".to_string()),
+                symbol: Some("TEST".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let metadata = fetcher.fetch("https://arweave.net/abc").await.unwrap();
+        assert_eq!(metadata.symbol.as_deref(), Some("TEST"));
+        assert!(fetcher.fetch("https://example.com/missing").await.is_err());
+    }
+
+    #[test]
+    fn test_resolve_ipfs_uri() {
+        let fetcher = HttpMetadataFetcher::new();
+        assert_eq!(fetcher.resolve_uri("ipfs://Qm123"), "https://ipfs.io/ipfs/Qm123");
+        assert_eq!(fetcher.resolve_uri("ipfs://ipfs/Qm123"), "https://ipfs.io/ipfs/Qm123");
+        assert_eq!(fetcher.resolve_uri("https://x.com/a.json"), "https://x.com/a.json");
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["127.0.0.1", "10.0.0.5", "172.16.3.4", "192.168.1.1", "169.254.169.254",
+                   "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should be rejected", ip);
+        }
+        for ip in ["104.16.0.1", "8.8.8.8", "2606:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{} should be allowed", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_internal_urls() {
+        let fetcher = HttpMetadataFetcher::new();
+
+        for uri in [
+            "http://169.254.169.254/latest/meta-data/",
+            "http://127.0.0.1:8080/token.json",
+            "http://[::1]/token.json",
+            "file:///etc/passwd",
+        ] {
+            let err = fetcher.fetch(uri).await.unwrap_err();
+            assert!(matches!(err, Error::InvalidInput(_)), "{}: {:?}", uri, err);
+        }
+    }
+}
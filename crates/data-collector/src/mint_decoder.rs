@@ -0,0 +1,315 @@
+use solsniper_core::{Error, Result, TokenExtension};
+use solana_sdk::pubkey::Pubkey;
+
+/// SPL Token Mint账户基础布局长度
+pub const MINT_LEN: usize = 82;
+
+/// Token-2022账户类型字节的位置（基础布局按Account长度165补齐）
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+
+/// AccountType::Mint
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// 代币程序类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenProgram {
+    /// SPL Token
+    Spl,
+    /// Token-2022
+    Token2022,
+}
+
+impl TokenProgram {
+    /// 根据账户owner识别代币程序
+    pub fn from_owner(owner: &Pubkey) -> Option<Self> {
+        if *owner == crate::token_programs::spl_token() {
+            Some(Self::Spl)
+        } else if *owner == crate::token_programs::token_2022() {
+            Some(Self::Token2022)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Spl => "SPL Token",
+            Self::Token2022 => "Token-2022",
+        }
+    }
+}
+
+/// 解码后的Mint账户
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintAccount {
+    pub program: TokenProgram,
+    pub mint_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+    pub is_initialized: bool,
+    pub freeze_authority: Option<Pubkey>,
+    pub extensions: Vec<TokenExtension>,
+}
+
+impl MintAccount {
+    /// 解码Mint账户数据
+    ///
+    /// 布局（与SPL Token和Token-2022的基础部分一致）:
+    /// - [0..36]  mint_authority: COption<Pubkey>
+    /// - [36..44] supply: u64
+    /// - [44]     decimals: u8
+    /// - [45]     is_initialized: bool
+    /// - [46..82] freeze_authority: COption<Pubkey>
+    pub fn decode(program: TokenProgram, data: &[u8]) -> Result<Self> {
+        if data.len() < MINT_LEN {
+            return Err(Error::Serialization(format!(
+                "Mint account too short: {} bytes (expected at least {})",
+                data.len(),
+                MINT_LEN
+            )));
+        }
+
+        if program == TokenProgram::Spl && data.len() != MINT_LEN {
+            return Err(Error::Serialization(format!(
+                "Unexpected SPL Token mint length: {} bytes",
+                data.len()
+            )));
+        }
+
+        let mint_authority = decode_coption_pubkey(&data[0..36])?;
+        let supply = read_u64(data, 36);
+        let decimals = data[44];
+        let is_initialized = data[45] != 0;
+        let freeze_authority = decode_coption_pubkey(&data[46..82])?;
+
+        if !is_initialized {
+            return Err(Error::InvalidInput("Mint account is not initialized".to_string()));
+        }
+
+        let extensions = if program == TokenProgram::Token2022 && data.len() > ACCOUNT_TYPE_OFFSET {
+            decode_extensions(data)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            program,
+            mint_authority,
+            supply,
+            decimals,
+            is_initialized,
+            freeze_authority,
+            extensions,
+        })
+    }
+
+    pub fn mint_authority_revoked(&self) -> bool {
+        self.mint_authority.is_none()
+    }
+
+    pub fn freeze_authority_revoked(&self) -> bool {
+        self.freeze_authority.is_none()
+    }
+
+    /// 以整币为单位的供应量（与TokenInfo::total_supply一致）
+    pub fn ui_supply(&self) -> u64 {
+        self.supply / 10u64.saturating_pow(self.decimals as u32)
+    }
+}
+
+/// 解码Token-2022扩展（TLV：u16类型 + u16长度 + 数据）
+fn decode_extensions(data: &[u8]) -> Result<Vec<TokenExtension>> {
+    if data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return Err(Error::Serialization(format!(
+            "Unexpected Token-2022 account type: {}",
+            data[ACCOUNT_TYPE_OFFSET]
+        )));
+    }
+
+    let mut extensions = Vec::new();
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        offset += 4;
+
+        // 类型0为未初始化的填充
+        if extension_type == 0 {
+            break;
+        }
+
+        if offset + length > data.len() {
+            return Err(Error::Serialization(format!(
+                "Token-2022 extension {} overflows account data",
+                extension_type
+            )));
+        }
+
+        extensions.push(decode_extension(extension_type, &data[offset..offset + length]));
+        offset += length;
+    }
+
+    Ok(extensions)
+}
+
+fn decode_extension(extension_type: u16, value: &[u8]) -> TokenExtension {
+    match extension_type {
+        // TransferFeeConfig: authority(32) + withdraw_authority(32) + withheld(8)
+        //   + older_fee(18) + newer_fee(epoch 8, maximum_fee 8, basis_points 2)
+        1 if value.len() >= 108 => TokenExtension::TransferFee {
+            basis_points: u16::from_le_bytes([value[106], value[107]]),
+            maximum_fee: read_u64(value, 98),
+            authority_retained: read_optional_pubkey(value, 0).is_some(),
+        },
+        3 if value.len() >= 32 => TokenExtension::MintCloseAuthority {
+            authority_retained: read_optional_pubkey(value, 0).is_some(),
+        },
+        4 => TokenExtension::ConfidentialTransfer,
+        // DefaultAccountState: state(u8)，2 = Frozen
+        6 if !value.is_empty() => TokenExtension::DefaultAccountState {
+            frozen: value[0] == 2,
+        },
+        9 => TokenExtension::NonTransferable,
+        10 => TokenExtension::InterestBearing,
+        12 if value.len() >= 32 => TokenExtension::PermanentDelegate {
+            delegate: read_optional_pubkey(value, 0),
+        },
+        // TransferHook: authority(32) + program_id(32)
+        14 if value.len() >= 64 => TokenExtension::TransferHook {
+            program_id: read_optional_pubkey(value, 32),
+            authority_retained: read_optional_pubkey(value, 0).is_some(),
+        },
+        18 => TokenExtension::MetadataPointer,
+        19 => TokenExtension::TokenMetadata,
+        26 if value.len() >= 32 => TokenExtension::Pausable {
+            authority_retained: read_optional_pubkey(value, 0).is_some(),
+        },
+        other => TokenExtension::Other(other),
+    }
+}
+
+/// 解码OptionalNonZeroPubkey（全零表示None）
+fn read_optional_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let pubkey = read_pubkey(data, offset);
+    if pubkey == Pubkey::default() {
+        None
+    } else {
+        Some(pubkey)
+    }
+}
+
+/// 解码COption<Pubkey>（4字节标签 + 32字节公钥）
+pub(crate) fn decode_coption_pubkey(data: &[u8]) -> Result<Option<Pubkey>> {
+    let tag = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    match tag {
+        0 => Ok(None),
+        1 => Ok(Some(read_pubkey(data, 4))),
+        other => Err(Error::Serialization(format!("Invalid COption tag: {}", other))),
+    }
+}
+
+pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[offset..offset + 32]);
+    Pubkey::new_from_array(bytes)
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+pub(crate) fn encode_test_mint(
+    mint_authority: Option<Pubkey>,
+    supply: u64,
+    decimals: u8,
+    freeze_authority: Option<Pubkey>,
+) -> Vec<u8> {
+    let mut data = vec![0u8; MINT_LEN];
+    if let Some(authority) = mint_authority {
+        data[0..4].copy_from_slice(&1u32.to_le_bytes());
+        data[4..36].copy_from_slice(authority.as_ref());
+    }
+    data[36..44].copy_from_slice(&supply.to_le_bytes());
+    data[44] = decimals;
+    data[45] = 1;
+    if let Some(authority) = freeze_authority {
+        data[46..50].copy_from_slice(&1u32.to_le_bytes());
+        data[50..82].copy_from_slice(authority.as_ref());
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_revoked_mint() {
+        let data = encode_test_mint(None, 1_000_000_000_000_000, 6, None);
+        let mint = MintAccount::decode(TokenProgram::Spl, &data).unwrap();
+
+        assert!(mint.mint_authority_revoked());
+        assert!(mint.freeze_authority_revoked());
+        assert_eq!(mint.supply, 1_000_000_000_000_000);
+        assert_eq!(mint.decimals, 6);
+        assert_eq!(mint.ui_supply(), 1_000_000_000);
+    }
+
+    #[test]
+    fn test_decode_active_authorities() {
+        let authority = Pubkey::new_unique();
+        let data = encode_test_mint(Some(authority), 42, 9, Some(authority));
+        let mint = MintAccount::decode(TokenProgram::Spl, &data).unwrap();
+
+        assert_eq!(mint.mint_authority, Some(authority));
+        assert_eq!(mint.freeze_authority, Some(authority));
+    }
+
+    #[test]
+    fn test_decode_token_2022_extensions() {
+        let delegate = Pubkey::new_unique();
+        let hook_program = Pubkey::new_unique();
+
+        let mut data = encode_test_mint(None, 1_000, 6, None);
+        data.resize(ACCOUNT_TYPE_OFFSET, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+
+        // PermanentDelegate
+        data.extend_from_slice(&12u16.to_le_bytes());
+        data.extend_from_slice(&32u16.to_le_bytes());
+        data.extend_from_slice(delegate.as_ref());
+
+        // TransferHook（authority保留）
+        data.extend_from_slice(&14u16.to_le_bytes());
+        data.extend_from_slice(&64u16.to_le_bytes());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(hook_program.as_ref());
+
+        // NonTransferable
+        data.extend_from_slice(&9u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+
+        let mint = MintAccount::decode(TokenProgram::Token2022, &data).unwrap();
+
+        assert_eq!(
+            mint.extensions,
+            vec![
+                TokenExtension::PermanentDelegate { delegate: Some(delegate) },
+                TokenExtension::TransferHook {
+                    program_id: Some(hook_program),
+                    authority_retained: true,
+                },
+                TokenExtension::NonTransferable,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_short_data() {
+        assert!(MintAccount::decode(TokenProgram::Spl, &[0u8; 40]).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use solsniper_core::{Error, Result, RpcManager, TokenExtension, TokenInfo};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::clmm::{ClmmDex, ClmmPoolDecoder, ClmmPoolState};
+use crate::holder_fetcher::{HolderSnapshot, HolderSnapshotFetcher};
+use crate::lp_verifier::{LpVerification, LpVerifier, RaydiumAmmLp};
+use crate::metadata_decoder::{MetadataEnricher, MetaplexMetadata};
+use crate::mint_decoder::{MintAccount, TokenProgram};
+use crate::pumpfun::{migration_time, BondingCurveState};
+
+/// 毕业时间缓存上限，超过后整体清空
+const MAX_CACHED_GRADUATIONS: usize = 10_000;
+
+/// 集中流动性池按当前价格±2%内的深度计算可成交流动性
+const CLMM_DEPTH_RANGE: f64 = 0.02;
+
+/// 通过RpcManager获取账户（阻塞RPC调用放到blocking线程池）
+pub(crate) async fn fetch_account(rpc: &RpcManager, address: &Pubkey) -> Result<Account> {
+    let address = *address;
+
+    rpc.execute_with_retry(|client| async move {
+        tokio::task::spawn_blocking(move || client.get_account(&address))
+            .await
+            .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+            .map_err(|e| Error::Rpc(format!("get_account {} failed: {}", address, e)))
+    })
+    .await
+}
+
+/// 批量获取账户（按RPC限制每批100个）
+pub(crate) async fn fetch_multiple_accounts(
+    rpc: &RpcManager,
+    addresses: &[Pubkey],
+) -> Result<Vec<Option<Account>>> {
+    let mut results = Vec::with_capacity(addresses.len());
+
+    for chunk in addresses.chunks(100) {
+        let chunk = chunk.to_vec();
+        let accounts = rpc
+            .execute_with_retry(|client| {
+                let chunk = chunk.clone();
+                async move {
+                    tokio::task::spawn_blocking(move || client.get_multiple_accounts(&chunk))
+                        .await
+                        .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                        .map_err(|e| Error::Rpc(format!("get_multiple_accounts failed: {}", e)))
+                }
+            })
+            .await?;
+        results.extend(accounts);
+    }
+
+    Ok(results)
+}
+
+/// 按签名获取已确认交易（JSON编码，支持v0交易）
+pub(crate) async fn fetch_transaction(
+    rpc: &RpcManager,
+    signature: &str,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    let signature = Signature::from_str(signature)
+        .map_err(|e| Error::InvalidInput(format!("Invalid signature: {}", e)))?;
+
+    rpc.execute_with_retry(|client| async move {
+        tokio::task::spawn_blocking(move || {
+            client.get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+        })
+        .await
+        .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+        .map_err(|e| Error::Rpc(format!("getTransaction failed: {}", e)))
+    })
+    .await
+}
+
+/// 链上数据增强器
+///
+/// 从链上解码Mint账户，权威地填充TokenInfo中的权限、精度和供应量字段。
+/// 与API数据冲突时以链上数据为准。Pump.fun代币同时读取联合曲线，填充进度与毕业时间。
+/// 配置了持有者快照等可选数据源时一并覆盖对应字段
+pub struct OnChainEnricher {
+    rpc: Arc<RpcManager>,
+
+    /// 已毕业代币的迁移时间（迁移交易的区块时间），避免重复扫描签名
+    graduations: DashMap<Pubkey, DateTime<Utc>>,
+
+    /// 链上持有者分布（覆盖API的top-N比例和持有者数）
+    holders: Option<HolderSnapshotFetcher>,
+
+    /// LP销毁/锁仓验证（覆盖API的`lp_burned`/`lp_locked`）
+    lp: Option<LpVerifier>,
+
+    /// Metaplex元数据（覆盖名称/符号，填充可变性与更新权限）
+    metadata: Option<MetadataEnricher>,
+
+    /// 集中流动性池深度（覆盖按TVL估算的`liquidity_sol`）
+    clmm: Option<ClmmPoolDecoder>,
+}
+
+impl OnChainEnricher {
+    pub fn new(rpc: Arc<RpcManager>) -> Self {
+        Self {
+            rpc,
+            graduations: DashMap::new(),
+            holders: None,
+            lp: None,
+            metadata: None,
+            clmm: None,
+        }
+    }
+
+    /// 从链上构建持有者快照
+    pub fn with_holder_fetcher(mut self, fetcher: HolderSnapshotFetcher) -> Self {
+        self.holders = Some(fetcher);
+        self
+    }
+
+    /// 验证Raydium AMM V4池子的LP销毁与锁仓
+    pub fn with_lp_verifier(mut self, verifier: LpVerifier) -> Self {
+        self.lp = Some(verifier);
+        self
+    }
+
+    /// 解码Metaplex元数据
+    pub fn with_metadata_enricher(mut self, enricher: MetadataEnricher) -> Self {
+        self.metadata = Some(enricher);
+        self
+    }
+
+    /// 对Raydium CLMM/Orca Whirlpool/Meteora DLMM池子计算近价深度
+    pub fn with_clmm_decoder(mut self, decoder: ClmmPoolDecoder) -> Self {
+        self.clmm = Some(decoder);
+        self
+    }
+
+    /// 获取并解码Mint账户
+    pub async fn fetch_mint(&self, mint: &Pubkey) -> Result<MintAccount> {
+        let account = fetch_account(&self.rpc, mint).await?;
+        Self::decode_mint(mint, &account)
+    }
+
+    fn decode_mint(mint: &Pubkey, account: &Account) -> Result<MintAccount> {
+        let program = TokenProgram::from_owner(&account.owner).ok_or_else(|| {
+            Error::InvalidInput(format!(
+                "Account {} is not owned by a token program (owner: {})",
+                mint, account.owner
+            ))
+        })?;
+
+        MintAccount::decode(program, &account.data)
+    }
+
+    /// 用链上Mint数据覆盖TokenInfo
+    ///
+    /// Mint、Pump.fun联合曲线与池子账户一次批量读取；曲线存在时写入进度，
+    /// 已毕业则以迁移交易的区块时间作为`graduated_at`。
+    /// 其余可选数据源并发获取，单项失败只记录警告并保留原值。
+    /// 返回与原值冲突的Mint字段描述
+    pub async fn enrich(&self, token: &mut TokenInfo) -> Result<Vec<String>> {
+        let bonding_curve = BondingCurveState::find_address(&token.mint);
+        let mut addresses = vec![token.mint, bonding_curve];
+        addresses.extend(token.pool_address);
+        let mut accounts = fetch_multiple_accounts(&self.rpc, &addresses).await?.into_iter();
+
+        let mint_account = accounts
+            .next()
+            .flatten()
+            .ok_or_else(|| Error::NotFound(format!("Mint account {} not found", token.mint)))?;
+        let mint = Self::decode_mint(&token.mint, &mint_account)?;
+        let conflicts = Self::apply_mint(token, &mint);
+
+        let curve = accounts.next().flatten();
+        let pool = accounts.next().flatten();
+
+        if let Some(curve) = curve.filter(|account| account.owner == crate::dex_programs::pumpfun()) {
+            match BondingCurveState::decode(&curve.data) {
+                Ok(state) => {
+                    state.apply_to(token);
+                    if state.complete && token.graduated_at.is_none() {
+                        token.graduated_at = self.graduation_time(&bonding_curve).await;
+                    }
+                }
+                Err(e) => tracing::debug!("Failed to decode bonding curve of {}: {}", token.mint, e),
+            }
+        }
+
+        self.enrich_optional(token, pool.as_ref()).await;
+
+        if !conflicts.is_empty() {
+            tracing::warn!(
+                "⚠️ On-chain mint data for {} overrides API values: {}",
+                token.mint,
+                conflicts.join(", ")
+            );
+        }
+
+        Ok(conflicts)
+    }
+
+    /// 并发获取已配置的可选数据源并写入TokenInfo
+    async fn enrich_optional(&self, token: &mut TokenInfo, pool: Option<&Account>) {
+        let snapshot = token.clone();
+        let (holders, lp, metadata, depth_sol) = tokio::join!(
+            self.fetch_holders(&snapshot),
+            self.verify_lp(&snapshot, pool),
+            self.fetch_metadata(&snapshot),
+            self.fetch_clmm_depth(&snapshot, pool),
+        );
+
+        if let Some(holders) = holders {
+            holders.apply_to(token);
+        }
+        if let Some(lp) = lp {
+            lp.apply_to(token);
+        }
+        if let Some(metadata) = metadata {
+            MetadataEnricher::apply_metadata(token, &metadata);
+        }
+        if let Some(depth_sol) = depth_sol {
+            Self::apply_depth(token, depth_sol);
+        }
+    }
+
+    /// 集中流动性池的近价深度（SOL）
+    async fn fetch_clmm_depth(&self, token: &TokenInfo, pool: Option<&Account>) -> Option<f64> {
+        let decoder = self.clmm.as_ref()?;
+        let pool_address = token.pool_address?;
+        let pool = pool?;
+        let dex = ClmmDex::from_program_id(&pool.owner)?;
+
+        let result = match ClmmPoolState::decode(dex, &pool.data) {
+            Ok(state) => decoder
+                .depth_of(&pool_address, &state, CLMM_DEPTH_RANGE)
+                .await
+                .map(|depth| depth.total_depth_in_sol(&state)),
+            Err(e) => Err(e),
+        };
+
+        result
+            .map_err(|e| tracing::warn!("CLMM depth unavailable for {}: {}", token.mint, e))
+            .ok()
+            .flatten()
+    }
+
+    /// 以近价深度作为流动性，`liquidity_usd`按原有的SOL/USD比例同步
+    fn apply_depth(token: &mut TokenInfo, depth_sol: f64) {
+        if token.liquidity_sol > 0.0 {
+            token.liquidity_usd *= depth_sol / token.liquidity_sol;
+        }
+        token.liquidity_sol = depth_sol;
+    }
+
+    async fn fetch_holders(&self, token: &TokenInfo) -> Option<HolderSnapshot> {
+        let fetcher = self.holders.as_ref()?;
+        let team_wallets: Vec<Pubkey> = token.creator.into_iter().collect();
+
+        fetcher
+            .fetch_token_snapshot(token, &team_wallets)
+            .await
+            .map_err(|e| tracing::warn!("Holder snapshot unavailable for {}: {}", token.mint, e))
+            .ok()
+    }
+
+    /// 验证池子LP（目前只支持Raydium AMM V4，其他池子没有LP代币）
+    async fn verify_lp(&self, token: &TokenInfo, pool: Option<&Account>) -> Option<LpVerification> {
+        let verifier = self.lp.as_ref()?;
+        let lp = pool
+            .filter(|account| account.owner == crate::dex_programs::raydium_amm())
+            .and_then(|account| RaydiumAmmLp::decode(&account.data))?;
+
+        verifier
+            .verify(&lp.lp_mint, Some(lp.lp_amount))
+            .await
+            .map_err(|e| tracing::warn!("LP verification failed for {}: {}", token.mint, e))
+            .ok()
+    }
+
+    async fn fetch_metadata(&self, token: &TokenInfo) -> Option<MetaplexMetadata> {
+        let enricher = self.metadata.as_ref()?;
+
+        // TokenInfo只使用链上字段，评估路径上不读取链下JSON
+        enricher
+            .fetch_metadata(&token.mint)
+            .await
+            .map_err(|e| tracing::warn!("Metaplex metadata unavailable for {}: {}", token.mint, e))
+            .ok()
+    }
+
+    /// 已毕业代币的迁移时间（带缓存）
+    async fn graduation_time(&self, bonding_curve: &Pubkey) -> Option<DateTime<Utc>> {
+        if let Some(time) = self.graduations.get(bonding_curve) {
+            return Some(*time);
+        }
+
+        match migration_time(&self.rpc, bonding_curve).await {
+            Ok(Some(time)) => {
+                if self.graduations.len() >= MAX_CACHED_GRADUATIONS {
+                    self.graduations.clear();
+                }
+                self.graduations.insert(*bonding_curve, time);
+                Some(time)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::debug!("Failed to look up migration time of {}: {}", bonding_curve, e);
+                None
+            }
+        }
+    }
+
+    /// 将解码后的Mint写入TokenInfo
+    pub fn apply_mint(token: &mut TokenInfo, mint: &MintAccount) -> Vec<String> {
+        let mut conflicts = Vec::new();
+
+        if token.mint_authority_revoked != mint.mint_authority_revoked() {
+            conflicts.push(format!(
+                "mint_authority_revoked: {} -> {}",
+                token.mint_authority_revoked,
+                mint.mint_authority_revoked()
+            ));
+            token.mint_authority_revoked = mint.mint_authority_revoked();
+        }
+
+        if token.freeze_authority_revoked != mint.freeze_authority_revoked() {
+            conflicts.push(format!(
+                "freeze_authority_revoked: {} -> {}",
+                token.freeze_authority_revoked,
+                mint.freeze_authority_revoked()
+            ));
+            token.freeze_authority_revoked = mint.freeze_authority_revoked();
+        }
+
+        if token.decimals != mint.decimals {
+            conflicts.push(format!("decimals: {} -> {}", token.decimals, mint.decimals));
+            token.decimals = mint.decimals;
+        }
+
+        if token.extensions != mint.extensions {
+            conflicts.push(format!("extensions: {} -> {}", token.extensions.len(), mint.extensions.len()));
+            token.extensions = mint.extensions.clone();
+        }
+
+        // 转账手续费对买卖双向生效，作为税费下限
+        for extension in &mint.extensions {
+            if let TokenExtension::TransferFee { basis_points, .. } = extension {
+                let fee_pct = *basis_points as f64 / 100.0;
+                token.buy_tax = token.buy_tax.max(fee_pct);
+                token.sell_tax = token.sell_tax.max(fee_pct);
+            }
+        }
+
+        let supply = mint.ui_supply();
+        if token.total_supply != supply {
+            conflicts.push(format!("total_supply: {} -> {}", token.total_supply, supply));
+            token.total_supply = supply;
+        }
+
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_token_2022_mint() {
+        let authority = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let mint = MintAccount {
+            program: TokenProgram::Token2022,
+            mint_authority: Some(authority),
+            supply: 1_000_000_000_000_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: Some(authority),
+            extensions: vec![
+                TokenExtension::TransferFee {
+                    basis_points: 250,
+                    maximum_fee: u64::MAX,
+                    authority_retained: true,
+                },
+                TokenExtension::PermanentDelegate { delegate: Some(delegate) },
+            ],
+        };
+
+        // API声称权限已放弃、无扩展
+        let mut token = TokenInfo {
+            mint: Pubkey::new_unique(),
+            decimals: 9,
+            total_supply: 1_000_000_000,
+            mint_authority_revoked: true,
+            freeze_authority_revoked: true,
+            buy_tax: 1.0,
+            ..Default::default()
+        };
+
+        let conflicts = OnChainEnricher::apply_mint(&mut token, &mint);
+
+        assert!(!token.mint_authority_revoked);
+        assert!(!token.freeze_authority_revoked);
+        assert_eq!(token.decimals, 6);
+        assert_eq!(token.total_supply, 1_000_000_000);
+        assert_eq!(token.extensions, mint.extensions);
+        // 转账手续费2.5%作为税费下限
+        assert_eq!(token.buy_tax, 2.5);
+        assert_eq!(token.sell_tax, 2.5);
+        assert_eq!(conflicts.len(), 4);
+        assert!(conflicts.iter().any(|c| c.starts_with("extensions")));
+
+        // 再次应用时没有冲突
+        assert!(OnChainEnricher::apply_mint(&mut token, &mint).is_empty());
+    }
+}
@@ -90,6 +90,12 @@ pub struct ParallelDataCollector {
     enabled_sources: Vec<DataSource>,
 }
 
+impl Default for ParallelDataCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ParallelDataCollector {
     /// 创建新的并行采集器
     pub fn new() -> Self {
@@ -444,11 +450,13 @@ impl ParallelDataCollector {
             .unwrap_or(0);
 
         tracing::debug!(
-            "🔄 Merged data from {} sources: price=${:.6}, liquidity=${:.0}, volume=${:.0}",
+            "🔄 Merged data from {} sources: price=${:.6}, liquidity=${:.0}, volume=${:.0}, change_24h={:.1}%, holders={}",
             result.data.len(),
             price_usd,
             liquidity_usd,
-            volume_24h
+            volume_24h,
+            price_change_24h,
+            holders_count
         );
 
         // 注意：这里返回部分填充的TokenInfo
@@ -1,18 +1,28 @@
-use solsniper_core::{Event, Result};
+use solsniper_core::{Event, Result, RpcManager};
 use solana_sdk::pubkey::Pubkey;
 use solana_client::nonblocking::pubsub_client::PubsubClient;
 use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
 use solana_sdk::commitment_config::CommitmentConfig;
 use futures::stream::StreamExt;
 use std::sync::Arc;
-use dashmap::DashMap;
 use chrono::Utc;
+use crate::dedup::{EventDeduplicator, InstructionIndexTracker};
+use crate::event_parser::EventParser;
+use crate::onchain_enricher::fetch_transaction;
+use crate::pumpfun::{fetch_bonding_curve, migration_pool, BondingCurveState, GraduationTracker};
+use solana_transaction_status::UiTransactionTokenBalance;
 
 /// Program订阅器
 #[derive(Clone)]
 pub struct ProgramSubscriber {
     program_id: Pubkey,
     program_name: String,
+
+    /// 用于按签名获取交易详情（日志中不含账户的事件需要）
+    rpc: Option<Arc<RpcManager>>,
+
+    /// 联合曲线毕业追踪（同一mint只发一次TokenGraduated）
+    graduations: Arc<GraduationTracker>,
 }
 
 impl ProgramSubscriber {
@@ -20,21 +30,31 @@ impl ProgramSubscriber {
         Self {
             program_id,
             program_name,
+            rpc: None,
+            graduations: Arc::new(GraduationTracker::new()),
         }
     }
 
+    /// 设置RPC管理器
+    ///
+    /// 未设置时无法解析Pump.fun迁移事件的mint，迁移日志会被丢弃
+    pub fn with_rpc(mut self, rpc: Arc<RpcManager>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
     /// 订阅并转发事件到Kafka
     ///
     /// 实现完整的WebSocket订阅流程:
     /// 1. 连接到Solana WebSocket RPC
     /// 2. 订阅指定program的日志
     /// 3. 解析日志为事件
-    /// 4. 去重检查
+    /// 4. 按签名+指令索引+事件序号去重（迁移事件在获取交易前去重）
     /// 5. 转发到Kafka
     pub async fn subscribe_and_forward(
         &self,
         kafka: Arc<super::KafkaProducer>,
-        seen_events: Arc<DashMap<u64, std::time::Instant>>,
+        dedup: Arc<EventDeduplicator>,
     ) -> Result<()> {
         tracing::info!(
             "🔌 Starting WebSocket subscription for {} ({})",
@@ -56,9 +76,9 @@ impl ProgramSubscriber {
         let (mut notifications, unsubscribe) = pubsub_client
             .logs_subscribe(
                 RpcTransactionLogsFilter::Mentions(vec![self.program_id.to_string()]),
-                Some(RpcTransactionLogsConfig {
+                RpcTransactionLogsConfig {
                     commitment: Some(CommitmentConfig::confirmed()),
-                }),
+                },
             )
             .await
             .map_err(|e| solsniper_core::Error::Internal(
@@ -90,56 +110,49 @@ impl ProgramSubscriber {
             }
 
             // 4. 解析每条日志
+            let mut ix_tracker = InstructionIndexTracker::new();
             for log in logs {
-                if let Some(event) = self.parse_log(&log) {
-                    // 5. 去重检查
-                    if self.is_duplicate(&event, &seen_events) {
-                        tracing::trace!(
-                            "Duplicate event detected for {}, skipping",
-                            self.program_name
-                        );
+                ix_tracker.observe(&log);
+
+                if self.is_migration_log(&log) {
+                    // 先按签名去重，同一迁移只由一个实例/数据源调用getTransaction
+                    let key = ix_tracker.key(&signature);
+                    if dedup.is_duplicate(&self.program_name, &key).await {
+                        tracing::trace!("Duplicate migration {} for {}, skipping", key.cache_key(), self.program_name);
                         continue;
                     }
 
-                    // 6. 发送到Kafka
-                    match kafka.send_event(&event).await {
-                        Ok(_) => {
-                            event_count += 1;
-                            tracing::debug!(
-                                "✅ Event #{} forwarded: {} - {} (sig: {})",
-                                event_count,
-                                self.program_name,
-                                self.describe_event(&event),
-                                signature
-                            );
-
-                            // 每100个事件输出统计
-                            if event_count % 100 == 0 {
-                                tracing::info!(
-                                    "📊 {} statistics: {} events forwarded, {} errors",
-                                    self.program_name,
-                                    event_count,
-                                    error_count
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            error_count += 1;
-                            tracing::error!(
-                                "❌ Failed to send event to Kafka for {}: {}",
-                                self.program_name,
-                                e
-                            );
-
-                            // 如果错误过多，可能需要重连
-                            if error_count > 50 {
-                                tracing::warn!(
-                                    "⚠️ Too many Kafka errors ({}), connection may be broken",
-                                    error_count
-                                );
-                            }
-                        }
+                    let events = self.resolve_migration(&signature).await;
+                    let mut forwarded = !events.is_empty();
+                    for event in &events {
+                        forwarded &= self.forward(&kafka, event, &signature, &mut event_count, &mut error_count).await;
+                    }
+
+                    // 解析或发送失败时释放，允许其他实例重试
+                    if !forwarded {
+                        dedup.release(&key).await;
                     }
+                    continue;
+                }
+
+                let Some(event) = self.parse_log(&log) else {
+                    continue;
+                };
+
+                // 5. 去重检查
+                let key = ix_tracker.key(&signature);
+                if dedup.is_duplicate(&self.program_name, &key).await {
+                    tracing::trace!(
+                        "Duplicate event {} detected for {}, skipping",
+                        key.cache_key(),
+                        self.program_name
+                    );
+                    continue;
+                }
+
+                // 6. 发送到Kafka，失败时释放去重键，让其他实例或重连后的订阅可以重新投递
+                if !self.forward(&kafka, &event, &signature, &mut event_count, &mut error_count).await {
+                    dedup.release(&key).await;
                 }
             }
         }
@@ -156,6 +169,57 @@ impl ProgramSubscriber {
         ))
     }
 
+    /// 发送事件到Kafka并更新统计，返回是否成功
+    async fn forward(
+        &self,
+        kafka: &super::KafkaProducer,
+        event: &Event,
+        signature: &str,
+        event_count: &mut u64,
+        error_count: &mut u64,
+    ) -> bool {
+        match kafka.send_event(event).await {
+            Ok(_) => {
+                *event_count += 1;
+                tracing::debug!(
+                    "✅ Event #{} forwarded: {} - {} (sig: {})",
+                    event_count,
+                    self.program_name,
+                    self.describe_event(event),
+                    signature
+                );
+
+                // 每100个事件输出统计
+                if event_count.is_multiple_of(100) {
+                    tracing::info!(
+                        "📊 {} statistics: {} events forwarded, {} errors",
+                        self.program_name,
+                        event_count,
+                        error_count
+                    );
+                }
+                true
+            }
+            Err(e) => {
+                *error_count += 1;
+                tracing::error!(
+                    "❌ Failed to send event to Kafka for {}: {}",
+                    self.program_name,
+                    e
+                );
+
+                // 如果错误过多，可能需要重连
+                if *error_count > 50 {
+                    tracing::warn!(
+                        "⚠️ Too many Kafka errors ({}), connection may be broken",
+                        error_count
+                    );
+                }
+                false
+            }
+        }
+    }
+
     /// 获取WebSocket URL
     ///
     /// 优先使用环境变量配置，否则使用默认的公共端点
@@ -175,6 +239,11 @@ impl ProgramSubscriber {
             Event::LargeSwap { pool, amount, .. } => {
                 format!("LargeSwap(pool={}, amount={})", pool, amount)
             }
+            Event::BondingCurveUpdated { mint, completion_pct, .. } => {
+                format!("BondingCurveUpdated(mint={}, completion={:.1}%)", mint, completion_pct)
+            }
+            Event::TokenGraduated { mint, .. } => format!("TokenGraduated(mint={})", mint),
+            other => format!("{:?}", other),
         }
     }
 
@@ -198,6 +267,11 @@ impl ProgramSubscriber {
             return self.parse_pool_initialization(log);
         }
 
+        // Pump.fun迁移需要交易账户，由resolve_migration处理
+        if self.is_migration_log(log) {
+            return None;
+        }
+
         // Pump.fun代币发行
         if log.contains("Program log: create") && self.program_name.contains("Pump") {
             return self.parse_pumpfun_launch(log);
@@ -254,6 +328,71 @@ impl ProgramSubscriber {
         })
     }
 
+    /// 是否为Pump.fun迁移到AMM（毕业）的日志
+    fn is_migration_log(&self, log: &str) -> bool {
+        self.program_name.contains("Pump")
+            && (log.contains("Program log: Instruction: Migrate")
+                || log.contains("Program log: Instruction: Withdraw"))
+    }
+
+    /// 解析Pump.fun迁移事件
+    ///
+    /// 日志中不含账户信息，按签名获取交易，在账户列表中定位mint及其联合曲线，
+    /// 从迁移后的代币余额推出新池子，以区块时间作为毕业时间。
+    /// 随后读取联合曲线的最终状态交给GraduationTracker，生成曲线更新与毕业事件。
+    /// 无法确定mint时返回空
+    async fn resolve_migration(&self, signature: &str) -> Vec<Event> {
+        let Some(rpc) = &self.rpc else {
+            tracing::debug!("Skipping Pump.fun migration {}: no RPC configured", signature);
+            return Vec::new();
+        };
+
+        let tx = match fetch_transaction(rpc, signature).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                tracing::warn!("Failed to fetch Pump.fun migration {}: {}", signature, e);
+                return Vec::new();
+            }
+        };
+
+        let Some((mint, bonding_curve)) = BondingCurveState::find_mint_in(&EventParser::account_keys(&tx)) else {
+            tracing::warn!("Could not determine mint of Pump.fun migration {}", signature);
+            return Vec::new();
+        };
+
+        let post_balances: Vec<UiTransactionTokenBalance> = tx
+            .transaction
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::from(meta.post_token_balances.clone()))
+            .unwrap_or_default();
+        let pool = migration_pool(&post_balances, &mint, &bonding_curve);
+
+        let timestamp = tx
+            .block_time
+            .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+            .unwrap_or_else(Utc::now);
+
+        tracing::debug!("Detected Pump.fun migration of {} to {:?} (sig: {})", mint, pool, signature);
+
+        match fetch_bonding_curve(rpc, &mint).await {
+            Ok(state) => {
+                // 迁移交易已确认，即使读到的账户状态滞后也按已完成处理
+                let state = BondingCurveState { complete: true, ..state };
+                self.graduations.observe(mint, state, pool, timestamp)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch bonding curve of {}: {}", mint, e);
+                vec![Event::TokenGraduated {
+                    mint,
+                    bonding_curve,
+                    pool,
+                    timestamp,
+                }]
+            }
+        }
+    }
+
     /// 解析swap事件
     fn parse_swap_event(&self, log: &str) -> Option<Event> {
         // 尝试从日志中提取交易金额
@@ -316,40 +455,6 @@ impl ProgramSubscriber {
 
         None
     }
-
-    /// 检查事件是否已处理（去重）
-    fn is_duplicate(
-        &self,
-        event: &Event,
-        seen: &DashMap<u64, std::time::Instant>,
-    ) -> bool {
-        let hash = self.compute_event_hash(event);
-
-        // 检查是否在最近1小时内见过
-        if let Some(entry) = seen.get(&hash) {
-            if entry.elapsed().as_secs() < 3600 {
-                return true;
-            }
-        }
-
-        // 记录新事件
-        seen.insert(hash, std::time::Instant::now());
-
-        // 清理超过1小时的旧记录
-        seen.retain(|_, v| v.elapsed().as_secs() < 3600);
-
-        false
-    }
-
-    fn compute_event_hash(&self, event: &Event) -> u64 {
-        // 简化的哈希实现
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = DefaultHasher::new();
-        format!("{:?}", event).hash(&mut hasher);
-        hasher.finish()
-    }
 }
 
 #[cfg(test)]
@@ -363,8 +468,21 @@ mod tests {
             "Raydium".to_string(),
         );
 
-        let log = "Program log: Initialize pool with 10 SOL";
+        let log = "Program log: initialize2: InitializeInstruction2 { nonce: 254, init_pc_amount: 10000000000 }";
         let event = subscriber.parse_log(log);
         assert!(event.is_some());
     }
+
+    #[tokio::test]
+    async fn test_migration_without_rpc_is_dropped() {
+        let subscriber = ProgramSubscriber::new(
+            crate::dex_programs::pumpfun(),
+            "PumpFun".to_string(),
+        );
+
+        let log = "Program log: Instruction: Migrate";
+        assert!(subscriber.is_migration_log(log));
+        assert!(subscriber.parse_log(log).is_none());
+        assert!(subscriber.resolve_migration("5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW").await.is_empty());
+    }
 }
@@ -0,0 +1,368 @@
+use solsniper_core::{Error, Event, Result, RpcManager, TokenInfo};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::UiTransactionTokenBalance;
+use std::str::FromStr;
+
+use crate::mint_decoder::read_u64;
+use crate::onchain_enricher::fetch_account;
+
+/// 联合曲线初始可售代币数量（793.1M，6位精度）
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+
+/// BondingCurve账户最小长度（8字节discriminator + 5个u64 + complete）
+const BONDING_CURVE_MIN_LEN: usize = 49;
+
+/// Pump.fun联合曲线状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondingCurveState {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool,
+}
+
+impl BondingCurveState {
+    /// 计算mint对应的BondingCurve PDA
+    pub fn find_address(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"bonding-curve", mint.as_ref()],
+            &crate::dex_programs::pumpfun(),
+        )
+        .0
+    }
+
+    /// 在交易账户中查找Pump.fun代币
+    ///
+    /// 返回(mint, 联合曲线)：只有其联合曲线PDA也出现在账户列表中的账户才视为mint，
+    /// 不依赖指令的账户顺序
+    pub fn find_mint_in(account_keys: &[Pubkey]) -> Option<(Pubkey, Pubkey)> {
+        account_keys.iter().find_map(|candidate| {
+            let curve = Self::find_address(candidate);
+            account_keys.contains(&curve).then_some((*candidate, curve))
+        })
+    }
+
+    /// 解码BondingCurve账户（Anchor布局）
+    ///
+    /// - [0..8]   discriminator
+    /// - [8..16]  virtual_token_reserves
+    /// - [16..24] virtual_sol_reserves
+    /// - [24..32] real_token_reserves
+    /// - [32..40] real_sol_reserves
+    /// - [40..48] token_total_supply
+    /// - [48]     complete
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < BONDING_CURVE_MIN_LEN {
+            return Err(Error::Serialization(format!(
+                "Bonding curve account too short: {} bytes",
+                data.len()
+            )));
+        }
+
+        Ok(Self {
+            virtual_token_reserves: read_u64(data, 8),
+            virtual_sol_reserves: read_u64(data, 16),
+            real_token_reserves: read_u64(data, 24),
+            real_sol_reserves: read_u64(data, 32),
+            token_total_supply: read_u64(data, 40),
+            complete: data[48] != 0,
+        })
+    }
+
+    /// 完成度（0-100），按已售出的可售代币比例计算
+    pub fn completion_pct(&self) -> f64 {
+        if self.complete {
+            return 100.0;
+        }
+
+        let sold = INITIAL_REAL_TOKEN_RESERVES.saturating_sub(self.real_token_reserves);
+        (sold as f64 / INITIAL_REAL_TOKEN_RESERVES as f64 * 100.0).clamp(0.0, 100.0)
+    }
+
+    /// 当前价格（SOL/代币，按虚拟储备计算）
+    pub fn price_sol(&self, token_decimals: u8) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+
+        let sol = self.virtual_sol_reserves as f64 / 1e9;
+        let tokens = self.virtual_token_reserves as f64 / 10f64.powi(token_decimals as i32);
+        sol / tokens
+    }
+
+    /// 写入TokenInfo
+    ///
+    /// 毕业后联合曲线已清空，流动性以AMM池为准，只更新进度；
+    /// 毕业时间取迁移交易的区块时间（见`migration_time`），不在这里写入
+    pub fn apply_to(&self, token: &mut TokenInfo) {
+        token.bonding_curve_progress = Some(self.completion_pct());
+        if !self.complete {
+            token.liquidity_sol = self.real_sol_reserves as f64 / 1e9;
+        }
+    }
+
+    /// 转换为事件
+    pub fn to_event(&self, mint: Pubkey, bonding_curve: Pubkey, timestamp: DateTime<Utc>) -> Event {
+        Event::BondingCurveUpdated {
+            mint,
+            bonding_curve,
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+            completion_pct: self.completion_pct(),
+            timestamp,
+        }
+    }
+}
+
+/// 获取并解码mint的联合曲线
+pub async fn fetch_bonding_curve(rpc: &RpcManager, mint: &Pubkey) -> Result<BondingCurveState> {
+    let address = BondingCurveState::find_address(mint);
+    let account = fetch_account(rpc, &address).await?;
+
+    if account.owner != crate::dex_programs::pumpfun() {
+        return Err(Error::InvalidInput(format!(
+            "Bonding curve {} has unexpected owner {}",
+            address, account.owner
+        )));
+    }
+
+    BondingCurveState::decode(&account.data)
+}
+
+/// 迁移（毕业）时间：联合曲线最近一笔成功交易的区块时间
+///
+/// 毕业后联合曲线不再接受买卖，最后一笔成功交易即迁移交易。未毕业或没有记录时返回None
+pub async fn migration_time(rpc: &RpcManager, bonding_curve: &Pubkey) -> Result<Option<DateTime<Utc>>> {
+    let address = *bonding_curve;
+
+    let signatures = rpc
+        .execute_with_retry(|client| async move {
+            tokio::task::spawn_blocking(move || {
+                client.get_signatures_for_address_with_config(
+                    &address,
+                    GetConfirmedSignaturesForAddress2Config {
+                        limit: Some(20),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        ..Default::default()
+                    },
+                )
+            })
+            .await
+            .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+            .map_err(|e| Error::Rpc(format!("getSignaturesForAddress {} failed: {}", address, e)))
+        })
+        .await?;
+
+    Ok(signatures
+        .iter()
+        .find(|status| status.err.is_none())
+        .and_then(|status| status.block_time)
+        .and_then(|t| DateTime::from_timestamp(t, 0)))
+}
+
+/// 迁移交易创建的池子
+///
+/// PumpSwap池子的代币金库由池子账户持有：迁移后持有该代币最多的owner（联合曲线除外）即池子。
+/// 旧的Raydium V4迁移金库归AMM authority所有，无法从余额推出池子，返回None
+pub fn migration_pool(
+    post_token_balances: &[UiTransactionTokenBalance],
+    mint: &Pubkey,
+    bonding_curve: &Pubkey,
+) -> Option<Pubkey> {
+    let mint = mint.to_string();
+
+    let owner = post_token_balances
+        .iter()
+        .filter(|balance| balance.mint == mint)
+        .filter_map(|balance| {
+            let OptionSerializer::Some(owner) = &balance.owner else {
+                return None;
+            };
+            let owner = Pubkey::from_str(owner).ok()?;
+            let amount: u64 = balance.ui_token_amount.amount.parse().ok()?;
+            (owner != *bonding_curve && amount > 0).then_some((owner, amount))
+        })
+        .max_by_key(|(_, amount)| *amount)
+        .map(|(owner, _)| owner)?;
+
+    (owner != crate::dex_programs::raydium_amm_authority()).then_some(owner)
+}
+
+/// 毕业追踪器
+///
+/// 记录每个mint最近一次的联合曲线状态，`complete`由false变为true时发出`TokenGraduated`
+#[derive(Default)]
+pub struct GraduationTracker {
+    last_seen: DashMap<Pubkey, BondingCurveState>,
+}
+
+impl GraduationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 观察一次联合曲线状态，返回需要发出的事件
+    ///
+    /// `timestamp`为观察到该状态的区块时间（迁移交易时即毕业时间），`pool`为迁移创建的池子
+    pub fn observe(
+        &self,
+        mint: Pubkey,
+        state: BondingCurveState,
+        pool: Option<Pubkey>,
+        timestamp: DateTime<Utc>,
+    ) -> Vec<Event> {
+        let bonding_curve = BondingCurveState::find_address(&mint);
+        let mut events = Vec::new();
+
+        let was_complete = self
+            .last_seen
+            .get(&mint)
+            .map(|previous| previous.complete)
+            .unwrap_or(false);
+
+        if self.last_seen.get(&mint).map(|previous| *previous != state).unwrap_or(true) {
+            events.push(state.to_event(mint, bonding_curve, timestamp));
+        }
+
+        if state.complete && !was_complete {
+            tracing::info!("🎓 Pump.fun token graduated: {}", mint);
+            events.push(Event::TokenGraduated {
+                mint,
+                bonding_curve,
+                pool,
+                timestamp,
+            });
+        }
+
+        self.last_seen.insert(mint, state);
+        events
+    }
+
+    /// 停止追踪已迁移的代币
+    pub fn forget(&self, mint: &Pubkey) {
+        self.last_seen.remove(mint);
+    }
+
+    pub fn tracked_count(&self) -> usize {
+        self.last_seen.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_curve(real_token_reserves: u64, real_sol_reserves: u64, complete: bool) -> Vec<u8> {
+        let mut data = vec![0u8; BONDING_CURVE_MIN_LEN];
+        data[8..16].copy_from_slice(&(real_token_reserves + 279_900_000_000_000).to_le_bytes());
+        data[16..24].copy_from_slice(&(real_sol_reserves + 30_000_000_000).to_le_bytes());
+        data[24..32].copy_from_slice(&real_token_reserves.to_le_bytes());
+        data[32..40].copy_from_slice(&real_sol_reserves.to_le_bytes());
+        data[40..48].copy_from_slice(&1_000_000_000_000_000u64.to_le_bytes());
+        data[48] = complete as u8;
+        data
+    }
+
+    #[test]
+    fn test_decode_and_completion() {
+        let data = encode_curve(INITIAL_REAL_TOKEN_RESERVES / 2, 40_000_000_000, false);
+        let state = BondingCurveState::decode(&data).unwrap();
+
+        assert!(!state.complete);
+        assert!((state.completion_pct() - 50.0).abs() < 0.01);
+        assert!(state.price_sol(6) > 0.0);
+    }
+
+    #[test]
+    fn test_graduation_emitted_once() {
+        let tracker = GraduationTracker::new();
+        let mint = Pubkey::new_unique();
+
+        let active = BondingCurveState::decode(&encode_curve(1_000, 84_000_000_000, false)).unwrap();
+        let done = BondingCurveState::decode(&encode_curve(0, 85_000_000_000, true)).unwrap();
+
+        let events = tracker.observe(mint, active, None, Utc::now());
+        assert_eq!(events.len(), 1);
+
+        let pool = Pubkey::new_unique();
+        let migrated_at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let events = tracker.observe(mint, done.clone(), Some(pool), migrated_at);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            Event::TokenGraduated { pool: Some(p), timestamp, .. } if *p == pool && *timestamp == migrated_at
+        )));
+
+        let events = tracker.observe(mint, done, Some(pool), Utc::now());
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_apply_graduated_curve_keeps_pool_liquidity() {
+        let done = BondingCurveState::decode(&encode_curve(0, 0, true)).unwrap();
+        let mut token = TokenInfo {
+            liquidity_sol: 80.0,
+            ..Default::default()
+        };
+
+        done.apply_to(&mut token);
+
+        assert_eq!(token.bonding_curve_progress, Some(100.0));
+        assert_eq!(token.liquidity_sol, 80.0);
+        assert!(token.graduated_at.is_none());
+    }
+
+    fn token_balance(mint: &Pubkey, owner: &Pubkey, amount: u64) -> UiTransactionTokenBalance {
+        serde_json::from_value(serde_json::json!({
+            "accountIndex": 1,
+            "mint": mint.to_string(),
+            "owner": owner.to_string(),
+            "uiTokenAmount": {
+                "uiAmount": null,
+                "decimals": 6,
+                "amount": amount.to_string(),
+                "uiAmountString": "",
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_migration_pool_from_vault_owner() {
+        let mint = Pubkey::new_unique();
+        let curve = BondingCurveState::find_address(&mint);
+        let pool = Pubkey::new_unique();
+        let balances = vec![
+            token_balance(&mint, &curve, 0),
+            token_balance(&mint, &Pubkey::new_unique(), 1_000),
+            token_balance(&mint, &pool, 206_900_000_000_000),
+            token_balance(&Pubkey::new_unique(), &Pubkey::new_unique(), u64::MAX),
+        ];
+
+        assert_eq!(migration_pool(&balances, &mint, &curve), Some(pool));
+
+        // Raydium V4的金库归authority所有，无法确定池子
+        let raydium = vec![token_balance(&mint, &crate::dex_programs::raydium_amm_authority(), 1_000)];
+        assert_eq!(migration_pool(&raydium, &mint, &curve), None);
+    }
+
+    #[test]
+    fn test_find_mint_in_transaction_accounts() {
+        let mint = Pubkey::new_unique();
+        let curve = BondingCurveState::find_address(&mint);
+        let keys = vec![Pubkey::new_unique(), Pubkey::new_unique(), mint, curve, Pubkey::new_unique()];
+
+        assert_eq!(BondingCurveState::find_mint_in(&keys), Some((mint, curve)));
+
+        // 没有对应的联合曲线账户时无法确定mint
+        assert_eq!(BondingCurveState::find_mint_in(&keys[..3]), None);
+    }
+}
@@ -0,0 +1,267 @@
+use solsniper_core::{Error, Result, RpcManager};
+use dashmap::DashMap;
+use futures::stream::StreamExt;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Semaphore};
+
+use crate::event_parser::{EventParser, ParsedTransaction};
+use crate::onchain_enricher::fetch_transaction;
+
+/// 去重签名的保留时间
+const SEEN_TTL: Duration = Duration::from_secs(120);
+
+/// 可能包含swap或加池/撤池的指令日志，其余交易（创建账户、领取手续费等）不获取
+const RELEVANT_LOGS: [&str; 13] = [
+    // Raydium V4的swap/deposit/withdraw/initialize2都会输出ray_log
+    "ray_log",
+    "Instruction: Swap",
+    "Instruction: SwapV2",
+    "Instruction: SwapBaseInput",
+    "Instruction: SwapBaseOutput",
+    "Instruction: TwoHopSwap",
+    "Instruction: Buy",
+    "Instruction: Sell",
+    "Instruction: Deposit",
+    "Instruction: Withdraw",
+    "Instruction: CreatePool",
+    // Add/Remove/Increase/DecreaseLiquidity
+    "Liquidity",
+    "initialize2",
+];
+
+/// 实时交易流
+///
+/// 订阅DEX程序日志，按签名获取交易并用`EventParser`解析出swap和加池/撤池，
+/// 通过broadcast通道分发给K线、成交记录等消费者。
+/// 同一笔交易涉及多个程序时只分发一次；日志中没有swap/流动性指令的交易不获取，
+/// 每秒获取的交易数超过上限时丢弃（按丢弃数量告警）
+pub struct SwapStream {
+    rpc: Arc<RpcManager>,
+    ws_url: String,
+    programs: Vec<Pubkey>,
+    parser: EventParser,
+    sender: broadcast::Sender<ParsedTransaction>,
+
+    /// 同时获取交易的最大数量
+    fetch_permits: Arc<Semaphore>,
+
+    /// 最近已处理的签名
+    seen: DashMap<String, Instant>,
+
+    /// 每秒最多获取的交易数
+    max_fetches_per_second: u32,
+    /// (当前秒的起点, 已获取数量)
+    fetch_window: Mutex<(Instant, u32)>,
+    /// 因限速丢弃的交易数
+    dropped: AtomicU64,
+}
+
+impl SwapStream {
+    pub fn new(rpc: Arc<RpcManager>, ws_url: impl Into<String>) -> Self {
+        let (sender, _) = broadcast::channel(4096);
+
+        Self {
+            rpc,
+            ws_url: ws_url.into(),
+            programs: crate::dex_programs::pool_programs(),
+            parser: EventParser::new(),
+            sender,
+            fetch_permits: Arc::new(Semaphore::new(16)),
+            seen: DashMap::new(),
+            max_fetches_per_second: 20,
+            fetch_window: Mutex::new((Instant::now(), 0)),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// 设置订阅的程序（默认为所有池子程序）
+    pub fn with_programs(mut self, programs: Vec<Pubkey>) -> Self {
+        self.programs = programs;
+        self
+    }
+
+    /// 设置并发获取交易的数量
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.fetch_permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        self
+    }
+
+    /// 设置每秒最多获取的交易数（getTransaction调用的上限）
+    pub fn with_rate_limit(mut self, max_fetches_per_second: u32) -> Self {
+        self.max_fetches_per_second = max_fetches_per_second.max(1);
+        self
+    }
+
+    /// 因限速丢弃的交易数
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// 订阅解析后的交易
+    ///
+    /// 消费者处理过慢时会丢失最早的交易（`RecvError::Lagged`）
+    pub fn subscribe(&self) -> broadcast::Receiver<ParsedTransaction> {
+        self.sender.subscribe()
+    }
+
+    /// 为每个程序启动订阅任务，连接断开后自动重连
+    pub fn start(self: Arc<Self>) -> Vec<tokio::task::JoinHandle<()>> {
+        self.programs
+            .iter()
+            .map(|program| {
+                let stream = Arc::clone(&self);
+                let program = *program;
+
+                tokio::spawn(async move {
+                    loop {
+                        if let Err(e) = Arc::clone(&stream).run_program(program).await {
+                            tracing::warn!("Swap stream for {} disconnected: {}", program, e);
+                        }
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// 订阅单个程序的日志
+    async fn run_program(self: Arc<Self>, program: Pubkey) -> Result<()> {
+        let client = PubsubClient::new(&self.ws_url)
+            .await
+            .map_err(|e| Error::Rpc(format!("Failed to connect to WebSocket {}: {}", self.ws_url, e)))?;
+
+        let (mut notifications, unsubscribe) = client
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![program.to_string()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await
+            .map_err(|e| Error::Rpc(format!("Failed to subscribe to {} logs: {}", program, e)))?;
+
+        tracing::info!("✅ Swap stream subscribed to {}", program);
+
+        while let Some(notification) = notifications.next().await {
+            if notification.value.err.is_some() || !Self::is_relevant(&notification.value.logs) {
+                continue;
+            }
+            if !self.mark_seen(&notification.value.signature) {
+                continue;
+            }
+            if !self.try_acquire_fetch() {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped % 1_000 == 1 {
+                    tracing::warn!(
+                        "⚠️ Swap stream over {} fetches/s, {} transactions dropped so far",
+                        self.max_fetches_per_second,
+                        dropped
+                    );
+                }
+                continue;
+            }
+
+            let permit = Arc::clone(&self.fetch_permits)
+                .acquire_owned()
+                .await
+                .map_err(|e| Error::Internal(format!("Swap stream semaphore closed: {}", e)))?;
+            let stream = Arc::clone(&self);
+            let signature = notification.value.signature;
+
+            tokio::spawn(async move {
+                let _permit = permit;
+                stream.process(&signature).await;
+            });
+        }
+
+        unsubscribe().await;
+
+        Err(Error::Rpc(format!("Swap stream for {} ended", program)))
+    }
+
+    /// 获取并解析交易后分发
+    async fn process(&self, signature: &str) {
+        let tx = match fetch_transaction(&self.rpc, signature).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                tracing::debug!("Swap stream failed to fetch {}: {}", signature, e);
+                return;
+            }
+        };
+
+        let Some(parsed) = self.parser.parse_transaction(&tx) else {
+            return;
+        };
+
+        if parsed.swap.is_some() || parsed.liquidity.is_some() {
+            // 没有消费者时发送失败，忽略
+            let _ = self.sender.send(parsed);
+        }
+    }
+
+    /// 日志中是否有swap或加池/撤池指令
+    fn is_relevant(logs: &[String]) -> bool {
+        logs.iter().any(|log| RELEVANT_LOGS.iter().any(|pattern| log.contains(pattern)))
+    }
+
+    /// 占用一次获取额度，本秒已用完时返回false
+    fn try_acquire_fetch(&self) -> bool {
+        let mut window = self.fetch_window.lock().unwrap();
+        if window.0.elapsed() >= Duration::from_secs(1) {
+            *window = (Instant::now(), 0);
+        }
+        if window.1 >= self.max_fetches_per_second {
+            return false;
+        }
+        window.1 += 1;
+        true
+    }
+
+    /// 记录签名，已处理过时返回false
+    fn mark_seen(&self, signature: &str) -> bool {
+        if self.seen.len() > 50_000 {
+            self.seen.retain(|_, seen_at| seen_at.elapsed() < SEEN_TTL);
+        }
+
+        self.seen.insert(signature.to_string(), Instant::now()).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solsniper_core::LoadBalancingStrategy;
+
+    #[test]
+    fn test_signature_dispatched_once() {
+        let rpc = Arc::new(RpcManager::new(vec![], 30, LoadBalancingStrategy::RoundRobin));
+        let stream = SwapStream::new(rpc, "ws://localhost:8900");
+
+        assert!(stream.mark_seen("sig-1"));
+        assert!(!stream.mark_seen("sig-1"));
+        assert!(stream.mark_seen("sig-2"));
+    }
+
+    #[test]
+    fn test_prefilter_and_rate_limit() {
+        let logs = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect::<Vec<_>>();
+        assert!(SwapStream::is_relevant(&logs(&[
+            "Program 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 invoke [1]",
+            "Program log: ray_log: A0BCDw==",
+        ])));
+        assert!(SwapStream::is_relevant(&logs(&["Program log: Instruction: Sell"])));
+        assert!(!SwapStream::is_relevant(&logs(&["Program log: Instruction: CollectFees"])));
+
+        let rpc = Arc::new(RpcManager::new(vec![], 30, LoadBalancingStrategy::RoundRobin));
+        let stream = SwapStream::new(rpc, "ws://localhost:8900").with_rate_limit(2);
+        assert!(stream.try_acquire_fetch());
+        assert!(stream.try_acquire_fetch());
+        assert!(!stream.try_acquire_fetch());
+    }
+}
@@ -1,6 +1,12 @@
 /// WebSocket客户端（占位符）
 pub struct WebSocketClient;
 
+impl Default for WebSocketClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WebSocketClient {
     pub fn new() -> Self {
         Self
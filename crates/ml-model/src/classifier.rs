@@ -9,7 +9,7 @@ pub struct RugPullClassifier {
 }
 
 impl RugPullClassifier {
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+    pub fn load<P: AsRef<Path>>(_path: P) -> Result<Self> {
         // TODO: 从文件加载训练好的模型
         // 这里使用简单的规则作为占位符
         Ok(Self { threshold: 0.5 })
@@ -18,7 +18,7 @@ impl RugPullClassifier {
     /// 预测 Rug Pull 概率
     pub fn predict_proba(&self, features: &Array1<f64>) -> Result<f64> {
         // 简化的风险评分逻辑 - 实际应该使用训练好的模型
-        let mut risk_score = 0.0;
+        let mut risk_score: f64 = 0.0;
 
         // 检查关键特征
         let lp_locked = features[7];
@@ -40,6 +40,11 @@ impl RugPullClassifier {
         Ok(risk_score.min(1.0))
     }
 
+    /// 按分类阈值判断是否为 Rug Pull
+    pub fn is_rug_pull(&self, features: &Array1<f64>) -> Result<bool> {
+        Ok(self.predict_proba(features)? >= self.threshold)
+    }
+
     /// 在线学习 - 部分拟合
     pub fn partial_fit(&mut self, _features: &Array1<f64>, _label: f64) -> Result<()> {
         // TODO: 实现在线学习逻辑
@@ -1,15 +1,42 @@
 use ndarray::Array1;
-use solsniper_core::TokenInfo;
+use solsniper_core::{SolPriceService, TokenInfo};
+use std::sync::Arc;
 
 /// 特征提取器 - 将 TokenInfo 转换为 ML 可用的特征向量
-pub struct FeatureExtractor;
+pub struct FeatureExtractor {
+    /// SOL/USD参考价格服务（未设置或没有新鲜报价时SOL价格特征缺失）
+    sol_price: Option<Arc<SolPriceService>>,
+}
+
+impl Default for FeatureExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl FeatureExtractor {
     pub fn new() -> Self {
-        Self
+        Self { sol_price: None }
+    }
+
+    /// 使用参考价格服务提供SOL价格特征
+    pub fn with_sol_price_service(mut self, service: Arc<SolPriceService>) -> Self {
+        self.sol_price = Some(service);
+        self
+    }
+
+    /// SOL价格：只使用参考价格缓存
+    ///
+    /// 不从`liquidity_usd / liquidity_sol`反推：该比值本身来自待评估的API数据，
+    /// 数据有误时会把错误当作特征
+    fn sol_price(&self) -> Option<f64> {
+        self.sol_price
+            .as_ref()
+            .and_then(|service| service.cached())
+            .map(|quote| quote.price)
     }
 
-    /// 提取特征向量 (50维特征)
+    /// 提取特征向量 (45维特征)
     pub fn extract(&self, token: &TokenInfo) -> Array1<f64> {
         let mut features = Vec::with_capacity(50);
 
@@ -27,7 +54,8 @@ impl FeatureExtractor {
         // === 流动性特征 (3个) ===
         features.push(if token.lp_locked { 1.0 } else { 0.0 });
         features.push(if token.lp_burned { 1.0 } else { 0.0 });
-        features.push(token.liquidity_usd / token.liquidity_sol.max(0.001)); // SOL价格估算
+        // SOL价格，缺失记为0（与置信度中的特征完整性统计一致）
+        features.push(self.sol_price().unwrap_or(0.0));
 
         // === 合约安全特征 (4个) ===
         features.push(if token.mint_authority_revoked { 1.0 } else { 0.0 });
@@ -154,8 +182,8 @@ impl FeatureExtractor {
         };
         features.push(activity_ratio);
 
-        // 确保特征向量长度为50
-        assert_eq!(features.len(), 50, "Feature vector must have exactly 50 dimensions");
+        // 确保特征向量长度与特征名称一致
+        assert_eq!(features.len(), 45, "Feature vector must have exactly 45 dimensions");
 
         Array1::from(features)
     }
@@ -246,6 +274,7 @@ mod tests {
             liquidity_usd: 5000.0,
             lp_locked: true,
             lp_burned: true,
+            lp_unlock_at: None,
             total_supply: 1_000_000_000,
             circulating_supply: 1_000_000_000,
             holders_count: 500,
@@ -254,6 +283,7 @@ mod tests {
             top50_ratio: 0.75,
             mint_authority_revoked: true,
             freeze_authority_revoked: true,
+            extensions: Vec::new(),
             buy_tax: 0.0,
             sell_tax: 0.0,
             created_at: Utc::now(),
@@ -275,17 +305,42 @@ mod tests {
             discord_members: Some(100),
             sentiment_score: 0.75,
             is_verified: false,
+            metadata_mutable: None,
+            metadata_update_authority: None,
+            metadata_uri: None,
             pool_address: Some(Pubkey::new_unique()),
             dex: "Raydium".to_string(),
+            bonding_curve_progress: None,
+            graduated_at: None,
+            sellable: None,
+            creator: None,
         };
 
         let features = extractor.extract(&token);
 
-        assert_eq!(features.len(), 50);
+        assert_eq!(features.len(), 45);
         assert_eq!(features[0], 50.0); // liquidity_sol
         assert_eq!(features[10], 1.0); // mint_authority_revoked
 
         let names = extractor.get_feature_names();
-        assert_eq!(names.len(), 50);
+        assert_eq!(names.len(), 45);
+    }
+
+    #[tokio::test]
+    async fn test_sol_price_only_from_reference() {
+        let token = TokenInfo {
+            liquidity_sol: 50.0,
+            liquidity_usd: 5000.0,
+            ..Default::default()
+        };
+
+        // 没有参考价格时不从流动性字段反推（5000/50=100）
+        let features = FeatureExtractor::new().extract(&token);
+        assert_eq!(features[9], 0.0);
+
+        let service = Arc::new(SolPriceService::new(vec![Arc::new(solsniper_core::FixedPriceSource::new(150.0))]));
+        service.refresh().await.unwrap();
+        let features = FeatureExtractor::new().with_sol_price_service(service).extract(&token);
+        assert_eq!(features[9], 150.0);
     }
 }
@@ -3,7 +3,7 @@ pub mod classifier;
 pub mod regressor;
 pub mod online_learning;
 
-use ndarray::{Array1, Array2};
+use ndarray::Array1;
 use solsniper_core::{TokenInfo, MLPrediction, Result};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -40,6 +40,12 @@ impl MLEnhancedStrategy {
         })
     }
 
+    /// 使用SOL/USD参考价格服务
+    pub fn with_sol_price_service(mut self, service: Arc<solsniper_core::SolPriceService>) -> Self {
+        self.feature_extractor = FeatureExtractor::new().with_sol_price_service(service);
+        self
+    }
+
     /// 预测代币结果
     pub async fn predict_outcome(&self, token: &TokenInfo) -> Result<MLPrediction> {
         // 1. 提取特征向量
@@ -143,6 +149,7 @@ mod tests {
             liquidity_usd: 5000.0,
             lp_locked: true,
             lp_burned: true,
+            lp_unlock_at: None,
             total_supply: 1_000_000_000,
             circulating_supply: 1_000_000_000,
             holders_count: 500,
@@ -151,6 +158,7 @@ mod tests {
             top50_ratio: 0.75,
             mint_authority_revoked: true,
             freeze_authority_revoked: true,
+            extensions: Vec::new(),
             buy_tax: 0.0,
             sell_tax: 0.0,
             created_at: Utc::now(),
@@ -172,8 +180,15 @@ mod tests {
             discord_members: Some(100),
             sentiment_score: 0.75,
             is_verified: false,
+            metadata_mutable: None,
+            metadata_update_authority: None,
+            metadata_uri: None,
             pool_address: Some(Pubkey::new_unique()),
             dex: "Raydium".to_string(),
+            bonding_curve_progress: None,
+            graduated_at: None,
+            sellable: None,
+            creator: None,
         };
 
         let prediction = strategy.predict_outcome(&token).await.unwrap();
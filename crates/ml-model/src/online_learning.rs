@@ -53,6 +53,11 @@ impl OnlineLearningBuffer {
     pub fn len(&self) -> usize {
         self.buffer.len()
     }
+
+    /// 缓冲区是否为空
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
 }
 
 #[cfg(test)]
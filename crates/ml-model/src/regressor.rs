@@ -3,17 +3,13 @@ use solsniper_core::Result;
 use std::path::Path;
 
 /// 涨幅预测回归器
-pub struct GainRegressor {
-    // 简化实现 - 实际应该使用 linfa 或外部模型
-    coefficients: Vec<f64>,
-}
+// 简化实现 - 实际应该使用 linfa 或外部模型
+pub struct GainRegressor;
 
 impl GainRegressor {
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+    pub fn load<P: AsRef<Path>>(_path: P) -> Result<Self> {
         // TODO: 从文件加载训练好的模型
-        Ok(Self {
-            coefficients: vec![1.0; 50], // 占位符
-        })
+        Ok(Self)
     }
 
     /// 预测预期涨幅百分比
@@ -26,7 +22,6 @@ impl GainRegressor {
         let buy_sell_ratio = features[17];
         let volume_1h = features[18];
         let price_change_1h = features[22];
-        let social_score = features[30]; // 假设社交分数在索引30
 
         // 预测公式 (简化版)
         let base_gain = 20.0; // 基础20%
@@ -47,7 +42,7 @@ impl GainRegressor {
             * (1.0 + volume_factor * 0.2)
             + price_change_1h * 0.1; // 加上短期动量
 
-        Ok(predicted_gain.max(-50.0).min(500.0)) // 限制在 -50% 到 +500%
+        Ok(predicted_gain.clamp(-50.0, 500.0)) // 限制在 -50% 到 +500%
     }
 
     /// 在线学习
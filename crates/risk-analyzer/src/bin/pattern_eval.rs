@@ -0,0 +1,106 @@
+//! 行为模式评估命令
+//!
+//! 用法:
+//!   solsniper-pattern-eval (--histories <PATH> | --database) [--since RFC3339] [--max-tokens N]
+//!                          [--record-outcomes] [--export PATH] [--holdout F] [--patterns DIR] [--threshold-step N] [--json]
+//!
+//! `--histories`为带结果标注的代币历史（JSON数组或`.jsonl`）；
+//! `--database`从`DATABASE_URL`读取记录的快照、成交和结果标注（`token_snapshots`有保留期限，
+//! 可用`--export`导出为`.jsonl`长期保存，或用`--record-outcomes`把推断的结果写入`token_outcomes`）；
+//! `--patterns`为自定义模式目录（与内置模式合并）；`--holdout`为留作验证集的代币比例。
+//!
+//! 设置`RPC_URLS`（逗号分隔）时为买入钱包查询画像并记录到历史中，协同买入与线上使用相同的证据
+
+use chrono::{DateTime, Utc};
+use solsniper_behavior_pattern::{
+    load_histories, record_wallet_profiles, write_histories, CoordinatedBuyConfig, PatternEvaluator, PatternLibrary,
+    PostgresHistoryLoader,
+};
+use solsniper_core::{LoadBalancingStrategy, RpcManager};
+use solsniper_risk_analyzer::holder_clusters::RpcHolderGraph;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut histories_path = None;
+    let mut database = false;
+    let mut since = None;
+    let mut max_tokens = None;
+    let mut record_outcomes = false;
+    let mut export_path = None;
+    let mut holdout = None;
+    let mut patterns_dir = None;
+    let mut threshold_step = None;
+    let mut json = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--histories" => histories_path = Some(value()?),
+            "--database" => database = true,
+            "--since" => since = Some(DateTime::parse_from_rfc3339(&value()?)?.with_timezone(&Utc)),
+            "--max-tokens" => max_tokens = Some(value()?.parse::<usize>()?),
+            "--record-outcomes" => record_outcomes = true,
+            "--export" => export_path = Some(value()?),
+            "--holdout" => holdout = Some(value()?.parse::<f64>()?),
+            "--patterns" => patterns_dir = Some(value()?),
+            "--threshold-step" => threshold_step = Some(value()?.parse::<f64>()?),
+            "--json" => json = true,
+            other => anyhow::bail!("Unknown argument: {}", other),
+        }
+    }
+
+    let mut histories = match (&histories_path, database) {
+        (Some(path), false) => load_histories(path)?,
+        (None, true) => {
+            let pool = sqlx::PgPool::connect(&std::env::var("DATABASE_URL")?).await?;
+            let mut loader = PostgresHistoryLoader::new(pool).with_record_inferred(record_outcomes);
+            if let Some(since) = since {
+                loader = loader.with_since(since);
+            }
+            if let Some(max_tokens) = max_tokens {
+                loader = loader.with_max_tokens(max_tokens);
+            }
+            loader.load().await?
+        }
+        _ => anyhow::bail!("Exactly one of --histories or --database is required"),
+    };
+
+    if let Ok(urls) = std::env::var("RPC_URLS") {
+        let rpc_urls: Vec<String> = urls
+            .split(',')
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+            .collect();
+        let rpc = Arc::new(RpcManager::new(rpc_urls, 30, LoadBalancingStrategy::RoundRobin));
+        record_wallet_profiles(&mut histories, &RpcHolderGraph::new(rpc), &CoordinatedBuyConfig::default()).await;
+    }
+
+    if let Some(path) = &export_path {
+        write_histories(path, &histories)?;
+        eprintln!("已导出 {} 条代币历史到 {}", histories.len(), path);
+    }
+
+    let patterns = match &patterns_dir {
+        Some(dir) => PatternLibrary::load_dir(dir)?,
+        None => PatternLibrary::load_all(),
+    };
+
+    let mut evaluator = PatternEvaluator::new(patterns);
+    if let Some(step) = threshold_step {
+        evaluator = evaluator.with_threshold_step(step);
+    }
+    if let Some(fraction) = holdout {
+        evaluator = evaluator.with_holdout(fraction);
+    }
+
+    let report = evaluator.evaluate(&histories).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report);
+    }
+
+    Ok(())
+}
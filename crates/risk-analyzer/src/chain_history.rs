@@ -0,0 +1,121 @@
+use solsniper_core::{Error, Result, RpcManager};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+
+/// getSignaturesForAddress单页上限
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+
+/// 地址最早的一页签名
+///
+/// 结果按时间倒序排列（最后一个最早）；超过翻页上限仍未到达最早交易时返回None
+pub(crate) async fn oldest_signatures(
+    rpc: &RpcManager,
+    address: &Pubkey,
+    max_pages: usize,
+) -> Result<Option<Vec<RpcConfirmedTransactionStatusWithSignature>>> {
+    let mut before: Option<Signature> = None;
+    let mut last_page: Option<Vec<RpcConfirmedTransactionStatusWithSignature>> = None;
+
+    for _ in 0..max_pages {
+        let address = *address;
+        let page = rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || {
+                    client.get_signatures_for_address_with_config(
+                        &address,
+                        GetConfirmedSignaturesForAddress2Config {
+                            before,
+                            until: None,
+                            limit: Some(SIGNATURE_PAGE_LIMIT),
+                            commitment: Some(CommitmentConfig::confirmed()),
+                        },
+                    )
+                })
+                .await
+                .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                .map_err(|e| Error::Rpc(format!("getSignaturesForAddress failed: {}", e)))
+            })
+            .await?;
+
+        let Some(last) = page.last() else {
+            return Ok(last_page);
+        };
+
+        if page.len() < SIGNATURE_PAGE_LIMIT {
+            return Ok(Some(page));
+        }
+
+        before = Some(
+            last.signature
+                .parse()
+                .map_err(|e| Error::Serialization(format!("Invalid signature: {}", e)))?,
+        );
+        last_page = Some(page);
+    }
+
+    Ok(None)
+}
+
+/// 地址最近的签名（按时间倒序，最多`limit`个）
+pub(crate) async fn recent_signatures(
+    rpc: &RpcManager,
+    address: &Pubkey,
+    limit: usize,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+    let address = *address;
+    let limit = limit.clamp(1, SIGNATURE_PAGE_LIMIT);
+
+    rpc.execute_with_retry(|client| async move {
+        tokio::task::spawn_blocking(move || {
+            client.get_signatures_for_address_with_config(
+                &address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before: None,
+                    until: None,
+                    limit: Some(limit),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+        })
+        .await
+        .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+        .map_err(|e| Error::Rpc(format!("getSignaturesForAddress failed: {}", e)))
+    })
+    .await
+}
+
+/// 读取已确认交易（base64编码）
+pub(crate) async fn fetch_transaction(
+    rpc: &RpcManager,
+    signature: Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    rpc.execute_with_retry(|client| async move {
+        tokio::task::spawn_blocking(move || {
+            client.get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+        })
+        .await
+        .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+        .map_err(|e| Error::Rpc(format!("getTransaction failed: {}", e)))
+    })
+    .await
+}
+
+/// 交易的静态账户列表（第一个为fee payer）
+pub(crate) fn account_keys(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<Pubkey> {
+    transaction
+        .transaction
+        .transaction
+        .decode()
+        .map(|tx| tx.message.static_account_keys().to_vec())
+        .unwrap_or_default()
+}
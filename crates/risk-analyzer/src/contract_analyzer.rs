@@ -1,15 +1,22 @@
-use solsniper_core::{TokenInfo, Score};
+use solsniper_core::{TokenInfo, TokenExtension, Score};
+use solsniper_data_collector::metadata_decoder::is_immutable_storage;
 
 /// 合约安全分析器
 pub struct ContractAnalyzer;
 
+impl Default for ContractAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ContractAnalyzer {
     pub fn new() -> Self {
         Self
     }
 
     pub async fn analyze(&self, token: &TokenInfo) -> Score {
-        let mut score = 100.0;
+        let mut score: f64 = 100.0;
         let mut issues = Vec::new();
 
         // 铸币权限检查
@@ -24,12 +31,79 @@ impl ContractAnalyzer {
             issues.push("⚠️ 冻结权限未撤销（可冻结账户）".to_string());
         }
 
+        // Token-2022 扩展检查
+        for extension in &token.extensions {
+            match extension {
+                TokenExtension::PermanentDelegate { delegate: Some(delegate) } => {
+                    score -= 50.0;
+                    issues.push(format!("🚨 永久委托权限: {}（可转走或销毁任意持有者代币）", delegate));
+                }
+                TokenExtension::NonTransferable => {
+                    score -= 60.0;
+                    issues.push("🚨 不可转让代币（无法卖出）".to_string());
+                }
+                TokenExtension::TransferHook { program_id: Some(program_id), authority_retained } => {
+                    if *authority_retained {
+                        score -= 40.0;
+                        issues.push(format!("🚨 可变转账钩子: {}（可随时改为拦截卖出）", program_id));
+                    } else {
+                        score -= 15.0;
+                        issues.push(format!("⚠️ 转账钩子: {}", program_id));
+                    }
+                }
+                TokenExtension::DefaultAccountState { frozen: true } => {
+                    score -= 40.0;
+                    issues.push("🚨 新账户默认冻结".to_string());
+                }
+                TokenExtension::Pausable { authority_retained: true } => {
+                    score -= 35.0;
+                    issues.push("🚨 转账可被暂停".to_string());
+                }
+                TokenExtension::TransferFee { basis_points, authority_retained: true, .. } => {
+                    score -= 15.0;
+                    issues.push(format!(
+                        "⚠️ 转账手续费 {:.2}% 且费率可修改",
+                        *basis_points as f64 / 100.0
+                    ));
+                }
+                TokenExtension::MintCloseAuthority { authority_retained: true } => {
+                    score -= 10.0;
+                    issues.push("⚠️ Mint关闭权限未撤销".to_string());
+                }
+                _ => {}
+            }
+        }
+
+        // Metaplex元数据检查
+        if token.metadata_mutable == Some(true) {
+            if let Some(authority) = token.metadata_update_authority {
+                score -= 15.0;
+                issues.push(format!("⚠️ 元数据可修改，更新权限未放弃: {}", authority));
+            } else {
+                score -= 5.0;
+                issues.push("⚠️ 元数据标记为可修改".to_string());
+            }
+
+            if let Some(uri) = &token.metadata_uri {
+                if !is_immutable_storage(uri) {
+                    score -= 5.0;
+                    issues.push(format!("⚠️ 元数据托管在可变存储: {}", uri));
+                }
+            }
+        }
+
         // 验证检查
         if !token.is_verified {
             score -= 10.0;
             issues.push("⚠️ 合约代码未验证".to_string());
         }
 
+        // 卖出模拟检查
+        if token.sellable == Some(false) {
+            score -= 80.0;
+            issues.push("🚨 模拟卖出失败（蜜罐）".to_string());
+        }
+
         // 税费检查
         let total_tax = token.buy_tax + token.sell_tax;
         if total_tax > 10.0 {
@@ -52,3 +126,88 @@ impl ContractAnalyzer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    /// 无任何风险项的代币
+    fn clean_token() -> TokenInfo {
+        TokenInfo {
+            mint_authority_revoked: true,
+            freeze_authority_revoked: true,
+            is_verified: true,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clean_token_scores_full() {
+        let score = ContractAnalyzer::new().analyze(&clean_token()).await;
+        assert_eq!(score.value, 100.0);
+        assert!(score.issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_extension_penalties() {
+        let analyzer = ContractAnalyzer::new();
+
+        let mut token = clean_token();
+        token.extensions = vec![TokenExtension::PermanentDelegate { delegate: Some(Pubkey::new_unique()) }];
+        assert_eq!(analyzer.analyze(&token).await.value, 50.0);
+
+        token.extensions = vec![TokenExtension::TransferHook {
+            program_id: Some(Pubkey::new_unique()),
+            authority_retained: true,
+        }];
+        assert_eq!(analyzer.analyze(&token).await.value, 60.0);
+
+        token.extensions = vec![TokenExtension::TransferHook {
+            program_id: Some(Pubkey::new_unique()),
+            authority_retained: false,
+        }];
+        assert_eq!(analyzer.analyze(&token).await.value, 85.0);
+
+        // 多项叠加后截断为0
+        token.extensions = vec![
+            TokenExtension::NonTransferable,
+            TokenExtension::DefaultAccountState { frozen: true },
+            TokenExtension::PermanentDelegate { delegate: Some(Pubkey::new_unique()) },
+        ];
+        let score = analyzer.analyze(&token).await;
+        assert_eq!(score.value, 0.0);
+        assert_eq!(score.issues.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_authority_and_honeypot_penalties() {
+        let analyzer = ContractAnalyzer::new();
+
+        let mut token = clean_token();
+        token.mint_authority_revoked = false;
+        token.freeze_authority_revoked = false;
+        assert_eq!(analyzer.analyze(&token).await.value, 45.0);
+
+        let mut token = clean_token();
+        token.sellable = Some(false);
+        assert_eq!(analyzer.analyze(&token).await.value, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_mutable_metadata_storage() {
+        let analyzer = ContractAnalyzer::new();
+
+        let mut token = clean_token();
+        token.metadata_mutable = Some(true);
+        token.metadata_update_authority = Some(Pubkey::new_unique());
+
+        // 可变元数据 + 可变存储
+        token.metadata_uri = Some("https://example.com/token.json".to_string());
+        assert_eq!(analyzer.analyze(&token).await.value, 80.0);
+
+        // 子域名形式的IPFS网关同样视为不可变存储
+        token.metadata_uri = Some("https://bafybeigdyrzt.ipfs.nftstorage.link/token.json".to_string());
+        assert_eq!(analyzer.analyze(&token).await.value, 85.0);
+    }
+}
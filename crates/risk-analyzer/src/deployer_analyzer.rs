@@ -0,0 +1,819 @@
+use solsniper_core::{Error, Result, RpcManager, Score, TokenInfo};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solsniper_data_collector::EventParser;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::chain_history;
+use crate::holder_clusters::BoundedCache;
+
+/// 部署者的一次历史发币
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeployerLaunch {
+    pub mint: String,
+    pub created_at: DateTime<Utc>,
+    /// 是否迁移（毕业）到Raydium
+    #[serde(default)]
+    pub graduated: bool,
+    /// 是否被判定为rug
+    #[serde(default)]
+    pub rugged: bool,
+}
+
+impl DeployerLaunch {
+    /// 是否存活超过24小时（未rug且已满24小时）
+    pub fn survived_24h(&self, now: DateTime<Utc>) -> bool {
+        !self.rugged && now - self.created_at >= Duration::hours(24)
+    }
+
+}
+
+/// 代币观测到的结果（每次风险评估时更新）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LaunchOutcome {
+    pub peak_liquidity_sol: Option<f64>,
+    pub last_liquidity_sol: Option<f64>,
+    pub last_price_change_24h: Option<f64>,
+}
+
+impl LaunchOutcome {
+    /// 判断是否rug
+    ///
+    /// 已加入黑名单、流动性从峰值（至少1 SOL）撤到0.5 SOL或峰值的10%以下、
+    /// 或24h跌幅超过90%视为rug；未观测到结果时不判定
+    pub fn is_rugged(&self, blacklisted: bool) -> bool {
+        let drained = match (self.peak_liquidity_sol, self.last_liquidity_sol) {
+            (Some(peak), Some(last)) if peak >= 1.0 => last < (peak * 0.1).max(0.5),
+            _ => false,
+        };
+
+        blacklisted || drained || self.last_price_change_24h.map(|change| change <= -90.0).unwrap_or(false)
+    }
+}
+
+/// 部署者历史
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeployerHistory {
+    /// 部署者地址是否在黑名单中
+    #[serde(default)]
+    pub blacklisted: bool,
+    #[serde(default)]
+    pub launches: Vec<DeployerLaunch>,
+}
+
+/// 部署者历史统计
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeployerStats {
+    pub launches: usize,
+    pub rugged: usize,
+    pub graduated: usize,
+    pub survived_24h: usize,
+    /// 最近24小时内的发币数
+    pub recent_24h: usize,
+}
+
+impl DeployerStats {
+    pub fn rug_rate(&self) -> f64 {
+        if self.launches == 0 {
+            return 0.0;
+        }
+        self.rugged as f64 / self.launches as f64
+    }
+}
+
+impl DeployerHistory {
+    pub fn stats(&self, now: DateTime<Utc>) -> DeployerStats {
+        DeployerStats {
+            launches: self.launches.len(),
+            rugged: self.launches.iter().filter(|l| l.rugged).count(),
+            graduated: self.launches.iter().filter(|l| l.graduated).count(),
+            survived_24h: self.launches.iter().filter(|l| l.survived_24h(now)).count(),
+            recent_24h: self
+                .launches
+                .iter()
+                .filter(|l| now - l.created_at < Duration::hours(24))
+                .count(),
+        }
+    }
+}
+
+/// 部署者历史数据源trait
+#[async_trait]
+pub trait DeployerHistorySource: Send + Sync {
+    /// 查询部署者发过的代币（不含当前mint）
+    async fn history(&self, creator: &Pubkey, exclude: &Pubkey) -> Result<DeployerHistory>;
+
+    /// 记录发币（部署者、发币时间）和当前观测到的结果，供后续发币查询
+    async fn record_launch(&self, _token: &TokenInfo, _creator: &Pubkey) -> Result<()> {
+        Ok(())
+    }
+
+    /// 数据源名称
+    fn name(&self) -> &str;
+}
+
+/// PostgreSQL部署者历史
+///
+/// 读写`token_launches`表（部署者、发币时间、峰值和最近的流动性/涨跌幅），结合`blacklist`表判断结果。
+/// 表中的记录来自风险评估和历史回填（`PostgresBackfillSink`写入回填区间内的发币）；
+/// 配置RPC后，表中没有某部署者的记录时扫描其最近的交易识别发币并补写
+pub struct PostgresDeployerHistory {
+    pool: PgPool,
+    /// 单个部署者最多读取的历史代币数
+    max_launches: i64,
+
+    rpc: Option<Arc<RpcManager>>,
+
+    /// RPC回退时最多扫描的部署者交易数
+    max_scanned_transactions: usize,
+
+    /// 已通过RPC扫描过的部署者（部署者 -> 扫描时间）
+    scanned_creators: Mutex<BoundedCache<DateTime<Utc>>>,
+}
+
+impl PostgresDeployerHistory {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            max_launches: 200,
+            rpc: None,
+            max_scanned_transactions: 50,
+            scanned_creators: Mutex::new(BoundedCache::new(10_000)),
+        }
+    }
+
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let pool = PgPool::connect(database_url)
+            .await
+            .map_err(|e| Error::Database(format!("PostgreSQL connection error: {}", e)))?;
+        Ok(Self::new(pool))
+    }
+
+    /// `token_launches`中没有部署者记录时通过RPC扫描其交易
+    pub fn with_rpc(mut self, rpc: Arc<RpcManager>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// 设置RPC回退时最多扫描的交易数（每笔一次getTransaction）
+    pub fn with_max_scanned_transactions(mut self, count: usize) -> Self {
+        self.max_scanned_transactions = count.max(1);
+        self
+    }
+
+    async fn query_launches(&self, creator: &str, exclude: &Pubkey) -> Result<Vec<DeployerLaunch>> {
+        let rows = sqlx::query(
+            "SELECT l.mint,
+                    l.launched_at,
+                    l.graduated_at IS NOT NULL AS graduated,
+                    l.peak_liquidity_sol::float8 AS peak_liquidity_sol,
+                    l.last_liquidity_sol::float8 AS last_liquidity_sol,
+                    l.last_price_change_24h::float8 AS last_price_change_24h,
+                    EXISTS (
+                        SELECT 1 FROM blacklist b WHERE b.type = 'token' AND b.address = l.mint
+                    ) AS blacklisted
+             FROM token_launches l
+             WHERE l.creator = $1 AND l.mint <> $2
+             ORDER BY l.launched_at DESC
+             LIMIT $3",
+        )
+        .bind(creator)
+        .bind(exclude.to_string())
+        .bind(self.max_launches)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Deployer history query failed: {}", e)))?;
+
+        rows.iter()
+            .map(|row| -> std::result::Result<DeployerLaunch, sqlx::Error> {
+                let outcome = LaunchOutcome {
+                    peak_liquidity_sol: row.try_get("peak_liquidity_sol")?,
+                    last_liquidity_sol: row.try_get("last_liquidity_sol")?,
+                    last_price_change_24h: row.try_get("last_price_change_24h")?,
+                };
+                let blacklisted: bool = row.try_get("blacklisted")?;
+
+                Ok(DeployerLaunch {
+                    mint: row.try_get("mint")?,
+                    created_at: row.try_get("launched_at")?,
+                    graduated: row.try_get("graduated")?,
+                    rugged: outcome.is_rugged(blacklisted),
+                })
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::Database(format!("Deployer history decode failed: {}", e)))
+    }
+
+    /// 扫描部署者最近的交易，将其作为fee payer发起的发币写入`token_launches`
+    ///
+    /// 每个部署者每小时最多扫描一次，返回写入的发币数
+    async fn scan_launches(&self, rpc: &RpcManager, creator: &Pubkey) -> Result<usize> {
+        let now = Utc::now();
+        if let Some(scanned_at) = self.scanned_creators.lock().unwrap().get(creator) {
+            if now - scanned_at < Duration::hours(1) {
+                return Ok(0);
+            }
+        }
+        self.scanned_creators.lock().unwrap().insert(*creator, now);
+
+        let signatures = chain_history::recent_signatures(rpc, creator, self.max_scanned_transactions).await?;
+        let parser = EventParser::new();
+        let mut found = 0;
+
+        for status in signatures.iter().filter(|status| status.err.is_none()) {
+            let Ok(signature) = status.signature.parse() else {
+                continue;
+            };
+            let transaction = match chain_history::fetch_transaction(rpc, signature).await {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    tracing::debug!("Skipping transaction {} of {}: {}", status.signature, creator, e);
+                    continue;
+                }
+            };
+
+            let Some(parsed) = parser.parse_transaction(&transaction) else {
+                continue;
+            };
+            let (Some(launch), Some(block_time)) = (parsed.launch, parsed.block_time) else {
+                continue;
+            };
+            if launch.creator != *creator {
+                continue;
+            }
+
+            sqlx::query(
+                "INSERT INTO token_launches (mint, creator, launched_at, launch_slot)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (mint) DO NOTHING",
+            )
+            .bind(launch.mint.to_string())
+            .bind(creator.to_string())
+            .bind(block_time)
+            .bind(parsed.slot as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Database(format!("Scanned launch insert failed: {}", e)))?;
+            found += 1;
+        }
+
+        tracing::debug!(
+            "🔎 Scanned {} transactions of deployer {}: {} launches",
+            signatures.len(),
+            creator,
+            found
+        );
+
+        Ok(found)
+    }
+}
+
+#[async_trait]
+impl DeployerHistorySource for PostgresDeployerHistory {
+    async fn history(&self, creator: &Pubkey, exclude: &Pubkey) -> Result<DeployerHistory> {
+        let creator_key = *creator;
+        let creator = creator.to_string();
+
+        let blacklisted: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM blacklist WHERE type = 'creator' AND address = $1)",
+        )
+        .bind(&creator)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Creator blacklist query failed: {}", e)))?;
+
+        let mut launches = self.query_launches(&creator, exclude).await?;
+
+        // 部署者从未出现在评估或回填中，回退到扫描其链上交易
+        if launches.is_empty() {
+            if let Some(rpc) = &self.rpc {
+                match self.scan_launches(rpc, &creator_key).await {
+                    Ok(0) => {}
+                    Ok(_) => launches = self.query_launches(&creator, exclude).await?,
+                    Err(e) => tracing::warn!("Deployer transaction scan failed for {}: {}", creator, e),
+                }
+            }
+        }
+
+        Ok(DeployerHistory {
+            blacklisted,
+            launches,
+        })
+    }
+
+    /// 发币时间未知（TokenInfo.created_at为默认值）时使用首次记录的时间，之后只会往前修正
+    async fn record_launch(&self, token: &TokenInfo, creator: &Pubkey) -> Result<()> {
+        let launched_at = (token.created_at.timestamp() > 0).then_some(token.created_at);
+
+        sqlx::query(
+            "INSERT INTO token_launches (mint, creator, launched_at, graduated_at, peak_liquidity_sol,
+                                         last_liquidity_sol, last_price_change_24h, observed_at)
+             VALUES ($1, $2, COALESCE($3, NOW()), $4, $5, $5, $6, NOW())
+             ON CONFLICT (mint) DO UPDATE SET
+                 creator = EXCLUDED.creator,
+                 launched_at = LEAST(token_launches.launched_at, EXCLUDED.launched_at),
+                 graduated_at = COALESCE(token_launches.graduated_at, EXCLUDED.graduated_at),
+                 peak_liquidity_sol = GREATEST(token_launches.peak_liquidity_sol, EXCLUDED.peak_liquidity_sol),
+                 last_liquidity_sol = EXCLUDED.last_liquidity_sol,
+                 last_price_change_24h = EXCLUDED.last_price_change_24h,
+                 observed_at = NOW()",
+        )
+        .bind(token.mint.to_string())
+        .bind(creator.to_string())
+        .bind(launched_at)
+        .bind(token.graduated_at)
+        .bind(token.liquidity_sol)
+        .bind(token.price_change_24h.clamp(-100.0, 99_999_999.0))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Launch upsert failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "postgres"
+    }
+}
+
+/// 本地fixture部署者历史
+///
+/// 用于测试和离线回放，按部署者地址返回预置的历史
+#[derive(Default)]
+pub struct FixtureDeployerHistory {
+    fixtures: HashMap<String, DeployerHistory>,
+}
+
+impl FixtureDeployerHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从JSON文件加载fixture（对象：部署者地址 -> 历史）
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read deployer fixtures {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+
+        let fixtures: HashMap<String, DeployerHistory> = serde_json::from_str(&content)
+            .map_err(|e| Error::Serialization(format!("Invalid deployer fixtures: {}", e)))?;
+
+        Ok(Self { fixtures })
+    }
+
+    /// 添加一条fixture
+    pub fn with_fixture(mut self, creator: Pubkey, history: DeployerHistory) -> Self {
+        self.fixtures.insert(creator.to_string(), history);
+        self
+    }
+}
+
+#[async_trait]
+impl DeployerHistorySource for FixtureDeployerHistory {
+    async fn history(&self, creator: &Pubkey, exclude: &Pubkey) -> Result<DeployerHistory> {
+        let mut history = self.fixtures.get(&creator.to_string()).cloned().unwrap_or_default();
+        let exclude = exclude.to_string();
+        history.launches.retain(|launch| launch.mint != exclude);
+        Ok(history)
+    }
+
+    fn name(&self) -> &str {
+        "fixture"
+    }
+}
+
+/// 本进程最近一次记录的发币状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LaunchState {
+    creator: Pubkey,
+    graduated: bool,
+    /// 流动性见底或24h跌幅超过90%
+    collapsed: bool,
+}
+
+impl LaunchState {
+    fn of(token: &TokenInfo, creator: Pubkey) -> Self {
+        Self {
+            creator,
+            graduated: token.graduated_at.is_some(),
+            collapsed: token.liquidity_sol < 0.5 || token.price_change_24h <= -90.0,
+        }
+    }
+}
+
+/// 部署者信誉分析器
+///
+/// 识别mint的部署者（创建交易的fee payer），统计其历史发币的rug/毕业/存活情况
+pub struct DeployerAnalyzer {
+    source: Option<Arc<dyn DeployerHistorySource>>,
+
+    /// 用于解析部署者的RPC（TokenInfo未提供creator时使用）
+    rpc: Option<Arc<RpcManager>>,
+
+    /// 回溯创建交易时最多翻页数（每页1000个签名）
+    max_signature_pages: usize,
+
+    /// 通过RPC解析出的部署者（mint -> 部署者）
+    resolved_creators: Mutex<BoundedCache<Pubkey>>,
+
+    /// mint -> 最近一次记录的发币状态
+    launch_states: Mutex<BoundedCache<LaunchState>>,
+
+    /// 部署者 -> 历史版本（其发币出现或状态变化时更新）
+    creator_versions: Mutex<BoundedCache<u64>>,
+
+    /// 版本号序列（淘汰后重新出现的部署者不会复用旧版本号）
+    next_version: AtomicU64,
+}
+
+impl DeployerAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            source: None,
+            rpc: None,
+            max_signature_pages: 50,
+            resolved_creators: Mutex::new(BoundedCache::new(50_000)),
+            launch_states: Mutex::new(BoundedCache::new(50_000)),
+            creator_versions: Mutex::new(BoundedCache::new(50_000)),
+            next_version: AtomicU64::new(1),
+        }
+    }
+
+    /// 设置部署者历史数据源
+    pub fn with_source(mut self, source: Arc<dyn DeployerHistorySource>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// 通过RPC解析部署者
+    pub fn with_rpc(mut self, rpc: Arc<RpcManager>) -> Self {
+        self.rpc = Some(rpc);
+        self
+    }
+
+    /// 设置回溯创建交易的最大翻页数（活跃代币的签名可能有数十万个）
+    pub fn with_max_signature_pages(mut self, pages: usize) -> Self {
+        self.max_signature_pages = pages.max(1);
+        self
+    }
+
+    /// 分析部署者信誉
+    ///
+    /// 未配置数据源或无法识别部署者时返回None
+    pub async fn analyze(&self, token: &TokenInfo) -> Option<Score> {
+        let source = self.source.as_ref()?;
+
+        let creator = match self.resolve_creator(token).await {
+            Ok(Some(creator)) => creator,
+            Ok(None) => return None,
+            Err(e) => {
+                tracing::warn!("Failed to resolve creator of {}: {}", token.mint, e);
+                return None;
+            }
+        };
+
+        match source.record_launch(token, &creator).await {
+            Ok(()) => self.note_launch(token, creator),
+            Err(e) => tracing::warn!("Failed to record launch of {}: {}", token.mint, e),
+        }
+
+        match source.history(&creator, &token.mint).await {
+            Ok(history) => Some(Self::score(&creator, &history, Utc::now())),
+            Err(e) => {
+                tracing::warn!(
+                    "Deployer history unavailable for {} via {}: {}",
+                    creator,
+                    source.name(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// 部署者历史的版本，用于增量评估判断历史是否变化
+    ///
+    /// 只反映本进程记录的发币；其他进程写入的变化依赖缓存过期。部署者未知时为0
+    pub fn history_version(&self, token: &TokenInfo) -> u64 {
+        let creator = token
+            .creator
+            .or_else(|| self.resolved_creators.lock().unwrap().get(&token.mint));
+
+        creator
+            .and_then(|creator| self.creator_versions.lock().unwrap().get(&creator))
+            .unwrap_or(0)
+    }
+
+    /// 记录发币状态，新发币或状态变化时更新部署者的历史版本
+    fn note_launch(&self, token: &TokenInfo, creator: Pubkey) {
+        let state = LaunchState::of(token, creator);
+
+        let mut states = self.launch_states.lock().unwrap();
+        if states.get(&token.mint) == Some(state) {
+            return;
+        }
+        states.insert(token.mint, state);
+        drop(states);
+
+        let version = self.next_version.fetch_add(1, Ordering::Relaxed);
+        self.creator_versions.lock().unwrap().insert(creator, version);
+    }
+
+    /// 根据历史计算信誉分
+    pub fn score(creator: &Pubkey, history: &DeployerHistory, now: DateTime<Utc>) -> Score {
+        let stats = history.stats(now);
+        let mut score: f64 = 100.0;
+        let mut issues = Vec::new();
+
+        if history.blacklisted {
+            score -= 80.0;
+            issues.push(format!("🚨 部署者在黑名单中: {}", creator));
+        }
+
+        if stats.launches == 0 {
+            score -= 5.0;
+            issues.push("ℹ️ 部署者无历史发币记录".to_string());
+        } else {
+            let rug_rate = stats.rug_rate();
+            if stats.rugged >= 3 && rug_rate >= 0.5 {
+                score -= 70.0;
+                issues.push(format!(
+                    "🚨 连环rug部署者: {}个历史代币中{}个rug",
+                    stats.launches, stats.rugged
+                ));
+            } else if rug_rate >= 0.3 {
+                score -= 40.0;
+                issues.push(format!(
+                    "⚠️ 部署者rug比例高: {:.0}%（{}/{}）",
+                    rug_rate * 100.0,
+                    stats.rugged,
+                    stats.launches
+                ));
+            } else if stats.rugged > 0 {
+                score -= 15.0;
+                issues.push(format!("⚠️ 部署者有{}个历史代币rug", stats.rugged));
+            }
+
+            if stats.recent_24h >= 5 {
+                score -= 20.0;
+                issues.push(format!("⚠️ 部署者24小时内发币{}个（批量发币）", stats.recent_24h));
+            }
+
+            // 历史表现良好的部署者少量加分
+            let graduated_rate = stats.graduated as f64 / stats.launches as f64;
+            let survived_rate = stats.survived_24h as f64 / stats.launches as f64;
+            if stats.rugged == 0 && (graduated_rate >= 0.3 || survived_rate >= 0.5) {
+                score += 5.0;
+                issues.push(format!(
+                    "✅ 部署者历史: {}个代币，{}个毕业，{}个存活超过24小时",
+                    stats.launches, stats.graduated, stats.survived_24h
+                ));
+            }
+        }
+
+        Score {
+            value: score.clamp(0.0, 100.0),
+            issues,
+        }
+    }
+
+    /// 识别部署者
+    ///
+    /// 优先使用TokenInfo中的creator，否则回溯mint最早的交易并取其fee payer
+    pub async fn resolve_creator(&self, token: &TokenInfo) -> Result<Option<Pubkey>> {
+        if let Some(creator) = token.creator {
+            return Ok(Some(creator));
+        }
+
+        if let Some(creator) = self.resolved_creators.lock().unwrap().get(&token.mint) {
+            return Ok(Some(creator));
+        }
+
+        let Some(rpc) = &self.rpc else {
+            return Ok(None);
+        };
+
+        let Some(signatures) =
+            chain_history::oldest_signatures(rpc, &token.mint, self.max_signature_pages).await?
+        else {
+            return Ok(None);
+        };
+        let Some(oldest) = signatures.last() else {
+            return Ok(None);
+        };
+
+        let signature = oldest
+            .signature
+            .parse()
+            .map_err(|e| Error::Serialization(format!("Invalid signature: {}", e)))?;
+        let transaction = chain_history::fetch_transaction(rpc, signature).await?;
+
+        let creator = chain_history::account_keys(&transaction).first().copied();
+        if let Some(creator) = creator {
+            self.resolved_creators.lock().unwrap().insert(token.mint, creator);
+        }
+
+        Ok(creator)
+    }
+}
+
+impl Default for DeployerAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch(hours_ago: i64, rugged: bool, graduated: bool) -> DeployerLaunch {
+        DeployerLaunch {
+            mint: Pubkey::new_unique().to_string(),
+            created_at: Utc::now() - Duration::hours(hours_ago),
+            graduated,
+            rugged,
+        }
+    }
+
+    #[test]
+    fn test_serial_rugger() {
+        let history = DeployerHistory {
+            blacklisted: false,
+            launches: vec![
+                launch(100, true, false),
+                launch(80, true, false),
+                launch(60, true, false),
+                launch(40, false, true),
+            ],
+        };
+
+        let score = DeployerAnalyzer::score(&Pubkey::new_unique(), &history, Utc::now());
+        assert!(score.value <= 30.0);
+        assert!(score.issues.iter().any(|issue| issue.contains("连环rug")));
+    }
+
+    #[test]
+    fn test_reputable_deployer() {
+        let history = DeployerHistory {
+            blacklisted: false,
+            launches: vec![launch(200, false, true), launch(100, false, false)],
+        };
+
+        let now = Utc::now();
+        let stats = history.stats(now);
+        assert_eq!(stats.graduated, 1);
+        assert_eq!(stats.survived_24h, 2);
+
+        let score = DeployerAnalyzer::score(&Pubkey::new_unique(), &history, now);
+        assert_eq!(score.value, 100.0);
+    }
+
+    fn outcome(peak: f64, last: f64, change: f64) -> LaunchOutcome {
+        LaunchOutcome {
+            peak_liquidity_sol: Some(peak),
+            last_liquidity_sol: Some(last),
+            last_price_change_24h: Some(change),
+        }
+    }
+
+    #[test]
+    fn test_rug_classification() {
+        // 流动性被撤走
+        assert!(outcome(50.0, 0.1, 0.0).is_rugged(false));
+        assert!(outcome(50.0, 4.0, 0.0).is_rugged(false));
+        // 价格崩盘
+        assert!(outcome(30.0, 30.0, -95.0).is_rugged(false));
+        assert!(LaunchOutcome::default().is_rugged(true));
+
+        // 从未有过流动性、或流动性正常
+        assert!(!outcome(0.2, 0.0, 0.0).is_rugged(false));
+        assert!(!outcome(50.0, 20.0, -40.0).is_rugged(false));
+        assert!(!LaunchOutcome::default().is_rugged(false));
+    }
+
+    /// mint -> (创建者, 发币时间, 结果)
+    type LaunchRecords = HashMap<Pubkey, (Pubkey, DateTime<Utc>, LaunchOutcome)>;
+
+    /// 内存中的发币记录，按Postgres实现的规则判定rug
+    #[derive(Default)]
+    struct RecordingHistory {
+        launches: std::sync::Mutex<LaunchRecords>,
+    }
+
+    #[async_trait]
+    impl DeployerHistorySource for RecordingHistory {
+        async fn history(&self, creator: &Pubkey, exclude: &Pubkey) -> Result<DeployerHistory> {
+            let launches = self.launches.lock().unwrap();
+            Ok(DeployerHistory {
+                blacklisted: false,
+                launches: launches
+                    .iter()
+                    .filter(|(mint, (launch_creator, _, _))| launch_creator == creator && *mint != exclude)
+                    .map(|(mint, (_, launched_at, outcome))| DeployerLaunch {
+                        mint: mint.to_string(),
+                        created_at: *launched_at,
+                        graduated: false,
+                        rugged: outcome.is_rugged(false),
+                    })
+                    .collect(),
+            })
+        }
+
+        async fn record_launch(&self, token: &TokenInfo, creator: &Pubkey) -> Result<()> {
+            let mut launches = self.launches.lock().unwrap();
+            let entry = launches
+                .entry(token.mint)
+                .or_insert((*creator, token.created_at, LaunchOutcome::default()));
+            let peak = entry.2.peak_liquidity_sol.unwrap_or(0.0).max(token.liquidity_sol);
+            entry.2 = LaunchOutcome {
+                peak_liquidity_sol: Some(peak),
+                last_liquidity_sol: Some(token.liquidity_sol),
+                last_price_change_24h: Some(token.price_change_24h),
+            };
+            Ok(())
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rugged_launches_flag_next_token() {
+        let source = Arc::new(RecordingHistory::default());
+        let analyzer = DeployerAnalyzer::new().with_source(source.clone());
+        let creator = Pubkey::new_unique();
+
+        // 三个历史代币：上线时有流动性，之后被撤池
+        for hours_ago in [72, 48, 30] {
+            let mut token = TokenInfo {
+                mint: Pubkey::new_unique(),
+                creator: Some(creator),
+                created_at: Utc::now() - Duration::hours(hours_ago),
+                liquidity_sol: 40.0,
+                ..Default::default()
+            };
+            analyzer.analyze(&token).await.unwrap();
+
+            token.liquidity_sol = 0.05;
+            analyzer.analyze(&token).await.unwrap();
+        }
+
+        let launched_at = Utc::now() - Duration::minutes(5);
+        let token = TokenInfo {
+            mint: Pubkey::new_unique(),
+            creator: Some(creator),
+            created_at: launched_at,
+            liquidity_sol: 30.0,
+            ..Default::default()
+        };
+        let score = analyzer.analyze(&token).await.unwrap();
+
+        assert!(score.value <= 30.0);
+        assert!(score.issues.iter().any(|issue| issue.contains("连环rug")));
+        // 记录的是发币时间而不是评估时间
+        assert_eq!(source.launches.lock().unwrap()[&token.mint].1, launched_at);
+    }
+
+    #[tokio::test]
+    async fn test_history_version_tracks_sibling_launches() {
+        let analyzer = DeployerAnalyzer::new().with_source(Arc::new(RecordingHistory::default()));
+        let creator = Pubkey::new_unique();
+        let token = TokenInfo {
+            mint: Pubkey::new_unique(),
+            creator: Some(creator),
+            liquidity_sol: 30.0,
+            ..Default::default()
+        };
+        assert_eq!(analyzer.history_version(&token), 0);
+
+        analyzer.analyze(&token).await;
+        let version = analyzer.history_version(&token);
+        assert_ne!(version, 0);
+
+        // 状态不变时版本不变
+        analyzer.analyze(&token).await;
+        assert_eq!(analyzer.history_version(&token), version);
+
+        // 同一部署者的另一个代币被撤池
+        let mut sibling = TokenInfo {
+            mint: Pubkey::new_unique(),
+            ..token.clone()
+        };
+        analyzer.analyze(&sibling).await;
+        sibling.liquidity_sol = 0.0;
+        analyzer.analyze(&sibling).await;
+        assert_ne!(analyzer.history_version(&token), version);
+    }
+}
@@ -1,9 +1,15 @@
 use solsniper_core::{TokenInfo, Score};
+use std::sync::Arc;
+
+use crate::holder_clusters::{ClusterReport, HolderClusterDetector};
 
 /// 持有者分布分析器
 pub struct HolderAnalyzer {
     max_top10_ratio: f64,
     max_top20_ratio: f64,
+
+    /// 关联钱包检测器（可选）
+    cluster_detector: Option<Arc<HolderClusterDetector>>,
 }
 
 impl HolderAnalyzer {
@@ -11,9 +17,21 @@ impl HolderAnalyzer {
         Self {
             max_top10_ratio,
             max_top20_ratio,
+            cluster_detector: None,
         }
     }
 
+    /// 启用资助关系聚类和捆绑买入检测
+    pub fn with_cluster_detector(mut self, detector: Arc<HolderClusterDetector>) -> Self {
+        self.cluster_detector = Some(detector);
+        self
+    }
+
+    /// 是否启用了关联钱包检测（其输入为链上持有者列表，不在TokenInfo中）
+    pub fn has_cluster_detector(&self) -> bool {
+        self.cluster_detector.is_some()
+    }
+
     pub async fn analyze(&self, token: &TokenInfo) -> Score {
         let mut score = 100.0;
         let mut issues = Vec::new();
@@ -43,6 +61,12 @@ impl HolderAnalyzer {
         if token.top20_ratio > 0.9 {
             score -= 20.0;
             issues.push("⚠️ Top20持有超过90%".to_string());
+        } else if token.top20_ratio > self.max_top20_ratio {
+            score -= 10.0;
+            issues.push(format!(
+                "⚠️ Top20 持有 {:.1}%（集中度偏高）",
+                token.top20_ratio * 100.0
+            ));
         }
 
         // 持有者总数
@@ -74,9 +98,136 @@ impl HolderAnalyzer {
             }
         }
 
+        // 关联钱包
+        if let Some(detector) = &self.cluster_detector {
+            match detector.detect(&token.mint).await {
+                Ok(report) => score -= Self::cluster_penalty(&report, &mut issues),
+                Err(e) => tracing::warn!("Holder cluster detection failed for {}: {}", token.mint, e),
+            }
+        }
+
         Score {
             value: score.max(0.0),
             issues,
         }
     }
+
+    /// 根据关联簇计算扣分并写入问题
+    pub fn cluster_penalty(report: &ClusterReport, issues: &mut Vec<String>) -> f64 {
+        let mut penalty = 0.0;
+
+        for cluster in &report.clusters {
+            if let Some(funder) = cluster.common_funder() {
+                issues.push(format!(
+                    "🚨 {:.0}%供应量由{}个同一地址资助的钱包持有（{}）",
+                    cluster.ratio * 100.0,
+                    cluster.wallets.len(),
+                    funder
+                ));
+            } else if let Some(slot) = cluster.bundle_slot() {
+                issues.push(format!(
+                    "🚨 {}个钱包在开盘slot {}捆绑买入，合计持有{:.0}%",
+                    cluster.wallets.len(),
+                    slot,
+                    cluster.ratio * 100.0
+                ));
+            }
+
+            penalty += if cluster.ratio > 0.3 {
+                30.0
+            } else if cluster.ratio > 0.1 {
+                15.0
+            } else {
+                5.0
+            };
+        }
+
+        // 合并后的有效集中度
+        if report.effective_top10_ratio > report.raw_top10_ratio + 0.05 {
+            issues.push(format!(
+                "⚠️ 合并关联钱包后Top10有效集中度 {:.1}%（原 {:.1}%）",
+                report.effective_top10_ratio * 100.0,
+                report.raw_top10_ratio * 100.0
+            ));
+            if report.effective_top10_ratio > 0.8 {
+                penalty += 20.0;
+            }
+        }
+
+        f64::min(penalty, 60.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holder_clusters::{ClusterLink, HolderCluster};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn cluster(wallets: usize, ratio: f64, link: ClusterLink) -> HolderCluster {
+        HolderCluster {
+            wallets: (0..wallets).map(|_| Pubkey::new_unique()).collect(),
+            amount: (ratio * 1000.0) as u64,
+            ratio,
+            links: vec![link],
+        }
+    }
+
+    #[test]
+    fn test_cluster_penalty_by_size() {
+        let report = ClusterReport {
+            clusters: vec![
+                cluster(5, 0.35, ClusterLink::CommonFunder(Pubkey::new_unique())),
+                cluster(3, 0.12, ClusterLink::SameSlotBuy(42)),
+                cluster(2, 0.02, ClusterLink::CommonFunder(Pubkey::new_unique())),
+            ],
+            raw_top10_ratio: 0.5,
+            effective_top10_ratio: 0.52,
+        };
+        let mut issues = Vec::new();
+
+        let penalty = HolderAnalyzer::cluster_penalty(&report, &mut issues);
+
+        assert_eq!(penalty, 30.0 + 15.0 + 5.0);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[1].contains("slot 42"));
+    }
+
+    #[test]
+    fn test_cluster_penalty_effective_concentration() {
+        let report = ClusterReport {
+            clusters: vec![cluster(12, 0.48, ClusterLink::CommonFunder(Pubkey::new_unique()))],
+            raw_top10_ratio: 0.4,
+            effective_top10_ratio: 0.85,
+        };
+        let mut issues = Vec::new();
+
+        let penalty = HolderAnalyzer::cluster_penalty(&report, &mut issues);
+
+        assert_eq!(penalty, 30.0 + 20.0);
+        assert!(issues.iter().any(|issue| issue.contains("有效集中度")));
+
+        // 总扣分封顶
+        let report = ClusterReport {
+            clusters: (0..4)
+                .map(|_| cluster(3, 0.2, ClusterLink::SameSlotBuy(7)))
+                .collect(),
+            raw_top10_ratio: 0.4,
+            effective_top10_ratio: 0.9,
+        };
+        assert_eq!(HolderAnalyzer::cluster_penalty(&report, &mut Vec::new()), 60.0);
+    }
+
+    #[test]
+    fn test_no_clusters_no_penalty() {
+        let report = ClusterReport {
+            clusters: Vec::new(),
+            raw_top10_ratio: 0.3,
+            effective_top10_ratio: 0.3,
+        };
+        let mut issues = Vec::new();
+
+        assert_eq!(HolderAnalyzer::cluster_penalty(&report, &mut issues), 0.0);
+        assert!(issues.is_empty());
+    }
 }
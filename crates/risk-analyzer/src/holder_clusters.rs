@@ -0,0 +1,801 @@
+use solsniper_core::{Error, Result, RpcManager};
+use solsniper_behavior_pattern::{SharedWalletLinks, WalletProfile, WalletProfileSource};
+/// 交易所热钱包列表与协同买入检测共用
+pub use solsniper_behavior_pattern::default_ignored_funders;
+use async_trait::async_trait;
+use chrono::DateTime;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::chain_history;
+
+/// 持有者余额（按钱包聚合）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HolderBalance {
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+/// 开盘阶段的一笔买入
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LaunchBuy {
+    pub wallet: Pubkey,
+    pub slot: u64,
+}
+
+/// 钱包之间的关联依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClusterLink {
+    /// 由同一地址资助SOL（N跳内）
+    CommonFunder(Pubkey),
+    /// 开盘时在同一slot买入
+    SameSlotBuy(u64),
+}
+
+/// 关联钱包簇
+#[derive(Debug, Clone)]
+pub struct HolderCluster {
+    pub wallets: Vec<Pubkey>,
+    pub amount: u64,
+    /// 占流通量比例（0-1）
+    pub ratio: f64,
+    pub links: Vec<ClusterLink>,
+}
+
+impl HolderCluster {
+    /// 簇内钱包共同的资助地址
+    pub fn common_funder(&self) -> Option<Pubkey> {
+        self.links.iter().find_map(|link| match link {
+            ClusterLink::CommonFunder(funder) => Some(*funder),
+            _ => None,
+        })
+    }
+
+    /// 簇内钱包捆绑买入的slot
+    pub fn bundle_slot(&self) -> Option<u64> {
+        self.links.iter().find_map(|link| match link {
+            ClusterLink::SameSlotBuy(slot) => Some(*slot),
+            _ => None,
+        })
+    }
+}
+
+/// 关联钱包检测结果
+#[derive(Debug, Clone)]
+pub struct ClusterReport {
+    /// 按持仓降序排列的关联簇（至少2个钱包）
+    pub clusters: Vec<HolderCluster>,
+    /// 按单个钱包计算的Top10比例
+    pub raw_top10_ratio: f64,
+    /// 合并关联簇后的Top10比例（有效集中度）
+    pub effective_top10_ratio: f64,
+}
+
+/// 按资助关系和捆绑买入合并持有者
+///
+/// - `funders`: 钱包 -> 资助链上的祖先地址（1..N跳）
+/// - `launch_buys`: 开盘阶段的买入，同一slot至少`min_bundle_size`个持有者视为捆绑
+pub fn build_clusters(
+    holders: &[HolderBalance],
+    circulating: u64,
+    funders: &HashMap<Pubkey, Vec<Pubkey>>,
+    launch_buys: &[LaunchBuy],
+    min_bundle_size: usize,
+) -> ClusterReport {
+    let index: HashMap<Pubkey, usize> = holders
+        .iter()
+        .enumerate()
+        .map(|(i, holder)| (holder.wallet, i))
+        .collect();
+
+    let mut parent: Vec<usize> = (0..holders.len()).collect();
+    let mut links: Vec<(usize, ClusterLink)> = Vec::new();
+
+    // 同一祖先资助的持有者
+    let mut by_funder: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+    for (wallet, ancestors) in funders {
+        let Some(&i) = index.get(wallet) else {
+            continue;
+        };
+        for ancestor in ancestors.iter().collect::<HashSet<_>>() {
+            by_funder.entry(*ancestor).or_default().push(i);
+        }
+    }
+    for (funder, members) in &by_funder {
+        // 资助者本身也是持有者时一并合并
+        let mut members = members.clone();
+        if let Some(&i) = index.get(funder) {
+            members.push(i);
+        }
+        members.sort_unstable();
+        members.dedup();
+        if members.len() < 2 {
+            continue;
+        }
+        for &member in &members[1..] {
+            union(&mut parent, members[0], member);
+        }
+        links.push((members[0], ClusterLink::CommonFunder(*funder)));
+    }
+
+    // 同一slot捆绑买入的持有者
+    let mut by_slot: HashMap<u64, Vec<usize>> = HashMap::new();
+    for buy in launch_buys {
+        if let Some(&i) = index.get(&buy.wallet) {
+            by_slot.entry(buy.slot).or_default().push(i);
+        }
+    }
+    for (slot, members) in &mut by_slot {
+        members.sort_unstable();
+        members.dedup();
+        if members.len() < min_bundle_size.max(2) {
+            continue;
+        }
+        for &member in &members[1..] {
+            union(&mut parent, members[0], member);
+        }
+        links.push((members[0], ClusterLink::SameSlotBuy(*slot)));
+    }
+
+    // 汇总连通分量
+    let mut components: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..holders.len() {
+        let root = find(&mut parent, i);
+        components.entry(root).or_default().push(i);
+    }
+
+    let mut component_links: HashMap<usize, Vec<ClusterLink>> = HashMap::new();
+    for (member, link) in links {
+        let root = find(&mut parent, member);
+        let entry = component_links.entry(root).or_default();
+        if !entry.contains(&link) {
+            entry.push(link);
+        }
+    }
+
+    let ratio = |amount: u64| -> f64 {
+        if circulating == 0 {
+            return 0.0;
+        }
+        (amount as f64 / circulating as f64).min(1.0)
+    };
+
+    // 每个实体（簇或单个钱包）的持仓
+    let mut entity_amounts: Vec<u64> = components
+        .values()
+        .map(|members| members.iter().map(|&i| holders[i].amount).sum())
+        .collect();
+    entity_amounts.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut wallet_amounts: Vec<u64> = holders.iter().map(|holder| holder.amount).collect();
+    wallet_amounts.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut clusters: Vec<HolderCluster> = components
+        .into_iter()
+        .filter(|(_, members)| members.len() >= 2)
+        .map(|(root, members)| {
+            let amount: u64 = members.iter().map(|&i| holders[i].amount).sum();
+            HolderCluster {
+                wallets: members.iter().map(|&i| holders[i].wallet).collect(),
+                amount,
+                ratio: ratio(amount),
+                links: component_links.remove(&root).unwrap_or_default(),
+            }
+        })
+        .collect();
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.amount));
+
+    ClusterReport {
+        clusters,
+        raw_top10_ratio: ratio(wallet_amounts.iter().take(10).sum()),
+        effective_top10_ratio: ratio(entity_amounts.iter().take(10).sum()),
+    }
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    // 路径压缩
+    let mut node = i;
+    while parent[node] != root {
+        let next = parent[node];
+        parent[node] = root;
+        node = next;
+    }
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let root_a = find(parent, a);
+    let root_b = find(parent, b);
+    if root_a != root_b {
+        parent[root_b] = root_a;
+    }
+}
+
+/// 持有者关系图数据源trait
+#[async_trait]
+pub trait HolderGraphSource: Send + Sync {
+    /// 最大的持有者（按钱包聚合，已排除池子/锁仓等PDA）及流通量
+    async fn top_holders(&self, mint: &Pubkey) -> Result<(Vec<HolderBalance>, u64)>;
+
+    /// 钱包的首个SOL资助地址（无法确定时为None）
+    async fn funder_of(&self, wallet: &Pubkey) -> Result<Option<Pubkey>>;
+
+    /// 资助地址是否为公共资金来源（交易量大、向大量钱包转账，如交易所、中转服务）
+    async fn is_public_funder(&self, _funder: &Pubkey) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// 开盘阶段的买入
+    async fn launch_buys(&self, mint: &Pubkey) -> Result<Vec<LaunchBuy>>;
+
+    /// 数据源名称
+    fn name(&self) -> &str;
+}
+
+/// 基于RPC的持有者关系图
+///
+/// 持有者来自`getTokenLargestAccounts`；资助地址取钱包最早一笔入账交易的fee payer；
+/// 开盘买入取mint最早几个slot内交易的fee payer
+pub struct RpcHolderGraph {
+    rpc: Arc<RpcManager>,
+
+    /// 开盘窗口（创建后的slot数）
+    launch_slots: u64,
+
+    /// 开盘窗口内最多读取的交易数
+    max_launch_transactions: usize,
+
+    /// 回溯mint最早交易时最多翻页数
+    max_signature_pages: usize,
+}
+
+impl RpcHolderGraph {
+    pub fn new(rpc: Arc<RpcManager>) -> Self {
+        Self {
+            rpc,
+            launch_slots: 2,
+            max_launch_transactions: 50,
+            max_signature_pages: 5,
+        }
+    }
+
+    pub fn with_launch_slots(mut self, slots: u64) -> Self {
+        self.launch_slots = slots;
+        self
+    }
+
+    /// 从钱包最早的交易中找第一笔由他人支付的SOL入账
+    async fn first_funder(
+        &self,
+        wallet: &Pubkey,
+        signatures: &[RpcConfirmedTransactionStatusWithSignature],
+    ) -> Result<Option<Pubkey>> {
+        for status in signatures.iter().rev().take(3) {
+            if status.err.is_some() {
+                continue;
+            }
+            let signature = status
+                .signature
+                .parse()
+                .map_err(|e| Error::Serialization(format!("Invalid signature: {}", e)))?;
+            let transaction = chain_history::fetch_transaction(&self.rpc, signature).await?;
+
+            let keys = chain_history::account_keys(&transaction);
+            let Some(meta) = transaction.transaction.meta.as_ref() else {
+                continue;
+            };
+            let Some(position) = keys.iter().position(|key| key == wallet) else {
+                continue;
+            };
+
+            let received = match (meta.pre_balances.get(position), meta.post_balances.get(position)) {
+                (Some(pre), Some(post)) => post > pre,
+                _ => false,
+            };
+            if received && keys[0] != *wallet {
+                return Ok(Some(keys[0]));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl HolderGraphSource for RpcHolderGraph {
+    async fn top_holders(&self, mint: &Pubkey) -> Result<(Vec<HolderBalance>, u64)> {
+        let mint = *mint;
+
+        let (largest, supply) = self
+            .rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || {
+                    let largest = client.get_token_largest_accounts(&mint)?;
+                    let supply = client.get_token_supply(&mint)?;
+                    Ok::<_, solana_client::client_error::ClientError>((largest, supply))
+                })
+                .await
+                .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                .map_err(|e| Error::Rpc(format!("getTokenLargestAccounts failed: {}", e)))
+            })
+            .await?;
+
+        let supply: u64 = supply.amount.parse().unwrap_or(0);
+        let addresses: Vec<Pubkey> = largest
+            .iter()
+            .filter_map(|entry| Pubkey::from_str(&entry.address).ok())
+            .collect();
+
+        let accounts = self
+            .rpc
+            .execute_with_retry(|client| {
+                let addresses = addresses.clone();
+                async move {
+                    tokio::task::spawn_blocking(move || client.get_multiple_accounts(&addresses))
+                        .await
+                        .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                        .map_err(|e| Error::Rpc(format!("getMultipleAccounts failed: {}", e)))
+                }
+            })
+            .await?;
+
+        // 按owner聚合；PDA（池子金库、联合曲线、锁仓）不是真实持有者，从流通量剔除
+        let mut by_owner: HashMap<Pubkey, u64> = HashMap::new();
+        let mut excluded = 0u64;
+        for account in accounts.into_iter().flatten() {
+            if account.data.len() < 72 {
+                continue;
+            }
+            let owner = Pubkey::try_from(&account.data[32..64]).unwrap_or_default();
+            let amount = u64::from_le_bytes(account.data[64..72].try_into().unwrap_or([0; 8]));
+
+            if owner.is_on_curve() {
+                *by_owner.entry(owner).or_insert(0) += amount;
+            } else {
+                excluded = excluded.saturating_add(amount);
+            }
+        }
+
+        let mut holders: Vec<HolderBalance> = by_owner
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0)
+            .map(|(wallet, amount)| HolderBalance { wallet, amount })
+            .collect();
+        holders.sort_by_key(|h| std::cmp::Reverse(h.amount));
+
+        Ok((holders, supply.saturating_sub(excluded)))
+    }
+
+    async fn funder_of(&self, wallet: &Pubkey) -> Result<Option<Pubkey>> {
+        // 只追溯新钱包：超过一页签名的老钱包不再回溯
+        let Some(signatures) = chain_history::oldest_signatures(&self.rpc, wallet, 1).await? else {
+            return Ok(None);
+        };
+
+        self.first_funder(wallet, &signatures).await
+    }
+
+    /// 签名超过一页（1000笔）的地址视为公共资金来源
+    async fn is_public_funder(&self, funder: &Pubkey) -> Result<bool> {
+        Ok(chain_history::oldest_signatures(&self.rpc, funder, 1).await?.is_none())
+    }
+
+    async fn launch_buys(&self, mint: &Pubkey) -> Result<Vec<LaunchBuy>> {
+        let Some(signatures) =
+            chain_history::oldest_signatures(&self.rpc, mint, self.max_signature_pages).await?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let Some(creation_slot) = signatures.last().map(|status| status.slot) else {
+            return Ok(Vec::new());
+        };
+
+        let mut buys = Vec::new();
+        let launch_window = signatures
+            .iter()
+            .rev()
+            .filter(|status| status.err.is_none())
+            .take_while(|status| status.slot <= creation_slot + self.launch_slots)
+            .take(self.max_launch_transactions);
+
+        for status in launch_window {
+            let signature = status
+                .signature
+                .parse()
+                .map_err(|e| Error::Serialization(format!("Invalid signature: {}", e)))?;
+            let transaction = chain_history::fetch_transaction(&self.rpc, signature).await?;
+
+            if let Some(wallet) = chain_history::account_keys(&transaction).first() {
+                buys.push(LaunchBuy {
+                    wallet: *wallet,
+                    slot: status.slot,
+                });
+            }
+        }
+
+        Ok(buys)
+    }
+
+    fn name(&self) -> &str {
+        "rpc"
+    }
+}
+
+#[async_trait]
+impl WalletProfileSource for RpcHolderGraph {
+    async fn profile(&self, wallet: &Pubkey) -> Result<WalletProfile> {
+        // 超过一页签名的老钱包不视为新钱包，也不追溯资金来源
+        let Some(signatures) = chain_history::oldest_signatures(&self.rpc, wallet, 1).await? else {
+            return Ok(WalletProfile::default());
+        };
+
+        Ok(WalletProfile {
+            funder: self.first_funder(wallet, &signatures).await?,
+            first_seen: signatures
+                .last()
+                .and_then(|status| status.block_time)
+                .and_then(|block_time| DateTime::from_timestamp(block_time, 0)),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "rpc"
+    }
+}
+
+/// 按插入顺序淘汰的有界缓存
+pub(crate) struct BoundedCache<V> {
+    entries: HashMap<Pubkey, V>,
+    order: VecDeque<Pubkey>,
+    capacity: usize,
+}
+
+impl<V: Clone> BoundedCache<V> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub(crate) fn get(&self, key: &Pubkey) -> Option<V> {
+        self.entries.get(key).cloned()
+    }
+
+    pub(crate) fn insert(&mut self, key: Pubkey, value: V) {
+        if self.entries.insert(key, value).is_none() {
+            self.order.push_back(key);
+        }
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+/// 关联钱包检测器
+///
+/// 钱包的首个资助地址不会变化，查询结果跨代币缓存
+pub struct HolderClusterDetector {
+    source: Arc<dyn HolderGraphSource>,
+
+    /// 资助关系回溯跳数
+    max_hops: usize,
+
+    /// 参与分析的最大持有者数
+    max_holders: usize,
+
+    /// 同一slot至少多少个持有者买入视为捆绑
+    min_bundle_size: usize,
+
+    /// 不参与聚类的资助地址（交易所热钱包等公共资金来源）
+    ignored_funders: HashSet<Pubkey>,
+
+    /// 检测到的关联簇写入此表，供交易图识别关联钱包之间的自成交
+    wallet_links: Option<Arc<SharedWalletLinks>>,
+
+    /// 钱包 -> 首个资助地址
+    funder_cache: Mutex<BoundedCache<Option<Pubkey>>>,
+
+    /// 资助地址 -> 是否为公共资金来源
+    public_funder_cache: Mutex<BoundedCache<bool>>,
+}
+
+impl HolderClusterDetector {
+    pub fn new(source: Arc<dyn HolderGraphSource>) -> Self {
+        Self {
+            source,
+            max_hops: 2,
+            max_holders: 20,
+            min_bundle_size: 3,
+            ignored_funders: default_ignored_funders().into_iter().collect(),
+            wallet_links: None,
+            funder_cache: Mutex::new(BoundedCache::new(100_000)),
+            public_funder_cache: Mutex::new(BoundedCache::new(10_000)),
+        }
+    }
+
+    pub fn with_max_hops(mut self, hops: usize) -> Self {
+        self.max_hops = hops.max(1);
+        self
+    }
+
+    pub fn with_min_bundle_size(mut self, size: usize) -> Self {
+        self.min_bundle_size = size;
+        self
+    }
+
+    /// 追加忽略的资助地址（默认已包含`default_ignored_funders`）
+    pub fn with_ignored_funders(mut self, funders: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.ignored_funders.extend(funders);
+        self
+    }
+
+    pub fn with_wallet_links(mut self, links: Arc<SharedWalletLinks>) -> Self {
+        self.wallet_links = Some(links);
+        self
+    }
+
+    /// 检测mint的关联持有者
+    pub async fn detect(&self, mint: &Pubkey) -> Result<ClusterReport> {
+        let (mut holders, circulating) = self.source.top_holders(mint).await?;
+        holders.truncate(self.max_holders);
+
+        // 逐跳追溯资助链，遇到交易所等公共资金来源时停止
+        let mut funders: HashMap<Pubkey, Vec<Pubkey>> = HashMap::new();
+
+        for holder in &holders {
+            let mut ancestors = Vec::new();
+            let mut current = holder.wallet;
+
+            for _ in 0..self.max_hops {
+                let Some(funder) = self.funder_of(&current).await else {
+                    break;
+                };
+                if ancestors.contains(&funder) || self.is_public_funder(&funder).await {
+                    break;
+                }
+                ancestors.push(funder);
+                current = funder;
+            }
+
+            if !ancestors.is_empty() {
+                funders.insert(holder.wallet, ancestors);
+            }
+        }
+
+        let launch_buys = match self.source.launch_buys(mint).await {
+            Ok(buys) => buys,
+            Err(e) => {
+                tracing::debug!("Launch buys unavailable for {}: {}", mint, e);
+                Vec::new()
+            }
+        };
+
+        let report = build_clusters(
+            &holders,
+            circulating,
+            &funders,
+            &launch_buys,
+            self.min_bundle_size,
+        );
+
+        if let Some(links) = &self.wallet_links {
+            for cluster in &report.clusters {
+                links.link(&cluster.wallets);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 查询失败时不缓存
+    async fn funder_of(&self, wallet: &Pubkey) -> Option<Pubkey> {
+        if let Some(funder) = self.funder_cache.lock().unwrap().get(wallet) {
+            return funder;
+        }
+
+        match self.source.funder_of(wallet).await {
+            Ok(funder) => {
+                self.funder_cache.lock().unwrap().insert(*wallet, funder);
+                funder
+            }
+            Err(e) => {
+                tracing::debug!("Funding trace failed for {}: {}", wallet, e);
+                None
+            }
+        }
+    }
+
+    /// 忽略列表中的地址，或数据源判定交易量过大的地址；查询失败时按非公共处理
+    async fn is_public_funder(&self, funder: &Pubkey) -> bool {
+        if self.ignored_funders.contains(funder) {
+            return true;
+        }
+        if let Some(public) = self.public_funder_cache.lock().unwrap().get(funder) {
+            return public;
+        }
+
+        match self.source.is_public_funder(funder).await {
+            Ok(public) => {
+                self.public_funder_cache.lock().unwrap().insert(*funder, public);
+                public
+            }
+            Err(e) => {
+                tracing::debug!("Funder activity check failed for {}: {}", funder, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holders(amounts: &[u64]) -> Vec<HolderBalance> {
+        amounts
+            .iter()
+            .map(|&amount| HolderBalance {
+                wallet: Pubkey::new_unique(),
+                amount,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_common_funder_merges_wallets() {
+        let holders = holders(&[100, 100, 100, 300, 50]);
+        let dev = Pubkey::new_unique();
+        let hop = Pubkey::new_unique();
+
+        // 前两个钱包由dev直接资助，第三个经过一跳
+        let funders: HashMap<Pubkey, Vec<Pubkey>> = [
+            (holders[0].wallet, vec![dev]),
+            (holders[1].wallet, vec![dev]),
+            (holders[2].wallet, vec![hop, dev]),
+        ]
+        .into_iter()
+        .collect();
+
+        let report = build_clusters(&holders, 1000, &funders, &[], 3);
+
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].wallets.len(), 3);
+        assert_eq!(report.clusters[0].amount, 300);
+        assert_eq!(report.clusters[0].common_funder(), Some(dev));
+        assert!((report.raw_top10_ratio - 0.65).abs() < 1e-9);
+        assert!((report.effective_top10_ratio - 0.65).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_same_slot_bundle() {
+        let holders = holders(&[200, 150, 100, 50]);
+        let buys: Vec<LaunchBuy> = holders[..3]
+            .iter()
+            .map(|holder| LaunchBuy {
+                wallet: holder.wallet,
+                slot: 42,
+            })
+            .chain(std::iter::once(LaunchBuy {
+                wallet: holders[3].wallet,
+                slot: 43,
+            }))
+            .collect();
+
+        let report = build_clusters(&holders, 1000, &HashMap::new(), &buys, 3);
+
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].bundle_slot(), Some(42));
+        assert!((report.clusters[0].ratio - 0.45).abs() < 1e-9);
+
+        // 两个钱包同slot买入不足以判定捆绑
+        let report = build_clusters(&holders, 1000, &HashMap::new(), &buys[..2], 3);
+        assert!(report.clusters.is_empty());
+    }
+
+    /// 预置持有者和资助关系的数据源，记录查询次数
+    struct StaticGraph {
+        holders: Vec<HolderBalance>,
+        funders: HashMap<Pubkey, Pubkey>,
+        public: HashSet<Pubkey>,
+        funder_queries: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HolderGraphSource for StaticGraph {
+        async fn top_holders(&self, _mint: &Pubkey) -> Result<(Vec<HolderBalance>, u64)> {
+            Ok((self.holders.clone(), 1000))
+        }
+
+        async fn funder_of(&self, wallet: &Pubkey) -> Result<Option<Pubkey>> {
+            self.funder_queries.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(self.funders.get(wallet).copied())
+        }
+
+        async fn is_public_funder(&self, funder: &Pubkey) -> Result<bool> {
+            Ok(self.public.contains(funder))
+        }
+
+        async fn launch_buys(&self, _mint: &Pubkey) -> Result<Vec<LaunchBuy>> {
+            Ok(Vec::new())
+        }
+
+        fn name(&self) -> &str {
+            "static"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_public_funders_do_not_link_wallets() {
+        let holders = holders(&[100, 100, 100, 100, 100, 100]);
+        let binance = default_ignored_funders()[0];
+        let busy_relay = Pubkey::new_unique();
+        let dev = Pubkey::new_unique();
+
+        let mut funders = HashMap::new();
+        funders.insert(holders[0].wallet, binance);
+        funders.insert(holders[1].wallet, binance);
+        funders.insert(holders[2].wallet, busy_relay);
+        funders.insert(holders[3].wallet, busy_relay);
+        funders.insert(holders[4].wallet, dev);
+        funders.insert(holders[5].wallet, dev);
+
+        let source = Arc::new(StaticGraph {
+            holders,
+            funders,
+            public: [busy_relay].into_iter().collect(),
+            funder_queries: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let detector = HolderClusterDetector::new(source.clone()).with_max_hops(1);
+
+        let report = detector.detect(&Pubkey::new_unique()).await.unwrap();
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].common_funder(), Some(dev));
+
+        // 资助关系跨代币缓存
+        let queries = source.funder_queries.load(std::sync::atomic::Ordering::SeqCst);
+        detector.detect(&Pubkey::new_unique()).await.unwrap();
+        assert_eq!(source.funder_queries.load(std::sync::atomic::Ordering::SeqCst), queries);
+    }
+
+    #[test]
+    fn test_bounded_cache_evicts_oldest() {
+        let mut cache = BoundedCache::new(2);
+        let keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for (i, key) in keys.iter().enumerate() {
+            cache.insert(*key, i);
+        }
+
+        assert_eq!(cache.get(&keys[0]), None);
+        assert_eq!(cache.get(&keys[2]), Some(2));
+    }
+
+    #[test]
+    fn test_effective_concentration_exceeds_raw() {
+        // 12个小钱包由同一地址资助，单看Top10不明显
+        let mut amounts = vec![40u64; 12];
+        amounts.extend([30u64; 10]);
+        let holders = holders(&amounts);
+        let funder = Pubkey::new_unique();
+        let funders: HashMap<Pubkey, Vec<Pubkey>> =
+            holders[..12].iter().map(|h| (h.wallet, vec![funder])).collect();
+
+        let report = build_clusters(&holders, 1000, &funders, &[], 3);
+
+        assert!((report.raw_top10_ratio - 0.40).abs() < 1e-9);
+        // 480 + 9 * 30
+        assert!((report.effective_top10_ratio - 0.75).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,454 @@
+use solsniper_core::{Error, Result, RpcManager, TokenInfo};
+use solsniper_trading_engine::{SwapInstructionProvider, TransactionBuilder};
+use chrono::{DateTime, Utc};
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, instruction::Instruction, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// 缓存的最大代币数
+const MAX_CACHED_REPORTS: usize = 10_000;
+
+/// 卖出模拟结果
+#[derive(Debug, Clone)]
+pub struct HoneypotReport {
+    /// 往返交易中卖出是否成功
+    pub sellable: bool,
+
+    /// 实测买入税（%）
+    pub buy_tax: f64,
+
+    /// 实测卖出税（%），不可卖出时为100
+    pub sell_tax: f64,
+
+    /// 往返总损耗（%，含DEX手续费）
+    pub round_trip_loss: f64,
+
+    /// 卖出失败原因
+    pub sell_error: Option<String>,
+
+    pub simulated_at: DateTime<Utc>,
+}
+
+impl HoneypotReport {
+    /// 由模拟测量值计算税率
+    ///
+    /// - 买入税 = 实际到账代币相对报价（已含价格影响和手续费）的缺口
+    /// - 卖出税 = 往返损耗 − 买入税 − 两次DEX手续费
+    pub fn from_measurements(
+        lamports_in: u64,
+        expected_tokens: u64,
+        tokens_received: u64,
+        lamports_back: u64,
+        fee_bps: u16,
+    ) -> Self {
+        let buy_tax = if expected_tokens > 0 {
+            ((1.0 - tokens_received as f64 / expected_tokens as f64) * 100.0).clamp(0.0, 100.0)
+        } else {
+            0.0
+        };
+
+        let round_trip_loss = if lamports_in > 0 {
+            ((1.0 - lamports_back as f64 / lamports_in as f64) * 100.0).clamp(0.0, 100.0)
+        } else {
+            0.0
+        };
+
+        let dex_fees = 2.0 * fee_bps as f64 / 100.0;
+        let sell_tax = (round_trip_loss - buy_tax - dex_fees).clamp(0.0, 100.0);
+
+        Self {
+            sellable: true,
+            buy_tax,
+            sell_tax,
+            round_trip_loss,
+            sell_error: None,
+            simulated_at: Utc::now(),
+        }
+    }
+
+    /// 卖出失败
+    pub fn unsellable(buy_tax: f64, error: String) -> Self {
+        Self {
+            sellable: false,
+            buy_tax,
+            sell_tax: 100.0,
+            round_trip_loss: 100.0,
+            sell_error: Some(error),
+            simulated_at: Utc::now(),
+        }
+    }
+
+    /// 写入TokenInfo（覆盖API的税费字段）
+    pub fn apply_to(&self, token: &mut TokenInfo) {
+        token.buy_tax = self.buy_tax;
+        token.sell_tax = self.sell_tax;
+        token.sellable = Some(self.sellable);
+    }
+}
+
+/// 单次模拟的测量值
+struct SimulationOutcome {
+    error: Option<String>,
+    wallet_lamports: u64,
+    token_amount: u64,
+}
+
+/// 蜜罐检测器
+///
+/// 用小额买入+卖出往返交易调用`simulateTransaction`，测量实际税率和能否卖出。
+/// 模拟不上链，钱包只需持有足够支付探测金额的SOL。结果按mint缓存
+pub struct HoneypotSimulator {
+    rpc: Arc<RpcManager>,
+    provider: Arc<dyn SwapInstructionProvider>,
+    builder: TransactionBuilder,
+
+    /// 用于模拟的钱包（只读，不需要私钥）
+    wallet: Pubkey,
+
+    /// 探测买入金额
+    probe_lamports: u64,
+
+    /// mint -> 最近一次模拟结果
+    cache: RwLock<HashMap<Pubkey, HoneypotReport>>,
+    cache_ttl: chrono::Duration,
+}
+
+impl HoneypotSimulator {
+    pub fn new(rpc: Arc<RpcManager>, provider: Arc<dyn SwapInstructionProvider>, wallet: Pubkey) -> Self {
+        Self {
+            rpc,
+            provider,
+            // 模拟不需要优先费，避免干扰SOL余额测量
+            builder: TransactionBuilder::new().with_compute_units(600_000).with_priority_fee(0),
+            wallet,
+            probe_lamports: 10_000_000, // 0.01 SOL
+            cache: RwLock::new(HashMap::new()),
+            cache_ttl: chrono::Duration::minutes(10),
+        }
+    }
+
+    pub fn with_probe_lamports(mut self, lamports: u64) -> Self {
+        self.probe_lamports = lamports;
+        self
+    }
+
+    /// 设置结果缓存时间
+    pub fn with_cache_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// 执行往返模拟（缓存未过期时直接返回缓存结果）
+    pub async fn simulate(&self, token: &TokenInfo) -> Result<HoneypotReport> {
+        if let Some(report) = self.cached(&token.mint) {
+            return Ok(report);
+        }
+
+        let report = self.simulate_round_trip(token).await?;
+        self.store(token.mint, report.clone());
+        Ok(report)
+    }
+
+    fn cached(&self, mint: &Pubkey) -> Option<HoneypotReport> {
+        self.cache
+            .read()
+            .unwrap()
+            .get(mint)
+            .filter(|report| Utc::now() - report.simulated_at < self.cache_ttl)
+            .cloned()
+    }
+
+    fn store(&self, mint: Pubkey, report: HoneypotReport) {
+        let mut cache = self.cache.write().unwrap();
+        if cache.len() >= MAX_CACHED_REPORTS {
+            let now = Utc::now();
+            cache.retain(|_, report| now - report.simulated_at < self.cache_ttl);
+        }
+        if cache.len() >= MAX_CACHED_REPORTS {
+            // 全部未过期时淘汰最旧的结果
+            if let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, report)| report.simulated_at)
+                .map(|(mint, _)| *mint)
+            {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(mint, report);
+    }
+
+    async fn simulate_round_trip(&self, token: &TokenInfo) -> Result<HoneypotReport> {
+        let buy = self
+            .provider
+            .buy_leg(token, &self.wallet, self.probe_lamports)
+            .await?;
+
+        let token_before = self.token_balance(&buy.token_account).await?;
+
+        // 1. 只买入
+        let bought = self
+            .run_simulation(buy.instructions.clone(), &buy.token_account)
+            .await?;
+        if let Some(error) = bought.error {
+            return Err(Error::TransactionFailed(format!(
+                "Buy simulation failed for {} via {}: {}",
+                token.mint,
+                self.provider.name(),
+                error
+            )));
+        }
+
+        let tokens_received = bought.token_amount.saturating_sub(token_before);
+        if tokens_received == 0 {
+            return Err(Error::TransactionFailed(format!(
+                "Buy simulation for {} returned no tokens",
+                token.mint
+            )));
+        }
+
+        let buy_tax = HoneypotReport::from_measurements(
+            self.probe_lamports,
+            buy.expected_out,
+            tokens_received,
+            self.probe_lamports,
+            0,
+        )
+        .buy_tax;
+
+        // 2. 买入后立即卖出全部到账代币
+        let sell = self
+            .provider
+            .sell_leg(token, &self.wallet, tokens_received)
+            .await?;
+
+        let mut instructions = buy.instructions;
+        instructions.extend(sell.instructions);
+
+        let round_trip = self.run_simulation(instructions, &buy.token_account).await?;
+
+        let report = match round_trip.error {
+            Some(error) => HoneypotReport::unsellable(buy_tax, error),
+            None => HoneypotReport::from_measurements(
+                self.probe_lamports,
+                buy.expected_out,
+                tokens_received,
+                round_trip.wallet_lamports.saturating_sub(bought.wallet_lamports),
+                self.provider.fee_bps(),
+            ),
+        };
+
+        if report.sellable {
+            tracing::debug!(
+                "🍯 {} sell simulation: buy_tax {:.2}%, sell_tax {:.2}%, round trip loss {:.2}%",
+                token.mint,
+                report.buy_tax,
+                report.sell_tax,
+                report.round_trip_loss
+            );
+        } else {
+            tracing::warn!(
+                "🍯 {} cannot be sold in simulation: {}",
+                token.mint,
+                report.sell_error.as_deref().unwrap_or("unknown")
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// 模拟交易并读取钱包SOL余额和代币账户余额
+    async fn run_simulation(
+        &self,
+        instructions: Vec<Instruction>,
+        token_account: &Pubkey,
+    ) -> Result<SimulationOutcome> {
+        let transaction = self.builder.build_simulation_transaction(&self.wallet, instructions);
+        let addresses = vec![self.wallet.to_string(), token_account.to_string()];
+
+        let result = self
+            .rpc
+            .execute_with_retry(|client| {
+                let transaction = transaction.clone();
+                let addresses = addresses.clone();
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        client.simulate_transaction_with_config(
+                            &transaction,
+                            RpcSimulateTransactionConfig {
+                                sig_verify: false,
+                                replace_recent_blockhash: true,
+                                commitment: Some(CommitmentConfig::processed()),
+                                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                                    encoding: None, // 默认base64
+                                    addresses,
+                                }),
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .await
+                    .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                    .map_err(|e| Error::Rpc(format!("simulateTransaction failed: {}", e)))
+                }
+            })
+            .await?
+            .value;
+
+        if let Some(err) = result.err {
+            // 附带最后几行程序日志，便于区分转账钩子/冻结等原因
+            let logs = result.logs.unwrap_or_default();
+            let tail: Vec<&str> = logs.iter().rev().take(3).rev().map(|l| l.as_str()).collect();
+            return Ok(SimulationOutcome {
+                error: Some(format!("{:?} | {}", err, tail.join(" | "))),
+                wallet_lamports: 0,
+                token_amount: 0,
+            });
+        }
+
+        let accounts = result.accounts.unwrap_or_default();
+        let wallet_lamports = accounts
+            .first()
+            .and_then(|account| account.as_ref())
+            .map(|account| account.lamports)
+            .unwrap_or(0);
+        let token_amount = accounts
+            .get(1)
+            .and_then(|account| account.as_ref())
+            .and_then(|account| account.decode::<Account>())
+            .map(|account| token_amount(&account.data))
+            .unwrap_or(0);
+
+        Ok(SimulationOutcome {
+            error: None,
+            wallet_lamports,
+            token_amount,
+        })
+    }
+
+    /// 代币账户当前余额（不存在为0）
+    async fn token_balance(&self, token_account: &Pubkey) -> Result<u64> {
+        let address = *token_account;
+
+        let account = self
+            .rpc
+            .execute_with_retry(|client| async move {
+                tokio::task::spawn_blocking(move || {
+                    client.get_account_with_commitment(&address, CommitmentConfig::processed())
+                })
+                .await
+                .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                .map_err(|e| Error::Rpc(format!("getAccountInfo failed: {}", e)))
+            })
+            .await?
+            .value;
+
+        Ok(account.map(|account| token_amount(&account.data)).unwrap_or(0))
+    }
+}
+
+/// SPL token账户余额（偏移64）
+fn token_amount(data: &[u8]) -> u64 {
+    if data.len() < 72 {
+        return 0;
+    }
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[64..72]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use solsniper_core::LoadBalancingStrategy;
+    use solsniper_trading_engine::SwapLeg;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 报价失败的provider，记录调用次数
+    struct StubProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SwapInstructionProvider for StubProvider {
+        async fn buy_leg(&self, token: &TokenInfo, _wallet: &Pubkey, _lamports_in: u64) -> Result<SwapLeg> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(Error::NotFound(format!("No pool for {}", token.mint)))
+        }
+
+        async fn sell_leg(&self, _token: &TokenInfo, _wallet: &Pubkey, _token_amount: u64) -> Result<SwapLeg> {
+            unreachable!("sell leg is only requested after a successful buy")
+        }
+
+        fn fee_bps(&self) -> u16 {
+            25
+        }
+
+        fn name(&self) -> &str {
+            "stub"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulation_uses_provider_and_cache() {
+        let rpc = Arc::new(RpcManager::new(vec![], 30, LoadBalancingStrategy::RoundRobin));
+        let provider = Arc::new(StubProvider { calls: AtomicUsize::new(0) });
+        let simulator = HoneypotSimulator::new(rpc, provider.clone(), Pubkey::new_unique());
+        let token = TokenInfo {
+            mint: Pubkey::new_unique(),
+            ..Default::default()
+        };
+
+        // 报价失败时返回错误且不缓存
+        assert!(simulator.simulate(&token).await.is_err());
+        assert!(simulator.simulate(&token).await.is_err());
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+
+        // 缓存命中时不再请求provider
+        simulator.store(token.mint, HoneypotReport::unsellable(0.0, "frozen".to_string()));
+        let report = simulator.simulate(&token).await.unwrap();
+        assert!(!report.sellable);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+
+        // 过期后重新模拟
+        let expired = HoneypotSimulator::new(
+            Arc::new(RpcManager::new(vec![], 30, LoadBalancingStrategy::RoundRobin)),
+            provider.clone(),
+            Pubkey::new_unique(),
+        )
+        .with_cache_ttl(chrono::Duration::zero());
+        expired.store(token.mint, HoneypotReport::unsellable(0.0, "frozen".to_string()));
+        assert!(expired.simulate(&token).await.is_err());
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_clean_round_trip() {
+        // 0.01 SOL往返，0.25%手续费×2，回收0.00995 SOL
+        let report = HoneypotReport::from_measurements(10_000_000, 1_000_000, 1_000_000, 9_950_000, 25);
+
+        assert!(report.sellable);
+        assert!(report.buy_tax.abs() < 1e-9);
+        assert!(report.sell_tax.abs() < 1e-9);
+        assert!((report.round_trip_loss - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hidden_sell_tax() {
+        // 买入足额到账，但往返只回收70%
+        let report = HoneypotReport::from_measurements(10_000_000, 1_000_000, 1_000_000, 7_000_000, 25);
+
+        assert!((report.sell_tax - 29.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unsellable_report() {
+        let report = HoneypotReport::unsellable(5.0, "custom program error: 0x1".to_string());
+
+        assert!(!report.sellable);
+        assert_eq!(report.buy_tax, 5.0);
+        assert_eq!(report.sell_tax, 100.0);
+        assert!(report.sell_error.is_some());
+    }
+}
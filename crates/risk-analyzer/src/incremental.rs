@@ -0,0 +1,280 @@
+use solsniper_core::{Score, TokenInfo};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// TokenInfo以外的分析器输入的版本（对应数据变化时改变）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputVersions {
+    /// 该mint的加池/撤池历史
+    pub liquidity: u64,
+    /// 部署者的历史发币
+    pub deployer: u64,
+    /// 仿盘参照集
+    pub similarity: u64,
+}
+
+/// 各分析器输入的指纹
+///
+/// 包含对应分析器读取的TokenInfo字段和外部输入的版本，指纹不变时复用上次的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fingerprints {
+    pub contract: u64,
+    pub liquidity: u64,
+    pub holder: u64,
+    pub deployer: u64,
+    pub similarity: u64,
+}
+
+fn fingerprint(inputs: impl Serialize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_vec(&inputs).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Fingerprints {
+    pub fn of(token: &TokenInfo, versions: &InputVersions) -> Self {
+        Self {
+            contract: fingerprint((
+                token.mint_authority_revoked,
+                token.freeze_authority_revoked,
+                &token.extensions,
+                token.buy_tax,
+                token.sell_tax,
+                token.sellable,
+                token.is_verified,
+                token.metadata_mutable,
+                token.metadata_update_authority,
+                &token.metadata_uri,
+            )),
+            liquidity: fingerprint((
+                token.liquidity_sol,
+                token.liquidity_usd,
+                token.price_usd,
+                token.circulating_supply,
+                token.lp_locked,
+                token.lp_burned,
+                token.lp_unlock_at,
+                versions.liquidity,
+            )),
+            holder: fingerprint((
+                token.holders_count,
+                token.top10_ratio,
+                token.top20_ratio,
+                // 持有者增长率按整小时计算
+                token.age_hours.floor() as u64,
+            )),
+            deployer: fingerprint((token.creator, versions.deployer)),
+            similarity: fingerprint((
+                &token.name,
+                &token.symbol,
+                &token.metadata_uri,
+                token.creator,
+                versions.similarity,
+            )),
+        }
+    }
+}
+
+/// 缓存的分析结果
+#[derive(Debug, Clone)]
+pub(crate) struct Cached<T> {
+    fingerprint: u64,
+    pub value: T,
+    computed_at: Instant,
+}
+
+impl<T: Clone> Cached<T> {
+    /// 指纹未变且未过期时复用，否则运行分析
+    ///
+    /// 返回结果和是否重新运行
+    pub async fn reuse_or_run(
+        previous: Option<&Cached<T>>,
+        fingerprint: u64,
+        ttl: Duration,
+        analysis: impl Future<Output = T>,
+    ) -> (Cached<T>, bool) {
+        if let Some(previous) = previous {
+            if previous.fingerprint == fingerprint && previous.computed_at.elapsed() < ttl {
+                return (previous.clone(), false);
+            }
+        }
+
+        let cached = Cached {
+            fingerprint,
+            value: analysis.await,
+            computed_at: Instant::now(),
+        };
+        (cached, true)
+    }
+
+    /// 用分析器运行后的输入指纹替换（分析器自身改变了外部输入时）
+    pub fn settle(&mut self, fingerprint: u64) {
+        self.fingerprint = fingerprint;
+    }
+}
+
+/// 一个mint上次的分析结果
+#[derive(Debug, Clone)]
+pub(crate) struct CachedAnalysis {
+    pub contract: Cached<Score>,
+    pub liquidity: Cached<Score>,
+    pub holder: Cached<Score>,
+    pub deployer: Cached<Option<Score>>,
+    pub similarity: Cached<Option<Score>>,
+}
+
+impl CachedAnalysis {
+    /// 最近一次有分析器运行的时间
+    fn last_computed(&self) -> Instant {
+        [
+            self.contract.computed_at,
+            self.liquidity.computed_at,
+            self.holder.computed_at,
+            self.deployer.computed_at,
+            self.similarity.computed_at,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(self.contract.computed_at)
+    }
+}
+
+/// 增量评估缓存
+pub(crate) struct AnalysisCache {
+    entries: RwLock<HashMap<Pubkey, CachedAnalysis>>,
+
+    /// 缓存结果的最长复用时间（链上/外部数据源可能在TokenInfo不变时变化）
+    pub ttl: Duration,
+
+    max_entries: usize,
+}
+
+impl AnalysisCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+            max_entries: 10_000,
+        }
+    }
+
+    pub fn get(&self, mint: &Pubkey) -> Option<CachedAnalysis> {
+        self.entries.read().unwrap().get(mint).cloned()
+    }
+
+    pub fn insert(&self, mint: Pubkey, analysis: CachedAnalysis) {
+        let mut entries = self.entries.write().unwrap();
+        entries.insert(mint, analysis);
+
+        if entries.len() <= self.max_entries {
+            return;
+        }
+
+        // 超过上限时先丢弃已过期的结果
+        let ttl = self.ttl;
+        entries.retain(|_, cached| cached.last_computed().elapsed() < ttl);
+
+        // 仍超过上限时按最近运行时间淘汰最早的，多淘汰10%避免每次插入都排序
+        if entries.len() > self.max_entries {
+            let target = self.max_entries - self.max_entries / 10;
+            let mut oldest: Vec<(Instant, Pubkey)> = entries
+                .iter()
+                .map(|(mint, cached)| (cached.last_computed(), *mint))
+                .collect();
+            oldest.sort();
+
+            let excess = entries.len() - target;
+            for (_, mint) in oldest.into_iter().take(excess) {
+                entries.remove(&mint);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn remove(&self, mint: &Pubkey) {
+        self.entries.write().unwrap().remove(mint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(value: f64) -> Score {
+        Score { value, issues: vec![] }
+    }
+
+    #[tokio::test]
+    async fn test_reuse_when_fingerprint_unchanged() {
+        let ttl = Duration::from_secs(60);
+        let (first, ran) = Cached::reuse_or_run(None, 1, ttl, async { score(80.0) }).await;
+        assert!(ran);
+
+        let (second, ran) = Cached::reuse_or_run(Some(&first), 1, ttl, async { score(10.0) }).await;
+        assert!(!ran);
+        assert_eq!(second.value.value, 80.0);
+
+        let (third, ran) = Cached::reuse_or_run(Some(&second), 2, ttl, async { score(10.0) }).await;
+        assert!(ran);
+        assert_eq!(third.value.value, 10.0);
+
+        // 过期后重新运行
+        let (_, ran) = Cached::reuse_or_run(Some(&third), 2, Duration::ZERO, async { score(10.0) }).await;
+        assert!(ran);
+    }
+
+    #[test]
+    fn test_external_versions_change_fingerprints() {
+        let token = TokenInfo::default();
+        let base = Fingerprints::of(&token, &InputVersions::default());
+
+        let liquidity = Fingerprints::of(&token, &InputVersions { liquidity: 1, ..Default::default() });
+        assert_ne!(liquidity.liquidity, base.liquidity);
+        assert_eq!(liquidity.contract, base.contract);
+
+        let deployer = Fingerprints::of(&token, &InputVersions { deployer: 1, ..Default::default() });
+        assert_ne!(deployer.deployer, base.deployer);
+
+        let similarity = Fingerprints::of(&token, &InputVersions { similarity: 1, ..Default::default() });
+        assert_ne!(similarity.similarity, base.similarity);
+        assert_eq!(similarity.deployer, base.deployer);
+    }
+
+    #[tokio::test]
+    async fn test_cache_enforces_hard_cap() {
+        let ttl = Duration::from_secs(60);
+        let cache = AnalysisCache::new(ttl).with_max_entries(10);
+
+        for _ in 0..25 {
+            let (contract, _) = Cached::reuse_or_run(None, 1, ttl, async { score(80.0) }).await;
+            let (optional, _) = Cached::reuse_or_run(None, 1, ttl, async { None::<Score> }).await;
+            cache.insert(
+                Pubkey::new_unique(),
+                CachedAnalysis {
+                    contract: contract.clone(),
+                    liquidity: contract.clone(),
+                    holder: contract,
+                    deployer: optional.clone(),
+                    similarity: optional,
+                },
+            );
+            assert!(cache.len() <= 10);
+        }
+    }
+}
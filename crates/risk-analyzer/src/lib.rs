@@ -1,13 +1,40 @@
 pub mod contract_analyzer;
 pub mod liquidity_analyzer;
 pub mod holder_analyzer;
+pub mod deployer_analyzer;
+pub mod similarity_analyzer;
+pub mod holder_clusters;
+pub mod social_signals;
+pub mod sentiment_analyzer;
+pub mod honeypot;
+pub mod position_monitor;
+pub mod scoring;
+pub mod score_history;
+mod chain_history;
+mod incremental;
 
-use solsniper_core::{TokenInfo, RiskScore, Score, ScoreBreakdown, Recommendation, Result};
+use solsniper_core::{
+    TokenInfo, RiskScore, Score, ScoreBreakdown, Result, CandleBuilder, LiquidityTracker, SolPriceService,
+    TokenInfoValidator,
+};
 use solsniper_ml_model::MLEnhancedStrategy;
-use solsniper_behavior_pattern::BehaviorPatternRecognizer;
-use std::sync::Arc;
+use solsniper_data_collector::OnChainEnricher;
+use solsniper_behavior_pattern::{
+    BehaviorPatternRecognizer, PatternSet, TokenSnapshotStore, TradeSource, WalletLinks, WalletProfileSource,
+};
+use std::sync::{Arc, RwLock};
+use social_signals::{SocialSignalProvider, SocialSignals};
+use honeypot::HoneypotSimulator;
+use scoring::{ScoreExplanation, ScoringConfig};
+use score_history::{ScoreHistoryEntry, ScoreHistoryStore};
+use incremental::{AnalysisCache, Cached, CachedAnalysis, Fingerprints, InputVersions};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
 use chrono::Utc;
 
+/// 启用关联钱包检测时持有者分析结果的最长复用时间
+const HOLDER_CLUSTER_REFRESH: std::time::Duration = std::time::Duration::from_secs(60);
+
 /// 综合风险评估引擎
 pub struct RiskAssessmentEngine {
     /// 合约安全分析器
@@ -19,11 +46,59 @@ pub struct RiskAssessmentEngine {
     /// 持有者分布分析器
     holder_analyzer: holder_analyzer::HolderAnalyzer,
 
+    /// 部署者信誉分析器
+    deployer_analyzer: deployer_analyzer::DeployerAnalyzer,
+
+    /// 仿盘/相似度分析器（维护近期、热门和已rug代币的参照集）
+    similarity_analyzer: Arc<similarity_analyzer::SimilarityAnalyzer>,
+
+    /// 社交情绪分析器
+    sentiment_analyzer: sentiment_analyzer::SentimentAnalyzer,
+
+    /// 社交信号提供者（可选，未设置时使用TokenInfo中的社交字段）
+    social_provider: Option<Arc<dyn SocialSignalProvider>>,
+
+    /// 链上Mint数据（可选，覆盖API的权限、精度、供应量和Token-2022扩展）
+    onchain: Option<Arc<OnChainEnricher>>,
+
     /// ML模型（可选）
     ml_strategy: Option<Arc<MLEnhancedStrategy>>,
 
     /// 行为模式识别器
-    pattern_recognizer: Arc<BehaviorPatternRecognizer>,
+    pattern_recognizer: BehaviorPatternRecognizer,
+
+    /// 链上K线（可选，覆盖API的价格/成交量快照）
+    candles: Option<Arc<CandleBuilder>>,
+
+    /// SOL/USD参考价格（可选）
+    sol_price: Option<Arc<SolPriceService>>,
+
+    /// 蜜罐模拟器（可选，未设置时使用API提供的税费）
+    honeypot: Option<Arc<HoneypotSimulator>>,
+
+    /// 权重、调整项和推荐阈值（可运行时更新）
+    scoring: Arc<RwLock<ScoringConfig>>,
+
+    /// 上次的分析结果（输入未变化的分析器不重新运行）
+    cache: AnalysisCache,
+
+    /// 风险分历史（可选）
+    history: Option<Arc<dyn ScoreHistoryStore>>,
+}
+
+/// 带评分解释的风险评分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainedRiskScore {
+    pub score: RiskScore,
+    pub explanation: ScoreExplanation,
+    /// 本次重新运行的分析器（其余复用上次结果）
+    pub recomputed: Vec<String>,
+}
+
+impl Default for RiskAssessmentEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RiskAssessmentEngine {
@@ -32,98 +107,456 @@ impl RiskAssessmentEngine {
             contract_analyzer: contract_analyzer::ContractAnalyzer::new(),
             liquidity_analyzer: liquidity_analyzer::LiquidityAnalyzer::new(10.0, 50.0),
             holder_analyzer: holder_analyzer::HolderAnalyzer::new(0.6, 0.8),
+            deployer_analyzer: deployer_analyzer::DeployerAnalyzer::new(),
+            similarity_analyzer: Arc::new(similarity_analyzer::SimilarityAnalyzer::new()),
+            sentiment_analyzer: sentiment_analyzer::SentimentAnalyzer::default(),
+            social_provider: None,
+            onchain: None,
             ml_strategy: None,
-            pattern_recognizer: Arc::new(BehaviorPatternRecognizer::new()),
+            pattern_recognizer: BehaviorPatternRecognizer::new(),
+            candles: None,
+            sol_price: None,
+            honeypot: None,
+            scoring: Arc::new(RwLock::new(ScoringConfig::default())),
+            cache: AnalysisCache::new(std::time::Duration::from_secs(300)),
+            history: None,
+        }
+    }
+
+    /// 分析结果的最长复用时间（为0时每次都完整评估）
+    pub fn with_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.cache = AnalysisCache::new(ttl);
+        self
+    }
+
+    /// 记录每次评估的风险分
+    pub fn with_score_history(mut self, store: Arc<dyn ScoreHistoryStore>) -> Self {
+        self.history = Some(store);
+        self
+    }
+
+    /// 代币的风险分历史（按时间正序）
+    pub async fn score_history(&self, mint: &Pubkey, limit: usize) -> Result<Vec<ScoreHistoryEntry>> {
+        match &self.history {
+            Some(store) => store.history(mint, limit).await,
+            None => Ok(Vec::new()),
         }
     }
 
+    /// 丢弃mint的缓存结果，下次评估时全部重新运行
+    pub fn invalidate(&self, mint: &Pubkey) {
+        self.cache.remove(mint);
+    }
+
+    /// 使用自定义评分配置
+    pub fn with_scoring_config(self, config: ScoringConfig) -> Result<Self> {
+        self.set_scoring_config(config)?;
+        Ok(self)
+    }
+
+    /// 共享评分配置（如API进程内更新后立即生效）
+    pub fn with_scoring_handle(mut self, handle: Arc<RwLock<ScoringConfig>>) -> Self {
+        self.scoring = handle;
+        self
+    }
+
+    /// 当前评分配置
+    pub fn scoring_config(&self) -> ScoringConfig {
+        self.scoring.read().unwrap().clone()
+    }
+
+    /// 校验并替换评分配置
+    pub fn set_scoring_config(&self, config: ScoringConfig) -> Result<()> {
+        config.validate()?;
+        *self.scoring.write().unwrap() = config;
+        Ok(())
+    }
+
+    /// 使用加池/撤池事件构建的流动性历史（流动性分析和撤池模式检测）
+    pub fn with_liquidity_tracker(mut self, tracker: Arc<LiquidityTracker>) -> Self {
+        self.liquidity_analyzer = self.liquidity_analyzer.with_liquidity_tracker(tracker.clone());
+        self.pattern_recognizer = self.pattern_recognizer.with_liquidity_tracker(tracker);
+        self
+    }
+
+    /// 行为模式指标使用的代币快照存储（默认内存环形缓冲区）
+    pub fn with_snapshot_store(mut self, store: Arc<dyn TokenSnapshotStore>) -> Self {
+        self.pattern_recognizer = self.pattern_recognizer.with_snapshot_store(store);
+        self
+    }
+
+    /// 洗售/刷量检测使用的成交数据源
+    pub fn with_trade_source(mut self, source: Arc<dyn TradeSource>) -> Self {
+        self.pattern_recognizer = self.pattern_recognizer.with_trade_source(source);
+        self
+    }
+
+    /// 交易图使用的钱包关联关系（可与`HolderClusterDetector::with_wallet_links`共享）
+    pub fn with_wallet_links(mut self, links: Arc<dyn WalletLinks>) -> Self {
+        self.pattern_recognizer = self.pattern_recognizer.with_wallet_links(links);
+        self
+    }
+
+    /// 协同买入检测使用的钱包画像（资金来源、新钱包），例如`RpcHolderGraph`
+    pub fn with_wallet_profiles(mut self, source: Arc<dyn WalletProfileSource>) -> Self {
+        self.pattern_recognizer = self.pattern_recognizer.with_wallet_profiles(source);
+        self
+    }
+
+    /// 行为模式使用的模式集合（`PatternSet::from_dir`加载的模式可热更新）
+    pub fn with_patterns(mut self, patterns: PatternSet) -> Self {
+        self.pattern_recognizer = self.pattern_recognizer.with_patterns(patterns);
+        self
+    }
+
+    /// 启用持有者关联钱包检测
+    pub fn with_holder_cluster_detector(mut self, detector: Arc<holder_clusters::HolderClusterDetector>) -> Self {
+        self.holder_analyzer = self.holder_analyzer.with_cluster_detector(detector);
+        self
+    }
+
+    /// 设置部署者信誉分析器（需配置历史数据源才会生效）
+    pub fn with_deployer_analyzer(mut self, analyzer: deployer_analyzer::DeployerAnalyzer) -> Self {
+        self.deployer_analyzer = analyzer;
+        self
+    }
+
+    /// 设置仿盘分析器（共享参照集，如外部标记rug代币）
+    pub fn with_similarity_analyzer(mut self, analyzer: Arc<similarity_analyzer::SimilarityAnalyzer>) -> Self {
+        self.similarity_analyzer = analyzer;
+        self
+    }
+
+    /// 记录已确认rug的代币，供仿盘分析识别重新发币
+    pub fn record_rugged(&self, token: &TokenInfo) {
+        self.similarity_analyzer.record_rugged(token);
+    }
+
+    /// 设置社交信号提供者
+    ///
+    /// 远程提供者应包装在`CachedSocialProvider`中，避免每次评估都请求上游
+    pub fn with_social_provider(mut self, provider: Arc<dyn SocialSignalProvider>) -> Self {
+        self.social_provider = Some(provider);
+        self
+    }
+
+    /// 获取社交信号，提供者失败时回退到TokenInfo字段
+    async fn social_signals(&self, token: &TokenInfo) -> SocialSignals {
+        let Some(provider) = &self.social_provider else {
+            return SocialSignals::from_token(token);
+        };
+
+        match provider.fetch(&token.mint).await {
+            Ok(signals) => signals,
+            Err(e) => {
+                tracing::warn!(
+                    "Social signals unavailable for {} via {}: {}",
+                    token.mint,
+                    provider.name(),
+                    e
+                );
+                SocialSignals::from_token(token)
+            }
+        }
+    }
+
+    /// 评估前用链上Mint账户覆盖权限、精度、供应量和扩展字段
+    pub fn with_onchain_enricher(mut self, enricher: Arc<OnChainEnricher>) -> Self {
+        self.onchain = Some(enricher);
+        self
+    }
+
+    /// 使用链上K线数据
+    pub fn with_candles(mut self, candles: Arc<CandleBuilder>) -> Self {
+        self.candles = Some(candles);
+        self
+    }
+
+    /// 使用SOL/USD参考价格
+    pub fn with_sol_price_service(mut self, service: Arc<SolPriceService>) -> Self {
+        self.liquidity_analyzer = self.liquidity_analyzer.with_sol_price_service(Arc::clone(&service));
+        self.sol_price = Some(service);
+        self
+    }
+
+    /// 评估前模拟买入+卖出往返交易
+    pub fn with_honeypot_simulator(mut self, simulator: Arc<HoneypotSimulator>) -> Self {
+        self.honeypot = Some(simulator);
+        self
+    }
+
+    /// 用链上数据刷新代币的市场字段
+    fn refresh_market_data(&self, token: &TokenInfo) -> TokenInfo {
+        let mut token = token.clone();
+
+        let sol_usd = self
+            .sol_price
+            .as_ref()
+            .and_then(|service| service.cached())
+            .map(|quote| quote.price);
+
+        let Some(sol_usd) = sol_usd else {
+            tracing::debug!("No fresh SOL/USD reference price, keeping API market data for {}", token.mint);
+            return token;
+        };
+
+        // 覆盖前按参考价格检查API数据
+        let issues = TokenInfoValidator::detect_data_issues_with_sol_price(&token, Some(sol_usd));
+        if !issues.is_empty() {
+            tracing::warn!("⚠️ API data issues for {}: {}", token.mint, issues.join("; "));
+        }
+
+        SolPriceService::apply_to(&mut token, sol_usd);
+
+        if let Some(candles) = &self.candles {
+            candles.apply_to(&mut token, sol_usd);
+        }
+
+        token
+    }
+
+    /// 加载ML模型
+    ///
+    /// 已设置SOL/USD参考价格时一并传给特征提取，因此应在`with_sol_price_service`之后调用
     pub fn with_ml(mut self, ml_path: &str) -> Result<Self> {
-        self.ml_strategy = Some(Arc::new(MLEnhancedStrategy::new(ml_path)?));
+        let mut strategy = MLEnhancedStrategy::new(ml_path)?;
+        if let Some(service) = &self.sol_price {
+            strategy = strategy.with_sol_price_service(Arc::clone(service));
+        }
+        self.ml_strategy = Some(Arc::new(strategy));
         Ok(self)
     }
 
     /// 综合评估代币风险
     pub async fn assess(&self, token: &TokenInfo) -> Result<RiskScore> {
-        // 并行执行所有分析器
+        Ok(self.assess_explained(token).await?.score)
+    }
+
+    /// 综合评估代币风险，并返回各分量对总分的贡献
+    pub async fn assess_explained(&self, token: &TokenInfo) -> Result<ExplainedRiskScore> {
+        let mut token = self.refresh_market_data(token);
+
+        // 链上Mint数据（失败时保留API字段）与社交信号互不依赖，并发获取
+        let social_input = token.clone();
+        let (_, signals) = tokio::join!(
+            async {
+                if let Some(enricher) = &self.onchain {
+                    if let Err(e) = enricher.enrich(&mut token).await {
+                        tracing::warn!("On-chain mint data unavailable for {}: {}", token.mint, e);
+                    }
+                }
+            },
+            self.social_signals(&social_input),
+        );
+        signals.apply_to(&mut token);
+        let sentiment_score = self.sentiment_analyzer.analyze(&signals);
+
+        // 卖出模拟（实测税费覆盖API字段）
+        if let Some(simulator) = &self.honeypot {
+            match simulator.simulate(&token).await {
+                Ok(report) => report.apply_to(&mut token),
+                Err(e) => tracing::warn!("Sell simulation failed for {}: {}", token.mint, e),
+            }
+        }
+        let token = &token;
+
+        // 并行执行输入有变化的分析器，其余复用上次结果
+        let versions = InputVersions {
+            liquidity: self.liquidity_analyzer.history_version(token),
+            deployer: self.deployer_analyzer.history_version(token),
+            similarity: self.similarity_analyzer.version(),
+        };
+        let fingerprints = Fingerprints::of(token, &versions);
+        let previous = self.cache.get(&token.mint);
+        let ttl = self.cache.ttl;
+        // 关联钱包检测读取链上持有者列表，没有版本可比对，按较短间隔重新运行
+        let holder_ttl = if self.holder_analyzer.has_cluster_detector() {
+            ttl.min(HOLDER_CLUSTER_REFRESH)
+        } else {
+            ttl
+        };
         let (
-            contract_score,
-            liquidity_score,
-            holder_score,
+            (contract, contract_ran),
+            (liquidity, liquidity_ran),
+            (holder, holder_ran),
+            (mut deployer, deployer_ran),
+            (mut similarity, similarity_ran),
         ) = tokio::join!(
-            self.contract_analyzer.analyze(token),
-            self.liquidity_analyzer.analyze(token),
-            self.holder_analyzer.analyze(token),
+            Cached::reuse_or_run(
+                previous.as_ref().map(|p| &p.contract),
+                fingerprints.contract,
+                ttl,
+                self.contract_analyzer.analyze(token),
+            ),
+            Cached::reuse_or_run(
+                previous.as_ref().map(|p| &p.liquidity),
+                fingerprints.liquidity,
+                ttl,
+                self.liquidity_analyzer.analyze(token),
+            ),
+            Cached::reuse_or_run(
+                previous.as_ref().map(|p| &p.holder),
+                fingerprints.holder,
+                holder_ttl,
+                self.holder_analyzer.analyze(token),
+            ),
+            Cached::reuse_or_run(
+                previous.as_ref().map(|p| &p.deployer),
+                fingerprints.deployer,
+                ttl,
+                self.deployer_analyzer.analyze(token),
+            ),
+            Cached::reuse_or_run(
+                previous.as_ref().map(|p| &p.similarity),
+                fingerprints.similarity,
+                ttl,
+                self.similarity_analyzer.analyze(token),
+            ),
+        );
+
+        // 部署者/仿盘分析会写入当前代币的发币记录和参照，以运行后的版本作为指纹，
+        // 避免下次评估因自身的写入而重跑
+        let settled = Fingerprints::of(
+            token,
+            &InputVersions {
+                deployer: self.deployer_analyzer.history_version(token),
+                similarity: self.similarity_analyzer.version(),
+                ..versions
+            },
+        );
+        if deployer_ran {
+            deployer.settle(settled.deployer);
+        }
+        if similarity_ran {
+            similarity.settle(settled.similarity);
+        }
+
+        let recomputed: Vec<String> = [
+            ("contract", contract_ran),
+            ("liquidity", liquidity_ran),
+            ("holder", holder_ran),
+            ("deployer", deployer_ran),
+            ("similarity", similarity_ran),
+        ]
+        .iter()
+        .filter(|(_, ran)| *ran)
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+        let contract_score = contract.value.clone();
+        let liquidity_score = liquidity.value.clone();
+        let holder_score = holder.value.clone();
+        let deployer_score = deployer.value.clone();
+        let similarity_score = similarity.value.clone();
+        self.cache.insert(
+            token.mint,
+            CachedAnalysis {
+                contract,
+                liquidity,
+                holder,
+                deployer,
+                similarity,
+            },
         );
 
         // ML预测
+        let scoring = self.scoring_config();
         let ml_adjustment = if let Some(ml) = &self.ml_strategy {
             let prediction = ml.predict_outcome(token).await?;
             // 如果ML预测是Rug,降低总分
             if prediction.is_rug {
-                -20.0 * prediction.confidence
+                -scoring.ml_rug_penalty * prediction.confidence
             } else {
-                5.0 * prediction.confidence
+                scoring.ml_safe_bonus * prediction.confidence
             }
         } else {
             0.0
         };
 
         // 行为模式检测
-        let pattern_matches = self.pattern_recognizer.match_patterns(token).await?;
+        let behavior = self.pattern_recognizer.analyze(token).await?;
+        let pattern_matches = &behavior.matches;
         let pattern_penalty = pattern_matches.iter()
             .filter(|m| matches!(m.pattern.risk_level, solsniper_core::RiskLevel::High | solsniper_core::RiskLevel::Critical))
-            .map(|m| m.confidence * 15.0)
+            .map(|m| m.confidence * scoring.pattern_penalty)
             .sum::<f64>();
 
-        // 加权计算总分
-        let base_score = contract_score.value * 0.35
-            + liquidity_score.value * 0.30
-            + holder_score.value * 0.25
-            + 50.0 * 0.10; // 社交情绪基准分
-
-        let total = (base_score + ml_adjustment - pattern_penalty)
-            .max(0.0)
-            .min(100.0);
-
-        // 生成推荐
-        let recommendation = if total >= 85.0 {
-            Recommendation::StrongBuy
-        } else if total >= 75.0 {
-            Recommendation::Buy
-        } else if total >= 60.0 {
-            Recommendation::Hold
-        } else if total >= 40.0 {
-            Recommendation::Avoid
-        } else {
-            Recommendation::StrongAvoid
+        let deployer_known = deployer_score.is_some();
+        let similarity_known = similarity_score.is_some();
+        let breakdown = ScoreBreakdown {
+            contract: contract_score,
+            liquidity: liquidity_score,
+            holder: holder_score,
+            sentiment: sentiment_score,
+            similarity: similarity_score.unwrap_or_else(|| Score {
+                value: 50.0,
+                issues: vec!["ℹ️ 暂无可比对的参照代币".to_string()],
+            }),
+            behavior: Score {
+                value: 100.0 - pattern_penalty,
+                issues: pattern_matches.iter()
+                    .map(|m| format!("{} ({:.1}%)", m.pattern.name, m.confidence * 100.0))
+                    .chain(behavior.coordinated_buys.iter().filter_map(|report| {
+                        let sniper = report.sniper_supply_share?;
+                        let bundle = report.bundle_supply_share.unwrap_or(0.0);
+                        (sniper > 0.0 || bundle > 0.0).then(|| format!(
+                            "ℹ️ 狙击买入占供应量 {:.1}%，捆绑买入占 {:.1}%（{}个协同钱包）",
+                            sniper * 100.0,
+                            bundle * 100.0,
+                            report.coordinated_wallets()
+                        ))
+                    }))
+                    .chain(behavior.trade_graph.iter().filter(|report| report.suspicious_volume_sol > 0.0).map(|report| {
+                        format!("ℹ️ 近1小时可疑成交量占比 {:.1}%", report.suspicious_ratio() * 100.0)
+                    }))
+                    .collect(),
+            },
+            deployer: deployer_score.unwrap_or_else(|| Score {
+                value: 50.0,
+                issues: vec!["ℹ️ 部署者历史未知".to_string()],
+            }),
         };
 
+        // 加权计算总分（部署者历史未知、无仿盘参照时不参与加权）
+        let explanation = scoring.explain(
+            &breakdown,
+            deployer_known,
+            similarity_known,
+            ml_adjustment,
+            pattern_penalty,
+        );
+
         // 计算置信度
-        let confidence = self.calculate_confidence(token, total);
-
-        Ok(RiskScore {
-            total,
-            breakdown: ScoreBreakdown {
-                contract: contract_score,
-                liquidity: liquidity_score,
-                holder: holder_score,
-                sentiment: Score { value: 50.0, issues: vec![] },
-                similarity: Score { value: 50.0, issues: vec![] },
-                behavior: Score {
-                    value: 100.0 - pattern_penalty,
-                    issues: pattern_matches.iter()
-                        .map(|m| format!("{} ({:.1}%)", m.pattern.name, m.confidence * 100.0))
-                        .collect(),
-                },
-            },
+        let confidence = self.calculate_confidence(token, explanation.total);
+
+        let score = RiskScore {
+            total: explanation.total,
+            breakdown,
             confidence,
-            recommendation,
+            recommendation: explanation.recommendation.clone(),
             timestamp: Utc::now(),
+        };
+
+        if let Some(store) = &self.history {
+            let entry = ScoreHistoryEntry {
+                mint: token.mint,
+                score: score.clone(),
+                recomputed: recomputed.clone(),
+            };
+            if let Err(e) = store.record(&entry).await {
+                tracing::warn!("Failed to record score history for {} via {}: {}", token.mint, store.name(), e);
+            }
+        }
+
+        Ok(ExplainedRiskScore {
+            score,
+            explanation,
+            recomputed,
         })
     }
 
     fn calculate_confidence(&self, token: &TokenInfo, score: f64) -> f64 {
-        let mut confidence = 0.5; // 基准50%
+        let mut confidence: f64 = 0.5; // 基准50%
 
         // 数据完整性
         if token.holders_count > 100 {
@@ -137,7 +570,7 @@ impl RiskAssessmentEngine {
         }
 
         // 评分极端性
-        if score > 90.0 || score < 20.0 {
+        if !(20.0..=90.0).contains(&score) {
             confidence += 0.15;
         }
 
@@ -153,14 +586,10 @@ impl RiskAssessmentEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use solana_sdk::pubkey::Pubkey;
-    use chrono::Utc;
+    use score_history::InMemoryScoreHistory;
 
-    #[tokio::test]
-    async fn test_risk_assessment() {
-        let engine = RiskAssessmentEngine::new();
-
-        let token = TokenInfo {
+    fn test_token() -> TokenInfo {
+        TokenInfo {
             mint: Pubkey::new_unique(),
             symbol: "TEST".to_string(),
             name: "Test Token".to_string(),
@@ -177,8 +606,6 @@ mod tests {
             top50_ratio: 0.75,
             mint_authority_revoked: true,
             freeze_authority_revoked: true,
-            buy_tax: 0.0,
-            sell_tax: 0.0,
             created_at: Utc::now(),
             age_minutes: 30,
             age_hours: 0.5,
@@ -197,10 +624,16 @@ mod tests {
             telegram_members: 200,
             discord_members: Some(100),
             sentiment_score: 0.75,
-            is_verified: false,
             pool_address: Some(Pubkey::new_unique()),
             dex: "Raydium".to_string(),
-        };
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_risk_assessment() {
+        let engine = RiskAssessmentEngine::new();
+        let token = test_token();
 
         let risk_score = engine.assess(&token).await.unwrap();
 
@@ -211,4 +644,51 @@ mod tests {
         assert!(risk_score.total >= 0.0 && risk_score.total <= 100.0);
         assert!(risk_score.confidence >= 0.0 && risk_score.confidence <= 1.0);
     }
+
+    #[tokio::test]
+    async fn test_incremental_reassessment_records_history() {
+        let engine = RiskAssessmentEngine::new()
+            .with_score_history(Arc::new(InMemoryScoreHistory::default()));
+        let mut token = test_token();
+
+        let first = engine.assess_explained(&token).await.unwrap();
+        assert_eq!(first.recomputed.len(), 5);
+
+        // 输入未变化时复用全部结果
+        let second = engine.assess_explained(&token).await.unwrap();
+        assert!(second.recomputed.is_empty());
+
+        // 只有流动性变化时只重新运行流动性分析
+        token.liquidity_sol = 5.0;
+        let third = engine.assess_explained(&token).await.unwrap();
+        assert_eq!(third.recomputed, vec!["liquidity".to_string()]);
+
+        let entries = engine.score_history(&token.mint, 10).await.unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].recomputed, vec!["liquidity".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_reference_changes_rerun_similarity() {
+        let engine = RiskAssessmentEngine::new();
+        let mut token = test_token();
+        token.age_minutes = 120;
+        token.age_hours = 2.0;
+
+        engine.assess_explained(&token).await.unwrap();
+
+        // 自身加入参照集不会导致下次重跑
+        let second = engine.assess_explained(&token).await.unwrap();
+        assert!(second.recomputed.is_empty());
+
+        // 其他代币加入参照集后重新比对
+        let mut other = test_token();
+        other.name = "Other Coin".to_string();
+        other.symbol = "OTHR".to_string();
+        other.age_minutes = 120;
+        engine.assess(&other).await.unwrap();
+
+        let third = engine.assess_explained(&token).await.unwrap();
+        assert_eq!(third.recomputed, vec!["similarity".to_string()]);
+    }
 }
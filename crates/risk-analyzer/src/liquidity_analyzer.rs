@@ -1,9 +1,17 @@
-use solsniper_core::{TokenInfo, Score};
+use solsniper_core::{DrainKind, LiquidityStats, LiquidityTracker, SolPriceService, TokenInfo, Score};
+use chrono::Duration;
+use std::sync::Arc;
 
 /// 流动性风险分析器
 pub struct LiquidityAnalyzer {
     min_liquidity_sol: f64,
     ideal_liquidity_sol: f64,
+
+    /// 流动性历史（可选，未设置时只看当前快照）
+    tracker: Option<Arc<LiquidityTracker>>,
+
+    /// SOL/USD参考价格（用于由SOL流动性换算USD）
+    sol_price: Option<Arc<SolPriceService>>,
 }
 
 impl LiquidityAnalyzer {
@@ -11,9 +19,31 @@ impl LiquidityAnalyzer {
         Self {
             min_liquidity_sol,
             ideal_liquidity_sol,
+            tracker: None,
+            sol_price: None,
         }
     }
 
+    /// 使用SOL/USD参考价格
+    pub fn with_sol_price_service(mut self, service: Arc<SolPriceService>) -> Self {
+        self.sol_price = Some(service);
+        self
+    }
+
+    /// 使用加池/撤池事件构建的流动性历史
+    pub fn with_liquidity_tracker(mut self, tracker: Arc<LiquidityTracker>) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
+    /// 流动性历史的版本（未设置历史时为0），用于增量评估判断输入是否变化
+    pub fn history_version(&self, token: &TokenInfo) -> u64 {
+        self.tracker
+            .as_ref()
+            .map(|tracker| tracker.version(&token.mint))
+            .unwrap_or(0)
+    }
+
     pub async fn analyze(&self, token: &TokenInfo) -> Score {
         let mut score = 100.0;
         let mut issues = Vec::new();
@@ -46,11 +76,23 @@ impl LiquidityAnalyzer {
             issues.push("✅ LP已锁定".to_string());
         }
 
-        // 流动性与市值比例
-        let liquidity_ratio = token.liquidity_usd / (token.price_usd * token.circulating_supply as f64);
-        if liquidity_ratio < 0.05 {
-            score -= 20.0;
-            issues.push("⚠️ 流动性占比过低（<5%）".to_string());
+        // 流动性与市值比例（链上SOL流动性按参考价格换算，没有可用报价时跳过）
+        let sol_usd = self.sol_price.as_ref().and_then(|service| service.cached()).map(|q| q.price);
+        let market_cap_usd = token.price_usd * token.circulating_supply as f64;
+        if let Some(sol_usd) = sol_usd.filter(|_| market_cap_usd > 0.0) {
+            let liquidity_ratio = token.liquidity_sol * sol_usd / market_cap_usd;
+            if liquidity_ratio < 0.05 {
+                score -= 20.0;
+                issues.push("⚠️ 流动性占比过低（<5%）".to_string());
+            }
+        }
+
+        // 流动性历史
+        if let Some(tracker) = &self.tracker {
+            if let Some(stats) = tracker.stats(&token.mint, Duration::hours(1)) {
+                let drain = stats.drain(tracker.thresholds());
+                score -= Self::history_penalty(&stats, drain, &mut issues);
+            }
         }
 
         Score {
@@ -58,4 +100,45 @@ impl LiquidityAnalyzer {
             issues,
         }
     }
+
+    /// 根据流动性历史计算扣分并写入问题
+    pub fn history_penalty(stats: &LiquidityStats, drain: Option<DrainKind>, issues: &mut Vec<String>) -> f64 {
+        let mut penalty = 0.0;
+
+        match drain {
+            Some(DrainKind::Fast) => {
+                penalty += 50.0;
+                issues.push(format!(
+                    "🚨 流动性快速抽离: 最大单笔撤出 {:.2} SOL（{:.0}%），自峰值下降 {:.0}%",
+                    stats.largest_removal_sol, stats.largest_removal_pct, stats.drop_from_peak_pct
+                ));
+            }
+            Some(DrainKind::Slow) => {
+                penalty += 35.0;
+                issues.push(format!(
+                    "🚨 流动性缓慢抽离: {}次撤池，自峰值 {:.2} SOL 下降 {:.0}%",
+                    stats.removal_count, stats.peak_sol, stats.drop_from_peak_pct
+                ));
+            }
+            None => {
+                if stats.drop_from_initial_pct >= 30.0 {
+                    penalty += 15.0;
+                    issues.push(format!(
+                        "⚠️ 流动性较初始下降 {:.0}%（初始 {:.2} SOL）",
+                        stats.drop_from_initial_pct, stats.initial_sol
+                    ));
+                }
+            }
+        }
+
+        if stats.withdrawal_velocity >= 1.0 {
+            penalty += 10.0;
+            issues.push(format!(
+                "⚠️ 近1小时撤池速度 {:.2} SOL/分钟",
+                stats.withdrawal_velocity
+            ));
+        }
+
+        penalty
+    }
 }
@@ -0,0 +1,747 @@
+use crate::RiskAssessmentEngine;
+use solsniper_core::{AlertType, Event, LiquidityTracker, Result, RiskScore, TokenInfo};
+use solsniper_trading_engine::{ExecutionOptions, MevPriority, TradingEngine};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// 持仓监控的输入
+#[derive(Debug, Clone)]
+pub enum MonitorUpdate {
+    /// 池子/代币账户变化后刷新的代币快照
+    Token(Box<TokenInfo>),
+    /// 链上解析到的卖出（用于识别部署者抛售）
+    Sell {
+        mint: Pubkey,
+        seller: Pubkey,
+        /// 卖出数量（原始单位，即链上数量，含`decimals`位小数）
+        token_amount: u64,
+        signature: String,
+    },
+}
+
+/// 告警阈值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonitorThresholds {
+    /// 流动性较买入时下降（%）超过该值告警
+    pub liquidity_drop_pct: f64,
+    /// 流动性较买入时下降（%）超过该值视为rug
+    pub rug_liquidity_drop_pct: f64,
+    /// 风险分较买入时下降超过该值告警
+    pub score_drop: f64,
+    /// 部署者单笔卖出占流通量（%）超过该值告警
+    pub creator_sell_pct: f64,
+    /// 部署者单笔卖出占流通量（%）超过该值视为rug
+    pub creator_dump_pct: f64,
+    /// 卖出税较买入时提高（百分点）超过该值告警
+    pub sell_tax_increase: f64,
+    /// 1小时跌幅（%）超过该值告警
+    pub price_crash_pct: f64,
+}
+
+impl Default for MonitorThresholds {
+    fn default() -> Self {
+        Self {
+            liquidity_drop_pct: 30.0,
+            rug_liquidity_drop_pct: 80.0,
+            score_drop: 20.0,
+            creator_sell_pct: 1.0,
+            creator_dump_pct: 5.0,
+            sell_tax_increase: 5.0,
+            price_crash_pct: 50.0,
+        }
+    }
+}
+
+/// 自动应对策略：满足条件时通过`TradingEngine::execute_sell`紧急清仓
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoExitPolicy {
+    pub enabled: bool,
+    /// 触发清仓的告警类型
+    pub exit_on: Vec<AlertType>,
+    /// 重新评估的风险分低于该值时清仓
+    pub exit_below_score: Option<f64>,
+    /// 紧急卖出的最大滑点（basis points）
+    pub max_slippage_bps: u16,
+    pub use_jito: bool,
+}
+
+impl Default for AutoExitPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exit_on: vec![AlertType::RugPullDetected],
+            exit_below_score: None,
+            max_slippage_bps: 1500, // 15%
+            use_jito: true,
+        }
+    }
+}
+
+impl AutoExitPolicy {
+    pub fn should_exit(&self, alerts: &[MonitorAlert], score: Option<f64>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let alerted = alerts.iter().any(|alert| self.exit_on.contains(&alert.alert_type));
+        let below_score = matches!(
+            (self.exit_below_score, score),
+            (Some(threshold), Some(score)) if score < threshold
+        );
+
+        alerted || below_score
+    }
+
+    /// 紧急卖出的执行选项
+    pub fn execution_options(&self) -> ExecutionOptions {
+        ExecutionOptions {
+            use_jito: self.use_jito,
+            max_slippage_bps: self.max_slippage_bps,
+            mev_priority: MevPriority::Critical,
+            ..ExecutionOptions::default()
+        }
+    }
+}
+
+/// 持仓告警
+#[derive(Debug, Clone)]
+pub struct MonitorAlert {
+    /// 去重键（同一持仓的同一告警只发出一次）
+    pub key: String,
+    pub alert_type: AlertType,
+    pub message: String,
+}
+
+impl MonitorAlert {
+    fn new(key: impl Into<String>, alert_type: AlertType, message: String) -> Self {
+        Self {
+            key: key.into(),
+            alert_type,
+            message,
+        }
+    }
+
+    pub fn to_event(&self, token: Pubkey) -> Event {
+        Event::RiskAlert {
+            token,
+            alert_type: self.alert_type,
+            message: self.message.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// 被监控的持仓
+#[derive(Debug, Clone)]
+struct WatchedPosition {
+    /// 买入时的代币快照
+    entry: TokenInfo,
+    latest: TokenInfo,
+    amount_tokens: u64,
+    baseline_score: f64,
+    last_assessed: Instant,
+    raised: HashSet<String>,
+    /// 清仓失败待重试（告警已去重，之后每次更新都会重试）
+    exit_pending: bool,
+}
+
+/// 持仓风险监控
+///
+/// 在池子/账户更新时重新评估每个持仓代币，发现撤池、部署者抛售、权限变更等情况时
+/// 发出`Event::RiskAlert`，并可按策略自动清仓
+pub struct PositionMonitor {
+    engine: Arc<RiskAssessmentEngine>,
+
+    positions: RwLock<HashMap<Pubkey, WatchedPosition>>,
+
+    thresholds: MonitorThresholds,
+
+    /// 加池/撤池历史（可选，用于识别逐步抽池）
+    liquidity: Option<Arc<LiquidityTracker>>,
+
+    /// 紧急清仓（可选）
+    trading: Option<Arc<TradingEngine>>,
+    policy: AutoExitPolicy,
+
+    /// 两次完整重新评估的最小间隔（有新告警时立即评估）
+    reassess_interval: Duration,
+
+    event_sender: mpsc::UnboundedSender<Event>,
+    event_receiver: Option<mpsc::UnboundedReceiver<Event>>,
+}
+
+impl PositionMonitor {
+    pub fn new(engine: Arc<RiskAssessmentEngine>) -> Self {
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+
+        Self {
+            engine,
+            positions: RwLock::new(HashMap::new()),
+            thresholds: MonitorThresholds::default(),
+            liquidity: None,
+            trading: None,
+            policy: AutoExitPolicy::default(),
+            reassess_interval: Duration::from_secs(30),
+            event_sender,
+            event_receiver: Some(event_receiver),
+        }
+    }
+
+    pub fn with_thresholds(mut self, thresholds: MonitorThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    pub fn with_liquidity_tracker(mut self, tracker: Arc<LiquidityTracker>) -> Self {
+        self.liquidity = Some(tracker);
+        self
+    }
+
+    /// 启用自动清仓
+    pub fn with_auto_exit(mut self, trading: Arc<TradingEngine>, policy: AutoExitPolicy) -> Self {
+        self.trading = Some(trading);
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_reassess_interval(mut self, interval: Duration) -> Self {
+        self.reassess_interval = interval;
+        self
+    }
+
+    /// 获取事件接收器（RiskAlert和紧急清仓的TradeExecuted）
+    pub fn take_event_receiver(&mut self) -> Option<mpsc::UnboundedReceiver<Event>> {
+        self.event_receiver.take()
+    }
+
+    /// 开始监控持仓
+    pub fn watch(&self, token: TokenInfo, amount_tokens: u64, baseline: &RiskScore) {
+        let position = WatchedPosition {
+            entry: token.clone(),
+            latest: token.clone(),
+            amount_tokens,
+            baseline_score: baseline.total,
+            last_assessed: Instant::now(),
+            raised: HashSet::new(),
+            exit_pending: false,
+        };
+        self.positions.write().unwrap().insert(token.mint, position);
+    }
+
+    /// 更新持仓数量（部分卖出后）
+    pub fn update_amount(&self, mint: &Pubkey, amount_tokens: u64) {
+        if let Some(position) = self.positions.write().unwrap().get_mut(mint) {
+            position.amount_tokens = amount_tokens;
+        }
+    }
+
+    /// 停止监控（已清仓）
+    pub fn unwatch(&self, mint: &Pubkey) -> bool {
+        self.positions.write().unwrap().remove(mint).is_some()
+    }
+
+    pub fn watched(&self) -> Vec<Pubkey> {
+        self.positions.read().unwrap().keys().copied().collect()
+    }
+
+    /// 持仓代币的最新快照（未监控时返回None）
+    pub fn latest(&self, mint: &Pubkey) -> Option<TokenInfo> {
+        self.positions.read().unwrap().get(mint).map(|position| position.latest.clone())
+    }
+
+    /// 处理一次更新，返回新发出的告警
+    pub async fn handle(&self, update: MonitorUpdate) -> Result<Vec<MonitorAlert>> {
+        match update {
+            MonitorUpdate::Token(token) => self.handle_token(*token).await,
+            MonitorUpdate::Sell { mint, seller, token_amount, signature } => {
+                let Some(position) = self.position(&mint) else {
+                    return Ok(Vec::new());
+                };
+                let alerts: Vec<MonitorAlert> = Self::creator_sell(
+                    &position.latest,
+                    &seller,
+                    token_amount,
+                    &signature,
+                    &self.thresholds,
+                )
+                .into_iter()
+                .collect();
+
+                self.raise(&mint, alerts, None).await
+            }
+        }
+    }
+
+    async fn handle_token(&self, token: TokenInfo) -> Result<Vec<MonitorAlert>> {
+        let mint = token.mint;
+        let Some(position) = self.position(&mint) else {
+            return Ok(Vec::new());
+        };
+
+        let mut alerts = Self::detect_changes(&position.entry, &token, &self.thresholds);
+
+        if let Some(tracker) = &self.liquidity {
+            tracker.observe(&mint, token.liquidity_sol, Utc::now());
+            if let Some(drain) = tracker.drain(&mint) {
+                alerts.push(MonitorAlert::new(
+                    "lp_drain",
+                    AlertType::RugPullDetected,
+                    format!("🚨 检测到撤池（{:?}）", drain),
+                ));
+            }
+        }
+
+        // 有新告警或距上次评估超过间隔时完整重新评估
+        let has_new = alerts.iter().any(|alert| !position.raised.contains(&alert.key));
+        let due = position.last_assessed.elapsed() >= self.reassess_interval;
+
+        let mut score = None;
+        if has_new || due {
+            match self.engine.assess(&token).await {
+                Ok(risk) => {
+                    let drop = position.baseline_score - risk.total;
+                    if drop >= self.thresholds.score_drop {
+                        alerts.push(MonitorAlert::new(
+                            "score_drop",
+                            AlertType::SuspiciousActivity,
+                            format!(
+                                "⚠️ 风险分较买入时下降{:.1}（{:.1} → {:.1}）: {}",
+                                drop,
+                                position.baseline_score,
+                                risk.total,
+                                risk.breakdown.contract.issues.iter()
+                                    .chain(risk.breakdown.liquidity.issues.iter())
+                                    .chain(risk.breakdown.holder.issues.iter())
+                                    .take(3)
+                                    .cloned()
+                                    .collect::<Vec<_>>()
+                                    .join("；")
+                            ),
+                        ));
+                    }
+                    score = Some(risk.total);
+                }
+                Err(e) => tracing::warn!("Re-assessment failed for {}: {}", mint, e),
+            }
+        }
+
+        if let Some(position) = self.positions.write().unwrap().get_mut(&mint) {
+            position.latest = token;
+            if score.is_some() {
+                position.last_assessed = Instant::now();
+            }
+        }
+
+        self.raise(&mint, alerts, score).await
+    }
+
+    /// 去重后发出告警，并按策略清仓（上次清仓失败时重试）
+    async fn raise(
+        &self,
+        mint: &Pubkey,
+        alerts: Vec<MonitorAlert>,
+        score: Option<f64>,
+    ) -> Result<Vec<MonitorAlert>> {
+        let (fresh, exit_pending): (Vec<MonitorAlert>, bool) = {
+            let mut positions = self.positions.write().unwrap();
+            let Some(position) = positions.get_mut(mint) else {
+                return Ok(Vec::new());
+            };
+            let fresh = alerts
+                .into_iter()
+                .filter(|alert| position.raised.insert(alert.key.clone()))
+                .collect();
+            (fresh, position.exit_pending)
+        };
+
+        for alert in &fresh {
+            tracing::warn!("{} {}", mint, alert.message);
+            let _ = self.event_sender.send(alert.to_event(*mint));
+        }
+
+        if fresh.iter().any(|alert| alert.alert_type == AlertType::RugPullDetected) {
+            if let Some(position) = self.position(mint) {
+                self.engine.record_rugged(&position.latest);
+            }
+        }
+
+        if exit_pending || self.policy.should_exit(&fresh, score) {
+            self.exit(mint).await?;
+        }
+
+        Ok(fresh)
+    }
+
+    /// 紧急清仓，失败时标记为待重试
+    async fn exit(&self, mint: &Pubkey) -> Result<()> {
+        let Some(trading) = &self.trading else {
+            return Ok(());
+        };
+        let Some(position) = self.position(mint) else {
+            return Ok(());
+        };
+
+        tracing::warn!("🚨 Emergency exit: {} tokens of {}", position.amount_tokens, mint);
+        let result = match trading
+            .execute_sell(
+                &position.latest,
+                position.amount_tokens,
+                Some(self.policy.execution_options()),
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                self.set_exit_pending(mint, true);
+                return Err(e);
+            }
+        };
+
+        let _ = self.event_sender.send(Event::TradeExecuted {
+            signature: result.signature.to_string(),
+            token: *mint,
+            amount: position.amount_tokens as f64,
+            success: result.success,
+            timestamp: Utc::now(),
+        });
+
+        if result.success {
+            self.unwatch(mint);
+        } else {
+            tracing::warn!("Emergency exit for {} failed, will retry on next update", mint);
+            self.set_exit_pending(mint, true);
+        }
+
+        Ok(())
+    }
+
+    fn set_exit_pending(&self, mint: &Pubkey, pending: bool) {
+        if let Some(position) = self.positions.write().unwrap().get_mut(mint) {
+            position.exit_pending = pending;
+        }
+    }
+
+    fn position(&self, mint: &Pubkey) -> Option<WatchedPosition> {
+        self.positions.read().unwrap().get(mint).cloned()
+    }
+
+    /// 比较买入时与当前的代币快照
+    pub fn detect_changes(
+        entry: &TokenInfo,
+        current: &TokenInfo,
+        thresholds: &MonitorThresholds,
+    ) -> Vec<MonitorAlert> {
+        let mut alerts = Vec::new();
+
+        if entry.liquidity_sol > 0.0 {
+            let drop_pct = (entry.liquidity_sol - current.liquidity_sol) / entry.liquidity_sol * 100.0;
+            let message = format!(
+                "流动性较买入时下降{:.0}%（{:.2} → {:.2} SOL）",
+                drop_pct, entry.liquidity_sol, current.liquidity_sol
+            );
+            if drop_pct >= thresholds.rug_liquidity_drop_pct {
+                alerts.push(MonitorAlert::new("lp_rug", AlertType::RugPullDetected, format!("🚨 {}", message)));
+            } else if drop_pct >= thresholds.liquidity_drop_pct {
+                alerts.push(MonitorAlert::new("lp_drop", AlertType::LiquidityDrop, format!("⚠️ {}", message)));
+            }
+        }
+
+        if entry.lp_locked && !current.lp_locked && !current.lp_burned {
+            alerts.push(MonitorAlert::new(
+                "lp_unlocked",
+                AlertType::LiquidityDrop,
+                "⚠️ LP锁仓已解除".to_string(),
+            ));
+        }
+
+        if entry.mint_authority_revoked && !current.mint_authority_revoked {
+            alerts.push(MonitorAlert::new(
+                "mint_authority",
+                AlertType::SuspiciousActivity,
+                "🚨 Mint权限被重新设置（可增发）".to_string(),
+            ));
+        }
+
+        if entry.freeze_authority_revoked && !current.freeze_authority_revoked {
+            alerts.push(MonitorAlert::new(
+                "freeze_authority",
+                AlertType::SuspiciousActivity,
+                "🚨 Freeze权限被重新设置（可冻结账户）".to_string(),
+            ));
+        }
+
+        if entry.metadata_mutable == Some(false) && current.metadata_mutable == Some(true) {
+            alerts.push(MonitorAlert::new(
+                "metadata_mutable",
+                AlertType::SuspiciousActivity,
+                "⚠️ 元数据变为可修改".to_string(),
+            ));
+        }
+
+        if let (Some(before), Some(after)) = (entry.metadata_update_authority, current.metadata_update_authority) {
+            if before != after {
+                alerts.push(MonitorAlert::new(
+                    "update_authority",
+                    AlertType::SuspiciousActivity,
+                    format!("⚠️ 元数据更新权限变更: {} → {}", before, after),
+                ));
+            }
+        }
+
+        if current.sell_tax - entry.sell_tax >= thresholds.sell_tax_increase {
+            alerts.push(MonitorAlert::new(
+                "sell_tax",
+                AlertType::SuspiciousActivity,
+                format!("🚨 卖出税从{:.1}%提高到{:.1}%", entry.sell_tax, current.sell_tax),
+            ));
+        }
+
+        if current.sellable == Some(false) {
+            alerts.push(MonitorAlert::new(
+                "unsellable",
+                AlertType::RugPullDetected,
+                "🚨 模拟卖出失败（蜜罐）".to_string(),
+            ));
+        }
+
+        if current.price_change_1h <= -thresholds.price_crash_pct {
+            alerts.push(MonitorAlert::new(
+                "price_crash",
+                AlertType::PriceManipulation,
+                format!("⚠️ 1小时价格下跌{:.0}%", -current.price_change_1h),
+            ));
+        }
+
+        alerts
+    }
+
+    /// 识别部署者卖出
+    ///
+    /// `token_amount`为原始单位，`TokenInfo::circulating_supply`为UI单位（整币数），
+    /// 按`decimals`换算后再计算占比
+    pub fn creator_sell(
+        token: &TokenInfo,
+        seller: &Pubkey,
+        token_amount: u64,
+        signature: &str,
+        thresholds: &MonitorThresholds,
+    ) -> Option<MonitorAlert> {
+        if token.creator.as_ref() != Some(seller) || token.circulating_supply == 0 {
+            return None;
+        }
+
+        let ui_amount = token_amount as f64 / 10f64.powi(token.decimals as i32);
+        let pct = ui_amount / token.circulating_supply as f64 * 100.0;
+        let key = format!("creator_sell:{}", signature);
+        if pct >= thresholds.creator_dump_pct {
+            Some(MonitorAlert::new(
+                key,
+                AlertType::RugPullDetected,
+                format!("🚨 部署者抛售{:.1}%流通量（{}）", pct, signature),
+            ))
+        } else if pct >= thresholds.creator_sell_pct {
+            Some(MonitorAlert::new(
+                key,
+                AlertType::SuspiciousActivity,
+                format!("⚠️ 部署者卖出{:.1}%流通量（{}）", pct, signature),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// 启动监控任务，消费池子/账户更新
+    pub fn spawn(self: Arc<Self>, mut updates: mpsc::UnboundedReceiver<MonitorUpdate>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(update) = updates.recv().await {
+                if let Err(e) = self.handle(update).await {
+                    tracing::error!("Position monitor update failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solsniper_core::{Recommendation, Score, ScoreBreakdown};
+    use crate::similarity_analyzer::{ReferenceKind, ReferenceToken, SimilarityAnalyzer};
+
+    fn token() -> TokenInfo {
+        TokenInfo {
+            mint: Pubkey::new_unique(),
+            symbol: "HELD".to_string(),
+            name: "Held Token".to_string(),
+            decimals: 6,
+            liquidity_sol: 100.0,
+            liquidity_usd: 15000.0,
+            lp_locked: true,
+            total_supply: 1_000_000_000,
+            circulating_supply: 1_000_000_000,
+            holders_count: 800,
+            top10_ratio: 0.3,
+            top20_ratio: 0.45,
+            top50_ratio: 0.6,
+            mint_authority_revoked: true,
+            freeze_authority_revoked: true,
+            created_at: Utc::now(),
+            age_minutes: 120,
+            age_hours: 2.0,
+            txns_1h_total: 300,
+            txns_1h_buys: 180,
+            txns_1h_sells: 120,
+            volume_1h: 20000.0,
+            volume_6h: 60000.0,
+            volume_24h: 90000.0,
+            price_usd: 0.0001,
+            price_change_1h: 5.0,
+            price_change_6h: 20.0,
+            price_change_24h: 40.0,
+            volatility_1h: 0.1,
+            twitter_mentions: 100,
+            telegram_members: 500,
+            sentiment_score: 0.6,
+            metadata_mutable: Some(false),
+            pool_address: Some(Pubkey::new_unique()),
+            dex: "Raydium".to_string(),
+            creator: Some(Pubkey::new_unique()),
+            ..Default::default()
+        }
+    }
+
+    fn baseline(total: f64) -> RiskScore {
+        let score = |value| Score { value, issues: vec![] };
+        RiskScore {
+            total,
+            breakdown: ScoreBreakdown {
+                contract: score(total),
+                liquidity: score(total),
+                holder: score(total),
+                sentiment: score(total),
+                similarity: score(total),
+                behavior: score(total),
+                deployer: score(total),
+            },
+            confidence: 0.8,
+            recommendation: Recommendation::Buy,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_detects_lp_pull_and_authority_change() {
+        let entry = token();
+        let mut current = entry.clone();
+        current.liquidity_sol = 10.0;
+        current.mint_authority_revoked = false;
+
+        let alerts = PositionMonitor::detect_changes(&entry, &current, &MonitorThresholds::default());
+        let types: Vec<AlertType> = alerts.iter().map(|a| a.alert_type).collect();
+
+        assert!(types.contains(&AlertType::RugPullDetected));
+        assert!(types.contains(&AlertType::SuspiciousActivity));
+        assert!(!alerts.iter().any(|a| a.key == "lp_drop"));
+    }
+
+    #[test]
+    fn test_creator_dump() {
+        let token = token();
+        let thresholds = MonitorThresholds::default();
+        let creator = token.creator.unwrap();
+
+        // 原始单位（6位小数）：8%和2%的流通量
+        let dump = PositionMonitor::creator_sell(&token, &creator, 80_000_000_000_000, "sig1", &thresholds).unwrap();
+        assert_eq!(dump.alert_type, AlertType::RugPullDetected);
+
+        let small = PositionMonitor::creator_sell(&token, &creator, 20_000_000_000_000, "sig2", &thresholds).unwrap();
+        assert_eq!(small.alert_type, AlertType::SuspiciousActivity);
+
+        // 8000万原始单位只有80个币
+        assert!(PositionMonitor::creator_sell(&token, &creator, 80_000_000, "sig3", &thresholds).is_none());
+
+        // 非部署者卖出不告警
+        assert!(PositionMonitor::creator_sell(&token, &Pubkey::new_unique(), 80_000_000_000_000, "sig4", &thresholds).is_none());
+    }
+
+    #[test]
+    fn test_auto_exit_policy() {
+        let rug = MonitorAlert::new("lp_rug", AlertType::RugPullDetected, String::new());
+        let lp_drop = MonitorAlert::new("lp_drop", AlertType::LiquidityDrop, String::new());
+
+        let disabled = AutoExitPolicy::default();
+        assert!(!disabled.should_exit(std::slice::from_ref(&rug), None));
+
+        let policy = AutoExitPolicy {
+            enabled: true,
+            exit_below_score: Some(40.0),
+            ..AutoExitPolicy::default()
+        };
+        assert!(policy.should_exit(&[rug], None));
+        assert!(!policy.should_exit(std::slice::from_ref(&lp_drop), Some(60.0)));
+        assert!(policy.should_exit(&[lp_drop], Some(30.0)));
+    }
+
+    #[tokio::test]
+    async fn test_alerts_are_emitted_once() {
+        let mut monitor = PositionMonitor::new(Arc::new(RiskAssessmentEngine::new()))
+            .with_reassess_interval(Duration::from_secs(3600));
+        let mut events = monitor.take_event_receiver().unwrap();
+
+        let entry = token();
+        monitor.watch(entry.clone(), 1_000_000, &baseline(80.0));
+
+        let mut update = entry.clone();
+        update.freeze_authority_revoked = false;
+        let alerts = monitor.handle(MonitorUpdate::Token(Box::new(update.clone()))).await.unwrap();
+        assert!(alerts.iter().any(|a| a.key == "freeze_authority"));
+
+        let repeated = monitor.handle(MonitorUpdate::Token(Box::new(update))).await.unwrap();
+        assert!(repeated.iter().all(|a| a.key != "freeze_authority"));
+
+        let mut freeze_events = 0;
+        while let Ok(event) = events.try_recv() {
+            if let Event::RiskAlert { message, .. } = event {
+                if message.contains("Freeze") {
+                    freeze_events += 1;
+                }
+            }
+        }
+        assert_eq!(freeze_events, 1);
+
+        // 未监控的代币忽略
+        assert!(monitor.handle(MonitorUpdate::Token(Box::new(token()))).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rug_alert_records_rugged_reference() {
+        let similarity = Arc::new(SimilarityAnalyzer::new());
+        let engine = RiskAssessmentEngine::new().with_similarity_analyzer(Arc::clone(&similarity));
+        let monitor = PositionMonitor::new(Arc::new(engine));
+
+        let entry = token();
+        monitor.watch(entry.clone(), 1_000_000, &baseline(80.0));
+
+        let mut drained = entry.clone();
+        drained.liquidity_sol = 5.0;
+        let alerts = monitor.handle(MonitorUpdate::Token(Box::new(drained))).await.unwrap();
+        assert!(alerts.iter().any(|a| a.alert_type == AlertType::RugPullDetected));
+
+        // 同一部署者重新发币会被识别
+        let relaunch = TokenInfo {
+            mint: Pubkey::new_unique(),
+            ..entry
+        };
+        let score = similarity
+            .assess(&ReferenceToken::from_token(&relaunch, None, ReferenceKind::Recent))
+            .unwrap();
+        assert!(score.issues[0].contains("rug后重新发币"));
+    }
+}
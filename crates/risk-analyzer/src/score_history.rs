@@ -0,0 +1,165 @@
+use solsniper_core::{Error, Result, RiskScore};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use sqlx::{PgPool, Row};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+/// 一次风险评估记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreHistoryEntry {
+    pub mint: Pubkey,
+    pub score: RiskScore,
+    /// 本次重新运行的分析器（其余复用上次结果）
+    #[serde(default)]
+    pub recomputed: Vec<String>,
+}
+
+/// 风险分历史存储trait
+#[async_trait]
+pub trait ScoreHistoryStore: Send + Sync {
+    /// 追加一次评估结果
+    async fn record(&self, entry: &ScoreHistoryEntry) -> Result<()>;
+
+    /// mint最近的评估记录（按时间正序）
+    async fn history(&self, mint: &Pubkey, limit: usize) -> Result<Vec<ScoreHistoryEntry>>;
+
+    /// 存储名称
+    fn name(&self) -> &str;
+}
+
+/// PostgreSQL风险分历史
+///
+/// 写入`risk_score_history`表，完整评分以JSONB保存
+pub struct PostgresScoreHistory {
+    pool: PgPool,
+}
+
+impl PostgresScoreHistory {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn connect(database_url: &str) -> Result<Self> {
+        let pool = PgPool::connect(database_url)
+            .await
+            .map_err(|e| Error::Database(format!("PostgreSQL connection error: {}", e)))?;
+        Ok(Self::new(pool))
+    }
+}
+
+#[async_trait]
+impl ScoreHistoryStore for PostgresScoreHistory {
+    async fn record(&self, entry: &ScoreHistoryEntry) -> Result<()> {
+        let score = serde_json::to_value(&entry.score)
+            .map_err(|e| Error::Serialization(format!("Risk score serialization failed: {}", e)))?;
+
+        sqlx::query(
+            "INSERT INTO risk_score_history (mint, total, recommendation, confidence, score, recomputed, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(entry.mint.to_string())
+        .bind(entry.score.total)
+        .bind(format!("{:?}", entry.score.recommendation))
+        .bind(entry.score.confidence)
+        .bind(score)
+        .bind(&entry.recomputed)
+        .bind(entry.score.timestamp)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Score history insert failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn history(&self, mint: &Pubkey, limit: usize) -> Result<Vec<ScoreHistoryEntry>> {
+        let rows = sqlx::query(
+            "SELECT score, recomputed FROM risk_score_history
+             WHERE mint = $1
+             ORDER BY created_at DESC
+             LIMIT $2",
+        )
+        .bind(mint.to_string())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Database(format!("Score history query failed: {}", e)))?;
+
+        let mut entries = rows
+            .iter()
+            .map(|row| -> Result<ScoreHistoryEntry> {
+                let score: serde_json::Value = row
+                    .try_get("score")
+                    .map_err(|e| Error::Database(format!("Score history decode failed: {}", e)))?;
+                let recomputed: Vec<String> = row
+                    .try_get("recomputed")
+                    .map_err(|e| Error::Database(format!("Score history decode failed: {}", e)))?;
+
+                Ok(ScoreHistoryEntry {
+                    mint: *mint,
+                    score: serde_json::from_value(score)
+                        .map_err(|e| Error::Serialization(format!("Invalid stored risk score: {}", e)))?,
+                    recomputed,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        entries.reverse();
+        Ok(entries)
+    }
+
+    fn name(&self) -> &str {
+        "postgres"
+    }
+}
+
+/// 内存风险分历史
+///
+/// 每个mint保留最近的若干条记录，用于测试和未配置数据库时
+pub struct InMemoryScoreHistory {
+    entries: RwLock<HashMap<Pubkey, VecDeque<ScoreHistoryEntry>>>,
+    max_per_mint: usize,
+}
+
+impl InMemoryScoreHistory {
+    pub fn new(max_per_mint: usize) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            max_per_mint,
+        }
+    }
+}
+
+impl Default for InMemoryScoreHistory {
+    fn default() -> Self {
+        Self::new(1_000)
+    }
+}
+
+#[async_trait]
+impl ScoreHistoryStore for InMemoryScoreHistory {
+    async fn record(&self, entry: &ScoreHistoryEntry) -> Result<()> {
+        let mut entries = self.entries.write().unwrap();
+        let history = entries.entry(entry.mint).or_default();
+        history.push_back(entry.clone());
+        while history.len() > self.max_per_mint {
+            history.pop_front();
+        }
+        Ok(())
+    }
+
+    async fn history(&self, mint: &Pubkey, limit: usize) -> Result<Vec<ScoreHistoryEntry>> {
+        let entries = self.entries.read().unwrap();
+        let Some(history) = entries.get(mint) else {
+            return Ok(Vec::new());
+        };
+
+        let skip = history.len().saturating_sub(limit);
+        Ok(history.iter().skip(skip).cloned().collect())
+    }
+
+    fn name(&self) -> &str {
+        "memory"
+    }
+}
@@ -0,0 +1,385 @@
+use solsniper_core::{Error, Recommendation, Result, ScoreBreakdown};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 各分析器权重
+///
+/// 只对可用的分量加权，权重按可用分量的总和归一化（如部署者历史未知、无仿盘参照时不参与）。
+/// 部署者与仿盘分量都未知时改用`fallback`，默认即原先固定的0.35/0.30/0.25/0.10
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    pub contract: f64,
+    pub liquidity: f64,
+    pub holder: f64,
+    pub sentiment: f64,
+    pub deployer: f64,
+    pub similarity: f64,
+
+    /// 部署者与仿盘分量都未知时的权重
+    pub fallback: FallbackWeights,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            contract: 0.30,
+            liquidity: 0.25,
+            holder: 0.20,
+            sentiment: 0.10,
+            deployer: 0.15,
+            similarity: 0.10,
+            fallback: FallbackWeights::default(),
+        }
+    }
+}
+
+/// 只有合约、流动性、持有者和情绪分量时的权重（同样按总和归一化）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FallbackWeights {
+    pub contract: f64,
+    pub liquidity: f64,
+    pub holder: f64,
+    pub sentiment: f64,
+}
+
+impl Default for FallbackWeights {
+    fn default() -> Self {
+        Self {
+            contract: 0.35,
+            liquidity: 0.30,
+            holder: 0.25,
+            sentiment: 0.10,
+        }
+    }
+}
+
+/// 推荐阈值（总分不低于阈值时给出对应推荐）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecommendationThresholds {
+    pub strong_buy: f64,
+    pub buy: f64,
+    pub hold: f64,
+    pub avoid: f64,
+}
+
+impl Default for RecommendationThresholds {
+    fn default() -> Self {
+        Self {
+            strong_buy: 85.0,
+            buy: 75.0,
+            hold: 60.0,
+            avoid: 40.0,
+        }
+    }
+}
+
+impl RecommendationThresholds {
+    pub fn recommend(&self, total: f64) -> Recommendation {
+        if total >= self.strong_buy {
+            Recommendation::StrongBuy
+        } else if total >= self.buy {
+            Recommendation::Buy
+        } else if total >= self.hold {
+            Recommendation::Hold
+        } else if total >= self.avoid {
+            Recommendation::Avoid
+        } else {
+            Recommendation::StrongAvoid
+        }
+    }
+}
+
+/// 风险评分配置
+///
+/// 对应`config.toml`中的`[risk.scoring]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    pub weights: ScoringWeights,
+
+    /// ML预测为Rug时的最大扣分（乘以预测置信度）
+    pub ml_rug_penalty: f64,
+
+    /// ML预测为安全时的最大加分（乘以预测置信度）
+    pub ml_safe_bonus: f64,
+
+    /// 每个高风险模式的最大扣分（乘以匹配置信度）
+    pub pattern_penalty: f64,
+
+    pub thresholds: RecommendationThresholds,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            weights: ScoringWeights::default(),
+            ml_rug_penalty: 20.0,
+            ml_safe_bonus: 5.0,
+            pattern_penalty: 15.0,
+            thresholds: RecommendationThresholds::default(),
+        }
+    }
+}
+
+impl ScoringConfig {
+    /// 从TOML文件读取`[risk.scoring]`，缺失的字段使用默认值
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let settings = config::Config::builder()
+            .add_source(config::File::from(path))
+            .build()
+            .map_err(|e| Error::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+
+        let scoring = match settings.get::<ScoringConfig>("risk.scoring") {
+            Ok(scoring) => scoring,
+            Err(config::ConfigError::NotFound(_)) => ScoringConfig::default(),
+            Err(e) => return Err(Error::Config(format!("Invalid [risk.scoring]: {}", e))),
+        };
+
+        scoring.validate()?;
+        Ok(scoring)
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<()> {
+        let weights = [
+            ("contract", self.weights.contract),
+            ("liquidity", self.weights.liquidity),
+            ("holder", self.weights.holder),
+            ("sentiment", self.weights.sentiment),
+            ("deployer", self.weights.deployer),
+            ("similarity", self.weights.similarity),
+        ];
+        if let Some((name, weight)) = weights.iter().find(|(_, weight)| !weight.is_finite() || *weight < 0.0) {
+            return Err(Error::Config(format!("Weight {} must be non-negative, got {}", name, weight)));
+        }
+        if weights.iter().map(|(_, weight)| weight).sum::<f64>() <= 0.0 {
+            return Err(Error::Config("At least one weight must be positive".to_string()));
+        }
+
+        let fallback = &self.weights.fallback;
+        let fallback = [fallback.contract, fallback.liquidity, fallback.holder, fallback.sentiment];
+        if fallback.iter().any(|weight| !weight.is_finite() || *weight < 0.0) || fallback.iter().sum::<f64>() <= 0.0 {
+            return Err(Error::Config(format!(
+                "Fallback weights must be non-negative with a positive sum, got {:?}",
+                fallback
+            )));
+        }
+
+        if [self.ml_rug_penalty, self.ml_safe_bonus, self.pattern_penalty]
+            .iter()
+            .any(|value| !value.is_finite() || *value < 0.0)
+        {
+            return Err(Error::Config("ML and pattern adjustments must be non-negative".to_string()));
+        }
+
+        let t = &self.thresholds;
+        if !(t.strong_buy >= t.buy && t.buy >= t.hold && t.hold >= t.avoid && t.avoid >= 0.0 && t.strong_buy <= 100.0) {
+            return Err(Error::Config(format!(
+                "Recommendation thresholds must be descending within 0-100: {:?}",
+                t
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// 计算总分并生成解释
+    ///
+    /// `deployer_known`/`similarity_known`为false表示对应分量无数据，不参与加权；
+    /// 两者都为false时使用`weights.fallback`
+    pub fn explain(
+        &self,
+        breakdown: &ScoreBreakdown,
+        deployer_known: bool,
+        similarity_known: bool,
+        ml_adjustment: f64,
+        pattern_penalty: f64,
+    ) -> ScoreExplanation {
+        let base = if deployer_known || similarity_known {
+            [self.weights.contract, self.weights.liquidity, self.weights.holder, self.weights.sentiment]
+        } else {
+            let fallback = &self.weights.fallback;
+            [fallback.contract, fallback.liquidity, fallback.holder, fallback.sentiment]
+        };
+        let mut weighted: Vec<(&str, f64, f64)> = vec![
+            ("contract", breakdown.contract.value, base[0]),
+            ("liquidity", breakdown.liquidity.value, base[1]),
+            ("holder", breakdown.holder.value, base[2]),
+            ("sentiment", breakdown.sentiment.value, base[3]),
+        ];
+        if deployer_known {
+            weighted.push(("deployer", breakdown.deployer.value, self.weights.deployer));
+        }
+        if similarity_known {
+            weighted.push(("similarity", breakdown.similarity.value, self.weights.similarity));
+        }
+
+        let weight_sum: f64 = weighted.iter().map(|(_, _, weight)| weight).sum();
+
+        let components: Vec<ComponentContribution> = weighted
+            .into_iter()
+            .map(|(name, score, weight)| {
+                let weight = if weight_sum > 0.0 { weight / weight_sum } else { 0.0 };
+                ComponentContribution {
+                    name: name.to_string(),
+                    score,
+                    weight,
+                    contribution: score * weight,
+                    // 与满分的差距即该分量损失的总分
+                    points_lost: (100.0 - score).max(0.0) * weight,
+                }
+            })
+            .collect();
+
+        let base_score: f64 = components.iter().map(|c| c.contribution).sum();
+        let total = (base_score + ml_adjustment - pattern_penalty).clamp(0.0, 100.0);
+
+        ScoreExplanation {
+            components,
+            base_score,
+            ml_adjustment,
+            pattern_penalty,
+            total,
+            recommendation: self.thresholds.recommend(total),
+        }
+    }
+}
+
+/// 单个分量对总分的贡献
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentContribution {
+    pub name: String,
+    /// 分量得分（0-100）
+    pub score: f64,
+    /// 归一化后的权重
+    pub weight: f64,
+    /// 对总分的贡献（得分×权重）
+    pub contribution: f64,
+    /// 相对满分损失的总分
+    pub points_lost: f64,
+}
+
+/// 评分解释
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreExplanation {
+    pub components: Vec<ComponentContribution>,
+    /// 加权基础分
+    pub base_score: f64,
+    /// ML调整（正为加分）
+    pub ml_adjustment: f64,
+    /// 行为模式扣分
+    pub pattern_penalty: f64,
+    pub total: f64,
+    pub recommendation: Recommendation,
+}
+
+impl ScoreExplanation {
+    /// 逐行说明各分量的贡献，按损失从大到小排列
+    pub fn summary(&self) -> Vec<String> {
+        let mut components: Vec<&ComponentContribution> = self.components.iter().collect();
+        components.sort_by(|a, b| b.points_lost.total_cmp(&a.points_lost));
+
+        let mut lines: Vec<String> = components
+            .iter()
+            .map(|c| {
+                format!(
+                    "{}: {:.1} × {:.2} = {:.1}（损失 {:.1}）",
+                    c.name, c.score, c.weight, c.contribution, c.points_lost
+                )
+            })
+            .collect();
+
+        if self.ml_adjustment != 0.0 {
+            lines.push(format!("ML调整: {:+.1}", self.ml_adjustment));
+        }
+        if self.pattern_penalty != 0.0 {
+            lines.push(format!("行为模式扣分: -{:.1}", self.pattern_penalty));
+        }
+        lines.push(format!("总分: {:.1}（{:?}）", self.total, self.recommendation));
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solsniper_core::Score;
+
+    fn breakdown(contract: f64, liquidity: f64, holder: f64, sentiment: f64, deployer: f64) -> ScoreBreakdown {
+        let score = |value: f64| Score { value, issues: vec![] };
+        ScoreBreakdown {
+            contract: score(contract),
+            liquidity: score(liquidity),
+            holder: score(holder),
+            sentiment: score(sentiment),
+            similarity: score(50.0),
+            behavior: score(100.0),
+            deployer: score(deployer),
+        }
+    }
+
+    #[test]
+    fn test_default_weights_match_legacy_scoring() {
+        let config = ScoringConfig::default();
+        let explanation = config.explain(&breakdown(100.0, 80.0, 60.0, 50.0, 0.0), false, false, 0.0, 0.0);
+
+        // 部署者与仿盘都未知：0.35 * 100 + 0.30 * 80 + 0.25 * 60 + 0.10 * 50
+        for (component, legacy) in explanation.components.iter().zip([0.35, 0.30, 0.25, 0.10]) {
+            assert!((component.weight - legacy).abs() < 1e-12, "{}: {}", component.name, component.weight);
+        }
+        assert_eq!(explanation.components.len(), 4);
+        assert!((explanation.base_score - 79.0).abs() < 1e-9);
+        assert!(matches!(explanation.recommendation, Recommendation::Buy));
+    }
+
+    #[test]
+    fn test_default_weights_with_deployer() {
+        let config = ScoringConfig::default();
+        let explanation = config.explain(&breakdown(100.0, 80.0, 60.0, 50.0, 40.0), true, false, 0.0, 0.0);
+
+        // 0.30 * 100 + 0.25 * 80 + 0.20 * 60 + 0.10 * 50 + 0.15 * 40
+        assert!((explanation.base_score - 73.0).abs() < 1e-9);
+        assert!(matches!(explanation.recommendation, Recommendation::Hold));
+    }
+
+    #[test]
+    fn test_default_weights_renormalized_without_deployer() {
+        let config = ScoringConfig::default();
+        let explanation = config.explain(&breakdown(100.0, 80.0, 60.0, 50.0, 0.0), false, true, 0.0, 0.0);
+
+        // (0.30 * 100 + 0.25 * 80 + 0.20 * 60 + 0.10 * 50 + 0.10 * 50) / 0.95
+        assert!((explanation.base_score - 72.0 / 0.95).abs() < 1e-9);
+        assert!(matches!(explanation.recommendation, Recommendation::Buy));
+    }
+
+    #[test]
+    fn test_contributions_sum_to_total() {
+        let config = ScoringConfig::default();
+        let explanation = config.explain(&breakdown(90.0, 40.0, 70.0, 50.0, 20.0), true, true, -10.0, 7.5);
+
+        let weight_sum: f64 = explanation.components.iter().map(|c| c.weight).sum();
+        assert!((weight_sum - 1.0).abs() < 1e-9);
+
+        let contributions: f64 = explanation.components.iter().map(|c| c.contribution).sum();
+        assert!((explanation.total - (contributions - 10.0 - 7.5)).abs() < 1e-9);
+
+        // 损失最大的分量排在第一行
+        assert!(explanation.summary()[0].starts_with("liquidity"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_thresholds() {
+        let mut config = ScoringConfig::default();
+        config.thresholds.buy = 90.0;
+        assert!(config.validate().is_err());
+
+        let mut config = ScoringConfig::default();
+        config.weights.holder = -1.0;
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use solsniper_core::Score;
+
+use crate::social_signals::SocialSignals;
+
+/// 社交情绪分析器
+pub struct SentimentAnalyzer {
+    /// 视为"有热度"的推特提及数
+    min_twitter_mentions: u64,
+
+    /// 视为"有社区"的Telegram成员数
+    min_telegram_members: u64,
+
+    /// 可接受的最大机器人比例
+    max_bot_ratio: f64,
+}
+
+impl SentimentAnalyzer {
+    pub fn new(min_twitter_mentions: u64, min_telegram_members: u64) -> Self {
+        Self {
+            min_twitter_mentions,
+            min_telegram_members,
+            max_bot_ratio: 0.5,
+        }
+    }
+
+    pub fn analyze(&self, signals: &SocialSignals) -> Score {
+        let mut score = 50.0;
+        let mut issues = Vec::new();
+
+        // 情绪分：0.5为中性，按偏离程度加减（±30）
+        let sentiment = signals.sentiment_score.clamp(0.0, 1.0);
+        score += (sentiment - 0.5) * 60.0;
+        if sentiment >= 0.7 {
+            issues.push(format!("✅ 社交情绪积极: {:.2}", sentiment));
+        } else if sentiment <= 0.3 {
+            issues.push(format!("⚠️ 社交情绪消极: {:.2}", sentiment));
+        }
+
+        // 推特热度
+        if signals.twitter_mentions == 0 {
+            score -= 15.0;
+            issues.push("⚠️ 推特无提及".to_string());
+        } else if signals.twitter_mentions >= self.min_twitter_mentions {
+            score += 10.0;
+            issues.push(format!("✅ 推特提及: {} 次", signals.twitter_mentions));
+        }
+
+        // 社区规模
+        let community = signals.telegram_members + signals.discord_members.unwrap_or(0);
+        if community == 0 {
+            score -= 10.0;
+            issues.push("⚠️ 没有Telegram/Discord社区".to_string());
+        } else if signals.telegram_members >= self.min_telegram_members {
+            score += 10.0;
+            issues.push(format!("✅ Telegram成员: {}", signals.telegram_members));
+        }
+
+        // 提及主要来自机器人账号，热度不可信
+        if let Some(bot_ratio) = signals.bot_ratio {
+            if bot_ratio > self.max_bot_ratio {
+                score -= 20.0;
+                issues.push(format!("🚨 提及中疑似机器人占比 {:.0}%", bot_ratio * 100.0));
+            }
+        }
+
+        Score {
+            value: score.clamp(0.0, 100.0),
+            issues,
+        }
+    }
+}
+
+impl Default for SentimentAnalyzer {
+    fn default() -> Self {
+        Self::new(50, 500)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neutral_without_activity_is_penalized() {
+        let score = SentimentAnalyzer::default().analyze(&SocialSignals {
+            sentiment_score: 0.5,
+            ..Default::default()
+        });
+
+        assert!((score.value - 25.0).abs() < 1e-9);
+        assert_eq!(score.issues.len(), 2);
+    }
+
+    #[test]
+    fn test_positive_community() {
+        let score = SentimentAnalyzer::default().analyze(&SocialSignals {
+            twitter_mentions: 120,
+            telegram_members: 2_000,
+            sentiment_score: 0.8,
+            ..Default::default()
+        });
+
+        assert!((score.value - 88.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bot_driven_hype() {
+        let score = SentimentAnalyzer::default().analyze(&SocialSignals {
+            twitter_mentions: 500,
+            telegram_members: 1_000,
+            sentiment_score: 0.9,
+            bot_ratio: Some(0.8),
+            ..Default::default()
+        });
+
+        assert!(score.value < 75.0);
+        assert!(score.issues.iter().any(|issue| issue.contains("机器人")));
+    }
+}
@@ -0,0 +1,879 @@
+use solsniper_core::{Error, Result, Score, TokenInfo};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use image::{imageops::FilterType, DynamicImage, ImageReader, Limits};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solsniper_data_collector::HttpMetadataFetcher;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::holder_clusters::BoundedCache;
+
+/// 元数据JSON大小上限
+const MAX_METADATA_BYTES: usize = 256 * 1024;
+
+/// 元数据图片大小上限
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// 解码图片的最大边长（像素）
+const MAX_IMAGE_DIMENSION: u32 = 4096;
+
+/// 解码图片时的内存分配上限
+const MAX_DECODE_ALLOC: u64 = 64 * 1024 * 1024;
+
+/// 参照代币类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferenceKind {
+    /// 近期出现的代币
+    Recent,
+    /// 热门代币（仿冒目标）
+    Trending,
+    /// 已确认rug的代币
+    Rugged,
+}
+
+/// 用于比对的代币指纹
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceToken {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    /// 元数据图片的感知哈希（dHash）
+    #[serde(default)]
+    pub image_hash: Option<u64>,
+    #[serde(default)]
+    pub creator: Option<Pubkey>,
+    pub kind: ReferenceKind,
+    pub seen_at: DateTime<Utc>,
+}
+
+impl ReferenceToken {
+    pub fn from_token(token: &TokenInfo, image_hash: Option<u64>, kind: ReferenceKind) -> Self {
+        Self {
+            mint: token.mint,
+            name: token.name.clone(),
+            symbol: token.symbol.clone(),
+            image_hash,
+            creator: token.creator,
+            kind,
+            seen_at: Utc::now(),
+        }
+    }
+}
+
+/// 与单个参照代币的比对结果
+#[derive(Debug, Clone)]
+pub struct SimilarityMatch {
+    pub reference: ReferenceToken,
+    pub name_similarity: f64,
+    pub symbol_similarity: f64,
+    /// 图片哈希的汉明距离（任一方缺少图片时为None）
+    pub image_distance: Option<u32>,
+    pub same_creator: bool,
+}
+
+/// 归一化名称：小写、替换形近字符、去掉非字母数字
+///
+/// 用于识别`B0NK`、`ΡEPE`（希腊字母）这类仿冒写法
+pub fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            '0' | 'о' | 'ο' => 'o',
+            '1' | 'i' | '|' | '!' | 'і' | 'ι' => 'l',
+            '3' | 'е' | 'ε' => 'e',
+            '4' | '@' | 'а' | 'α' => 'a',
+            '5' | '$' | 'ѕ' => 's',
+            '7' | 'τ' => 't',
+            'р' | 'ρ' => 'p',
+            'с' => 'c',
+            'у' | 'γ' => 'y',
+            'х' | 'χ' => 'x',
+            'к' | 'κ' => 'k',
+            'н' | 'η' => 'n',
+            'в' | 'β' => 'b',
+            'м' | 'μ' => 'm',
+            other => other,
+        })
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+/// 编辑距离
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// 文本相似度（0-1）
+///
+/// 归一化后的编辑距离相似度；较短一方（至少4个字符）被包含在较长一方中时（如`BABYBONK`与`BONK`）至少为0.9
+pub fn text_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = normalize(a).chars().collect();
+    let b: Vec<char> = normalize(b).chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let max_len = a.len().max(b.len());
+    let similarity = 1.0 - levenshtein(&a, &b) as f64 / max_len as f64;
+
+    let (shorter, longer) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+    let contained = shorter.len() >= 4
+        && longer.windows(shorter.len()).any(|window| window == shorter.as_slice());
+
+    if contained {
+        similarity.max(0.9)
+    } else {
+        similarity
+    }
+}
+
+/// 图片的差值哈希（dHash，64位）
+///
+/// 缩放为9x8灰度图后比较相邻像素亮度，对缩放、压缩和轻微调色不敏感
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let gray = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if gray.get_pixel(x, y)[0] > gray.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+/// 两个感知哈希的汉明距离
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 元数据图片哈希器trait
+#[async_trait]
+pub trait ImageHasher: Send + Sync {
+    /// 计算元数据URI所指图片的感知哈希（元数据没有图片时返回None）
+    async fn hash(&self, metadata_uri: &str) -> Result<Option<u64>>;
+
+    /// 哈希器名称
+    fn name(&self) -> &str;
+}
+
+/// HTTP图片哈希器
+///
+/// 读取元数据JSON的`image`字段，下载图片并计算dHash。
+/// 下载复用`HttpMetadataFetcher`（网关改写、只访问公网地址、按块读取并限制大小），
+/// 解码时限制尺寸和内存分配，防止小文件解压出超大图片
+pub struct HttpImageHasher {
+    fetcher: HttpMetadataFetcher,
+}
+
+impl HttpImageHasher {
+    pub fn new() -> Self {
+        Self {
+            fetcher: HttpMetadataFetcher::new(),
+        }
+    }
+
+    /// 设置IPFS网关（如`https://cloudflare-ipfs.com/ipfs/`）
+    pub fn with_ipfs_gateway(mut self, gateway: impl Into<String>) -> Self {
+        self.fetcher = self.fetcher.with_ipfs_gateway(gateway.into());
+        self
+    }
+}
+
+impl Default for HttpImageHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Deserialize)]
+struct MetadataJson {
+    #[serde(default)]
+    image: Option<String>,
+}
+
+/// 在尺寸和内存限制内解码图片并计算dHash
+fn decode_and_hash(bytes: &[u8]) -> image::ImageResult<u64> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    reader.limits(limits);
+
+    Ok(dhash(&reader.decode()?))
+}
+
+#[async_trait]
+impl ImageHasher for HttpImageHasher {
+    async fn hash(&self, metadata_uri: &str) -> Result<Option<u64>> {
+        let body = self.fetcher.fetch_bytes(metadata_uri, MAX_METADATA_BYTES).await?;
+        let metadata = serde_json::from_slice::<MetadataJson>(&body)
+            .map_err(|e| Error::Serialization(format!("Metadata JSON parse error: {}", e)))?;
+
+        let Some(image_uri) = metadata.image.filter(|uri| !uri.is_empty()) else {
+            return Ok(None);
+        };
+
+        let bytes = self.fetcher.fetch_bytes(&image_uri, MAX_IMAGE_BYTES).await?;
+
+        let hash = tokio::task::spawn_blocking(move || decode_and_hash(&bytes))
+            .await
+            .map_err(|e| Error::Internal(format!("Image decode task join error: {}", e)))?
+            .map_err(|e| Error::Serialization(format!("Image decode error: {}", e)))?;
+
+        Ok(Some(hash))
+    }
+
+    fn name(&self) -> &str {
+        "http"
+    }
+}
+
+/// 本地fixture图片哈希器
+///
+/// 用于测试和离线回放，按元数据URI返回预置的哈希
+#[derive(Default)]
+pub struct FixtureImageHasher {
+    hashes: HashMap<String, u64>,
+}
+
+impl FixtureImageHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_fixture(mut self, metadata_uri: impl Into<String>, hash: u64) -> Self {
+        self.hashes.insert(metadata_uri.into(), hash);
+        self
+    }
+}
+
+#[async_trait]
+impl ImageHasher for FixtureImageHasher {
+    async fn hash(&self, metadata_uri: &str) -> Result<Option<u64>> {
+        Ok(self.hashes.get(metadata_uri).copied())
+    }
+
+    fn name(&self) -> &str {
+        "fixture"
+    }
+}
+
+/// 仿盘/相似度分析器
+///
+/// 将新代币的名称、符号、元数据图片和部署者与近期代币、热门代币和已rug代币比对。
+/// 只有上线满一定时长或成交量达到热门标准的代币才会加入参照集，
+/// 避免刚上线的仿盘抢先成为参照、反过来把后上线的正主判为仿冒
+pub struct SimilarityAnalyzer {
+    references: RwLock<HashMap<Pubkey, ReferenceToken>>,
+
+    /// 参照集版本（增删参照或标记rug时递增）
+    version: AtomicU64,
+
+    image_hasher: Option<Arc<dyn ImageHasher>>,
+
+    /// 未加入参照集的代币的图片哈希（mint -> 哈希，元数据无图片时为None）
+    image_hashes: Mutex<BoundedCache<Option<u64>>>,
+
+    /// 名称/符号相似度阈值
+    min_text_similarity: f64,
+
+    /// 视为同一图片的最大汉明距离
+    max_image_distance: u32,
+
+    /// 1小时成交量（USD）达到该值的代币视为热门
+    trending_volume_usd: f64,
+
+    /// 近期/热门代币的保留时长
+    recent_window: Duration,
+
+    /// 已rug代币的保留时长（从标记rug时起算）
+    rugged_window: Duration,
+
+    /// 加入参照集所需的最短上线时长（热门代币不受限制）
+    min_reference_age: Duration,
+
+    /// 参照集上限
+    max_references: usize,
+}
+
+impl SimilarityAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            references: RwLock::new(HashMap::new()),
+            version: AtomicU64::new(0),
+            image_hasher: None,
+            image_hashes: Mutex::new(BoundedCache::new(10_000)),
+            min_text_similarity: 0.85,
+            max_image_distance: 10,
+            trending_volume_usd: 50_000.0,
+            recent_window: Duration::hours(24),
+            rugged_window: Duration::days(30),
+            min_reference_age: Duration::hours(1),
+            max_references: 5_000,
+        }
+    }
+
+    /// 启用元数据图片比对
+    pub fn with_image_hasher(mut self, hasher: Arc<dyn ImageHasher>) -> Self {
+        self.image_hasher = Some(hasher);
+        self
+    }
+
+    pub fn with_min_text_similarity(mut self, similarity: f64) -> Self {
+        self.min_text_similarity = similarity;
+        self
+    }
+
+    pub fn with_max_image_distance(mut self, distance: u32) -> Self {
+        self.max_image_distance = distance;
+        self
+    }
+
+    pub fn with_trending_volume(mut self, volume_usd: f64) -> Self {
+        self.trending_volume_usd = volume_usd;
+        self
+    }
+
+    pub fn with_recent_window(mut self, window: Duration) -> Self {
+        self.recent_window = window;
+        self
+    }
+
+    pub fn with_rugged_window(mut self, window: Duration) -> Self {
+        self.rugged_window = window;
+        self
+    }
+
+    pub fn with_min_reference_age(mut self, age: Duration) -> Self {
+        self.min_reference_age = age;
+        self
+    }
+
+    /// 添加参照代币（如已知rug代币、外部热门榜单）
+    pub fn add_reference(&self, reference: ReferenceToken) {
+        let mut references = self.references.write().unwrap();
+        references.insert(reference.mint, reference);
+        self.version.fetch_add(1, Ordering::Relaxed);
+        self.prune(&mut references, Utc::now());
+    }
+
+    /// 参照集版本，用于增量评估判断参照集是否变化
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    /// 将已记录的代币标记为rug（保留时长从此刻起算）
+    pub fn mark_rugged(&self, mint: &Pubkey) -> bool {
+        match self.references.write().unwrap().get_mut(mint) {
+            Some(reference) => {
+                reference.kind = ReferenceKind::Rugged;
+                reference.seen_at = Utc::now();
+                self.version.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 记录已rug的代币（未在参照集中时直接加入，不受上线时长限制）
+    pub fn record_rugged(&self, token: &TokenInfo) {
+        if !self.mark_rugged(&token.mint) {
+            self.add_reference(ReferenceToken::from_token(token, None, ReferenceKind::Rugged));
+        }
+    }
+
+    /// 批量加入已rug的参照代币（如启动时从数据库恢复），返回保留下来的数量
+    ///
+    /// `seen_at`应为确认rug的时间，超出`rugged_window`的会被直接淘汰
+    pub fn seed_rugged(&self, seeds: impl IntoIterator<Item = ReferenceToken>) -> usize {
+        let mut references = self.references.write().unwrap();
+        let mut added = Vec::new();
+        for mut reference in seeds {
+            reference.kind = ReferenceKind::Rugged;
+            added.push(reference.mint);
+            references.insert(reference.mint, reference);
+        }
+        self.version.fetch_add(1, Ordering::Relaxed);
+        self.prune(&mut references, Utc::now());
+
+        added.iter().filter(|mint| references.contains_key(mint)).count()
+    }
+
+    /// 从`token_outcomes`（rugged）和`blacklist`（token）恢复`rugged_window`内的已rug代币
+    ///
+    /// 名称和符号取自`tokens`表，部署者取自`token_launches`
+    pub async fn load_rugged(&self, pool: &PgPool) -> Result<usize> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT ON (r.mint)
+                    r.mint,
+                    COALESCE(t.name, '') AS name,
+                    COALESCE(t.symbol, '') AS symbol,
+                    l.creator,
+                    r.rugged_at
+             FROM (
+                 SELECT mint, outcome_at AS rugged_at FROM token_outcomes WHERE outcome = 'rugged'
+                 UNION ALL
+                 SELECT address, created_at AT TIME ZONE 'UTC' FROM blacklist WHERE type = 'token'
+             ) r
+             JOIN tokens t ON t.mint = r.mint
+             LEFT JOIN token_launches l ON l.mint = r.mint
+             WHERE r.rugged_at > $1
+             ORDER BY r.mint, r.rugged_at DESC",
+        )
+        .bind(Utc::now() - self.rugged_window)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| Error::Database(format!("Rugged reference query failed: {}", e)))?;
+
+        let seeds = rows
+            .iter()
+            .map(|row| -> std::result::Result<Option<ReferenceToken>, sqlx::Error> {
+                let mint: String = row.try_get("mint")?;
+                let creator: Option<String> = row.try_get("creator")?;
+                let Ok(mint) = Pubkey::from_str(&mint) else {
+                    return Ok(None);
+                };
+
+                Ok(Some(ReferenceToken {
+                    mint,
+                    name: row.try_get("name")?,
+                    symbol: row.try_get("symbol")?,
+                    image_hash: None,
+                    creator: creator.and_then(|creator| Pubkey::from_str(&creator).ok()),
+                    kind: ReferenceKind::Rugged,
+                    seen_at: row.try_get("rugged_at")?,
+                }))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::Database(format!("Rugged reference decode failed: {}", e)))?;
+
+        Ok(self.seed_rugged(seeds.into_iter().flatten()))
+    }
+
+    /// 记录代币（热门或近期，已标记rug的保持不变）
+    pub fn observe(&self, token: &TokenInfo, image_hash: Option<u64>) {
+        let reference = ReferenceToken::from_token(token, image_hash, self.kind_of(token));
+        self.record(reference);
+    }
+
+    /// 是否可作为参照：热门代币，或上线已满`min_reference_age`
+    pub fn is_reference_eligible(&self, token: &TokenInfo) -> bool {
+        self.kind_of(token) == ReferenceKind::Trending
+            || token.age_minutes as i64 >= self.min_reference_age.num_minutes()
+    }
+
+    fn kind_of(&self, token: &TokenInfo) -> ReferenceKind {
+        if token.volume_1h >= self.trending_volume_usd {
+            ReferenceKind::Trending
+        } else {
+            ReferenceKind::Recent
+        }
+    }
+
+    /// 写入参照集，保留已有的rug标记（及其标记时间）和图片哈希
+    fn record(&self, mut reference: ReferenceToken) {
+        let mut references = self.references.write().unwrap();
+        let changed = match references.get(&reference.mint) {
+            Some(existing) => {
+                if existing.kind == ReferenceKind::Rugged {
+                    reference.kind = ReferenceKind::Rugged;
+                    reference.seen_at = existing.seen_at;
+                }
+                reference.image_hash = reference.image_hash.or(existing.image_hash);
+
+                existing.kind != reference.kind
+                    || existing.image_hash != reference.image_hash
+                    || existing.name != reference.name
+                    || existing.symbol != reference.symbol
+            }
+            None => true,
+        };
+        references.insert(reference.mint, reference);
+        if changed {
+            self.version.fetch_add(1, Ordering::Relaxed);
+        }
+        self.prune(&mut references, Utc::now());
+    }
+
+    pub fn reference_count(&self) -> usize {
+        self.references.read().unwrap().len()
+    }
+
+    /// 淘汰过期的参照代币，超过上限时先淘汰最早的近期/热门代币，再淘汰最早的rug代币
+    fn prune(&self, references: &mut HashMap<Pubkey, ReferenceToken>, now: DateTime<Utc>) {
+        let before = references.len();
+        let recent_cutoff = now - self.recent_window;
+        let rugged_cutoff = now - self.rugged_window;
+        references.retain(|_, r| match r.kind {
+            ReferenceKind::Rugged => r.seen_at >= rugged_cutoff,
+            _ => r.seen_at >= recent_cutoff,
+        });
+
+        if references.len() > self.max_references {
+            let mut evictable: Vec<(bool, DateTime<Utc>, Pubkey)> = references
+                .values()
+                .map(|r| (r.kind == ReferenceKind::Rugged, r.seen_at, r.mint))
+                .collect();
+            evictable.sort();
+
+            let excess = references.len() - self.max_references;
+            for (_, _, mint) in evictable.into_iter().take(excess) {
+                references.remove(&mint);
+            }
+        }
+
+        if references.len() < before {
+            self.version.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 与参照集比对，返回相似的代币
+    pub fn find_matches(&self, candidate: &ReferenceToken) -> Vec<SimilarityMatch> {
+        let references = self.references.read().unwrap();
+
+        references
+            .values()
+            .filter(|reference| reference.mint != candidate.mint)
+            .filter_map(|reference| {
+                let name_similarity = text_similarity(&candidate.name, &reference.name);
+                let symbol_similarity = text_similarity(&candidate.symbol, &reference.symbol);
+                let image_distance = candidate
+                    .image_hash
+                    .zip(reference.image_hash)
+                    .map(|(a, b)| hamming_distance(a, b));
+
+                let text_match = name_similarity.max(symbol_similarity) >= self.min_text_similarity;
+                let image_match = image_distance.is_some_and(|d| d <= self.max_image_distance);
+                if !text_match && !image_match {
+                    return None;
+                }
+
+                Some(SimilarityMatch {
+                    reference: reference.clone(),
+                    name_similarity,
+                    symbol_similarity,
+                    image_distance,
+                    same_creator: candidate.creator.is_some() && candidate.creator == reference.creator,
+                })
+            })
+            .collect()
+    }
+
+    /// 根据比对结果计算分数（越相似扣分越多）
+    pub fn score(&self, matches: &[SimilarityMatch]) -> Score {
+        let mut scored: Vec<(f64, String)> = matches
+            .iter()
+            .map(|m| {
+                let text = m.name_similarity.max(m.symbol_similarity);
+                let text_match = text >= self.min_text_similarity;
+                let image_match = m.image_distance.is_some_and(|d| d <= self.max_image_distance);
+
+                let base = match m.reference.kind {
+                    ReferenceKind::Trending => 40.0,
+                    ReferenceKind::Rugged => 30.0,
+                    ReferenceKind::Recent => 10.0,
+                };
+                let mut penalty = match (text_match, image_match) {
+                    (true, true) => base * 1.5,
+                    (true, false) => base,
+                    _ => base * 0.75,
+                };
+
+                let mut evidence = Vec::new();
+                if text_match {
+                    evidence.push(format!("名称相似度{:.0}%", text * 100.0));
+                }
+                if image_match {
+                    evidence.push("图片相同".to_string());
+                }
+                if m.same_creator {
+                    evidence.push("部署者相同".to_string());
+                }
+
+                let target = format!("{}（{}）", m.reference.symbol, m.reference.mint);
+                let issue = match m.reference.kind {
+                    ReferenceKind::Trending => format!("🚨 疑似仿冒热门代币 {}: {}", target, evidence.join("，")),
+                    ReferenceKind::Rugged if m.same_creator => {
+                        penalty += 25.0;
+                        format!("🚨 疑似rug后重新发币，与已rug代币 {} 相似: {}", target, evidence.join("，"))
+                    }
+                    ReferenceKind::Rugged => format!("🚨 与已rug代币 {} 相似: {}", target, evidence.join("，")),
+                    ReferenceKind::Recent => format!("⚠️ 与近期代币 {} 相似: {}", target, evidence.join("，")),
+                };
+
+                (penalty, issue)
+            })
+            .collect();
+
+        // 只计入扣分最多的3个相似代币
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut issues = Vec::new();
+        let mut score: f64 = 100.0;
+        for (penalty, issue) in scored.iter().take(3) {
+            score -= penalty;
+            issues.push(issue.clone());
+        }
+        if scored.len() > 3 {
+            issues.push(format!("⚠️ 另有{}个相似代币", scored.len() - 3));
+        }
+
+        Score {
+            value: score.clamp(0.0, 100.0),
+            issues,
+        }
+    }
+
+    /// 比对候选代币
+    ///
+    /// 参照集为空时返回None
+    pub fn assess(&self, candidate: &ReferenceToken) -> Option<Score> {
+        let has_references = self
+            .references
+            .read()
+            .unwrap()
+            .keys()
+            .any(|mint| *mint != candidate.mint);
+
+        has_references.then(|| self.score(&self.find_matches(candidate)))
+    }
+
+    /// 分析代币的仿盘风险，符合条件时将其加入参照集
+    ///
+    /// 参照集为空时返回None（不参与加权）
+    pub async fn analyze(&self, token: &TokenInfo) -> Option<Score> {
+        let image_hash = self.image_hash(token).await;
+        let candidate = ReferenceToken::from_token(token, image_hash, self.kind_of(token));
+
+        let score = self.assess(&candidate);
+        if self.is_reference_eligible(token) {
+            self.record(candidate);
+        }
+
+        score
+    }
+
+    /// 元数据图片哈希（已计算过的代币复用之前的结果，失败不缓存）
+    async fn image_hash(&self, token: &TokenInfo) -> Option<u64> {
+        if let Some(hash) = self
+            .references
+            .read()
+            .unwrap()
+            .get(&token.mint)
+            .and_then(|reference| reference.image_hash)
+        {
+            return Some(hash);
+        }
+        if let Some(hash) = self.image_hashes.lock().unwrap().get(&token.mint) {
+            return hash;
+        }
+
+        let hasher = self.image_hasher.as_ref()?;
+        let uri = token.metadata_uri.as_deref().filter(|uri| !uri.is_empty())?;
+
+        match hasher.hash(uri).await {
+            Ok(hash) => {
+                self.image_hashes.lock().unwrap().insert(token.mint, hash);
+                hash
+            }
+            Err(e) => {
+                tracing::warn!("Image hash unavailable for {} via {}: {}", token.mint, hasher.name(), e);
+                None
+            }
+        }
+    }
+}
+
+impl Default for SimilarityAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn reference(name: &str, symbol: &str, kind: ReferenceKind) -> ReferenceToken {
+        ReferenceToken {
+            mint: Pubkey::new_unique(),
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            image_hash: None,
+            creator: None,
+            kind,
+            seen_at: Utc::now(),
+        }
+    }
+
+    fn token(name: &str, symbol: &str, age_minutes: u64) -> TokenInfo {
+        TokenInfo {
+            mint: Pubkey::new_unique(),
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            age_minutes,
+            ..Default::default()
+        }
+    }
+
+    fn gradient(width: u32, height: u32, reversed: bool) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, _| {
+            let value = (x * 255 / (width - 1)) as u8;
+            Luma([if reversed { 255 - value } else { value }])
+        }))
+    }
+
+    #[test]
+    fn test_text_similarity_handles_lookalikes() {
+        assert_eq!(normalize("B0NK"), normalize("bonk"));
+        assert_eq!(normalize("ΡΕΡΕ"), "pepe");
+        assert!(text_similarity("BabyBonk", "BONK") >= 0.9);
+        assert!(text_similarity("Dogwifhat", "Dogwifhatt") > 0.85);
+        assert!(text_similarity("Jupiter", "Bonk") < 0.5);
+    }
+
+    #[test]
+    fn test_dhash_tolerates_rescaling() {
+        let original = dhash(&gradient(64, 64, false));
+        let rescaled = dhash(&gradient(200, 120, false));
+        let reversed = dhash(&gradient(64, 64, true));
+
+        assert!(hamming_distance(original, rescaled) <= 4);
+        assert!(hamming_distance(original, reversed) > 32);
+    }
+
+    #[test]
+    fn test_impersonation_of_trending_token_is_penalized() {
+        let analyzer = SimilarityAnalyzer::new();
+        let mut bonk = reference("Bonk", "BONK", ReferenceKind::Trending);
+        bonk.image_hash = Some(0xF0F0_F0F0_F0F0_F0F0);
+        analyzer.add_reference(bonk);
+        analyzer.add_reference(reference("Jupiter", "JUP", ReferenceKind::Recent));
+
+        let mut copycat = reference("B0nk", "B0NK", ReferenceKind::Recent);
+        copycat.image_hash = Some(0xF0F0_F0F0_F0F0_F0F1);
+        let score = analyzer.assess(&copycat).unwrap();
+
+        assert!(score.value <= 40.0);
+        assert!(score.issues[0].contains("仿冒热门代币 BONK"));
+        assert!(score.issues[0].contains("图片相同"));
+
+        // 只比对，不写入参照集
+        assert_eq!(analyzer.reference_count(), 2);
+    }
+
+    #[test]
+    fn test_relaunch_of_rugged_token_by_same_creator() {
+        let analyzer = SimilarityAnalyzer::new();
+        let creator = Pubkey::new_unique();
+        let mut rugged = reference("Moon Cat", "MCAT", ReferenceKind::Recent);
+        rugged.creator = Some(creator);
+        let rugged_mint = rugged.mint;
+        analyzer.add_reference(rugged);
+        assert!(analyzer.mark_rugged(&rugged_mint));
+
+        let mut relaunch = reference("MoonCat", "MCAT", ReferenceKind::Recent);
+        relaunch.creator = Some(creator);
+        let score = analyzer.assess(&relaunch).unwrap();
+
+        assert_eq!(score.value, 45.0);
+        assert!(score.issues[0].contains("rug后重新发币"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_without_references() {
+        let analyzer = SimilarityAnalyzer::new();
+        let first = token("Alpha", "ALPHA", 120);
+        assert!(analyzer.analyze(&first).await.is_none());
+
+        // 与自身不比对，第二个无关代币得满分
+        let second = analyzer.analyze(&token("Zebra Finance", "ZBR", 120)).await.unwrap();
+        assert_eq!(second.value, 100.0);
+        assert!(second.issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_young_tokens_are_not_references() {
+        let analyzer = SimilarityAnalyzer::new();
+
+        // 刚上线的仿盘先出现，不应成为正主的参照
+        assert!(analyzer.analyze(&token("Moon Dog", "MDOG", 5)).await.is_none());
+        assert_eq!(analyzer.reference_count(), 0);
+
+        let mut trending = token("Sun Cat", "SCAT", 5);
+        trending.volume_1h = 80_000.0;
+        analyzer.analyze(&trending).await;
+        analyzer.analyze(&token("Star Fish", "SFSH", 90)).await;
+        assert_eq!(analyzer.reference_count(), 2);
+    }
+
+    #[test]
+    fn test_rugged_references_expire() {
+        let analyzer = SimilarityAnalyzer::new().with_rugged_window(Duration::days(7));
+        let rugged = token("Moon Cat", "MCAT", 5);
+        analyzer.record_rugged(&rugged);
+        assert_eq!(analyzer.reference_count(), 1);
+
+        let mut old = reference("Old Rug", "ORUG", ReferenceKind::Rugged);
+        old.seen_at = Utc::now() - Duration::days(8);
+        analyzer.add_reference(old);
+
+        assert_eq!(analyzer.reference_count(), 1);
+        assert!(analyzer.mark_rugged(&rugged.mint));
+    }
+
+    #[test]
+    fn test_seeded_rugged_references() {
+        let analyzer = SimilarityAnalyzer::new().with_rugged_window(Duration::days(7));
+
+        let mut recent = reference("Moon Cat", "MCAT", ReferenceKind::Recent);
+        recent.seen_at = Utc::now() - Duration::days(2);
+        let mut expired = reference("Old Rug", "ORUG", ReferenceKind::Rugged);
+        expired.seen_at = Utc::now() - Duration::days(8);
+
+        assert_eq!(analyzer.seed_rugged([recent, expired]), 1);
+        assert_eq!(analyzer.reference_count(), 1);
+
+        let score = analyzer.assess(&reference("MoonCat", "MCAT", ReferenceKind::Recent)).unwrap();
+        assert!(score.value < 100.0);
+    }
+
+    #[test]
+    fn test_decode_rejects_oversized_images() {
+        let mut small = Vec::new();
+        gradient(64, 64, false)
+            .write_to(&mut Cursor::new(&mut small), image::ImageFormat::Png)
+            .unwrap();
+        assert!(decode_and_hash(&small).is_ok());
+
+        let mut wide = Vec::new();
+        DynamicImage::ImageLuma8(GrayImage::new(MAX_IMAGE_DIMENSION + 1, 1))
+            .write_to(&mut Cursor::new(&mut wide), image::ImageFormat::Png)
+            .unwrap();
+        assert!(decode_and_hash(&wide).is_err());
+    }
+}
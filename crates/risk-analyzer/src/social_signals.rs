@@ -0,0 +1,304 @@
+use solsniper_core::{Error, Result, TokenInfo};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// 代币的社交信号
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SocialSignals {
+    #[serde(default)]
+    pub twitter_mentions: u64,
+    /// 官方推特粉丝数
+    #[serde(default)]
+    pub twitter_followers: Option<u64>,
+    #[serde(default)]
+    pub telegram_members: u64,
+    #[serde(default)]
+    pub discord_members: Option<u64>,
+    /// 情绪分（0-1，0.5为中性）
+    #[serde(default = "neutral_sentiment")]
+    pub sentiment_score: f64,
+    /// 提及者中疑似机器人账号的比例（0-1，未知为None）
+    #[serde(default)]
+    pub bot_ratio: Option<f64>,
+}
+
+fn neutral_sentiment() -> f64 {
+    0.5
+}
+
+impl SocialSignals {
+    /// 写入TokenInfo的社交字段
+    pub fn apply_to(&self, token: &mut TokenInfo) {
+        token.twitter_mentions = self.twitter_mentions;
+        token.telegram_members = self.telegram_members;
+        token.discord_members = self.discord_members;
+        token.sentiment_score = self.sentiment_score.clamp(0.0, 1.0);
+    }
+
+    /// 从TokenInfo已有字段构造（无提供者时使用）
+    pub fn from_token(token: &TokenInfo) -> Self {
+        Self {
+            twitter_mentions: token.twitter_mentions,
+            twitter_followers: None,
+            telegram_members: token.telegram_members,
+            discord_members: token.discord_members,
+            sentiment_score: token.sentiment_score,
+            bot_ratio: None,
+        }
+    }
+}
+
+/// 社交信号提供者trait
+#[async_trait]
+pub trait SocialSignalProvider: Send + Sync {
+    /// 获取mint的社交信号
+    async fn fetch(&self, mint: &Pubkey) -> Result<SocialSignals>;
+
+    /// 提供者名称
+    fn name(&self) -> &str;
+}
+
+/// HTTP社交信号提供者
+///
+/// 请求`{base_url}/tokens/{mint}/social`，返回`SocialSignals`JSON
+pub struct HttpSocialProvider {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl HttpSocialProvider {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(3))
+            .build()
+            .unwrap();
+
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+        }
+    }
+
+    /// 设置API Key（通过`X-API-KEY`请求头发送）
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+}
+
+#[async_trait]
+impl SocialSignalProvider for HttpSocialProvider {
+    async fn fetch(&self, mint: &Pubkey) -> Result<SocialSignals> {
+        let url = format!("{}/tokens/{}/social", self.base_url, mint);
+
+        let mut request = self.client.get(&url);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("X-API-KEY", api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::Internal(format!("Social signal request error: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Internal(format!(
+                "Social signal fetch error: {} ({})",
+                response.status(),
+                url
+            )));
+        }
+
+        response
+            .json::<SocialSignals>()
+            .await
+            .map_err(|e| Error::Serialization(format!("Social signal JSON parse error: {}", e)))
+    }
+
+    fn name(&self) -> &str {
+        "http"
+    }
+}
+
+/// 带缓存的社交信号提供者
+///
+/// 社交数据变化较慢，TTL内重复评估同一mint时不再请求上游；
+/// 失败结果也短暂缓存，避免上游不可用时每次评估都等待超时
+pub struct CachedSocialProvider {
+    inner: Arc<dyn SocialSignalProvider>,
+    entries: RwLock<HashMap<Pubkey, (Instant, Option<SocialSignals>)>>,
+    ttl: Duration,
+    failure_ttl: Duration,
+    max_entries: usize,
+}
+
+impl CachedSocialProvider {
+    pub fn new(inner: Arc<dyn SocialSignalProvider>) -> Self {
+        Self {
+            inner,
+            entries: RwLock::new(HashMap::new()),
+            ttl: Duration::from_secs(300),
+            failure_ttl: Duration::from_secs(30),
+            max_entries: 10_000,
+        }
+    }
+
+    /// 设置成功/失败结果的缓存时间
+    pub fn with_ttl(mut self, ttl: Duration, failure_ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self.failure_ttl = failure_ttl;
+        self
+    }
+
+    fn lookup(&self, mint: &Pubkey) -> Option<Option<SocialSignals>> {
+        let entries = self.entries.read().unwrap();
+        let (fetched_at, signals) = entries.get(mint)?;
+        let ttl = if signals.is_some() { self.ttl } else { self.failure_ttl };
+
+        (fetched_at.elapsed() < ttl).then(|| signals.clone())
+    }
+
+    fn store(&self, mint: Pubkey, signals: Option<SocialSignals>) {
+        let mut entries = self.entries.write().unwrap();
+        if entries.len() >= self.max_entries {
+            let ttl = self.ttl;
+            entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < ttl);
+        }
+        entries.insert(mint, (Instant::now(), signals));
+    }
+}
+
+#[async_trait]
+impl SocialSignalProvider for CachedSocialProvider {
+    async fn fetch(&self, mint: &Pubkey) -> Result<SocialSignals> {
+        match self.lookup(mint) {
+            Some(Some(signals)) => return Ok(signals),
+            Some(None) => {
+                return Err(Error::NotFound(format!(
+                    "Social signals for {} recently failed via {}",
+                    mint,
+                    self.inner.name()
+                )))
+            }
+            None => {}
+        }
+
+        let result = self.inner.fetch(mint).await;
+        self.store(*mint, result.as_ref().ok().cloned());
+        result
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+/// 本地fixture社交信号提供者
+///
+/// 用于测试和离线回放，按mint返回预置的信号
+#[derive(Default)]
+pub struct FixtureSocialProvider {
+    fixtures: HashMap<String, SocialSignals>,
+}
+
+impl FixtureSocialProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从JSON文件加载fixture（对象：mint -> 社交信号）
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read social fixtures {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+
+        let fixtures: HashMap<String, SocialSignals> = serde_json::from_str(&content)
+            .map_err(|e| Error::Serialization(format!("Invalid social fixtures: {}", e)))?;
+
+        Ok(Self { fixtures })
+    }
+
+    /// 添加一条fixture
+    pub fn with_fixture(mut self, mint: Pubkey, signals: SocialSignals) -> Self {
+        self.fixtures.insert(mint.to_string(), signals);
+        self
+    }
+}
+
+#[async_trait]
+impl SocialSignalProvider for FixtureSocialProvider {
+    async fn fetch(&self, mint: &Pubkey) -> Result<SocialSignals> {
+        self.fixtures
+            .get(&mint.to_string())
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("No social fixture for {}", mint)))
+    }
+
+    fn name(&self) -> &str {
+        "fixture"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 记录调用次数的提供者
+    struct CountingProvider {
+        calls: AtomicUsize,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl SocialSignalProvider for CountingProvider {
+        async fn fetch(&self, mint: &Pubkey) -> Result<SocialSignals> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                return Err(Error::Internal(format!("unavailable: {}", mint)));
+            }
+            Ok(SocialSignals { twitter_mentions: 42, ..Default::default() })
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_provider_reuses_results() {
+        let inner = Arc::new(CountingProvider { calls: AtomicUsize::new(0), fail: false });
+        let provider = CachedSocialProvider::new(inner.clone());
+        let mint = Pubkey::new_unique();
+
+        assert_eq!(provider.fetch(&mint).await.unwrap().twitter_mentions, 42);
+        assert_eq!(provider.fetch(&mint).await.unwrap().twitter_mentions, 42);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+
+        provider.fetch(&Pubkey::new_unique()).await.unwrap();
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cached_provider_backs_off_after_failure() {
+        let inner = Arc::new(CountingProvider { calls: AtomicUsize::new(0), fail: true });
+        let provider = CachedSocialProvider::new(inner.clone());
+        let mint = Pubkey::new_unique();
+
+        assert!(provider.fetch(&mint).await.is_err());
+        assert!(provider.fetch(&mint).await.is_err());
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+}
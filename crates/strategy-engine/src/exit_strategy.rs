@@ -1,6 +1,7 @@
-use solsniper_core::{Error, Result, TokenInfo};
+use solsniper_core::{CandleBuilder, Error, Result, TokenInfo};
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::time::Instant;
 
 /// 退出策略类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -196,6 +197,14 @@ pub struct ExitStrategyManager {
     trailing_stop_activated: bool,
     breakeven_activated: bool,
     scale_out_executed: Vec<bool>, // 跟踪哪些分批退出已执行
+    candles: Option<Arc<CandleBuilder>>, // 链上K线（可选）
+}
+
+impl Default for ExitStrategyManager {
+    /// 创建默认配置的管理器
+    fn default() -> Self {
+        Self::new(ExitStrategyConfig::default())
+    }
 }
 
 impl ExitStrategyManager {
@@ -208,12 +217,14 @@ impl ExitStrategyManager {
             trailing_stop_activated: false,
             breakeven_activated: false,
             scale_out_executed: vec![false; scale_out_count],
+            candles: None,
         }
     }
 
-    /// 创建默认配置的管理器
-    pub fn default() -> Self {
-        Self::new(ExitStrategyConfig::default())
+    /// 使用链上K线数据做短周期指标判断
+    pub fn with_candles(mut self, candles: Arc<CandleBuilder>) -> Self {
+        self.candles = Some(candles);
+        self
     }
 
     /// 开仓
@@ -232,7 +243,8 @@ impl ExitStrategyManager {
 
     /// 检查是否应该退出
     pub fn check_exit(&mut self, current_price: f64, token: &TokenInfo) -> Result<ExitSignal> {
-        let position = self.position.as_mut().ok_or_else(|| {
+        // 检查期间取出持仓，各检查项需要可变借用self
+        let mut position = self.position.take().ok_or_else(|| {
             Error::Internal("No active position to check exit".to_string())
         })?;
 
@@ -240,42 +252,42 @@ impl ExitStrategyManager {
         position.update_price(current_price);
 
         let mut signal = ExitSignal::no_exit();
-        let pnl_pct = position.unrealized_pnl_pct();
 
         // 1. 检查固定止损
-        if let Some(sl_signal) = self.check_stop_loss(position) {
+        if let Some(sl_signal) = self.check_stop_loss(&position) {
             signal = Self::merge_signals(signal, sl_signal);
         }
 
         // 2. 检查固定止盈
-        if let Some(tp_signal) = self.check_take_profit(position) {
+        if let Some(tp_signal) = self.check_take_profit(&position) {
             signal = Self::merge_signals(signal, tp_signal);
         }
 
         // 3. 检查追踪止损
-        if let Some(trail_signal) = self.check_trailing_stop(position) {
+        if let Some(trail_signal) = self.check_trailing_stop(&position) {
             signal = Self::merge_signals(signal, trail_signal);
         }
 
         // 4. 检查时间退出
-        if let Some(time_signal) = self.check_time_based_exit(position) {
+        if let Some(time_signal) = self.check_time_based_exit(&position) {
             signal = Self::merge_signals(signal, time_signal);
         }
 
         // 5. 检查分批退出
-        if let Some(scale_signal) = self.check_scaled_exit(position) {
+        if let Some(scale_signal) = self.check_scaled_exit(&position) {
             signal = Self::merge_signals(signal, scale_signal);
         }
 
         // 6. 检查保本止损
-        if let Some(be_signal) = self.check_breakeven_protection(position) {
+        if let Some(be_signal) = self.check_breakeven_protection(&position) {
             signal = Self::merge_signals(signal, be_signal);
         }
 
         // 7. 检查基于指标的退出
-        if let Some(indicator_signal) = self.check_indicator_exit(position, token) {
+        if let Some(indicator_signal) = self.check_indicator_exit(token) {
             signal = Self::merge_signals(signal, indicator_signal);
         }
+        self.position = Some(position);
 
         if signal.should_exit {
             tracing::info!(
@@ -472,7 +484,7 @@ impl ExitStrategyManager {
     }
 
     /// 检查基于指标的退出
-    fn check_indicator_exit(&self, position: &PositionTracker, token: &TokenInfo) -> Option<ExitSignal> {
+    fn check_indicator_exit(&self, token: &TokenInfo) -> Option<ExitSignal> {
         let mut reasons = Vec::new();
         let mut should_exit = false;
 
@@ -497,6 +509,28 @@ impl ExitStrategyManager {
             should_exit = true;
         }
 
+        // 链上K线：5分钟内急跌或卖盘主导
+        if let Some(candles) = &self.candles {
+            let window = chrono::Duration::minutes(5);
+
+            if let Some(change) = candles.price_change_pct(&token.mint, window) {
+                if change < -10.0 {
+                    reasons.push(format!("Sharp price drop: {:.1}% in 5m", change));
+                    should_exit = true;
+                }
+            }
+
+            let volume = candles.volume(&token.mint, window);
+            if volume.volume_sol > 0.0 && volume.sell_volume_sol / volume.volume_sol > 0.8 {
+                reasons.push(format!(
+                    "High sell volume: {:.1}% of {:.2} SOL in 5m",
+                    volume.sell_volume_sol / volume.volume_sol * 100.0,
+                    volume.volume_sol
+                ));
+                should_exit = true;
+            }
+        }
+
         if should_exit {
             Some(ExitSignal {
                 should_exit: true,
@@ -584,7 +618,6 @@ mod tests {
     use super::*;
 
     fn create_test_token() -> TokenInfo {
-        use chrono::Utc;
         TokenInfo {
             mint: solana_sdk::pubkey::Pubkey::new_unique(),
             symbol: "TEST".to_string(),
@@ -593,7 +626,6 @@ mod tests {
             total_supply: 1_000_000,
             circulating_supply: 800_000,
             price_usd: 0.001,
-            market_cap_usd: 800.0,
             liquidity_sol: 50.0,
             liquidity_usd: 7500.0,
             volume_24h: 1000.0,
@@ -609,25 +641,15 @@ mod tests {
             dex: "Raydium".to_string(),
             pool_address: Some(solana_sdk::pubkey::Pubkey::new_unique()),
             creator: Some(solana_sdk::pubkey::Pubkey::new_unique()),
-            age_minutes: 60.0,
+            age_minutes: 60,
             age_hours: 1.0,
             buy_tax: 0.0,
             sell_tax: 0.0,
-            is_renounced: true,
-            is_frozen: false,
             txns_1h_buys: 50,
             txns_1h_sells: 30,
             txns_1h_total: 80,
-            txns_6h_buys: 200,
-            txns_6h_sells: 150,
-            txns_6h_total: 350,
             volatility_1h: 0.05,
-            volatility_6h: 0.08,
-            social_twitter: Some("@test".to_string()),
-            social_telegram: None,
-            social_website: None,
-            discovered_at: Utc::now(),
-            updated_at: Utc::now(),
+            ..Default::default()
         }
     }
 
@@ -711,11 +733,11 @@ mod tests {
         let mut tracker = PositionTracker::new(1.0, 100.0);
 
         tracker.update_price(1.2);
-        assert_eq!(tracker.unrealized_pnl_pct(), 20.0);
+        assert!((tracker.unrealized_pnl_pct() - 20.0).abs() < 1e-9);
 
         tracker.update_price(0.9);
-        assert_eq!(tracker.unrealized_pnl_pct(), -10.0);
-        assert_eq!(tracker.drawdown_from_high(), 25.0); // (1.2 - 0.9) / 1.2
+        assert!((tracker.unrealized_pnl_pct() + 10.0).abs() < 1e-9);
+        assert!((tracker.drawdown_from_high() - 25.0).abs() < 1e-9); // (1.2 - 0.9) / 1.2
     }
 
     #[test]
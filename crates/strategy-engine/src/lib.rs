@@ -18,6 +18,12 @@ pub struct StrategyEngine {
     strategies: Vec<Box<dyn Strategy>>,
 }
 
+impl Default for StrategyEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StrategyEngine {
     pub fn new() -> Self {
         Self {
@@ -84,6 +90,7 @@ mod tests {
             liquidity_usd: 5000.0,
             lp_locked: true,
             lp_burned: true,
+            lp_unlock_at: None,
             total_supply: 1_000_000_000,
             circulating_supply: 1_000_000_000,
             holders_count: 500,
@@ -92,6 +99,7 @@ mod tests {
             top50_ratio: 0.75,
             mint_authority_revoked: true,
             freeze_authority_revoked: true,
+            extensions: Vec::new(),
             buy_tax: 0.0,
             sell_tax: 0.0,
             created_at: Utc::now(),
@@ -113,8 +121,15 @@ mod tests {
             discord_members: Some(100),
             sentiment_score: 0.75,
             is_verified: false,
+            metadata_mutable: None,
+            metadata_update_authority: None,
+            metadata_uri: None,
             pool_address: Some(Pubkey::new_unique()),
             dex: "Raydium".to_string(),
+            bonding_curve_progress: None,
+            graduated_at: None,
+            sellable: None,
+            creator: None,
         };
 
         let risk_score = solsniper_core::RiskScore {
@@ -126,6 +141,7 @@ mod tests {
                 sentiment: solsniper_core::Score { value: 75.0, issues: vec![] },
                 similarity: solsniper_core::Score { value: 70.0, issues: vec![] },
                 behavior: solsniper_core::Score { value: 85.0, issues: vec![] },
+                deployer: solsniper_core::Score { value: 90.0, issues: vec![] },
             },
             confidence: 0.85,
             recommendation: solsniper_core::Recommendation::StrongBuy,
@@ -140,6 +156,6 @@ mod tests {
                 m.strategy_name, m.position_size, m.expected_profit);
         }
 
-        assert!(matches.len() > 0);
+        assert!(!matches.is_empty());
     }
 }
@@ -1,6 +1,12 @@
 /// 策略匹配器（占位符）
 pub struct StrategyMatcher;
 
+impl Default for StrategyMatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StrategyMatcher {
     pub fn new() -> Self {
         Self
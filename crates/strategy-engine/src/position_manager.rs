@@ -100,6 +100,12 @@ pub struct TradeHistory {
     pub consecutive_losses: u32,
 }
 
+impl Default for TradeHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TradeHistory {
     pub fn new() -> Self {
         Self {
@@ -148,6 +154,13 @@ pub struct PositionManager {
     trade_history: TradeHistory,
 }
 
+impl Default for PositionManager {
+    /// 创建默认配置的管理器
+    fn default() -> Self {
+        Self::new(PositionManagerConfig::default())
+    }
+}
+
 impl PositionManager {
     /// 创建新的仓位管理器
     pub fn new(config: PositionManagerConfig) -> Self {
@@ -157,11 +170,6 @@ impl PositionManager {
         }
     }
 
-    /// 创建默认配置的管理器
-    pub fn default() -> Self {
-        Self::new(PositionManagerConfig::default())
-    }
-
     /// 计算推荐仓位大小
     ///
     /// # 参数
@@ -284,7 +292,7 @@ impl PositionManager {
         let fractional_kelly = kelly_percentage * self.config.kelly_fraction;
 
         // 确保非负且合理
-        let safe_kelly = fractional_kelly.max(0.0).min(0.5);
+        let safe_kelly = fractional_kelly.clamp(0.0, 0.5);
 
         account_balance * safe_kelly
     }
@@ -448,7 +456,6 @@ impl PositionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
 
     fn create_test_token() -> TokenInfo {
         TokenInfo {
@@ -459,7 +466,6 @@ mod tests {
             total_supply: 1_000_000,
             circulating_supply: 800_000,
             price_usd: 0.001,
-            market_cap_usd: 800.0,
             liquidity_sol: 50.0,
             liquidity_usd: 7500.0,
             volume_24h: 1000.0,
@@ -475,25 +481,15 @@ mod tests {
             dex: "Raydium".to_string(),
             pool_address: Some(solana_sdk::pubkey::Pubkey::new_unique()),
             creator: Some(solana_sdk::pubkey::Pubkey::new_unique()),
-            age_minutes: 60.0,
+            age_minutes: 60,
             age_hours: 1.0,
             buy_tax: 0.0,
             sell_tax: 0.0,
-            is_renounced: true,
-            is_frozen: false,
             txns_1h_buys: 50,
             txns_1h_sells: 30,
             txns_1h_total: 80,
-            txns_6h_buys: 200,
-            txns_6h_sells: 150,
-            txns_6h_total: 350,
             volatility_1h: 0.05,
-            volatility_6h: 0.08,
-            social_twitter: Some("@test".to_string()),
-            social_telegram: None,
-            social_website: None,
-            discovered_at: Utc::now(),
-            updated_at: Utc::now(),
+            ..Default::default()
         }
     }
 
@@ -623,12 +619,12 @@ mod tests {
         let mut manager = PositionManager::new(config);
         let token = create_test_token();
 
-        // Initial position
-        let pos1 = manager.calculate_position_size(100.0, &token, 70.0).unwrap();
+        // Initial position (kept below max_position_size_sol so doubling isn't capped)
+        let pos1 = manager.calculate_position_size(20.0, &token, 70.0).unwrap();
 
         // After one loss
         manager.record_trade(-1.0);
-        let pos2 = manager.calculate_position_size(100.0, &token, 70.0).unwrap();
+        let pos2 = manager.calculate_position_size(20.0, &token, 70.0).unwrap();
 
         // Position should roughly double after loss
         assert!(pos2.amount_sol > pos1.amount_sol * 1.5);
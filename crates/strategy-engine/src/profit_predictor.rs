@@ -34,7 +34,7 @@ pub struct HistoricalTrade {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenFeatures {
     pub liquidity_sol: f64,
-    pub holders_count: u32,
+    pub holders_count: u64,
     pub age_hours: f64,
     pub volume_1h: f64,
     pub price_change_1h: f64,
@@ -186,6 +186,12 @@ pub struct ProfitPredictor {
     min_sample_size: usize,
 }
 
+impl Default for ProfitPredictor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ProfitPredictor {
     /// 创建新的预测器
     pub fn new() -> Self {
@@ -347,7 +353,7 @@ impl ProfitPredictor {
         let mut sorted = returns.to_vec();
         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
-        let median_return = if sorted.len() % 2 == 0 {
+        let median_return = if sorted.len().is_multiple_of(2) {
             (sorted[sorted.len() / 2 - 1] + sorted[sorted.len() / 2]) / 2.0
         } else {
             sorted[sorted.len() / 2]
@@ -452,7 +458,7 @@ mod tests {
     fn create_test_trade(
         return_pct: f64,
         liquidity: f64,
-        holders: u32,
+        holders: u64,
         strategy: &str,
     ) -> HistoricalTrade {
         HistoricalTrade {
@@ -477,7 +483,6 @@ mod tests {
     }
 
     fn create_test_token() -> TokenInfo {
-        use chrono::Utc;
         TokenInfo {
             mint: solana_sdk::pubkey::Pubkey::new_unique(),
             symbol: "TEST".to_string(),
@@ -486,7 +491,6 @@ mod tests {
             total_supply: 1_000_000,
             circulating_supply: 800_000,
             price_usd: 0.001,
-            market_cap_usd: 800.0,
             liquidity_sol: 50.0,
             liquidity_usd: 7500.0,
             volume_24h: 1000.0,
@@ -502,25 +506,15 @@ mod tests {
             dex: "Raydium".to_string(),
             pool_address: Some(solana_sdk::pubkey::Pubkey::new_unique()),
             creator: Some(solana_sdk::pubkey::Pubkey::new_unique()),
-            age_minutes: 60.0,
+            age_minutes: 60,
             age_hours: 1.0,
             buy_tax: 0.0,
             sell_tax: 0.0,
-            is_renounced: true,
-            is_frozen: false,
             txns_1h_buys: 50,
             txns_1h_sells: 30,
             txns_1h_total: 80,
-            txns_6h_buys: 200,
-            txns_6h_sells: 150,
-            txns_6h_total: 350,
             volatility_1h: 0.05,
-            volatility_6h: 0.08,
-            social_twitter: Some("@test".to_string()),
-            social_telegram: None,
-            social_website: None,
-            discovered_at: Utc::now(),
-            updated_at: Utc::now(),
+            ..Default::default()
         }
     }
 
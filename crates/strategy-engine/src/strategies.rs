@@ -14,6 +14,12 @@ pub trait Strategy: Send + Sync {
 /// 策略1: 早鸟极速狙击
 pub struct EarlyBirdStrategy;
 
+impl Default for EarlyBirdStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EarlyBirdStrategy {
     pub fn new() -> Self {
         Self
@@ -51,6 +57,12 @@ impl Strategy for EarlyBirdStrategy {
 /// 策略2: 流动性追踪策略
 pub struct LiquidityHunterStrategy;
 
+impl Default for LiquidityHunterStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LiquidityHunterStrategy {
     pub fn new() -> Self {
         Self
@@ -88,6 +100,12 @@ impl Strategy for LiquidityHunterStrategy {
 /// 策略3: 交易量爆发策略
 pub struct VolumeExplosionStrategy;
 
+impl Default for VolumeExplosionStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VolumeExplosionStrategy {
     pub fn new() -> Self {
         Self
@@ -126,6 +144,12 @@ impl Strategy for VolumeExplosionStrategy {
 /// 策略4: 稳健价值投资
 pub struct ValueInvestingStrategy;
 
+impl Default for ValueInvestingStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ValueInvestingStrategy {
     pub fn new() -> Self {
         Self
@@ -169,6 +193,12 @@ impl Strategy for ValueInvestingStrategy {
 /// 策略5: 反向套利策略
 pub struct ContrarianArbitrageStrategy;
 
+impl Default for ContrarianArbitrageStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ContrarianArbitrageStrategy {
     pub fn new() -> Self {
         Self
@@ -206,6 +236,12 @@ impl Strategy for ContrarianArbitrageStrategy {
 /// 策略6: 时间套利策略
 pub struct TimeBasedArbitrageStrategy;
 
+impl Default for TimeBasedArbitrageStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TimeBasedArbitrageStrategy {
     pub fn new() -> Self {
         Self
@@ -1,4 +1,4 @@
-use solsniper_core::{Error, Result, TokenInfo, RiskScore, StrategyMatch};
+use solsniper_core::{Error, Result, RiskScore, StrategyMatch};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -90,6 +90,12 @@ pub struct StrategyPriorityManager {
     max_combined_strategies: usize,
 }
 
+impl Default for StrategyPriorityManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StrategyPriorityManager {
     /// 创建新的管理器
     pub fn new() -> Self {
@@ -456,6 +462,10 @@ mod tests {
                     value: total,
                     issues: vec![],
                 },
+                deployer: Score {
+                    value: total,
+                    issues: vec![],
+                },
             },
             confidence: 0.85,
             recommendation: Recommendation::StrongBuy,
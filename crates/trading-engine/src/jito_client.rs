@@ -1,6 +1,12 @@
 /// Jito客户端（MEV保护）
 pub struct JitoClient;
 
+impl Default for JitoClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl JitoClient {
     pub fn new() -> Self {
         Self
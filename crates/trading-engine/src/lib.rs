@@ -3,8 +3,9 @@ pub mod transaction_builder;
 pub mod jito_client;
 pub mod slippage_protector;
 pub mod mev_protector;
+pub mod swap_instructions;
 
-use solsniper_core::{Result, TokenInfo};
+use solsniper_core::{Error, Result, SolPriceService, TokenInfo};
 use solana_sdk::{
     pubkey::Pubkey,
     signature::Signature,
@@ -19,6 +20,7 @@ pub use wallet_manager::WalletManager;
 pub use transaction_builder::TransactionBuilder;
 pub use slippage_protector::{SlippageProtector, SwapQuote};
 pub use mev_protector::{MevProtector, MevPriority, ProtectedTransaction};
+pub use swap_instructions::{RaydiumPoolKeys, RaydiumSwapProvider, SwapInstructionProvider, SwapLeg};
 
 /// 执行选项
 #[derive(Clone, Debug)]
@@ -97,6 +99,9 @@ pub struct TradingEngine {
 
     /// 默认执行选项
     default_options: ExecutionOptions,
+
+    /// SOL/USD参考价格服务
+    sol_price: Option<Arc<SolPriceService>>,
 }
 
 impl TradingEngine {
@@ -112,9 +117,18 @@ impl TradingEngine {
             mev_protector: MevProtector::default(), // JITO enabled, 0.001 SOL min tip
             transaction_cache: Arc::new(DashMap::new()),
             default_options: ExecutionOptions::default(),
+            sol_price: None,
         }
     }
 
+    /// 设置SOL/USD参考价格服务
+    ///
+    /// 买入前需要参考价格计算最小输出；服务应已通过`start_refresh_task`定时刷新
+    pub fn with_sol_price_service(mut self, service: Arc<SolPriceService>) -> Self {
+        self.sol_price = Some(service);
+        self
+    }
+
     /// 设置自定义滑点保护器
     pub fn with_slippage_protector(mut self, protector: SlippageProtector) -> Self {
         self.slippage_protector = protector;
@@ -216,6 +230,20 @@ impl TradingEngine {
         })
     }
 
+    /// 主钱包持有的代币数量（原始单位）
+    pub async fn token_balance(&self, mint: &Pubkey) -> Result<u64> {
+        let owner = self.wallet_manager.get_primary_pubkey();
+        let ata = swap_instructions::associated_token_address(&owner, mint);
+
+        let balance = self.rpc_client
+            .get_token_account_balance(&ata)
+            .map_err(|e| Error::Rpc(format!("Failed to get token balance for {}: {}", mint, e)))?;
+
+        balance.amount.parse().map_err(|e| {
+            Error::Rpc(format!("Invalid token balance {} for {}: {}", balance.amount, mint, e))
+        })
+    }
+
     /// 执行卖出交易
     pub async fn execute_sell(
         &self,
@@ -349,11 +377,12 @@ impl TradingEngine {
                 })?;
 
                 self.transaction_builder
+                    .clone()
                     .with_priority_fee(options.priority_fee)
                     .build_raydium_swap(
                         wallet,
                         &pool_id,
-                        &spl_token::native_mint::id(), // WSOL
+                        &swap_instructions::wsol_mint(),
                         &token.mint,
                         amount_lamports,
                         min_tokens_out,
@@ -367,6 +396,7 @@ impl TradingEngine {
                 })?;
 
                 self.transaction_builder
+                    .clone()
                     .with_priority_fee(options.priority_fee)
                     .build_orca_swap(
                         wallet,
@@ -429,21 +459,35 @@ impl TradingEngine {
         tip_lamports: u64,
     ) -> Result<TransactionResult> {
         // TODO: 实际JITO Bundle实现
-        tracing::warn!("JITO execution not fully implemented, falling back to RPC");
+        tracing::warn!(
+            "JITO execution not fully implemented, falling back to RPC (tip {} lamports not paid)",
+            tip_lamports
+        );
 
         self.execute_via_rpc(transaction, &self.default_options).await
     }
 
+    /// 当前SOL/USD价格
+    ///
+    /// 缓存报价过期时向各来源刷新；未配置服务或没有可用报价时返回错误，
+    /// 不使用硬编码价格下单
+    async fn sol_price_usd(&self) -> Result<f64> {
+        let service = self.sol_price.as_ref().ok_or_else(|| {
+            Error::Config("SOL/USD price service not configured".to_string())
+        })?;
+
+        service.sol_usd().await
+    }
+
     /// 计算最小输出代币数量（滑点保护）
     fn calculate_min_tokens_out(
         &self,
         amount_sol: f64,
+        sol_price_usd: f64,
         token_price_usd: f64,
         max_slippage_bps: u16,
     ) -> u64 {
-        const SOL_PRICE_USD: f64 = 150.0; // 应从价格API获取
-
-        let expected_usd = amount_sol * SOL_PRICE_USD;
+        let expected_usd = amount_sol * sol_price_usd;
         let expected_tokens = expected_usd / token_price_usd;
 
         // 应用滑点
@@ -464,6 +508,7 @@ impl TradingEngine {
         options: &ExecutionOptions,
     ) -> Result<u64> {
         let amount_in_lamports = (amount_sol * 1e9) as u64;
+        let sol_price_usd = self.sol_price_usd().await?;
 
         // 尝试获取池子储备数据（如果可用）
         // 注意：实际实现需要从DEX查询储备数据
@@ -472,12 +517,14 @@ impl TradingEngine {
             // 估算储备（假设50%的流动性是SOL，50%是Token）
             let reserve_sol = (token.liquidity_sol * 0.5 * 1e9) as u64;
             let reserve_token = if token.price_usd > 0.0 {
-                let token_value_usd = token.liquidity_usd * 0.5;
+                // 用参考价格由SOL流动性换算USD，而非信任API的liquidity_usd
+                let token_value_usd = token.liquidity_sol * sol_price_usd * 0.5;
                 let token_count = token_value_usd / token.price_usd;
                 (token_count * 10f64.powi(token.decimals as i32)) as u64
             } else {
                 return Ok(self.calculate_min_tokens_out(
                     amount_sol,
+                    sol_price_usd,
                     token.price_usd,
                     options.max_slippage_bps,
                 ));
@@ -515,6 +562,7 @@ impl TradingEngine {
         tracing::debug!("Using simple price-based slippage calculation");
         Ok(self.calculate_min_tokens_out(
             amount_sol,
+            sol_price_usd,
             token.price_usd,
             options.max_slippage_bps,
         ))
@@ -528,8 +576,10 @@ impl TradingEngine {
             transaction_builder: TransactionBuilder::new()
                 .with_priority_fee(self.default_options.priority_fee),
             slippage_protector: self.slippage_protector.clone(),
+            mev_protector: self.mev_protector.clone(),
             transaction_cache: Arc::clone(&self.transaction_cache),
             default_options: self.default_options.clone(),
+            sol_price: self.sol_price.clone(),
         }
     }
 
@@ -544,7 +594,6 @@ impl TradingEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
 
     #[tokio::test]
     async fn test_trading_engine_creation() {
@@ -563,6 +612,7 @@ mod tests {
 
         let min_tokens = engine.calculate_min_tokens_out(
             1.0,       // 1 SOL
+            150.0,     // SOL price
             0.00001,   // token price
             300,       // 3% slippage
         );
@@ -571,4 +621,20 @@ mod tests {
         // With 3% slippage: 15,000,000 * 0.97 = 14,550,000
         assert!(min_tokens > 14_000_000_000_000_000);
     }
+
+    #[tokio::test]
+    async fn test_missing_sol_price_is_an_error() {
+        let rpc_client = Arc::new(RpcClient::new("https://api.mainnet-beta.solana.com".to_string()));
+        let wallet_manager = Arc::new(WalletManager::new(Arc::clone(&rpc_client)));
+        let engine = TradingEngine::new(Arc::clone(&wallet_manager), Arc::clone(&rpc_client));
+
+        assert!(engine.sol_price_usd().await.is_err());
+
+        let service = Arc::new(solsniper_core::SolPriceService::new(vec![
+            Arc::new(solsniper_core::FixedPriceSource::new(180.0)),
+        ]));
+        let engine = TradingEngine::new(wallet_manager, rpc_client).with_sol_price_service(service);
+
+        assert_eq!(engine.sol_price_usd().await.unwrap(), 180.0);
+    }
 }
@@ -1,6 +1,5 @@
 use solsniper_core::Result;
 use solana_sdk::transaction::Transaction;
-use std::sync::Arc;
 
 /// MEV保护优先级
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -40,6 +39,7 @@ pub enum ProtectedTransaction {
 /// 1. JITO Bundle: 通过支付tip获得优先执行权
 /// 2. Priority Fee: 通过设置高优先费用提升交易优先级
 /// 3. 动态调整: 根据网络状况和优先级自动调整费用
+#[derive(Debug, Clone)]
 pub struct MevProtector {
     /// 是否启用JITO Bundle
     jito_enabled: bool,
@@ -57,6 +57,13 @@ pub struct MevProtector {
     dynamic_adjustment: bool,
 }
 
+impl Default for MevProtector {
+    /// 创建默认保护器（启用JITO，最小tip 0.001 SOL）
+    fn default() -> Self {
+        Self::new(true, 1_000_000, true) // 0.001 SOL
+    }
+}
+
 impl MevProtector {
     /// 创建新的MEV保护器
     ///
@@ -74,11 +81,6 @@ impl MevProtector {
         }
     }
 
-    /// 创建默认保护器（启用JITO，最小tip 0.001 SOL）
-    pub fn default() -> Self {
-        Self::new(true, 1_000_000, true) // 0.001 SOL
-    }
-
     /// 创建仅使用priority fee的保护器
     pub fn priority_fee_only() -> Self {
         Self::new(false, 0, true)
@@ -275,9 +277,9 @@ mod tests {
     #[test]
     fn test_default_protector() {
         let protector = MevProtector::default();
-        assert_eq!(protector.jito_enabled, true);
+        assert!(protector.jito_enabled);
         assert_eq!(protector.min_tip_lamports, 1_000_000);
-        assert_eq!(protector.dynamic_adjustment, true);
+        assert!(protector.dynamic_adjustment);
     }
 
     #[test]
@@ -15,9 +15,6 @@ pub struct SlippageProtector {
 
     /// 是否启用动态调整
     pub(crate) dynamic_adjustment: bool,
-
-    /// 动态调整因子
-    pub(crate) dynamic_factor: f64,
 }
 
 /// Swap报价
@@ -45,6 +42,13 @@ pub struct SwapQuote {
     pub reserve_out: u64,
 }
 
+impl Default for SlippageProtector {
+    /// 创建默认保护器（3%最大滑点，启用动态调整）
+    fn default() -> Self {
+        Self::new(300, true)
+    }
+}
+
 impl SlippageProtector {
     /// 创建新的滑点保护器
     ///
@@ -55,15 +59,9 @@ impl SlippageProtector {
         Self {
             max_slippage_bps,
             dynamic_adjustment,
-            dynamic_factor: 1.0,
         }
     }
 
-    /// 创建默认保护器（3%最大滑点，启用动态调整）
-    pub fn default() -> Self {
-        Self::new(300, true)
-    }
-
     /// 计算价格影响
     ///
     /// 使用恒定乘积做市商（CPMM）公式: x * y = k
@@ -0,0 +1,458 @@
+use solsniper_core::{Error, Result, RpcManager, TokenInfo};
+use async_trait::async_trait;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::slippage_protector::SlippageProtector;
+use crate::transaction_builder::TransactionBuilder;
+
+/// 一侧swap的指令及报价
+#[derive(Debug, Clone)]
+pub struct SwapLeg {
+    /// swap所需的全部指令（含ATA创建等准备指令，不含计算预算指令）
+    pub instructions: Vec<Instruction>,
+
+    /// 按当前池子状态报价的预期输出（买入为代币最小单位，卖出为lamports）
+    ///
+    /// 必须已包含本次交易的价格影响和DEX手续费，实际到账低于此值的部分视为代币税
+    pub expected_out: u64,
+
+    /// 接收代币的账户（买入时为用户的代币ATA）
+    pub token_account: Pubkey,
+}
+
+/// DEX相关的swap指令构建trait
+///
+/// 由具体DEX/聚合器实现，供真实交易和卖出模拟共用
+#[async_trait]
+pub trait SwapInstructionProvider: Send + Sync {
+    /// 用`lamports_in`买入代币
+    async fn buy_leg(&self, token: &TokenInfo, wallet: &Pubkey, lamports_in: u64) -> Result<SwapLeg>;
+
+    /// 卖出`token_amount`个代币（最小单位）
+    async fn sell_leg(&self, token: &TokenInfo, wallet: &Pubkey, token_amount: u64) -> Result<SwapLeg>;
+
+    /// DEX单边手续费（basis points）
+    fn fee_bps(&self) -> u16;
+
+    fn name(&self) -> &str;
+}
+
+/// Raydium AMM V4程序
+pub fn raydium_amm_program() -> Pubkey {
+    Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap()
+}
+
+/// Raydium AMM V4权限账户
+pub fn raydium_amm_authority() -> Pubkey {
+    Pubkey::from_str("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1").unwrap()
+}
+
+/// SPL Token程序
+pub fn token_program() -> Pubkey {
+    Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap()
+}
+
+/// Associated Token Account程序
+pub fn associated_token_program() -> Pubkey {
+    Pubkey::from_str("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL").unwrap()
+}
+
+pub fn wsol_mint() -> Pubkey {
+    Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap()
+}
+
+/// 钱包在某个mint上的ATA地址
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program().as_ref(), mint.as_ref()],
+        &associated_token_program(),
+    )
+    .0
+}
+
+/// 创建ATA（已存在时不报错）
+fn create_associated_token_account_idempotent(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: associated_token_program(),
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(token_program(), false),
+        ],
+        data: vec![1], // CreateIdempotent
+    }
+}
+
+/// 同步WSOL账户余额
+fn sync_native(account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: token_program(),
+        accounts: vec![AccountMeta::new(*account, false)],
+        data: vec![17], // SyncNative
+    }
+}
+
+/// 关闭代币账户，剩余lamports退回`destination`
+fn close_account(account: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: token_program(),
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![9], // CloseAccount
+    }
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[offset..offset + 32]);
+    Pubkey::new_from_array(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Raydium AMM V4池子swap所需的账户
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaydiumPoolKeys {
+    pub amm: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub market_program: Pubkey,
+    pub market: Pubkey,
+    pub market_bids: Pubkey,
+    pub market_asks: Pubkey,
+    pub market_event_queue: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+    pub market_vault_signer: Pubkey,
+}
+
+impl RaydiumPoolKeys {
+    /// AMM账户大小
+    pub const AMM_LEN: usize = 752;
+
+    /// OpenBook/Serum市场账户的最小长度（到asks为止）
+    pub const MARKET_MIN_LEN: usize = 349;
+
+    /// AMM账户中的市场地址（offset 528）
+    pub fn market_of(amm_data: &[u8]) -> Result<Pubkey> {
+        if amm_data.len() < Self::AMM_LEN {
+            return Err(Error::InvalidInput(format!(
+                "Raydium AMM account too short: {} bytes",
+                amm_data.len()
+            )));
+        }
+        Ok(read_pubkey(amm_data, 528))
+    }
+
+    /// 解析AMM账户和对应的市场账户
+    ///
+    /// AMM布局: base_vault@336, quote_vault@368, base_mint@400, quote_mint@432,
+    /// open_orders@496, market@528, market_program@560, target_orders@592。
+    /// 市场布局（含5字节头）: vault_signer_nonce@45, base_vault@117, quote_vault@165,
+    /// event_queue@253, bids@285, asks@317
+    pub fn decode(amm: Pubkey, amm_data: &[u8], market_data: &[u8]) -> Result<Self> {
+        let market = Self::market_of(amm_data)?;
+        if market_data.len() < Self::MARKET_MIN_LEN {
+            return Err(Error::InvalidInput(format!(
+                "Market account too short: {} bytes",
+                market_data.len()
+            )));
+        }
+
+        let market_program = read_pubkey(amm_data, 560);
+        let nonce = read_u64(market_data, 45);
+        let market_vault_signer =
+            Pubkey::create_program_address(&[market.as_ref(), &nonce.to_le_bytes()], &market_program)
+                .map_err(|e| Error::InvalidInput(format!("Invalid market vault signer for {}: {}", market, e)))?;
+
+        Ok(Self {
+            amm,
+            open_orders: read_pubkey(amm_data, 496),
+            target_orders: read_pubkey(amm_data, 592),
+            base_vault: read_pubkey(amm_data, 336),
+            quote_vault: read_pubkey(amm_data, 368),
+            base_mint: read_pubkey(amm_data, 400),
+            quote_mint: read_pubkey(amm_data, 432),
+            market_program,
+            market,
+            market_bids: read_pubkey(market_data, 285),
+            market_asks: read_pubkey(market_data, 317),
+            market_event_queue: read_pubkey(market_data, 253),
+            market_base_vault: read_pubkey(market_data, 117),
+            market_quote_vault: read_pubkey(market_data, 165),
+            market_vault_signer,
+        })
+    }
+}
+
+/// Raydium AMM V4的swap指令构建
+///
+/// 报价按vault余额用恒定乘积公式计算，已包含价格影响和DEX手续费。
+/// 只支持代币/WSOL池子，买入时临时包装SOL，卖出后关闭WSOL账户取回SOL
+pub struct RaydiumSwapProvider {
+    rpc: Arc<RpcManager>,
+    builder: TransactionBuilder,
+    slippage: SlippageProtector,
+
+    /// 最小输出相对报价的容忍度（basis points）
+    slippage_bps: u16,
+}
+
+impl RaydiumSwapProvider {
+    /// Raydium AMM V4交易手续费
+    const FEE_BPS: u16 = 25;
+
+    pub fn new(rpc: Arc<RpcManager>) -> Self {
+        Self {
+            rpc,
+            builder: TransactionBuilder::new(),
+            slippage: SlippageProtector::default(),
+            slippage_bps: 300,
+        }
+    }
+
+    /// 设置最小输出容忍度（卖出模拟用10000，测量税费而不是因滑点失败）
+    pub fn with_slippage_bps(mut self, slippage_bps: u16) -> Self {
+        self.slippage_bps = slippage_bps.min(10_000);
+        self
+    }
+
+    fn min_amount_out(&self, expected_out: u64) -> u64 {
+        (expected_out as u128 * (10_000 - self.slippage_bps as u128) / 10_000) as u64
+    }
+
+    /// 获取池子账户和当前储备，返回(账户, SOL储备, 代币储备)
+    async fn pool(&self, token: &TokenInfo) -> Result<(RaydiumPoolKeys, u64, u64)> {
+        let amm = token
+            .pool_address
+            .ok_or_else(|| Error::InvalidInput(format!("No pool address for {}", token.mint)))?;
+
+        let amm_data = self
+            .fetch_accounts(vec![amm])
+            .await?
+            .pop()
+            .flatten()
+            .ok_or_else(|| Error::NotFound(format!("Raydium AMM {} not found", amm)))?
+            .data;
+        let market = RaydiumPoolKeys::market_of(&amm_data)?;
+        let base_vault = read_pubkey(&amm_data, 336);
+        let quote_vault = read_pubkey(&amm_data, 368);
+
+        let accounts = self.fetch_accounts(vec![market, base_vault, quote_vault]).await?;
+        let data = |index: usize| -> Result<&[u8]> {
+            accounts
+                .get(index)
+                .and_then(|account| account.as_ref())
+                .map(|account| account.data.as_slice())
+                .ok_or_else(|| Error::NotFound(format!("Raydium pool account missing for {}", amm)))
+        };
+
+        let keys = RaydiumPoolKeys::decode(amm, &amm_data, data(0)?)?;
+        let vault_amount = |data: &[u8]| if data.len() >= 72 { read_u64(data, 64) } else { 0 };
+        let base_reserve = vault_amount(data(1)?);
+        let quote_reserve = vault_amount(data(2)?);
+
+        let wsol = wsol_mint();
+        if keys.quote_mint == wsol && keys.base_mint == token.mint {
+            Ok((keys, quote_reserve, base_reserve))
+        } else if keys.base_mint == wsol && keys.quote_mint == token.mint {
+            Ok((keys, base_reserve, quote_reserve))
+        } else {
+            Err(Error::InvalidInput(format!("Pool {} is not a SOL pair for {}", amm, token.mint)))
+        }
+    }
+
+    async fn fetch_accounts(&self, addresses: Vec<Pubkey>) -> Result<Vec<Option<Account>>> {
+        self.rpc
+            .execute_with_retry(|client| {
+                let addresses = addresses.clone();
+                async move {
+                    tokio::task::spawn_blocking(move || client.get_multiple_accounts(&addresses))
+                        .await
+                        .map_err(|e| Error::Internal(format!("RPC task join error: {}", e)))?
+                        .map_err(|e| Error::Rpc(format!("getMultipleAccounts failed: {}", e)))
+                }
+            })
+            .await
+    }
+
+    /// 买入指令：包装SOL → 创建代币ATA → swap → 关闭WSOL账户
+    pub fn buy_instructions(
+        &self,
+        keys: &RaydiumPoolKeys,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        lamports_in: u64,
+        min_amount_out: u64,
+    ) -> Vec<Instruction> {
+        let wsol = wsol_mint();
+        let wsol_account = associated_token_address(wallet, &wsol);
+        let token_account = associated_token_address(wallet, mint);
+
+        vec![
+            create_associated_token_account_idempotent(wallet, wallet, &wsol),
+            system_instruction::transfer(wallet, &wsol_account, lamports_in),
+            sync_native(&wsol_account),
+            create_associated_token_account_idempotent(wallet, wallet, mint),
+            self.builder.raydium_swap_instruction(
+                keys,
+                &wsol_account,
+                &token_account,
+                wallet,
+                lamports_in,
+                min_amount_out,
+            ),
+            close_account(&wsol_account, wallet, wallet),
+        ]
+    }
+
+    /// 卖出指令：创建WSOL账户 → swap → 关闭WSOL账户
+    pub fn sell_instructions(
+        &self,
+        keys: &RaydiumPoolKeys,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        token_amount: u64,
+        min_amount_out: u64,
+    ) -> Vec<Instruction> {
+        let wsol = wsol_mint();
+        let wsol_account = associated_token_address(wallet, &wsol);
+        let token_account = associated_token_address(wallet, mint);
+
+        vec![
+            create_associated_token_account_idempotent(wallet, wallet, &wsol),
+            self.builder.raydium_swap_instruction(
+                keys,
+                &token_account,
+                &wsol_account,
+                wallet,
+                token_amount,
+                min_amount_out,
+            ),
+            close_account(&wsol_account, wallet, wallet),
+        ]
+    }
+}
+
+#[async_trait]
+impl SwapInstructionProvider for RaydiumSwapProvider {
+    async fn buy_leg(&self, token: &TokenInfo, wallet: &Pubkey, lamports_in: u64) -> Result<SwapLeg> {
+        let (keys, sol_reserve, token_reserve) = self.pool(token).await?;
+        let expected_out = self
+            .slippage
+            .calculate_output_amount(lamports_in, sol_reserve, token_reserve, Self::FEE_BPS);
+        let min_amount_out = self.min_amount_out(expected_out);
+
+        Ok(SwapLeg {
+            instructions: self.buy_instructions(&keys, wallet, &token.mint, lamports_in, min_amount_out),
+            expected_out,
+            token_account: associated_token_address(wallet, &token.mint),
+        })
+    }
+
+    async fn sell_leg(&self, token: &TokenInfo, wallet: &Pubkey, token_amount: u64) -> Result<SwapLeg> {
+        let (keys, sol_reserve, token_reserve) = self.pool(token).await?;
+        let expected_out = self
+            .slippage
+            .calculate_output_amount(token_amount, token_reserve, sol_reserve, Self::FEE_BPS);
+        let min_amount_out = self.min_amount_out(expected_out);
+
+        Ok(SwapLeg {
+            instructions: self.sell_instructions(&keys, wallet, &token.mint, token_amount, min_amount_out),
+            expected_out,
+            token_account: associated_token_address(wallet, &token.mint),
+        })
+    }
+
+    fn fee_bps(&self) -> u16 {
+        Self::FEE_BPS
+    }
+
+    fn name(&self) -> &str {
+        "raydium-amm-v4"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solsniper_core::LoadBalancingStrategy;
+
+    fn pool_keys() -> RaydiumPoolKeys {
+        let market_program = Pubkey::new_unique();
+        let market = Pubkey::new_unique();
+
+        let mut amm_data = vec![0u8; RaydiumPoolKeys::AMM_LEN];
+        amm_data[400..432].copy_from_slice(Pubkey::new_unique().as_ref());
+        amm_data[432..464].copy_from_slice(wsol_mint().as_ref());
+        amm_data[528..560].copy_from_slice(market.as_ref());
+        amm_data[560..592].copy_from_slice(market_program.as_ref());
+
+        // 找到能生成有效vault signer的nonce
+        let mut market_data = vec![0u8; RaydiumPoolKeys::MARKET_MIN_LEN];
+        let nonce = (0u64..)
+            .find(|nonce| {
+                Pubkey::create_program_address(&[market.as_ref(), &nonce.to_le_bytes()], &market_program)
+                    .is_ok()
+            })
+            .unwrap();
+        market_data[45..53].copy_from_slice(&nonce.to_le_bytes());
+
+        RaydiumPoolKeys::decode(Pubkey::new_unique(), &amm_data, &market_data).unwrap()
+    }
+
+    #[test]
+    fn test_decode_pool_keys() {
+        let keys = pool_keys();
+
+        assert_eq!(keys.quote_mint, wsol_mint());
+        assert!(RaydiumPoolKeys::decode(keys.amm, &[0u8; 100], &[]).is_err());
+    }
+
+    #[test]
+    fn test_buy_and_sell_instructions() {
+        let rpc = Arc::new(RpcManager::new(vec![], 30, LoadBalancingStrategy::RoundRobin));
+        let provider = RaydiumSwapProvider::new(rpc).with_slippage_bps(10_000);
+        let keys = pool_keys();
+        let wallet = Pubkey::new_unique();
+
+        let min_out = provider.min_amount_out(500);
+        let buy = provider.buy_instructions(&keys, &wallet, &keys.base_mint, 10_000_000, min_out);
+        assert_eq!(buy.len(), 6);
+
+        let swap = &buy[4];
+        assert_eq!(swap.program_id, raydium_amm_program());
+        assert_eq!(swap.accounts.len(), 18);
+        assert_eq!(swap.accounts[15].pubkey, associated_token_address(&wallet, &wsol_mint()));
+        assert_eq!(swap.accounts[16].pubkey, associated_token_address(&wallet, &keys.base_mint));
+        assert_eq!(swap.data[0], 9);
+        assert_eq!(&swap.data[1..9], &10_000_000u64.to_le_bytes());
+        // 容忍度10000时最小输出为0
+        assert_eq!(&swap.data[9..17], &0u64.to_le_bytes());
+
+        let sell = provider.sell_instructions(&keys, &wallet, &keys.base_mint, 1_000, 0);
+        assert_eq!(sell.len(), 3);
+        assert_eq!(sell[1].accounts[15].pubkey, associated_token_address(&wallet, &keys.base_mint));
+    }
+}
@@ -4,19 +4,22 @@ use solana_sdk::{
     transaction::Transaction,
     message::Message,
     signature::{Keypair, Signer},
-    system_instruction,
     compute_budget::ComputeBudgetInstruction,
     hash::Hash,
 };
-use solsniper_core::{Result, TokenInfo};
+use solana_system_interface::instruction as system_instruction;
+use solsniper_core::Result;
 use std::sync::Arc;
 
+use crate::swap_instructions::{raydium_amm_authority, raydium_amm_program, token_program, RaydiumPoolKeys};
+
 /// 交易构建器
 ///
 /// 负责构建各种Solana交易，包括：
 /// - Token Swap交易
 /// - 计算单元预算设置
 /// - 优先费用设置
+#[derive(Debug, Clone)]
 pub struct TransactionBuilder {
     /// 最大计算单元
     max_compute_units: u32,
@@ -55,6 +58,7 @@ impl TransactionBuilder {
     /// - `amount_in`: 输入金额（lamports）
     /// - `min_amount_out`: 最小输出金额（用于滑点保护）
     /// - `recent_blockhash`: 最新区块哈希
+    #[allow(clippy::too_many_arguments)]
     pub async fn build_raydium_swap(
         &self,
         wallet: Arc<Keypair>,
@@ -71,6 +75,11 @@ impl TransactionBuilder {
             102, 114, 49, 78, 89, 72, 117, 122, 101, 76, 88, 102, 81, 77, 57, 72, 50
         ]); // 675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8
 
+        tracing::debug!(
+            "Building Raydium swap on {}: {} -> {}, amount_in: {}, min_out: {}",
+            pool_id, token_in_mint, token_out_mint, amount_in, min_amount_out
+        );
+
         // 构建Swap指令数据
         // Raydium Swap指令布局: [9, amount_in(u64), min_amount_out(u64)]
         let mut instruction_data = vec![9u8]; // Swap指令ID
@@ -81,7 +90,7 @@ impl TransactionBuilder {
         let swap_instruction = Instruction {
             program_id: raydium_program_id,
             accounts: vec![
-                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(token_program(), false),
                 AccountMeta::new(*pool_id, false),
                 // ... 实际需要更多账户，包括AMM authority, open orders, target orders等
                 // 这里仅作示例
@@ -97,6 +106,48 @@ impl TransactionBuilder {
         ).await
     }
 
+    /// 构建Raydium AMM V4 `swap_base_in`指令（完整账户列表）
+    ///
+    /// 方向由`user_source`的mint决定：WSOL账户为买入，代币账户为卖出
+    pub fn raydium_swap_instruction(
+        &self,
+        keys: &RaydiumPoolKeys,
+        user_source: &Pubkey,
+        user_destination: &Pubkey,
+        owner: &Pubkey,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Instruction {
+        let mut data = vec![9u8]; // swap_base_in
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+        Instruction {
+            program_id: raydium_amm_program(),
+            accounts: vec![
+                AccountMeta::new_readonly(token_program(), false),
+                AccountMeta::new(keys.amm, false),
+                AccountMeta::new_readonly(raydium_amm_authority(), false),
+                AccountMeta::new(keys.open_orders, false),
+                AccountMeta::new(keys.target_orders, false),
+                AccountMeta::new(keys.base_vault, false),
+                AccountMeta::new(keys.quote_vault, false),
+                AccountMeta::new_readonly(keys.market_program, false),
+                AccountMeta::new(keys.market, false),
+                AccountMeta::new(keys.market_bids, false),
+                AccountMeta::new(keys.market_asks, false),
+                AccountMeta::new(keys.market_event_queue, false),
+                AccountMeta::new(keys.market_base_vault, false),
+                AccountMeta::new(keys.market_quote_vault, false),
+                AccountMeta::new_readonly(keys.market_vault_signer, false),
+                AccountMeta::new(*user_source, false),
+                AccountMeta::new(*user_destination, false),
+                AccountMeta::new_readonly(*owner, true),
+            ],
+            data,
+        }
+    }
+
     /// 构建Orca Whirlpool Swap交易
     pub async fn build_orca_swap(
         &self,
@@ -112,6 +163,11 @@ impl TransactionBuilder {
             52, 113, 118, 85, 102, 77, 53, 75, 65, 103, 54, 67, 116, 56, 86, 119, 112, 89
         ]); // whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc
 
+        tracing::debug!(
+            "Building Orca swap on {}: amount_in: {}, min_out: {}",
+            whirlpool, amount_in, min_amount_out
+        );
+
         // Orca swap指令构建
         let instruction_data = vec![
             // Orca指令格式
@@ -148,8 +204,8 @@ impl TransactionBuilder {
         // POST https://quote-api.jup.ag/v6/swap
 
         tracing::info!(
-            "Building Jupiter swap: {} -> {}, amount: {}, slippage: {}bps",
-            input_mint, output_mint, amount, slippage_bps
+            "Building Jupiter swap for {}: {} -> {}, amount: {}, slippage: {}bps",
+            wallet.pubkey(), input_mint, output_mint, amount, slippage_bps
         );
 
         // TODO: 实际Jupiter API集成
@@ -220,11 +276,25 @@ impl TransactionBuilder {
         Ok(transaction)
     }
 
+    /// 构建用于`simulateTransaction`的未签名交易
+    ///
+    /// 模拟时关闭签名校验并替换区块哈希，因此不需要私钥
+    pub fn build_simulation_transaction(
+        &self,
+        payer: &Pubkey,
+        mut instructions: Vec<Instruction>,
+    ) -> Transaction {
+        instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_price(self.default_priority_fee));
+        instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(self.max_compute_units));
+
+        let message = Message::new_with_blockhash(&instructions, Some(payer), &Hash::default());
+        Transaction::new_unsigned(message)
+    }
+
     /// 计算交易大小（字节）
     pub fn estimate_transaction_size(instruction_count: usize) -> usize {
         // 基础大小 + 每个指令的大小估算
         const SIGNATURE_SIZE: usize = 64;
-        const PUBKEY_SIZE: usize = 32;
         const HEADER_SIZE: usize = 3;
         const BLOCKHASH_SIZE: usize = 32;
 
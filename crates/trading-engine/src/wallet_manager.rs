@@ -43,13 +43,13 @@ impl WalletManager {
 
         let keypair = if keypair_bytes.len() == 64 {
             // Raw bytes format
-            Keypair::from_bytes(&keypair_bytes)
+            Keypair::try_from(&keypair_bytes[..])
                 .map_err(|e| solsniper_core::Error::Internal(format!("Invalid keypair bytes: {}", e)))?
         } else {
             // JSON format
             let keypair_json: Vec<u8> = serde_json::from_slice(&keypair_bytes)
                 .map_err(|e| solsniper_core::Error::Internal(format!("Invalid keypair JSON: {}", e)))?;
-            Keypair::from_bytes(&keypair_json)
+            Keypair::try_from(&keypair_json[..])
                 .map_err(|e| solsniper_core::Error::Internal(format!("Invalid keypair bytes: {}", e)))?
         };
 
@@ -50,6 +50,32 @@ CREATE INDEX idx_tokens_symbol ON tokens USING GIN(symbol gin_trgm_ops);
 -- JSONB 索引
 CREATE INDEX idx_tokens_data_gin ON tokens USING GIN(data);
 
+-- ============================================================================
+-- 发币记录表（部署者信誉：部署者、发币时间和最近观测到的结果）
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS token_launches (
+    mint VARCHAR(44) PRIMARY KEY,
+    -- 创建交易的fee payer
+    creator VARCHAR(44) NOT NULL,
+    launched_at TIMESTAMPTZ NOT NULL,
+    -- 创建/建池交易所在slot（历史回填写入）
+    launch_slot BIGINT,
+    graduated_at TIMESTAMPTZ,
+
+    -- 结果（每次风险评估时更新）
+    peak_liquidity_sol NUMERIC(20, 9),
+    last_liquidity_sol NUMERIC(20, 9),
+    last_price_change_24h NUMERIC(10, 2),
+    observed_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_launches_creator ON token_launches(creator, launched_at DESC);
+
+-- 已有库补列
+ALTER TABLE token_launches ADD COLUMN IF NOT EXISTS launch_slot BIGINT;
+
 -- ============================================================================
 -- 交易表
 -- ============================================================================
@@ -99,6 +125,37 @@ CREATE INDEX idx_trades_status ON trades(status);
 CREATE INDEX idx_trades_strategy ON trades(strategy);
 CREATE INDEX idx_trades_executed_at ON trades(executed_at DESC NULLS LAST);
 
+-- ============================================================================
+-- 市场成交表（链上swap，历史回填和实时交易流写入）
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS market_trades (
+    id BIGSERIAL PRIMARY KEY,
+    mint VARCHAR(44) NOT NULL,
+    trader VARCHAR(44) NOT NULL,
+    side VARCHAR(10) NOT NULL CHECK (side IN ('buy', 'sell')),
+
+    -- 成交额（不含手续费、小费和账户租金）
+    amount_sol NUMERIC(20, 9) NOT NULL,
+    -- 原始单位代币数量
+    amount_tokens NUMERIC(30, 0) NOT NULL,
+    decimals SMALLINT NOT NULL,
+
+    tx_signature VARCHAR(88) NOT NULL,
+    slot BIGINT NOT NULL,
+    block_time TIMESTAMPTZ NOT NULL,
+    fee_lamports BIGINT,
+    jito_tip_lamports BIGINT,
+
+    source VARCHAR(20) NOT NULL DEFAULT 'backfill',
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE (tx_signature, mint, side)
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_trades_mint_slot ON market_trades(mint, slot);
+CREATE INDEX IF NOT EXISTS idx_market_trades_mint_time ON market_trades(mint, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_market_trades_trader ON market_trades(trader);
+
 -- ============================================================================
 -- 活跃持仓表
 -- ============================================================================
@@ -198,6 +255,57 @@ CREATE TABLE IF NOT EXISTS blacklist (
 -- 索引
 CREATE INDEX idx_blacklist_type_address ON blacklist(type, address);
 
+-- ============================================================================
+-- 风险评分配置表（PUT /api/v1/risk/scoring 保存，启动时取最新一行）
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS risk_scoring_config (
+    id SERIAL PRIMARY KEY,
+    config JSONB NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- ============================================================================
+-- 风险分历史表（每次评估一行，用于回看rug前的评分变化）
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS risk_score_history (
+    id BIGSERIAL PRIMARY KEY,
+    mint VARCHAR(44) NOT NULL,
+    total DOUBLE PRECISION NOT NULL,
+    recommendation VARCHAR(20) NOT NULL,
+    confidence DOUBLE PRECISION NOT NULL,
+    score JSONB NOT NULL, -- 完整RiskScore（含各分量和issues）
+    recomputed TEXT[] NOT NULL DEFAULT '{}', -- 本次重新运行的分析器
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- 索引
+CREATE INDEX IF NOT EXISTS idx_risk_score_history_mint_created_at ON risk_score_history(mint, created_at DESC);
+
+-- ============================================================================
+-- 代币快照表（行为模式指标的时间序列）
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS token_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    mint VARCHAR(44) NOT NULL,
+    data JSONB NOT NULL, -- 完整TokenInfo
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- 索引
+CREATE INDEX IF NOT EXISTS idx_token_snapshots_mint_created_at ON token_snapshots(mint, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_token_snapshots_created_at ON token_snapshots(created_at);
+
+-- ============================================================================
+-- 代币结果标注表（行为模式评估，优先于从快照推断的结果）
+-- ============================================================================
+CREATE TABLE IF NOT EXISTS token_outcomes (
+    mint VARCHAR(44) PRIMARY KEY,
+    outcome VARCHAR(10) NOT NULL CHECK (outcome IN ('rugged', 'pumped', 'flat')),
+    outcome_at TIMESTAMPTZ NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
 -- ============================================================================
 -- 触发器：自动更新 updated_at
 -- ============================================================================
@@ -243,6 +351,18 @@ BEGIN
     -- 删除 90 天前的交易记录
     DELETE FROM trades WHERE created_at < NOW() - INTERVAL '90 days';
 
+    -- 删除 90 天前的市场成交
+    DELETE FROM market_trades WHERE block_time < NOW() - INTERVAL '90 days';
+
+    -- 删除 180 天前的发币记录
+    DELETE FROM token_launches WHERE launched_at < NOW() - INTERVAL '180 days';
+
+    -- 删除 7 天前的代币快照
+    DELETE FROM token_snapshots WHERE created_at < NOW() - INTERVAL '7 days';
+
+    -- 删除 90 天前的风险分历史
+    DELETE FROM risk_score_history WHERE created_at < NOW() - INTERVAL '90 days';
+
     -- 删除 30 天前的系统事件
     DELETE FROM system_events WHERE created_at < NOW() - INTERVAL '30 days';
 
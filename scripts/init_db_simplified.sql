@@ -137,6 +137,27 @@ INSERT INTO risk_limits (max_position_size_sol, max_position_size_percent, max_t
                         min_risk_score, max_risk_score, block_extreme_risk)
 VALUES (10.0, 20.0, 100.0, 10, 2.0, 10.0, 20.0, 70, 95, TRUE);
 
+-- ==================== 风险评分配置表 ====================
+-- 通过 PUT /api/v1/risk/scoring 保存的权重、调整项和推荐阈值（取最新一行）
+CREATE TABLE IF NOT EXISTS risk_scoring_config (
+    id SERIAL PRIMARY KEY,
+    config JSONB NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW()
+);
+
+-- ==================== 风险分历史表 ====================
+-- 每次风险评估一行，GET /api/v1/tokens/:mint/risk-history 读取
+CREATE TABLE IF NOT EXISTS risk_score_history (
+    id BIGSERIAL PRIMARY KEY,
+    mint VARCHAR(44) NOT NULL,
+    total DOUBLE PRECISION NOT NULL,
+    recommendation VARCHAR(20) NOT NULL,
+    confidence DOUBLE PRECISION NOT NULL,
+    score JSONB NOT NULL,
+    recomputed TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
 -- ==================== 索引 ====================
 CREATE INDEX IF NOT EXISTS idx_tokens_symbol ON tokens(symbol);
 CREATE INDEX IF NOT EXISTS idx_tokens_mint ON tokens(mint);
@@ -150,6 +171,8 @@ CREATE INDEX IF NOT EXISTS idx_positions_created_at ON positions(created_at DESC
 
 CREATE INDEX IF NOT EXISTS idx_trades_created_at ON trades(created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_trades_strategy ON trades(strategy_name);
+
+CREATE INDEX IF NOT EXISTS idx_risk_score_history_mint ON risk_score_history(mint, created_at DESC);
 CREATE INDEX IF NOT EXISTS idx_trades_status ON trades(status);
 
 -- ==================== 触发器：自动更新 updated_at ====================